@@ -0,0 +1,94 @@
+//! Encoding and decoding throughput across input sizes, as a baseline for performance work. With the `simd` feature the
+//! standard table is handled by the vector kernels and the xx table is always left to the scalar code, so comparing the
+//! two shows what the kernels are worth; the `base64` crate is measured alongside as a yardstick. Run with
+//! `cargo bench`, adding `--features unsafe-fast` for the fastest build, or `--features parallel` for the
+//! multi-threaded functions.
+
+use base64::Engine as _;
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use std::hint::black_box;
+use uuencode_lite::{DecodeOptions, EncodeOptions, FileOptions, Table};
+
+/// The input sizes measured: a few lines, a page, and enough to leave the caches.
+const SIZES: [usize; 3] = [256, 64 << 10, 16 << 20];
+
+/// Bytes which look random, so that every character of the alphabet is exercised.
+fn data(len: usize) -> Vec<u8> {
+    (0..len as u64).map(|i| (i.wrapping_mul(0x9E37_79B9) >> 13) as u8).collect()
+}
+
+fn encode(c: &mut Criterion) {
+    let mut group = c.benchmark_group("encode");
+    for size in SIZES {
+        let data = data(size);
+        group.throughput(Throughput::Bytes(size as u64));
+        for (name, table) in [("standard", Table::STANDARD), ("xx", Table::XX)] {
+            let options = EncodeOptions::new().table(table);
+            let mut output = vec![0u8; uuencode_lite::uuencoded_len(size, &options)];
+            group.bench_with_input(BenchmarkId::new(format!("to_slice/{}", name), size), &data, |b, data| {
+                b.iter(|| uuencode_lite::uuencode_to_slice_with(black_box(data), &options, &mut output).expect("encodes"))
+            });
+        }
+        group.bench_with_input(BenchmarkId::new("uuencode", size), &data, |b, data| b.iter(|| uuencode_lite::uuencode(black_box(data)).expect("encodes")));
+        let file = FileOptions::new("data.bin");
+        group.bench_with_input(BenchmarkId::new("uuencode_file", size), &data, |b, data| b.iter(|| uuencode_lite::uuencode_file(black_box(data), &file).expect("encodes")));
+        #[cfg(feature = "parallel")]
+        group.bench_with_input(BenchmarkId::new("uuencode_parallel", size), &data, |b, data| b.iter(|| uuencode_lite::uuencode_parallel(black_box(data)).expect("encodes")));
+        group.bench_with_input(BenchmarkId::new("base64", size), &data, |b, data| b.iter(|| base64::engine::general_purpose::STANDARD.encode(black_box(data))));
+    }
+    group.finish();
+}
+
+fn decode(c: &mut Criterion) {
+    let mut group = c.benchmark_group("decode");
+    for size in SIZES {
+        let data = data(size);
+        // measured against the decoded size, so that every codec is compared on the same work
+        group.throughput(Throughput::Bytes(size as u64));
+        for (name, table) in [("standard", Table::STANDARD), ("xx", Table::XX)] {
+            let encoded = uuencode_lite::uuencode_with(&data, &EncodeOptions::new().table(table.clone())).expect("encodes");
+            let options = DecodeOptions::new().table(table);
+            let mut output = vec![0u8; size];
+            group.bench_with_input(BenchmarkId::new(format!("to_slice/{}", name), size), encoded.as_bytes(), |b, encoded| {
+                b.iter(|| uuencode_lite::uudecode_to_slice_with(black_box(encoded), &options, &mut output).expect("decodes"))
+            });
+        }
+        let encoded = uuencode_lite::uuencode(&data).expect("encodes");
+        group.bench_with_input(BenchmarkId::new("uudecode", size), encoded.as_bytes(), |b, encoded| b.iter(|| uuencode_lite::uudecode(black_box(encoded)).expect("decodes")));
+        let file = uuencode_lite::uuencode_file(&data, &FileOptions::new("data.bin")).expect("encodes");
+        group.bench_with_input(BenchmarkId::new("uudecode_file", size), file.as_bytes(), |b, file| b.iter(|| uuencode_lite::uudecode_file(black_box(file)).expect("decodes")));
+        #[cfg(feature = "parallel")]
+        group.bench_with_input(BenchmarkId::new("uudecode_parallel", size), encoded.as_bytes(), |b, encoded| {
+            b.iter(|| uuencode_lite::uudecode_parallel(black_box(encoded)).expect("decodes"))
+        });
+        let base64 = base64::engine::general_purpose::STANDARD.encode(&data);
+        group.bench_with_input(BenchmarkId::new("base64", size), base64.as_bytes(), |b, base64| {
+            b.iter(|| base64::engine::general_purpose::STANDARD.decode(black_box(base64)).expect("decodes"))
+        });
+    }
+    group.finish();
+}
+
+/// The decoders which accept damaged or unusual input, on input which needs their help.
+fn lenient(c: &mut Criterion) {
+    let mut group = c.benchmark_group("lenient");
+    for size in SIZES {
+        let data = data(size);
+        group.throughput(Throughput::Bytes(size as u64));
+        let encoded = uuencode_lite::uuencode(&data).expect("encodes");
+        let crlf = encoded.replace('\n', "\r\n\r\n");
+        let options = DecodeOptions::new();
+        group.bench_with_input(BenchmarkId::new("uudecode_with/crlf", size), crlf.as_bytes(), |b, crlf| b.iter(|| uuencode_lite::uudecode_with(black_box(crlf), &options).expect("decodes")));
+        // quoted by a mail client, which also stripped trailing spaces
+        let damaged: String = encoded.lines().map(|line| format!("> {}\n", line.replace('`', " ").trim_end())).collect();
+        group.bench_with_input(BenchmarkId::new("repair", size), damaged.as_str(), |b, damaged| {
+            b.iter(|| uuencode_lite::uudecode_with(uuencode_lite::repair(black_box(damaged)).0.as_bytes(), &options).expect("decodes"))
+        });
+        let mail = format!("Subject: data\n\n{}", uuencode_lite::uuencode_file(&data, &FileOptions::new("data.bin")).expect("encodes"));
+        group.bench_with_input(BenchmarkId::new("decode_auto", size), mail.as_bytes(), |b, mail| b.iter(|| uuencode_lite::decode_auto(black_box(mail)).expect("decodes")));
+    }
+    group.finish();
+}
+
+criterion_group!(benches, encode, decode, lenient);
+criterion_main!(benches);
@@ -0,0 +1,5 @@
+fn main() {
+    // Node.js addons are loaded into the node process, which provides the N-API symbols.
+    #[cfg(feature = "napi")]
+    napi_build::setup();
+}
@@ -0,0 +1,28 @@
+//! Decodes arbitrary text as bare uuencoded lines, with any alphabet, into a `Vec` and into a slice of any size,
+//! checking that the two agree.
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use uuencode_lite::{uudecode_to_slice_with, uudecode_with, DecodeOptions, ErrorKind, Table};
+
+#[derive(Debug, arbitrary::Arbitrary)]
+struct Input<'a> {
+    table: u8,
+    verify_checksums: bool,
+    output_len: u16,
+    data: &'a [u8],
+}
+
+fuzz_target!(|input: Input| {
+    let table = [Table::STANDARD, Table::XX, Table::BASE64, Table::BASE64_URL][input.table as usize % 4].clone();
+    let options = DecodeOptions::new().table(table).verify_checksums(input.verify_checksums);
+    let decoded = uudecode_with(input.data, &options);
+    let mut output = vec![0u8; input.output_len as usize];
+    let sliced = uudecode_to_slice_with(input.data, &options, &mut output);
+    match (decoded, sliced) {
+        (Ok(decoded), Ok(len)) => assert_eq!(&output[..len], decoded, "decodes the same into a slice"),
+        (Ok(decoded), Err(err)) => assert!(err.kind() == ErrorKind::Capacity && decoded.len() > output.len(), "fails to decode into a slice: {}", err),
+        (Err(err), Ok(_)) => panic!("decodes into a slice, but not into a Vec: {}", err),
+        (Err(_), Err(_)) => {}
+    }
+});
@@ -0,0 +1,31 @@
+//! Scans arbitrary text for embedded files, as a mailing-list digest, and reassembles it split into arbitrary
+//! parts, checking that every name handed back is safe to use as a path.
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use uuencode_lite::{decode_all, is_safe_file_name, reassemble_parts, DecodeOptions};
+
+#[derive(Debug, arbitrary::Arbitrary)]
+struct Input<'a> {
+    splits: Vec<u16>,
+    data: &'a [u8],
+}
+
+fuzz_target!(|input: Input| {
+    let options = DecodeOptions::new();
+    for file in decode_all(input.data, &options).into_iter().flatten() {
+        assert!(is_safe_file_name(&file.name), "extracts an unsafe name: {:?}", file.name);
+    }
+
+    let mut parts = Vec::new();
+    let mut rest = input.data;
+    for &split in &input.splits {
+        let (part, tail) = rest.split_at((split as usize).min(rest.len()));
+        parts.push(part);
+        rest = tail;
+    }
+    parts.push(rest);
+    if let Ok(file) = reassemble_parts(&parts, &options) {
+        assert!(is_safe_file_name(&file.name), "reassembles an unsafe name: {:?}", file.name);
+    }
+});
@@ -0,0 +1,19 @@
+//! Decodes files whose `begin` line is arbitrary, so the fuzzer works on modes, names and encoded names, checking
+//! that an accepted name is safe and that the incremental decoder reads the same header.
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use uuencode_lite::{is_safe_file_name, uudecode_file, Decoder, DecodeOptions};
+
+fuzz_target!(|data: &[u8]| {
+    let text = [b"begin", data].concat();
+    let file = uudecode_file(&text);
+    let mut decoder = Decoder::new(DecodeOptions::new());
+    let mut decoded = Vec::new();
+    let streamed = decoder.push(&text, &mut decoded).and_then(|()| decoder.finish(&mut decoded));
+    if let Ok(file) = file {
+        assert!(is_safe_file_name(&file.name), "accepts an unsafe name: {:?}", file.name);
+        assert!(streamed.is_ok(), "the incremental decoder rejects an accepted file");
+        assert_eq!(decoder.header(), Some((file.mode, file.name.as_str())), "the incremental decoder reads another header");
+    }
+});
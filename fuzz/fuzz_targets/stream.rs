@@ -0,0 +1,54 @@
+//! Feeds the incremental decoder arbitrary text, or a generated document, in pieces of random sizes, checking that
+//! how the text is split makes no difference, and that it agrees with decoding the whole text at once.
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use uuencode_lite::{uudecode_file, DecodedFile, Decoder, DecodeOptions, EncodedDocument, UUEncodeError};
+
+#[derive(Debug, arbitrary::Arbitrary)]
+enum Text<'a> {
+    Raw(&'a [u8]),
+    Document(EncodedDocument),
+}
+
+#[derive(Debug, arbitrary::Arbitrary)]
+struct Input<'a> {
+    pieces: Vec<u8>,
+    text: Text<'a>,
+}
+
+/// Decodes `text`, pushed to the decoder in pieces of the given sizes, and the rest in one piece.
+fn decode_pieces(text: &[u8], pieces: &[u8]) -> Result<DecodedFile, UUEncodeError> {
+    let mut decoder = Decoder::new(DecodeOptions::new());
+    let mut data = Vec::new();
+    let mut rest = text;
+    for &len in pieces {
+        let (piece, tail) = rest.split_at((len as usize).min(rest.len()));
+        decoder.push(piece, &mut data)?;
+        rest = tail;
+    }
+    decoder.push(rest, &mut data)?;
+    decoder.finish(&mut data)?;
+    let (mode, name) = decoder.header().expect("a finished file has a header");
+    Ok(DecodedFile { name: name.to_string(), mode, data })
+}
+
+fuzz_target!(|input: Input| {
+    let (text, expected) = match &input.text {
+        Text::Raw(text) => (*text, None),
+        Text::Document(document) => (document.text.as_bytes(), Some(&document.data)),
+    };
+    let pieces = decode_pieces(text, &input.pieces);
+    let whole = decode_pieces(text, &[]);
+    match (&pieces, &whole) {
+        (Ok(pieces), Ok(whole)) => assert_eq!(pieces, whole, "decodes differently in pieces"),
+        (Err(pieces), Err(whole)) => assert_eq!((pieces.line(), pieces.character()), (whole.line(), whole.character()), "fails differently in pieces"),
+        _ => panic!("decodes in pieces {:?}, but whole {:?}", pieces, whole),
+    }
+    if let Some(expected) = expected {
+        assert_eq!(&whole.as_ref().expect("decodes a generated document").data, expected, "decodes a generated document");
+    }
+    if let Ok(file) = uudecode_file(text) {
+        assert_eq!(whole.ok(), Some(file), "the incremental decoder disagrees with uudecode_file");
+    }
+});
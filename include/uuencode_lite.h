@@ -0,0 +1,181 @@
+/* C bindings for uuencode_lite. Build the library with `cargo rustc --release --features ffi --crate-type staticlib`. */
+
+#ifndef UUENCODE_LITE_H
+#define UUENCODE_LITE_H
+
+/* Generated by cbindgen from src/ffi.rs; do not edit. */
+
+#include <stddef.h>
+#include <stdint.h>
+
+/*
+ The result of a call from C. Null pointers are reported rather than dereferenced.
+ */
+typedef enum UUStatus {
+  /*
+   The call succeeded.
+   */
+  UU_STATUS_OK = 0,
+  /*
+   The input was malformed, or an argument was invalid. `uu_last_error` describes the problem.
+   */
+  UU_STATUS_INVALID = 1,
+  /*
+   The output did not fit in the buffer given for it.
+   */
+  UU_STATUS_CAPACITY = 2,
+  /*
+   A required pointer was null.
+   */
+  UU_STATUS_NULL_POINTER = 3,
+} UUStatus;
+
+/*
+ A streaming decoder for a complete file, as `Decoder`, created by `uu_decoder_new`.
+ */
+typedef struct UUDecoder UUDecoder;
+
+/*
+ A streaming encoder for a complete file, as `Encoder`, created by `uu_encoder_new`.
+ */
+typedef struct UUEncoder UUEncoder;
+
+/*
+ Returns the message of the last failed call on this thread, or null if no call has failed.
+ The string is valid until the next failed call on this thread.
+ */
+const char *uu_last_error(void);
+
+/*
+ Returns a static description of a status, or "Unknown status" for any other value.
+ The status is taken as an `int`, as C may pass any value of an enum's type.
+ */
+const char *uu_status_string(int status);
+
+/*
+ The number of bytes `uu_encode` writes for `data_len` bytes of input.
+ */
+size_t uu_encoded_len(size_t data_len);
+
+/*
+ Encodes `data_len` bytes of `data` as bare uuencoded lines, as `uuencode`, into the `out_cap` bytes at `out`,
+ storing the number of bytes written in `out_len`. `uu_encoded_len` gives the space needed.
+ The output is not nul-terminated.
+
+ # Safety
+ `data` must point to `data_len` readable bytes, `out` to `out_cap` writable bytes, and `out_len` must be valid
+ for writes. `data` and `out` may be null when their lengths are zero.
+ */
+enum UUStatus uu_encode(const uint8_t *data,
+                        size_t data_len,
+                        uint8_t *out,
+                        size_t out_cap,
+                        size_t *out_len);
+
+/*
+ Decodes `data_len` bytes of bare uuencoded lines from `data`, as `uudecode`, into the `out_cap` bytes at `out`,
+ storing the number of bytes written in `out_len`. The decoded data is never longer than the text, so an
+ `out_cap` of `data_len` is always enough.
+
+ # Safety
+ `data` must point to `data_len` readable bytes, `out` to `out_cap` writable bytes, and `out_len` must be valid
+ for writes. `data` and `out` may be null when their lengths are zero.
+ */
+enum UUStatus uu_decode(const uint8_t *data,
+                        size_t data_len,
+                        uint8_t *out,
+                        size_t out_cap,
+                        size_t *out_len);
+
+/*
+ Creates a decoder for a complete file, with the default options. Free it with `uu_decoder_free`.
+ */
+struct UUDecoder *uu_decoder_new(void);
+
+/*
+ Decodes the next `data_len` bytes of text, which may end part way through a line. Points `out` at the bytes
+ decoded from the lines it completed, and stores their number in `out_len`; they are valid until the next call
+ with this decoder.
+
+ # Safety
+ `decoder` must come from `uu_decoder_new` and not have been freed, `data` must point to `data_len` readable
+ bytes, and `out` and `out_len` must be valid for writes.
+ */
+enum UUStatus uu_decoder_feed(struct UUDecoder *decoder,
+                              const uint8_t *data,
+                              size_t data_len,
+                              const uint8_t **out,
+                              size_t *out_len);
+
+/*
+ Decodes any last line without a newline, handing out its bytes as `uu_decoder_feed` does, and checks that the
+ file was complete.
+
+ # Safety
+ As for `uu_decoder_feed`.
+ */
+enum UUStatus uu_decoder_finish(struct UUDecoder *decoder,
+                                const uint8_t **out,
+                                size_t *out_len);
+
+/*
+ Returns the file name from the `begin` line, storing its permission bits in `mode` unless it is null,
+ or returns null if the `begin` line has not been seen yet. The name is valid until the decoder is freed.
+
+ # Safety
+ `decoder` must come from `uu_decoder_new` and not have been freed, and `mode` must be null or valid for writes.
+ */
+const char *uu_decoder_header(struct UUDecoder *decoder,
+                              uint32_t *mode);
+
+/*
+ Frees a decoder. Does nothing if `decoder` is null.
+
+ # Safety
+ `decoder` must be null, or come from `uu_decoder_new` and not have been freed.
+ */
+void uu_decoder_free(struct UUDecoder *decoder);
+
+/*
+ Creates an encoder for a complete file with the given nul-terminated UTF-8 name and permission bits.
+ Returns null if the name is null or invalid, with the reason in `uu_last_error`. Free it with `uu_encoder_free`.
+
+ # Safety
+ `name` must be null or a valid nul-terminated string.
+ */
+struct UUEncoder *uu_encoder_new(const char *name,
+                                 uint32_t mode);
+
+/*
+ Encodes the next `data_len` bytes. Points `out` at the text of the lines completed, and stores its length in
+ `out_len`; it is valid until the next call with this encoder, and is not nul-terminated.
+
+ # Safety
+ `encoder` must come from `uu_encoder_new` and not have been freed, `data` must point to `data_len` readable
+ bytes, and `out` and `out_len` must be valid for writes.
+ */
+enum UUStatus uu_encoder_feed(struct UUEncoder *encoder,
+                              const uint8_t *data,
+                              size_t data_len,
+                              const uint8_t **out,
+                              size_t *out_len);
+
+/*
+ Encodes the last partial line and the end of the file, handing out the text as `uu_encoder_feed` does.
+
+ # Safety
+ As for `uu_encoder_feed`.
+ */
+enum UUStatus uu_encoder_finish(struct UUEncoder *encoder,
+                                const uint8_t **out,
+                                size_t *out_len);
+
+/*
+ Frees an encoder. Does nothing if `encoder` is null.
+
+ # Safety
+ `encoder` must be null, or come from `uu_encoder_new` and not have been freed.
+ */
+void uu_encoder_free(struct UUEncoder *encoder);
+
+#endif  /* UUENCODE_LITE_H */
@@ -0,0 +1,142 @@
+use alloc::string::String;
+use alloc::vec::Vec;
+use crate::{Message, UUEncodeError};
+
+/// Encodes the input data as Adobe Ascii85, wrapped in `<~` and `~>` delimiters.
+/// Groups of four zero bytes are abbreviated to `z`. No line breaks are inserted.
+/// Example:
+/// ```rust
+/// assert_eq!(uuencode_lite::ascii85_encode(b"cat"), "<~@psH~>");
+/// ```
+pub fn ascii85_encode(data: &[u8]) -> String {
+    let mut encoded = String::with_capacity(data.len().div_ceil(4) * 5 + 4);
+    encoded.push_str("<~");
+    for chunk in data.chunks(4) {
+        let mut buffer = [0u8; 4];
+        buffer[..chunk.len()].copy_from_slice(chunk);
+        let mut value = u32::from_be_bytes(buffer);
+        if value == 0 && chunk.len() == 4 {
+            encoded.push('z');
+            continue;
+        }
+        let mut digits = [0u8; 5];
+        for digit in digits.iter_mut().rev() {
+            *digit = (value % 85) as u8 + b'!';
+            value /= 85;
+        }
+        // a partial group of n bytes is written as n + 1 characters
+        for &digit in &digits[..chunk.len() + 1] {
+            encoded.push(digit as char);
+        }
+    }
+    encoded.push_str("~>");
+    encoded
+}
+
+/// Decodes Adobe Ascii85 text, as produced by `ascii85_encode`.
+/// The `<~` and `~>` delimiters are optional, whitespace is ignored, and `z` is accepted for four zero bytes.
+/// Errors report the line and character of the offending input.
+/// Example:
+/// ```rust
+/// fn decode() -> Result<(), uuencode_lite::UUEncodeError> {
+///     let decoded = uuencode_lite::ascii85_decode(b"<~@psH~>")?;
+///     assert_eq!(decoded, b"cat");
+///     Ok(())
+/// }
+/// ```
+pub fn ascii85_decode(data: &[u8]) -> Result<Vec<u8>, UUEncodeError> {
+    let start = data.iter().position(|ch| !ch.is_ascii_whitespace()).unwrap_or(data.len());
+    let body_start = if data[start..].starts_with(b"<~") { start + 2 } else { start };
+
+    let mut decoded = Vec::with_capacity(data.len() / 5 * 4 + 4);
+    let mut group = [0u8; 5];
+    let mut filled = 0;
+    let mut cur_line = data[..body_start].iter().filter(|&&ch| ch == b'\n').count();
+    let mut line_start = data[..body_start].iter().rposition(|&ch| ch == b'\n').map_or(0, |idx| idx + 1);
+    let mut terminated = false;
+    let mut idx = body_start;
+    while idx < data.len() {
+        let ch = data[idx];
+        let cur_char = idx - line_start;
+        match ch {
+            b'\n' => {
+                cur_line += 1;
+                line_start = idx + 1;
+            }
+            _ if ch.is_ascii_whitespace() => {}
+            b'~' if data.get(idx + 1) == Some(&b'>') => {
+                terminated = true;
+                idx += 2;
+                break;
+            }
+            b'z' if filled == 0 => decoded.extend_from_slice(&[0; 4]),
+            b'!'..=b'u' => {
+                group[filled] = ch - b'!';
+                filled += 1;
+                if filled == 5 {
+                    let value = group.iter().try_fold(0u32, |value, &digit| value.checked_mul(85)?.checked_add(digit as u32));
+                    let Some(value) = value else {
+                        return Err(UUEncodeError::new(cur_line, cur_char, "Ascii85 group out of range"));
+                    };
+                    decoded.extend_from_slice(&value.to_be_bytes());
+                    filled = 0;
+                }
+            }
+            _ => return Err(UUEncodeError::new(cur_line, cur_char, Message::InvalidCharacter(ch))),
+        }
+        idx += 1;
+    }
+    if terminated && data[idx..].iter().any(|ch| !ch.is_ascii_whitespace()) {
+        return Err(UUEncodeError::new(cur_line, idx - line_start, "Data after ~> in input"));
+    }
+
+    match filled {
+        0 => {}
+        1 => return Err(UUEncodeError::new(cur_line, idx - line_start, "Incomplete Ascii85 group at end of input")),
+        _ => {
+            // pad a partial group with the highest digit, then drop the padding bytes
+            group[filled..].fill(84);
+            let value = group.iter().try_fold(0u32, |value, &digit| value.checked_mul(85)?.checked_add(digit as u32));
+            let Some(value) = value else {
+                return Err(UUEncodeError::new(cur_line, idx - line_start, "Ascii85 group out of range"));
+            };
+            decoded.extend_from_slice(&value.to_be_bytes()[..filled - 1]);
+        }
+    }
+    Ok(decoded)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Tests the well-known example from the Ascii85 specification
+    #[test]
+    fn test_ascii85_vectors() {
+        let plain = b"Man is distinguished";
+        assert_eq!(ascii85_encode(plain), "<~9jqo^BlbD-BleB1DJ+*+F(f,q~>", "can ascii85 encode a sentence");
+        assert_eq!(ascii85_decode(b"<~9jqo^BlbD-BleB1DJ+*+F(f,q~>").unwrap(), plain, "can ascii85 decode a sentence");
+        assert_eq!(ascii85_encode(&[0, 0, 0, 0, 0]), "<~z!!~>", "abbreviates zero groups");
+        assert_eq!(ascii85_decode(b"9jqo^\n  Blb D-BleB1DJ+*+F(f,q").unwrap(), plain, "ignores whitespace and missing delimiters");
+    }
+
+    /// Tests round-tripping random data
+    #[test]
+    fn test_ascii85_rt() {
+        let source_data = std::fs::read("test_data/random_data.bin").expect("Can open test data");
+        for len in [0, 1, 2, 3, 4, 5, source_data.len()] {
+            let encoded = ascii85_encode(&source_data[..len]);
+            assert_eq!(ascii85_decode(encoded.as_bytes()).unwrap(), &source_data[..len], "can round-trip {} bytes", len);
+        }
+    }
+
+    /// Tests error reporting
+    #[test]
+    fn test_ascii85_errors() {
+        let err = ascii85_decode(b"<~9jqo^\nBl{D~>").unwrap_err();
+        assert_eq!((err.line, err.character), (1, 2), "reports the position of an invalid character");
+        assert!(ascii85_decode(b"<~s8W-\"~>").is_err(), "rejects an out of range group");
+        assert!(ascii85_decode(b"<~9~>").is_err(), "rejects a single trailing character");
+        assert!(ascii85_decode(b"<~9jqo^~>junk").is_err(), "rejects data after the end delimiter");
+    }
+}
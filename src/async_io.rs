@@ -0,0 +1,580 @@
+use crate::decoder::Decoder;
+use crate::encoder::Encoder;
+use crate::stream::READ_CHUNK;
+use crate::{DecodeOptions, FileOptions, UUEncodeError};
+use std::boxed::Box;
+use std::io;
+use std::pin::Pin;
+use std::string::String;
+use std::task::{ready, Context, Poll};
+use std::vec;
+use std::vec::Vec;
+
+/// Writes `pending[*position..]` to the inner writer, advancing `position` as it goes.
+fn poll_drain<W: Unpin>(
+    inner: &mut W,
+    cx: &mut Context<'_>,
+    pending: &[u8],
+    position: &mut usize,
+    write_inner: &mut impl FnMut(Pin<&mut W>, &mut Context<'_>, &[u8]) -> Poll<io::Result<usize>>,
+) -> Poll<io::Result<()>> {
+    while *position < pending.len() {
+        match ready!(write_inner(Pin::new(inner), cx, &pending[*position..])) {
+            Ok(0) => return Poll::Ready(Err(io::ErrorKind::WriteZero.into())),
+            Ok(len) => *position += len,
+            Err(err) if err.kind() == io::ErrorKind::Interrupted => {}
+            Err(err) => return Poll::Ready(Err(err)),
+        }
+    }
+    Poll::Ready(Ok(()))
+}
+
+/// Reads from the inner reader, retrying if interrupted.
+fn poll_fill<R: Unpin>(
+    inner: &mut R,
+    cx: &mut Context<'_>,
+    chunk: &mut [u8],
+    read_inner: &mut impl FnMut(Pin<&mut R>, &mut Context<'_>, &mut [u8]) -> Poll<io::Result<usize>>,
+) -> Poll<io::Result<usize>> {
+    loop {
+        match ready!(read_inner(Pin::new(inner), cx, chunk)) {
+            Err(err) if err.kind() == io::ErrorKind::Interrupted => {}
+            result => return Poll::Ready(result),
+        }
+    }
+}
+
+/// An async writer that uuencodes everything written to it as a complete file, as `EncodeWriter` does.
+/// Close (shut down) the writer once all the data is written, to write the last line, the terminator and `end`.
+/// With the `tokio` feature this implements tokio's `AsyncWrite`, for writers such as sockets and `tokio::fs::File`,
+/// and with the `futures-io` feature it implements the runtime-agnostic `futures::io::AsyncWrite`.
+/// Example:
+/// ```rust
+/// # #[cfg(feature = "tokio")]
+/// async fn encode() -> std::io::Result<()> {
+///     use tokio::io::AsyncWriteExt;
+///     let mut writer = uuencode_lite::AsyncEncodeWriter::new(Vec::new(), uuencode_lite::FileOptions::new("cat.txt"))?;
+///     writer.write_all(b"cat").await?;
+///     writer.shutdown().await?;
+///     assert_eq!(writer.into_inner(), b"begin 644 cat.txt\n#8V%T\n`\nend\n");
+///     Ok(())
+/// }
+/// ```
+#[derive(Debug)]
+pub struct AsyncEncodeWriter<W> {
+    inner: W,
+    encoder: Encoder,
+    /// Encoded text waiting to be written to `inner`.
+    buffer: String,
+    /// How much of `buffer` has been written.
+    position: usize,
+    /// Whether the end of the file has been encoded.
+    finished: bool,
+}
+
+impl<W: Unpin> AsyncEncodeWriter<W> {
+    /// Creates a writer that encodes a file with the given options into `inner`.
+    /// Fails if the file name is invalid, as `uuencode_file` does.
+    pub fn new(inner: W, options: FileOptions) -> Result<Self, UUEncodeError> {
+        Ok(AsyncEncodeWriter { inner, encoder: Encoder::new(options)?, buffer: String::new(), position: 0, finished: false })
+    }
+
+    /// The inner writer.
+    pub fn get_ref(&self) -> &W {
+        &self.inner
+    }
+
+    /// Returns the inner writer. Any encoded text not yet written to it is lost.
+    pub fn into_inner(self) -> W {
+        self.inner
+    }
+
+    /// Writes any buffered text to the inner writer.
+    fn poll_drain(&mut self, cx: &mut Context<'_>, write_inner: &mut impl FnMut(Pin<&mut W>, &mut Context<'_>, &[u8]) -> Poll<io::Result<usize>>) -> Poll<io::Result<()>> {
+        ready!(poll_drain(&mut self.inner, cx, self.buffer.as_bytes(), &mut self.position, write_inner))?;
+        self.buffer.clear();
+        self.position = 0;
+        Poll::Ready(Ok(()))
+    }
+
+    fn poll_write_with(&mut self, cx: &mut Context<'_>, buf: &[u8], mut write_inner: impl FnMut(Pin<&mut W>, &mut Context<'_>, &[u8]) -> Poll<io::Result<usize>>) -> Poll<io::Result<usize>> {
+        ready!(self.poll_drain(cx, &mut write_inner))?;
+        let buf = &buf[..buf.len().min(READ_CHUNK)];
+        self.encoder.push(buf, &mut self.buffer)?;
+        Poll::Ready(Ok(buf.len()))
+    }
+
+    fn poll_flush_with(
+        &mut self,
+        cx: &mut Context<'_>,
+        mut write_inner: impl FnMut(Pin<&mut W>, &mut Context<'_>, &[u8]) -> Poll<io::Result<usize>>,
+        flush_inner: impl FnOnce(Pin<&mut W>, &mut Context<'_>) -> Poll<io::Result<()>>,
+    ) -> Poll<io::Result<()>> {
+        ready!(self.poll_drain(cx, &mut write_inner))?;
+        flush_inner(Pin::new(&mut self.inner), cx)
+    }
+
+    fn poll_close_with(
+        &mut self,
+        cx: &mut Context<'_>,
+        mut write_inner: impl FnMut(Pin<&mut W>, &mut Context<'_>, &[u8]) -> Poll<io::Result<usize>>,
+        close_inner: impl FnOnce(Pin<&mut W>, &mut Context<'_>) -> Poll<io::Result<()>>,
+    ) -> Poll<io::Result<()>> {
+        if !self.finished {
+            self.finished = true;
+            self.encoder.finish(&mut self.buffer)?;
+        }
+        ready!(self.poll_drain(cx, &mut write_inner))?;
+        close_inner(Pin::new(&mut self.inner), cx)
+    }
+}
+
+/// An async writer that decodes a complete uuencoded file written to it, as `DecodeWriter` does.
+/// Close (shut down) the writer once all the text is written, to check that the file was complete.
+/// With the `tokio` feature this implements tokio's `AsyncWrite`, and with `futures-io`, `futures::io::AsyncWrite`.
+/// Example:
+/// ```rust
+/// # #[cfg(feature = "tokio")]
+/// async fn decode() -> std::io::Result<()> {
+///     use tokio::io::AsyncWriteExt;
+///     let mut writer = uuencode_lite::AsyncDecodeWriter::new(Vec::new(), uuencode_lite::DecodeOptions::new());
+///     writer.write_all(b"begin 644 cat.txt\n#8V%T\n`\nend\n").await?;
+///     writer.shutdown().await?;
+///     assert_eq!(writer.name(), Some("cat.txt"));
+///     assert_eq!(writer.into_inner(), b"cat");
+///     Ok(())
+/// }
+/// ```
+#[derive(Debug)]
+pub struct AsyncDecodeWriter<W> {
+    inner: W,
+    decoder: Decoder,
+    /// Decoded bytes waiting to be written to `inner`.
+    buffer: Vec<u8>,
+    /// How much of `buffer` has been written.
+    position: usize,
+    /// Whether the end of the input has been checked.
+    finished: bool,
+}
+
+impl<W: Unpin> AsyncDecodeWriter<W> {
+    /// Creates a writer that decodes a file with the given options into `inner`.
+    pub fn new(inner: W, options: DecodeOptions) -> Self {
+        AsyncDecodeWriter { inner, decoder: Decoder::new(options), buffer: Vec::new(), position: 0, finished: false }
+    }
+
+    /// The file name from the `begin` line, once it has been written.
+    pub fn name(&self) -> Option<&str> {
+        self.decoder.header().map(|(_, name)| name)
+    }
+
+    /// The permission bits from the `begin` line, once it has been written.
+    pub fn mode(&self) -> Option<u32> {
+        self.decoder.header().map(|(mode, _)| mode)
+    }
+
+    /// The inner writer.
+    pub fn get_ref(&self) -> &W {
+        &self.inner
+    }
+
+    /// Returns the inner writer. Any decoded bytes not yet written to it are lost.
+    pub fn into_inner(self) -> W {
+        self.inner
+    }
+
+    /// Writes any buffered bytes to the inner writer.
+    fn poll_drain(&mut self, cx: &mut Context<'_>, write_inner: &mut impl FnMut(Pin<&mut W>, &mut Context<'_>, &[u8]) -> Poll<io::Result<usize>>) -> Poll<io::Result<()>> {
+        ready!(poll_drain(&mut self.inner, cx, &self.buffer, &mut self.position, write_inner))?;
+        self.buffer.clear();
+        self.position = 0;
+        Poll::Ready(Ok(()))
+    }
+
+    fn poll_write_with(&mut self, cx: &mut Context<'_>, buf: &[u8], mut write_inner: impl FnMut(Pin<&mut W>, &mut Context<'_>, &[u8]) -> Poll<io::Result<usize>>) -> Poll<io::Result<usize>> {
+        ready!(self.poll_drain(cx, &mut write_inner))?;
+        let buf = &buf[..buf.len().min(READ_CHUNK)];
+        self.decoder.push(buf, &mut self.buffer)?;
+        Poll::Ready(Ok(buf.len()))
+    }
+
+    fn poll_flush_with(
+        &mut self,
+        cx: &mut Context<'_>,
+        mut write_inner: impl FnMut(Pin<&mut W>, &mut Context<'_>, &[u8]) -> Poll<io::Result<usize>>,
+        flush_inner: impl FnOnce(Pin<&mut W>, &mut Context<'_>) -> Poll<io::Result<()>>,
+    ) -> Poll<io::Result<()>> {
+        ready!(self.poll_drain(cx, &mut write_inner))?;
+        flush_inner(Pin::new(&mut self.inner), cx)
+    }
+
+    fn poll_close_with(
+        &mut self,
+        cx: &mut Context<'_>,
+        mut write_inner: impl FnMut(Pin<&mut W>, &mut Context<'_>, &[u8]) -> Poll<io::Result<usize>>,
+        close_inner: impl FnOnce(Pin<&mut W>, &mut Context<'_>) -> Poll<io::Result<()>>,
+    ) -> Poll<io::Result<()>> {
+        if !self.finished {
+            self.finished = true;
+            self.decoder.finish(&mut self.buffer)?;
+        }
+        ready!(self.poll_drain(cx, &mut write_inner))?;
+        close_inner(Pin::new(&mut self.inner), cx)
+    }
+}
+
+/// An async reader that uuencodes the data read from the inner reader as a complete file, as `EncodeReader` does.
+/// With the `tokio` feature this implements tokio's `AsyncRead`, and with `futures-io`, `futures::io::AsyncRead`.
+/// Example:
+/// ```rust
+/// # #[cfg(feature = "tokio")]
+/// async fn encode() -> std::io::Result<()> {
+///     use tokio::io::AsyncReadExt;
+///     let mut reader = uuencode_lite::AsyncEncodeReader::new(&b"cat"[..], uuencode_lite::FileOptions::new("cat.txt"))?;
+///     let mut encoded = String::new();
+///     reader.read_to_string(&mut encoded).await?;
+///     assert_eq!(encoded, "begin 644 cat.txt\n#8V%T\n`\nend\n");
+///     Ok(())
+/// }
+/// ```
+#[derive(Debug)]
+pub struct AsyncEncodeReader<R> {
+    inner: R,
+    encoder: Encoder,
+    /// Data read from `inner` but not yet encoded.
+    chunk: Box<[u8]>,
+    /// Encoded text not yet read.
+    buffer: String,
+    /// How much of `buffer` has been read.
+    position: usize,
+    /// Whether the inner reader has reached its end.
+    finished: bool,
+}
+
+impl<R: Unpin> AsyncEncodeReader<R> {
+    /// Creates a reader that encodes the data from `inner` as a file with the given options.
+    /// Fails if the file name is invalid, as `uuencode_file` does.
+    pub fn new(inner: R, options: FileOptions) -> Result<Self, UUEncodeError> {
+        Ok(AsyncEncodeReader { inner, encoder: Encoder::new(options)?, chunk: vec![0; READ_CHUNK].into(), buffer: String::new(), position: 0, finished: false })
+    }
+
+    /// Returns the inner reader.
+    pub fn into_inner(self) -> R {
+        self.inner
+    }
+
+    fn poll_read_with(&mut self, cx: &mut Context<'_>, buf: &mut [u8], mut read_inner: impl FnMut(Pin<&mut R>, &mut Context<'_>, &mut [u8]) -> Poll<io::Result<usize>>) -> Poll<io::Result<usize>> {
+        while self.position == self.buffer.len() && !self.finished {
+            self.buffer.clear();
+            self.position = 0;
+            let len = ready!(poll_fill(&mut self.inner, cx, &mut self.chunk, &mut read_inner))?;
+            if len == 0 {
+                self.finished = true;
+                self.encoder.finish(&mut self.buffer)?;
+            } else {
+                self.encoder.push(&self.chunk[..len], &mut self.buffer)?;
+            }
+        }
+        let pending = &self.buffer.as_bytes()[self.position..];
+        let len = pending.len().min(buf.len());
+        buf[..len].copy_from_slice(&pending[..len]);
+        self.position += len;
+        Poll::Ready(Ok(len))
+    }
+}
+
+/// An async reader that decodes a complete uuencoded file from the inner reader, as `DecodeReader` does.
+/// Reading from the inner reader stops once the end of the file has been decoded.
+/// With the `tokio` feature this implements tokio's `AsyncRead`, and with `futures-io`, `futures::io::AsyncRead`.
+/// Example:
+/// ```rust
+/// # #[cfg(feature = "tokio")]
+/// async fn decode() -> std::io::Result<()> {
+///     use tokio::io::AsyncReadExt;
+///     let mut reader = uuencode_lite::AsyncDecodeReader::new(&b"begin 644 cat.txt\n#8V%T\n`\nend\n"[..], uuencode_lite::DecodeOptions::new());
+///     let mut decoded = Vec::new();
+///     reader.read_to_end(&mut decoded).await?;
+///     assert_eq!(decoded, b"cat");
+///     Ok(())
+/// }
+/// ```
+#[derive(Debug)]
+pub struct AsyncDecodeReader<R> {
+    inner: R,
+    decoder: Decoder,
+    /// Text read from `inner` but not yet decoded.
+    chunk: Box<[u8]>,
+    /// Decoded bytes not yet read.
+    buffer: Vec<u8>,
+    /// How much of `buffer` has been read.
+    position: usize,
+    /// Whether the end of the file has been decoded and checked.
+    finished: bool,
+}
+
+impl<R: Unpin> AsyncDecodeReader<R> {
+    /// Creates a reader that decodes a file with the given options from `inner`.
+    pub fn new(inner: R, options: DecodeOptions) -> Self {
+        AsyncDecodeReader { inner, decoder: Decoder::new(options), chunk: vec![0; READ_CHUNK].into(), buffer: Vec::new(), position: 0, finished: false }
+    }
+
+    /// The file name from the `begin` line, once it has been read.
+    pub fn name(&self) -> Option<&str> {
+        self.decoder.header().map(|(_, name)| name)
+    }
+
+    /// The permission bits from the `begin` line, once it has been read.
+    pub fn mode(&self) -> Option<u32> {
+        self.decoder.header().map(|(mode, _)| mode)
+    }
+
+    /// Returns the inner reader.
+    pub fn into_inner(self) -> R {
+        self.inner
+    }
+
+    fn poll_read_with(&mut self, cx: &mut Context<'_>, buf: &mut [u8], mut read_inner: impl FnMut(Pin<&mut R>, &mut Context<'_>, &mut [u8]) -> Poll<io::Result<usize>>) -> Poll<io::Result<usize>> {
+        while self.position == self.buffer.len() && !self.finished {
+            self.buffer.clear();
+            self.position = 0;
+            let len = if self.decoder.is_done() { 0 } else { ready!(poll_fill(&mut self.inner, cx, &mut self.chunk, &mut read_inner))? };
+            if len == 0 {
+                self.finished = true;
+                self.decoder.finish(&mut self.buffer)?;
+            } else {
+                self.decoder.push(&self.chunk[..len], &mut self.buffer)?;
+            }
+        }
+        let pending = &self.buffer[self.position..];
+        let len = pending.len().min(buf.len());
+        buf[..len].copy_from_slice(&pending[..len]);
+        self.position += len;
+        Poll::Ready(Ok(len))
+    }
+}
+
+#[cfg(feature = "tokio")]
+mod tokio_impls {
+    use super::*;
+    use tokio::io::{AsyncRead, AsyncWrite, ReadBuf};
+
+    fn read<R: AsyncRead>(inner: Pin<&mut R>, cx: &mut Context<'_>, chunk: &mut [u8]) -> Poll<io::Result<usize>> {
+        let mut buf = ReadBuf::new(chunk);
+        ready!(inner.poll_read(cx, &mut buf))?;
+        Poll::Ready(Ok(buf.filled().len()))
+    }
+
+    fn write<W: AsyncWrite>(inner: Pin<&mut W>, cx: &mut Context<'_>, data: &[u8]) -> Poll<io::Result<usize>> {
+        inner.poll_write(cx, data)
+    }
+
+    impl<R: AsyncRead + Unpin> AsyncRead for AsyncEncodeReader<R> {
+        fn poll_read(self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &mut ReadBuf<'_>) -> Poll<io::Result<()>> {
+            let len = ready!(self.get_mut().poll_read_with(cx, buf.initialize_unfilled(), read))?;
+            buf.advance(len);
+            Poll::Ready(Ok(()))
+        }
+    }
+
+    impl<R: AsyncRead + Unpin> AsyncRead for AsyncDecodeReader<R> {
+        fn poll_read(self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &mut ReadBuf<'_>) -> Poll<io::Result<()>> {
+            let len = ready!(self.get_mut().poll_read_with(cx, buf.initialize_unfilled(), read))?;
+            buf.advance(len);
+            Poll::Ready(Ok(()))
+        }
+    }
+
+    impl<W: AsyncWrite + Unpin> AsyncWrite for AsyncEncodeWriter<W> {
+        fn poll_write(self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &[u8]) -> Poll<io::Result<usize>> {
+            self.get_mut().poll_write_with(cx, buf, write)
+        }
+
+        fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+            self.get_mut().poll_flush_with(cx, write, W::poll_flush)
+        }
+
+        fn poll_shutdown(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+            self.get_mut().poll_close_with(cx, write, W::poll_shutdown)
+        }
+    }
+
+    impl<W: AsyncWrite + Unpin> AsyncWrite for AsyncDecodeWriter<W> {
+        fn poll_write(self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &[u8]) -> Poll<io::Result<usize>> {
+            self.get_mut().poll_write_with(cx, buf, write)
+        }
+
+        fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+            self.get_mut().poll_flush_with(cx, write, W::poll_flush)
+        }
+
+        fn poll_shutdown(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+            self.get_mut().poll_close_with(cx, write, W::poll_shutdown)
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+        use crate::{uuencode_file, Checksum};
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        /// Tests round-tripping a file through the tokio adapters, over a small in-memory pipe
+        #[tokio::test]
+        async fn test_tokio_rt() {
+            let source_data = std::fs::read("test_data/the_machine_stops.txt").expect("Can open test data");
+            let options = FileOptions::new("the_machine_stops.txt").trailer(Some(Checksum::Crc32));
+            let expected = uuencode_file(&source_data, &options).unwrap();
+
+            let (client, mut server) = tokio::io::duplex(64);
+            let send = {
+                let source_data = source_data.clone();
+                let options = options.clone();
+                tokio::spawn(async move {
+                    let mut writer = AsyncEncodeWriter::new(client, options).unwrap();
+                    for chunk in source_data.chunks(1000) {
+                        writer.write_all(chunk).await.unwrap();
+                    }
+                    writer.shutdown().await.unwrap();
+                })
+            };
+            let mut encoded = String::new();
+            server.read_to_string(&mut encoded).await.unwrap();
+            send.await.unwrap();
+            assert_eq!(encoded, expected, "can encode to an async writer");
+
+            let mut reader = AsyncEncodeReader::new(&source_data[..], options).unwrap();
+            let mut encoded = String::new();
+            reader.read_to_string(&mut encoded).await.unwrap();
+            assert_eq!(encoded, expected, "can encode from an async reader");
+
+            let decode_options = DecodeOptions::new().verify_trailer(true);
+            let mut writer = AsyncDecodeWriter::new(Vec::new(), decode_options.clone());
+            for chunk in expected.as_bytes().chunks(1000) {
+                writer.write_all(chunk).await.unwrap();
+            }
+            writer.shutdown().await.unwrap();
+            assert_eq!(writer.into_inner(), source_data, "can decode to an async writer");
+
+            let mut reader = AsyncDecodeReader::new(expected.as_bytes(), decode_options.clone());
+            let mut decoded = Vec::new();
+            reader.read_to_end(&mut decoded).await.unwrap();
+            assert_eq!(decoded, source_data, "can decode from an async reader");
+
+            let mut reader = AsyncDecodeReader::new(&expected.as_bytes()[..1000], decode_options);
+            let err = reader.read_to_end(&mut Vec::new()).await.unwrap_err();
+            assert_eq!(err.kind(), io::ErrorKind::InvalidData, "reports truncated input");
+        }
+    }
+}
+
+#[cfg(feature = "futures-io")]
+mod futures_impls {
+    use super::*;
+    use futures_io::{AsyncRead, AsyncWrite};
+
+    impl<R: AsyncRead + Unpin> AsyncRead for AsyncEncodeReader<R> {
+        fn poll_read(self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &mut [u8]) -> Poll<io::Result<usize>> {
+            self.get_mut().poll_read_with(cx, buf, R::poll_read)
+        }
+    }
+
+    impl<R: AsyncRead + Unpin> AsyncRead for AsyncDecodeReader<R> {
+        fn poll_read(self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &mut [u8]) -> Poll<io::Result<usize>> {
+            self.get_mut().poll_read_with(cx, buf, R::poll_read)
+        }
+    }
+
+    impl<W: AsyncWrite + Unpin> AsyncWrite for AsyncEncodeWriter<W> {
+        fn poll_write(self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &[u8]) -> Poll<io::Result<usize>> {
+            self.get_mut().poll_write_with(cx, buf, W::poll_write)
+        }
+
+        fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+            self.get_mut().poll_flush_with(cx, W::poll_write, W::poll_flush)
+        }
+
+        fn poll_close(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+            self.get_mut().poll_close_with(cx, W::poll_write, W::poll_close)
+        }
+    }
+
+    impl<W: AsyncWrite + Unpin> AsyncWrite for AsyncDecodeWriter<W> {
+        fn poll_write(self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &[u8]) -> Poll<io::Result<usize>> {
+            self.get_mut().poll_write_with(cx, buf, W::poll_write)
+        }
+
+        fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+            self.get_mut().poll_flush_with(cx, W::poll_write, W::poll_flush)
+        }
+
+        fn poll_close(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+            self.get_mut().poll_close_with(cx, W::poll_write, W::poll_close)
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+        use crate::{uuencode_file, Checksum};
+        use futures::io::{AsyncReadExt, AsyncWriteExt, Cursor};
+
+        /// Tests round-tripping a file through the futures-io adapters
+        #[test]
+        fn test_futures_io_rt() {
+            let source_data = std::fs::read("test_data/the_machine_stops.txt").expect("Can open test data");
+            let options = FileOptions::new("the_machine_stops.txt").trailer(Some(Checksum::Crc32));
+            let expected = uuencode_file(&source_data, &options).unwrap();
+            let decode_options = DecodeOptions::new().verify_trailer(true);
+            futures::executor::block_on(async {
+                let mut writer = AsyncEncodeWriter::new(Cursor::new(Vec::new()), options.clone()).unwrap();
+                for chunk in source_data.chunks(1000) {
+                    writer.write_all(chunk).await.unwrap();
+                }
+                writer.close().await.unwrap();
+                assert_eq!(writer.into_inner().into_inner(), expected.as_bytes(), "can encode to an async writer");
+
+                let mut reader = AsyncEncodeReader::new(&source_data[..], options).unwrap();
+                let mut encoded = String::new();
+                reader.read_to_string(&mut encoded).await.unwrap();
+                assert_eq!(encoded, expected, "can encode from an async reader");
+
+                let mut writer = AsyncDecodeWriter::new(Cursor::new(Vec::new()), decode_options.clone());
+                for chunk in expected.as_bytes().chunks(1000) {
+                    writer.write_all(chunk).await.unwrap();
+                }
+                writer.close().await.unwrap();
+                assert_eq!(writer.into_inner().into_inner(), source_data, "can decode to an async writer");
+
+                let mut reader = AsyncDecodeReader::new(expected.as_bytes(), decode_options);
+                let mut decoded = Vec::new();
+                reader.read_to_end(&mut decoded).await.unwrap();
+                assert_eq!(decoded, source_data, "can decode from an async reader");
+            });
+        }
+
+        /// Tests that the async writers, like the blocking ones, take writes of any size without allocating once
+        /// warmed up
+        #[test]
+        fn test_steady_state_allocations() {
+            use crate::tests::count_allocations;
+            use futures::executor::block_on;
+            let source_data = std::fs::read("test_data/the_machine_stops.txt").expect("Can open test data");
+            let source_data = &source_data[..source_data.len() / 45 * 45];
+            let large = source_data.repeat(16);
+            let body = crate::uuencode(source_data).unwrap() + "\n";
+            let large_body = body.repeat(16);
+            let mut writer = AsyncEncodeWriter::new(futures::io::sink(), FileOptions::new("the_machine_stops.txt")).unwrap();
+            block_on(writer.write_all(source_data)).unwrap();
+            let (write, allocations) = count_allocations(|| block_on(writer.write_all(&large)));
+            write.unwrap();
+            assert_eq!(allocations, 0, "encodes to an async writer without allocating");
+
+            let mut writer = AsyncDecodeWriter::new(futures::io::sink(), DecodeOptions::new());
+            block_on(writer.write_all(b"begin 644 the_machine_stops.txt\n")).unwrap();
+            block_on(writer.write_all(body.as_bytes())).unwrap();
+            let (write, allocations) = count_allocations(|| block_on(writer.write_all(large_body.as_bytes())));
+            write.unwrap();
+            assert_eq!(allocations, 0, "decodes to an async writer without allocating");
+        }
+    }
+}
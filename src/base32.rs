@@ -0,0 +1,179 @@
+use alloc::string::String;
+use alloc::vec::Vec;
+use crate::{Message, UUEncodeError};
+
+/// The standard Base32 alphabet from RFC 4648.
+const ALPHABET: &[u8; 32] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZ234567";
+
+/// The "Extended Hex" Base32 alphabet from RFC 4648, which preserves sort order.
+const HEX_ALPHABET: &[u8; 32] = b"0123456789ABCDEFGHIJKLMNOPQRSTUV";
+
+/// The Base16 alphabet from RFC 4648.
+const BASE16_ALPHABET: &[u8; 16] = b"0123456789ABCDEF";
+
+/// Encodes 5-byte groups as 8 characters from `alphabet`, padding the final group with `=`.
+fn encode_with(data: &[u8], alphabet: &[u8; 32]) -> String {
+    let mut encoded = String::with_capacity(data.len().div_ceil(5) * 8);
+    for chunk in data.chunks(5) {
+        let mut buffer = [0u8; 8];
+        buffer[3..3 + chunk.len()].copy_from_slice(chunk);
+        let value = u64::from_be_bytes(buffer);
+        // a group of n bytes carries ceil(n * 8 / 5) characters of data
+        let used = (chunk.len() * 8).div_ceil(5);
+        for idx in 0..8 {
+            encoded.push(if idx < used { alphabet[((value >> (35 - idx * 5)) & 0x1F) as usize] as char } else { '=' });
+        }
+    }
+    encoded
+}
+
+/// Decodes Base32 text using `alphabet`. Padding is optional, but must be correct if present.
+fn decode_with(data: &[u8], alphabet: &[u8; 32]) -> Result<Vec<u8>, UUEncodeError> {
+    let padding_start = data.iter().position(|&ch| ch == b'=').unwrap_or(data.len());
+    if padding_start < data.len() && (!data.len().is_multiple_of(8) || data[padding_start..].iter().any(|&ch| ch != b'=')) {
+        return Err(UUEncodeError::new(0, padding_start, "Invalid padding in input"));
+    }
+    let mut decoded = Vec::with_capacity(data.len() / 8 * 5 + 4);
+    for (group_idx, chunk) in data[..padding_start].chunks(8).enumerate() {
+        // a partial group of 2, 4, 5 or 7 characters holds 1, 2, 3 or 4 bytes
+        let len = match chunk.len() {
+            8 => 5,
+            2 => 1,
+            4 => 2,
+            5 => 3,
+            7 => 4,
+            _ => return Err(UUEncodeError::new(0, group_idx * 8 + chunk.len(), "Incomplete Base32 group at end of input")),
+        };
+        let mut value = 0u64;
+        for (idx, &ch) in chunk.iter().enumerate() {
+            let Some(digit) = alphabet.iter().position(|&digit| digit == ch) else {
+                return Err(UUEncodeError::new(0, group_idx * 8 + idx, Message::InvalidCharacter(ch)));
+            };
+            value |= (digit as u64) << (35 - idx * 5);
+        }
+        decoded.extend_from_slice(&value.to_be_bytes()[3..3 + len]);
+    }
+    Ok(decoded)
+}
+
+/// Encodes the input data as Base32 (RFC 4648), with padding.
+/// Example:
+/// ```rust
+/// assert_eq!(uuencode_lite::base32_encode(b"foobar"), "MZXW6YTBOI======");
+/// ```
+pub fn base32_encode(data: &[u8]) -> String {
+    encode_with(data, ALPHABET)
+}
+
+/// Decodes Base32 (RFC 4648) text. Padding is optional, but no whitespace is allowed.
+/// Example:
+/// ```rust
+/// fn decode() -> Result<(), uuencode_lite::UUEncodeError> {
+///     assert_eq!(uuencode_lite::base32_decode(b"MZXW6YTBOI======")?, b"foobar");
+///     assert_eq!(uuencode_lite::base32_decode(b"MZXW6YTBOI")?, b"foobar");
+///     Ok(())
+/// }
+/// ```
+pub fn base32_decode(data: &[u8]) -> Result<Vec<u8>, UUEncodeError> {
+    decode_with(data, ALPHABET)
+}
+
+/// Encodes the input data as Base32 with the "Extended Hex" alphabet (RFC 4648), with padding.
+/// Example:
+/// ```rust
+/// assert_eq!(uuencode_lite::base32hex_encode(b"foobar"), "CPNMUOJ1E8======");
+/// ```
+pub fn base32hex_encode(data: &[u8]) -> String {
+    encode_with(data, HEX_ALPHABET)
+}
+
+/// Decodes Base32 text with the "Extended Hex" alphabet (RFC 4648). Padding is optional, but no whitespace is allowed.
+/// Example:
+/// ```rust
+/// fn decode() -> Result<(), uuencode_lite::UUEncodeError> {
+///     assert_eq!(uuencode_lite::base32hex_decode(b"CPNMUOJ1E8======")?, b"foobar");
+///     Ok(())
+/// }
+/// ```
+pub fn base32hex_decode(data: &[u8]) -> Result<Vec<u8>, UUEncodeError> {
+    decode_with(data, HEX_ALPHABET)
+}
+
+/// Encodes the input data as Base16 (RFC 4648), using uppercase hex digits.
+/// Example:
+/// ```rust
+/// assert_eq!(uuencode_lite::base16_encode(b"cat"), "636174");
+/// ```
+pub fn base16_encode(data: &[u8]) -> String {
+    let mut encoded = String::with_capacity(data.len() * 2);
+    for &byte in data {
+        encoded.push(BASE16_ALPHABET[(byte >> 4) as usize] as char);
+        encoded.push(BASE16_ALPHABET[(byte & 0xF) as usize] as char);
+    }
+    encoded
+}
+
+/// Decodes Base16 (RFC 4648) text. Lowercase hex digits are accepted, but no whitespace is allowed.
+/// Example:
+/// ```rust
+/// fn decode() -> Result<(), uuencode_lite::UUEncodeError> {
+///     assert_eq!(uuencode_lite::base16_decode(b"636174")?, b"cat");
+///     Ok(())
+/// }
+/// ```
+pub fn base16_decode(data: &[u8]) -> Result<Vec<u8>, UUEncodeError> {
+    if !data.len().is_multiple_of(2) {
+        return Err(UUEncodeError::new(0, data.len(), "Incomplete Base16 group at end of input"));
+    }
+    let mut decoded = Vec::with_capacity(data.len() / 2);
+    for (idx, pair) in data.chunks_exact(2).enumerate() {
+        let mut value = 0u8;
+        for (offset, &ch) in pair.iter().enumerate() {
+            let Some(digit) = (ch as char).to_digit(16) else {
+                return Err(UUEncodeError::new(0, idx * 2 + offset, Message::InvalidCharacter(ch)));
+            };
+            value = (value << 4) | digit as u8;
+        }
+        decoded.push(value);
+    }
+    Ok(decoded)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Tests the RFC 4648 test vectors
+    #[test]
+    fn test_base32_vectors() {
+        let vectors = [
+            ("", "", "", ""),
+            ("f", "MY======", "CO======", "66"),
+            ("fo", "MZXQ====", "CPNG====", "666F"),
+            ("foo", "MZXW6===", "CPNMU===", "666F6F"),
+            ("foob", "MZXW6YQ=", "CPNMUOG=", "666F6F62"),
+            ("fooba", "MZXW6YTB", "CPNMUOJ1", "666F6F6261"),
+            ("foobar", "MZXW6YTBOI======", "CPNMUOJ1E8======", "666F6F626172"),
+        ];
+        for (plain, base32, base32hex, base16) in vectors {
+            assert_eq!(base32_encode(plain.as_bytes()), base32, "can base32 encode {:?}", plain);
+            assert_eq!(base32_decode(base32.as_bytes()).unwrap(), plain.as_bytes(), "can base32 decode {:?}", base32);
+            assert_eq!(base32hex_encode(plain.as_bytes()), base32hex, "can base32hex encode {:?}", plain);
+            assert_eq!(base32hex_decode(base32hex.as_bytes()).unwrap(), plain.as_bytes(), "can base32hex decode {:?}", base32hex);
+            assert_eq!(base16_encode(plain.as_bytes()), base16, "can base16 encode {:?}", plain);
+            assert_eq!(base16_decode(base16.as_bytes()).unwrap(), plain.as_bytes(), "can base16 decode {:?}", base16);
+        }
+    }
+
+    /// Tests rejection of malformed input
+    #[test]
+    fn test_base32_errors() {
+        assert!(base32_decode(b"MZXW6YTBO").is_err(), "rejects an impossible group length");
+        assert!(base32_decode(b"MY=====").is_err(), "rejects short padding");
+        assert!(base32_decode(b"MY==MY==").is_err(), "rejects data after padding");
+        assert_eq!(base32_decode(b"MZX1").unwrap_err().character, 3, "reports the position of an invalid character");
+        assert_eq!(base16_decode(b"6g").unwrap_err().character, 1, "reports the position of an invalid hex digit");
+        assert!(base16_decode(b"636").is_err(), "rejects an odd length");
+        assert_eq!(base16_decode(b"6f").unwrap(), b"o", "accepts lowercase hex");
+    }
+}
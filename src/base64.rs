@@ -0,0 +1,235 @@
+use alloc::string::String;
+use alloc::vec::Vec;
+use crate::checkpoint::{CheckpointReader, CheckpointWriter};
+use crate::file::numbered_lines;
+use crate::{push_encoded, Message, Table, UUEncodeError};
+
+/// Encodes the input data as standard Base64 with padding, appending to `encoded`.
+pub(crate) fn encode_into(data: &[u8], encoded: &mut String) {
+    encode_into_with(data, &Table::BASE64, Some(b'='), encoded);
+}
+
+/// Encodes the input data as Base64 with the given alphabet, padding the final group if `padding` is set.
+/// The padding must be ASCII, as `push_encoded` relies on it with `unsafe-fast`; `Engine::new` checks it.
+pub(crate) fn encode_into_with(data: &[u8], table: &Table, padding: Option<u8>, encoded: &mut String) {
+    // groups are collected on the stack and appended a block at a time
+    let mut block = [0u8; 256];
+    let mut len = 0;
+    for chunk in data.chunks(3) {
+        if len + 4 > block.len() {
+            push_encoded(encoded, &block[..len]);
+            len = 0;
+        }
+        let mut buffer = [0u8; 3];
+        buffer[..chunk.len()].copy_from_slice(chunk);
+        let quad = [
+            table.encode(buffer[0] >> 2),
+            table.encode((buffer[0] << 4) | (buffer[1] >> 4)),
+            table.encode((buffer[1] << 2) | (buffer[2] >> 6)),
+            table.encode(buffer[2]),
+        ];
+        for (idx, &ch) in quad.iter().enumerate() {
+            match padding {
+                _ if idx <= chunk.len() => block[len] = ch,
+                Some(padding) => block[len] = padding,
+                None => continue,
+            }
+            len += 1;
+        }
+    }
+    push_encoded(encoded, &block[..len]);
+}
+
+/// The longest encoded line allowed by RFC 2045.
+const MIME_LINE_LEN: usize = 76;
+
+/// Encodes the input data as MIME Base64 (RFC 2045), with padding and lines of 76 characters ending in CRLF.
+/// Example:
+/// ```rust
+/// assert_eq!(uuencode_lite::base64_encode(b"cat"), "Y2F0\r\n");
+/// ```
+pub fn base64_encode(data: &[u8]) -> String {
+    let line_bytes = MIME_LINE_LEN / 4 * 3;
+    let mut encoded = String::with_capacity(data.len().div_ceil(3) * 4 + data.len().div_ceil(line_bytes) * 2);
+    for line in data.chunks(line_bytes) {
+        encode_into(line, &mut encoded);
+        encoded.push_str("\r\n");
+    }
+    encoded
+}
+
+/// Decodes MIME Base64 (RFC 2045) text, such as a `Content-Transfer-Encoding: base64` body.
+/// Lines may be any length, and spaces, tabs and line endings are ignored wherever they appear.
+/// Errors report the line and character of the offending input.
+/// Example:
+/// ```rust
+/// fn decode() -> Result<(), uuencode_lite::UUEncodeError> {
+///     let decoded = uuencode_lite::base64_decode(b"Y2 F0\n")?;
+///     assert_eq!(decoded, b"cat");
+///     Ok(())
+/// }
+/// ```
+pub fn base64_decode(data: &[u8]) -> Result<Vec<u8>, UUEncodeError> {
+    let mut decoder = Base64Decoder::default();
+    let mut decoded = Vec::with_capacity(data.len() / 4 * 3);
+    let mut last_line = 0;
+    for (cur_line, line) in numbered_lines(data) {
+        decoder.push_line(line, cur_line, b" \t", &mut decoded)?;
+        last_line = cur_line;
+    }
+    decoder.finish(last_line, &mut decoded)?;
+    Ok(decoded)
+}
+
+/// Incrementally decodes Base64 text, which may be split across lines at any point.
+#[derive(Debug, Clone)]
+pub(crate) struct Base64Decoder {
+    /// The alphabet being decoded.
+    table: Table,
+    /// The padding character, if the final group must be padded.
+    padding: Option<u8>,
+    /// The characters of the current, incomplete quadruple.
+    quad: [u8; 4],
+    /// How many characters of `quad` are filled.
+    filled: usize,
+    /// How many padding characters have been seen, which must end the data.
+    padded: usize,
+}
+
+impl Default for Base64Decoder {
+    fn default() -> Self {
+        Base64Decoder::new(Table::BASE64, Some(b'='))
+    }
+}
+
+impl Base64Decoder {
+    /// Creates a decoder for the given alphabet. Without a padding character, the final group may be left short.
+    pub(crate) fn new(table: Table, padding: Option<u8>) -> Self {
+        Base64Decoder { table, padding, quad: [0; 4], filled: 0, padded: 0 }
+    }
+
+    /// Decodes one line of Base64 text, appending any completed bytes to `decoded`.
+    /// Characters in `ignore` (such as whitespace) are skipped.
+    pub(crate) fn push_line(&mut self, line: &[u8], cur_line: usize, ignore: &[u8], decoded: &mut Vec<u8>) -> Result<(), UUEncodeError> {
+        for (cur_char, &ch) in line.iter().enumerate() {
+            if ignore.contains(&ch) {
+                continue;
+            }
+            let value = if Some(ch) == self.padding {
+                // padding may only fill the last one or two characters of a quadruple
+                if self.filled < 2 {
+                    return Err(UUEncodeError::new(cur_line, cur_char, "Unexpected padding in input"));
+                }
+                self.padded += 1;
+                0
+            } else if self.padded > 0 {
+                return Err(UUEncodeError::new(cur_line, cur_char, "Data after padding in input"));
+            } else {
+                match self.table.decode(ch) {
+                    Some(value) => value,
+                    None => return Err(UUEncodeError::new(cur_line, cur_char, Message::InvalidCharacter(ch))),
+                }
+            };
+            self.quad[self.filled] = value;
+            self.filled += 1;
+            if self.filled == 4 {
+                self.flush(decoded);
+            }
+        }
+        Ok(())
+    }
+
+    /// Decodes the current quadruple, which holds `filled - padded - 1` bytes.
+    fn flush(&mut self, decoded: &mut Vec<u8>) {
+        let bytes = [
+            (self.quad[0] << 2) | (self.quad[1] >> 4),
+            (self.quad[1] << 4) | (self.quad[2] >> 2),
+            (self.quad[2] << 6) | self.quad[3],
+        ];
+        decoded.extend_from_slice(&bytes[..self.filled - self.padded - 1]);
+        self.quad = [0; 4];
+        self.filled = 0;
+    }
+
+    /// Writes the decoder's state to a checkpoint.
+    pub(crate) fn save(&self, writer: &mut CheckpointWriter) {
+        writer.table(&self.table);
+        writer.bool(self.padding.is_some());
+        writer.u8(self.padding.unwrap_or(0));
+        self.quad.iter().for_each(|&value| writer.u8(value));
+        writer.u8(self.filled as u8);
+        writer.u8(self.padded as u8);
+    }
+
+    /// Reads a decoder's state from a checkpoint.
+    pub(crate) fn load(reader: &mut CheckpointReader) -> Result<Self, UUEncodeError> {
+        let table = reader.table()?;
+        let has_padding = reader.bool()?;
+        let padding = has_padding.then_some(reader.u8()?);
+        let mut quad = [0u8; 4];
+        for value in &mut quad {
+            *value = reader.u8()?;
+        }
+        let (filled, padded) = (reader.u8()? as usize, reader.u8()? as usize);
+        if filled > 3 || padded > filled || quad.iter().any(|&value| value > 63) {
+            return Err(reader.error("Invalid Base64 state in checkpoint"));
+        }
+        Ok(Base64Decoder { table, padding, quad, filled, padded })
+    }
+
+    /// Checks that the input did not end part way through a quadruple, decoding a short final group
+    /// if there is no padding character.
+    pub(crate) fn finish(&mut self, cur_line: usize, decoded: &mut Vec<u8>) -> Result<(), UUEncodeError> {
+        match self.filled {
+            0 => {}
+            2 | 3 if self.padding.is_none() => self.flush(decoded),
+            _ => return Err(UUEncodeError::new(cur_line, 0, "Incomplete Base64 group at end of input")),
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn decode(data: &[u8]) -> Result<Vec<u8>, UUEncodeError> {
+        let mut decoder = Base64Decoder::default();
+        let mut decoded = Vec::new();
+        decoder.push_line(data, 0, b"", &mut decoded)?;
+        decoder.finish(0, &mut decoded)?;
+        Ok(decoded)
+    }
+
+    /// Tests the RFC 4648 test vectors
+    #[test]
+    fn test_base64_vectors() {
+        for (plain, encoded) in [("", ""), ("f", "Zg=="), ("fo", "Zm8="), ("foo", "Zm9v"), ("foob", "Zm9vYg=="), ("fooba", "Zm9vYmE="), ("foobar", "Zm9vYmFy")] {
+            let mut actual = String::new();
+            encode_into(plain.as_bytes(), &mut actual);
+            assert_eq!(actual, encoded, "can base64 encode {:?}", plain);
+            assert_eq!(decode(encoded.as_bytes()).unwrap(), plain.as_bytes(), "can base64 decode {:?}", encoded);
+        }
+    }
+
+    /// Tests rejection of malformed input
+    #[test]
+    fn test_base64_errors() {
+        assert!(decode(b"Zm9").is_err(), "rejects an incomplete group");
+        assert!(decode(b"Z===").is_err(), "rejects excess padding");
+        assert!(decode(b"Zg==Zm9v").is_err(), "rejects data after padding");
+        assert!(decode(b"Zm9!").is_err(), "rejects invalid characters");
+    }
+
+    /// Tests round-tripping MIME Base64 with line wrapping
+    #[test]
+    fn test_mime_base64_rt() {
+        let source_data = std::fs::read("test_data/random_data.bin").expect("Can open test data");
+        let encoded = base64_encode(&source_data);
+        assert!(encoded.split("\r\n").all(|line| line.len() <= MIME_LINE_LEN), "wraps lines at 76 characters");
+        assert_eq!(base64_decode(encoded.as_bytes()).unwrap(), source_data, "can round-trip random data");
+        assert_eq!(base64_decode(b"\tZm9v\r\n  YmFy \n\n").unwrap(), b"foobar", "ignores whitespace");
+        let err = base64_decode(b"Zm9v\nYm!y").unwrap_err();
+        assert_eq!((err.line, err.character), (1, 2), "reports the position of an invalid character");
+    }
+}
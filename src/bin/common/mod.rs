@@ -0,0 +1,157 @@
+//! Argument parsing and error reporting shared by the `uuencode` and `uudecode` binaries.
+
+// each binary uses its own subset of these
+#![allow(dead_code)]
+
+pub mod progress;
+
+use std::collections::VecDeque;
+use std::ffi::OsString;
+use std::io::{self, Read, Write};
+
+/// One command-line argument, as split by `Parser`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Arg {
+    /// A single-letter option, such as `-m`. Clustered options, such as `-mo`, are split up.
+    Short(char),
+    /// A long option, such as `--base64`, without its dashes or any `=value`.
+    Long(String),
+    /// A positional argument. `-` on its own is positional, as is everything after `--`.
+    Positional(OsString),
+}
+
+/// Splits command-line arguments into options and positional arguments, the way `getopt_long` does.
+#[derive(Debug)]
+pub struct Parser {
+    args: VecDeque<OsString>,
+    /// The rest of a cluster of short options, after the one last returned.
+    cluster: VecDeque<char>,
+    /// The value given to the last long option with `=`.
+    long_value: Option<String>,
+    /// Whether `--` has been seen.
+    positional_only: bool,
+}
+
+impl Parser {
+    /// Creates a parser over the arguments, without the program name.
+    pub fn new(args: impl IntoIterator<Item = OsString>) -> Self {
+        Parser { args: args.into_iter().collect(), cluster: VecDeque::new(), long_value: None, positional_only: false }
+    }
+
+    /// The next argument. Fails if a long option has a value it does not take, or an option is not valid Unicode.
+    pub fn next_arg(&mut self) -> Result<Option<Arg>, String> {
+        if let Some(value) = self.long_value.take() {
+            return Err(format!("option does not take an argument: {:?}", value));
+        }
+        if let Some(ch) = self.cluster.pop_front() {
+            return Ok(Some(Arg::Short(ch)));
+        }
+        let Some(arg) = self.args.pop_front() else {
+            return Ok(None);
+        };
+        if self.positional_only || arg == "-" || !arg.to_string_lossy().starts_with('-') {
+            return Ok(Some(Arg::Positional(arg)));
+        }
+        let arg = arg.into_string().map_err(|arg| format!("invalid option: {}", arg.to_string_lossy()))?;
+        if arg == "--" {
+            self.positional_only = true;
+            return self.next_arg();
+        }
+        if let Some(long) = arg.strip_prefix("--") {
+            let (name, value) = long.split_once('=').map_or((long, None), |(name, value)| (name, Some(value.to_string())));
+            self.long_value = value;
+            return Ok(Some(Arg::Long(name.to_string())));
+        }
+        self.cluster = arg.chars().skip(1).collect();
+        Ok(self.cluster.pop_front().map(Arg::Short))
+    }
+
+    /// The value of the option just returned: the rest of its cluster, the part after `=`, or the next argument.
+    pub fn value(&mut self, option: &str) -> Result<OsString, String> {
+        if let Some(value) = self.long_value.take() {
+            return Ok(value.into());
+        }
+        if !self.cluster.is_empty() {
+            return Ok(self.cluster.drain(..).collect::<String>().into());
+        }
+        self.args.pop_front().ok_or_else(|| format!("option requires an argument -- '{}'", option))
+    }
+}
+
+/// Reports a usage error the way GNU tools do, returning the exit status for it.
+pub fn usage_error(program: &str, msg: &str, stderr: &mut dyn Write) -> i32 {
+    let _ = writeln!(stderr, "{}: {}\nTry '{} --help' for more information.", program, msg, program);
+    1
+}
+
+/// Reports an error, returning the exit status for it.
+pub fn error(program: &str, msg: impl std::fmt::Display, stderr: &mut dyn Write) -> i32 {
+    let _ = writeln!(stderr, "{}: {}", program, msg);
+    1
+}
+
+/// The exit status when the reader of standard output goes away, as `head` does once it has enough.
+/// Rust ignores `SIGPIPE`, so the write fails instead; the tools then stop quietly with the status a shell
+/// reports for a C program killed by the signal.
+pub const BROKEN_PIPE_STATUS: i32 = 128 + 13;
+
+/// Which side of a `copy` failed.
+#[derive(Debug)]
+pub enum CopyError {
+    /// Reading, which includes encoding and decoding.
+    Read(io::Error),
+    /// Writing.
+    Write(io::Error),
+}
+
+/// Copies everything from the reader to the writer, as `io::copy` does, but reports which side failed so the
+/// error can name the right file. Data is passed on a buffer at a time, so memory use stays constant however long
+/// the stream is, and output starts as soon as the first input arrives.
+pub fn copy(reader: &mut dyn Read, writer: &mut dyn Write) -> Result<u64, CopyError> {
+    let mut buf = vec![0u8; 64 * 1024];
+    let mut copied = 0;
+    loop {
+        let len = match reader.read(&mut buf) {
+            Ok(0) => break,
+            Ok(len) => len,
+            Err(err) if err.kind() == io::ErrorKind::Interrupted => continue,
+            Err(err) => return Err(CopyError::Read(err)),
+        };
+        writer.write_all(&buf[..len]).map_err(CopyError::Write)?;
+        copied += len as u64;
+    }
+    writer.flush().map_err(CopyError::Write)?;
+    Ok(copied)
+}
+
+/// Reports an I/O error on the given file, returning the exit status for it. A broken pipe is not reported.
+pub fn io_error(program: &str, file: &str, err: &io::Error, stderr: &mut dyn Write) -> i32 {
+    if err.kind() == io::ErrorKind::BrokenPipe {
+        return BROKEN_PIPE_STATUS;
+    }
+    error(program, format_args!("{}: {}", file, describe(err)), stderr)
+}
+
+/// Describes an I/O error as `strerror` would, without the `(os error N)` suffix Rust adds.
+pub fn describe(err: &io::Error) -> String {
+    let msg = err.to_string();
+    let suffix = err.raw_os_error().map(|code| format!(" (os error {})", code));
+    suffix.as_deref().and_then(|suffix| msg.strip_suffix(suffix)).map_or_else(|| msg.clone(), str::to_string)
+}
+
+/// The name used for a file argument in messages: `standard input` for `-`.
+pub fn display_name(path: &std::ffi::OsStr) -> std::borrow::Cow<'_, str> {
+    if path == "-" { "standard input".into() } else { path.to_string_lossy() }
+}
+
+/// Prints the usage text, as `--help` does.
+pub fn help(usage: &str, stdout: &mut dyn Write) -> i32 {
+    let _ = stdout.write_all(usage.as_bytes());
+    0
+}
+
+/// Prints the version, as `--version` does.
+pub fn version(program: &str, stdout: &mut dyn Write) -> i32 {
+    let _ = writeln!(stdout, "{} (uuencode_lite) {}", program, env!("CARGO_PKG_VERSION"));
+    0
+}
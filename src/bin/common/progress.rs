@@ -0,0 +1,132 @@
+//! A progress bar and throughput summary for long encodes and decodes, driven by the streaming adapters'
+//! `on_progress` callbacks.
+
+use std::io::{self, IsTerminal, Write};
+use std::sync::{Arc, Mutex, PoisonError};
+use std::time::{Duration, Instant};
+use uuencode_lite::Progress;
+
+/// How many input bytes pass between progress callbacks.
+pub const INTERVAL: u64 = 256 * 1024;
+
+/// How long a run must take before the bar appears when it wasn't asked for, so that quick runs stay silent.
+const DELAY: Duration = Duration::from_secs(1);
+
+/// How often the bar is redrawn.
+const REDRAW: Duration = Duration::from_millis(100);
+
+/// The number of characters in the bar itself.
+const BAR_WIDTH: usize = 30;
+
+/// When progress is shown, as chosen by `--progress` and `--no-progress`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Show {
+    /// A bar on a terminal for runs that take more than a moment, followed by a summary if it was drawn.
+    Auto,
+    /// A bar on a terminal, and a summary in any case.
+    Always,
+    /// Nothing.
+    Never,
+}
+
+/// What the callback shares with the meter.
+#[derive(Debug)]
+struct State {
+    progress: Progress,
+    /// When the bar was last drawn, if it has been.
+    drawn: Option<Instant>,
+}
+
+/// Tracks the progress of one input, drawing a bar on standard error while it runs and summarising it at the end.
+#[derive(Debug)]
+pub struct Meter {
+    show: Show,
+    /// The length of the input, if it is known, for the percentage and the time left.
+    total: Option<u64>,
+    start: Instant,
+    state: Arc<Mutex<State>>,
+}
+
+impl Meter {
+    /// Creates a meter for an input of the given length, if that is known.
+    pub fn new(show: Show, total: Option<u64>) -> Self {
+        Meter { show, total, start: Instant::now(), state: Arc::new(Mutex::new(State { progress: Progress::default(), drawn: None })) }
+    }
+
+    /// The callback to pass to `on_progress`, with `INTERVAL`. The bar is only drawn when standard error is a
+    /// terminal, so it never ends up in a log.
+    pub fn callback(&self) -> impl FnMut(Progress) + Send + 'static {
+        let (show, total, start, state) = (self.show, self.total, self.start, Arc::clone(&self.state));
+        let bar = show != Show::Never && io::stderr().is_terminal();
+        move |progress| {
+            let mut state = state.lock().unwrap_or_else(PoisonError::into_inner);
+            state.progress = progress;
+            let now = Instant::now();
+            let due = match state.drawn {
+                Some(drawn) => now - drawn >= REDRAW,
+                None => show == Show::Always || now - start >= DELAY,
+            };
+            if bar && due {
+                state.drawn = Some(now);
+                let _ = write!(io::stderr(), "\r{}", render_bar(progress, total, now - start));
+            }
+        }
+    }
+
+    /// Clears the bar, then prints the summary if it is wanted and the run succeeded.
+    pub fn finish(self, program: &str, succeeded: bool, stderr: &mut dyn Write) {
+        let state = self.state.lock().unwrap_or_else(PoisonError::into_inner);
+        if state.drawn.is_some() {
+            let _ = write!(io::stderr(), "\r\x1b[K");
+        }
+        let wanted = match self.show {
+            Show::Auto => state.drawn.is_some(),
+            Show::Always => true,
+            Show::Never => false,
+        };
+        if wanted && succeeded {
+            let _ = writeln!(stderr, "{}: {}", program, render_summary(state.progress, self.start.elapsed()));
+        }
+    }
+}
+
+/// Formats a byte count in decimal units, as `1.5 MB`.
+pub fn format_bytes(bytes: u64) -> String {
+    const UNITS: [&str; 5] = ["kB", "MB", "GB", "TB", "PB"];
+    if bytes < 1000 {
+        return format!("{} B", bytes);
+    }
+    let mut value = bytes as f64 / 1000.0;
+    let mut unit = 0;
+    while value >= 999.95 && unit < UNITS.len() - 1 {
+        value /= 1000.0;
+        unit += 1;
+    }
+    format!("{:.1} {}", value, UNITS[unit])
+}
+
+/// The rate at which `bytes` passed in `elapsed`, in megabytes a second.
+fn rate(bytes: u64, elapsed: Duration) -> f64 {
+    bytes as f64 / 1e6 / elapsed.as_secs_f64().max(1e-3)
+}
+
+/// Renders the bar for the input consumed so far: a bar, percentage and time left when the total is known,
+/// otherwise just the amount read and the rate.
+pub fn render_bar(progress: Progress, total: Option<u64>, elapsed: Duration) -> String {
+    let speed = rate(progress.consumed, elapsed);
+    match total.filter(|&total| total > 0) {
+        Some(total) => {
+            let done = progress.consumed.min(total);
+            let filled = (done as u128 * BAR_WIDTH as u128 / total as u128) as usize;
+            let bar = format!("{:=<filled$}{:<rest$}", "", if filled < BAR_WIDTH { ">" } else { "" }, rest = BAR_WIDTH - filled);
+            let left = if speed > 0.0 { ((total - done) as f64 / 1e6 / speed) as u64 } else { 0 };
+            format!("[{}] {:>3}%  {} / {}  {:.1} MB/s  ETA {}:{:02}", bar, done * 100 / total, format_bytes(done), format_bytes(total), speed, left / 60, left % 60)
+        }
+        None => format!("{} read  {:.1} MB/s", format_bytes(progress.consumed), speed),
+    }
+}
+
+/// Renders the summary of a finished run.
+pub fn render_summary(progress: Progress, elapsed: Duration) -> String {
+    format!("{} in, {} out, {:.1} s, {:.1} MB/s", format_bytes(progress.consumed), format_bytes(progress.produced), elapsed.as_secs_f64(), rate(progress.consumed, elapsed))
+}
@@ -0,0 +1,523 @@
+//! `uudecode [-o output] [file]...`: decodes uuencoded files, or standard input, writing each to the file named on
+//! its `begin` line, accepting the same options and giving the same exit statuses as the sharutils `uudecode`, so it
+//! can stand in for it in scripts. The data is streamed, so files of any size can be decoded. With `--all`, every
+//! file embedded in a mailbox or digest is extracted, and a summary of what was found is printed. Files go in the
+//! directory given with `--output-dir`, keep the mode from their `begin` line unless `--no-mode` is given, and names
+//! which would escape the directory are refused unless `--force-name` is given. `uudecode validate`
+//! checks files without decoding them anywhere.
+
+mod common;
+#[path = "uudecode/validate.rs"]
+mod validate;
+
+use common::progress::{self, Meter, Show};
+use common::{Arg, CopyError, Parser};
+use std::ffi::OsString;
+use std::fs::File;
+use std::io::{self, BufRead, BufReader, BufWriter, Read, Write};
+use std::path::PathBuf;
+use uuencode_lite::{DecodeEvent, DecodeOptions, DecodeReader, Decoder, UUEncodeError};
+
+const PROGRAM: &str = "uudecode";
+
+const USAGE: &str = "Usage: uudecode [OPTION]... [FILE]...
+  or:  uudecode validate [FILE]...
+Decode each uuencoded FILE, or standard input, into the file named on its begin line.
+
+  -o, --output-file=FILE  write the decoded file to FILE; /dev/stdout or - writes to standard output
+      --output-dir=DIR    write each file to DIR, under the name on its begin line, creating DIR if needed
+      --no-mode           leave files with the default permissions rather than the mode on the begin line
+      --force-name        accept names which are absolute or lead out of the directory with '..'; these are
+                          refused by default, as they could overwrite any file the user can write
+      --all               extract every uuencoded and base64 file found in each FILE, such as a
+                          mailbox or digest, and print a summary of them
+      --progress          print a summary of the bytes read and written and the rate after each file
+      --no-progress       never show progress, even on a terminal
+  -h, --help              display this help and exit
+  -v, --version           output version information and exit
+
+When standard error is a terminal, a progress bar is shown for files that take more than a second to
+decode, followed by the summary. With no FILE, or when FILE is -, read standard input. Exit status is 0 on success, and 1 if any file
+could not be decoded or written, or the command line was not valid. See 'uudecode validate --help' for
+checking files without writing them.
+";
+
+/// How decoded files are written.
+struct Options {
+    output: Output,
+    /// The directory files written under the name on their `begin` line go in, rather than the current one.
+    dir: Option<PathBuf>,
+    /// Whether the mode from the `begin` line is applied to files on disk.
+    apply_mode: bool,
+    /// Whether unsafe names on `begin` lines are accepted.
+    force_name: bool,
+}
+
+impl Options {
+    fn decode_options(&self) -> DecodeOptions {
+        DecodeOptions::new().raw_names(self.force_name)
+    }
+
+    /// The path to write a file to under the name from its `begin` line.
+    fn named_path(&self, name: &str) -> PathBuf {
+        match &self.dir {
+            Some(dir) => dir.join(name),
+            None => PathBuf::from(name),
+        }
+    }
+}
+
+/// Where a decoded file is written.
+enum Output {
+    /// The file named on the `begin` line.
+    Named,
+    /// Standard output.
+    Stdout,
+    /// The given path.
+    Path(PathBuf),
+}
+
+/// Runs the program with the given arguments, without the program name, returning its exit status.
+fn run(args: impl IntoIterator<Item = OsString>, stdin: &mut dyn Read, stdout: &mut dyn Write, stderr: &mut dyn Write) -> i32 {
+    let mut args = args.into_iter().peekable();
+    if args.next_if(|arg| arg == "validate").is_some() {
+        return validate::run(args, stdin, stdout, stderr);
+    }
+    let mut parser = Parser::new(args);
+    let mut options = Options { output: Output::Named, dir: None, apply_mode: true, force_name: false };
+    let mut all = false;
+    let mut show = Show::Auto;
+    let mut files = Vec::new();
+    loop {
+        let arg = match parser.next_arg() {
+            Ok(Some(arg)) => arg,
+            Ok(None) => break,
+            Err(msg) => return common::usage_error(PROGRAM, &msg, stderr),
+        };
+        match arg {
+            Arg::Short('o') => match parser.value("o") {
+                Ok(path) => options.output = Output::from(path),
+                Err(msg) => return common::usage_error(PROGRAM, &msg, stderr),
+            },
+            Arg::Long(long) if long == "output-file" => match parser.value("output-file") {
+                Ok(path) => options.output = Output::from(path),
+                Err(msg) => return common::usage_error(PROGRAM, &msg, stderr),
+            },
+            Arg::Long(long) if long == "output-dir" => match parser.value("output-dir") {
+                Ok(dir) => options.dir = Some(dir.into()),
+                Err(msg) => return common::usage_error(PROGRAM, &msg, stderr),
+            },
+            Arg::Long(long) if long == "no-mode" => options.apply_mode = false,
+            Arg::Long(long) if long == "force-name" => options.force_name = true,
+            Arg::Long(long) if long == "all" => all = true,
+            Arg::Long(long) if long == "progress" => show = Show::Always,
+            Arg::Long(long) if long == "no-progress" => show = Show::Never,
+            Arg::Short('h') => return common::help(USAGE, stdout),
+            Arg::Long(long) if long == "help" => return common::help(USAGE, stdout),
+            Arg::Short('v') => return common::version(PROGRAM, stdout),
+            Arg::Long(long) if long == "version" => return common::version(PROGRAM, stdout),
+            Arg::Positional(value) => files.push(value),
+            Arg::Short(ch) => return common::usage_error(PROGRAM, &format!("invalid option -- '{}'", ch), stderr),
+            Arg::Long(long) => return common::usage_error(PROGRAM, &format!("unrecognized option '--{}'", long), stderr),
+        }
+    }
+    let named = matches!(options.output, Output::Named);
+    if files.len() > 1 && !named {
+        return common::usage_error(PROGRAM, "an output file can only be given for a single input", stderr);
+    }
+    if all && !named {
+        return common::usage_error(PROGRAM, "an output file cannot be given with --all", stderr);
+    }
+    if options.dir.is_some() && !named {
+        return common::usage_error(PROGRAM, "an output file cannot be given with --output-dir", stderr);
+    }
+    if files.is_empty() {
+        files.push("-".into());
+    }
+    if let Some(dir) = &options.dir
+        && let Err(err) = std::fs::create_dir_all(dir)
+    {
+        return common::io_error(PROGRAM, &dir.to_string_lossy(), &err, stderr);
+    }
+    if all {
+        return extract_all(&files, &options, stdin, stdout, stderr);
+    }
+
+    let mut status = 0;
+    for path in &files {
+        let input_name = common::display_name(path);
+        let mut file = None;
+        if path != "-" {
+            match File::open(path) {
+                Ok(opened) => file = Some(opened),
+                Err(err) => {
+                    status = common::io_error(PROGRAM, &input_name, &err, stderr);
+                    continue;
+                }
+            }
+        }
+        let len = file.as_ref().and_then(|file| file.metadata().ok()).filter(|metadata| metadata.is_file()).map(|metadata| metadata.len());
+        let meter = Meter::new(show, len);
+        let input: &mut dyn Read = match &mut file {
+            Some(file) => file,
+            None => stdin,
+        };
+        let result = decode(input, &input_name, &options, stdout, &meter);
+        meter.finish(PROGRAM, result.is_ok(), stderr);
+        if let Err((file, err)) = result {
+            status = common::io_error(PROGRAM, &file, &err, stderr);
+            if status == common::BROKEN_PIPE_STATUS {
+                break;
+            }
+        }
+    }
+    status
+}
+
+impl From<OsString> for Output {
+    fn from(path: OsString) -> Self {
+        if path == "-" || path == "/dev/stdout" { Output::Stdout } else { Output::Path(path.into()) }
+    }
+}
+
+/// Decodes the first file in the input into the output, applying the mode from its `begin` line to a file on disk
+/// unless told not to, and reporting progress to the meter. Fails with the name of the file the error concerns.
+fn decode(input: &mut dyn Read, input_name: &str, options: &Options, stdout: &mut dyn Write, meter: &Meter) -> Result<(), (String, io::Error)> {
+    let read_error = |err| (input_name.to_string(), err);
+    let mut reader = DecodeReader::new(input, options.decode_options()).on_progress(progress::INTERVAL, meter.callback());
+    // the begin line has been read by the time the first decoded bytes are
+    let mut first = [0u8; 4096];
+    let len = reader.read(&mut first).map_err(read_error)?;
+    let (Some(name), Some(mode)) = (reader.name(), reader.mode()) else {
+        unreachable!("a file with data or an end has a begin line");
+    };
+    let path = match &options.output {
+        Output::Stdout => None,
+        Output::Named => Some(options.named_path(name)),
+        Output::Path(path) => Some(path.clone()),
+    };
+    let output_name = path.as_ref().map_or_else(|| "standard output".to_string(), |path| path.to_string_lossy().into_owned());
+    let mut file = match &path {
+        Some(path) => Some(File::create(path).map_err(|err| (output_name.clone(), err))?),
+        None => None,
+    };
+    let writer: &mut dyn Write = match &mut file {
+        Some(file) => file,
+        None => stdout,
+    };
+    match common::copy(&mut (&first[..len]).chain(&mut reader), writer) {
+        Ok(_) => {}
+        Err(CopyError::Read(err)) => return Err(read_error(err)),
+        Err(CopyError::Write(err)) => return Err((output_name, err)),
+    }
+    match path {
+        Some(path) if options.apply_mode => uuencode_lite::apply_mode(&path, mode).map_err(|err| (output_name, err)),
+        _ => Ok(()),
+    }
+}
+
+/// A file found by `--all`.
+struct Found {
+    /// The input it was found in.
+    input: String,
+    /// The line its `begin` line is on, from 1.
+    line: usize,
+    /// The name and mode from its `begin` line, unless that was invalid.
+    header: Option<(String, u32)>,
+    /// The number of bytes written, or why the file could not be extracted.
+    result: Result<u64, String>,
+}
+
+/// The file being extracted by `--all`.
+struct Extracting {
+    path: PathBuf,
+    file: BufWriter<File>,
+    len: u64,
+}
+
+/// Extracts every file from the inputs, as `--all` does, then prints a summary of them.
+fn extract_all(files: &[OsString], options: &Options, stdin: &mut dyn Read, stdout: &mut dyn Write, stderr: &mut dyn Write) -> i32 {
+    let mut found = Vec::new();
+    let mut status = 0;
+    for path in files {
+        let input_name = common::display_name(path);
+        let result = if path == "-" { scan(stdin, &input_name, options, &mut found) } else { File::open(path).and_then(|mut input| scan(&mut input, &input_name, options, &mut found)) };
+        if let Err(err) = result {
+            status = common::io_error(PROGRAM, &input_name, &err, stderr);
+        }
+    }
+    if let Err(err) = print_summary(&found, stdout) {
+        return common::io_error(PROGRAM, "standard output", &err, stderr);
+    }
+    if found.is_empty() && status == 0 {
+        return common::error(PROGRAM, "no encoded files found", stderr);
+    }
+    if found.iter().any(|file| file.result.is_err()) { 1 } else { status }
+}
+
+/// Scans the input a line at a time for encoded files, extracting each as the options say. Only the line
+/// being decoded is held in memory, so mailboxes of any size can be scanned. Decoding and writing errors are
+/// recorded against the file they affect, and scanning carries on after the broken file; only failing to read the
+/// input stops it.
+fn scan(input: &mut dyn Read, input_name: &str, options: &Options, found: &mut Vec<Found>) -> io::Result<()> {
+    let mut reader = BufReader::new(input);
+    let mut decoder = Decoder::new(options.decode_options());
+    let (mut line, mut decoded) = (Vec::new(), Vec::new());
+    // the line numbers, from 1, of the current line, the begin line of the current file, and the first line given
+    // to the current decoder, which its errors count from
+    let (mut line_number, mut begin_line, mut first_line) = (0, 0, 0);
+    let mut extracting: Option<Extracting> = None;
+    // after a broken file, its remaining lines are skipped so they aren't mistaken for text
+    let mut skipping = false;
+    let mut record = |decoder: &Decoder, begin_line, result| {
+        let header = decoder.header().map(|(mode, name)| (name.to_string(), mode));
+        found.push(Found { input: input_name.to_string(), line: begin_line, header, result });
+    };
+    loop {
+        line.clear();
+        if reader.read_until(b'\n', &mut line)? == 0 {
+            break;
+        }
+        line_number += 1;
+        if !line.ends_with(b"\n") {
+            line.push(b'\n');
+        }
+        if skipping {
+            match line.trim_ascii_end() {
+                b"end" | b"====" => {
+                    skipping = false;
+                    continue;
+                }
+                _ if line.starts_with(b"begin") => skipping = false,
+                _ => continue,
+            }
+        }
+        if decoder.input_offset() == 0 {
+            first_line = line_number;
+        }
+        if !decoder.has_started() {
+            begin_line = line_number;
+        }
+        decoded.clear();
+        match extract_line(&mut decoder, &line, first_line, options, &mut decoded, &mut extracting) {
+            Ok(DecodeEvent::End) => {
+                let mut extracted = extracting.take().expect("a finished file was being extracted");
+                let (mode, _) = decoder.header().expect("a finished file has a begin line");
+                let result = extracted.file.flush().and_then(|_| if options.apply_mode { uuencode_lite::apply_mode(&extracted.path, mode) } else { Ok(()) });
+                record(&decoder, begin_line, result.map(|_| extracted.len).map_err(|err| common::describe(&err)));
+                decoder = Decoder::new(options.decode_options());
+            }
+            Ok(_) => {}
+            Err(msg) => {
+                abandon(extracting.take());
+                record(&decoder, begin_line, Err(msg));
+                decoder = Decoder::new(options.decode_options());
+                skipping = true;
+            }
+        }
+    }
+    if decoder.has_started() {
+        abandon(extracting.take());
+        let msg = decoder.finish(&mut decoded).err().map_or_else(|| "Missing end line".to_string(), |err| describe_error(&err, first_line));
+        record(&decoder, begin_line, Err(msg));
+    }
+    Ok(())
+}
+
+/// Decodes one line of a file being extracted by `--all`, creating the file at its `begin` line and writing the
+/// decoded data to it. Fails with the reason the file can't be extracted.
+fn extract_line(decoder: &mut Decoder, line: &[u8], first_line: usize, options: &Options, decoded: &mut Vec<u8>, extracting: &mut Option<Extracting>) -> Result<DecodeEvent, String> {
+    let (_, event) = decoder.consume(line, decoded).map_err(|err| describe_error(&err, first_line))?;
+    match event {
+        DecodeEvent::Begin => {
+            // unless names are forced, the decoder has rejected names which would escape the directory
+            let path = options.named_path(decoder.header().expect("a begin event has a header").1);
+            let file = path.parent().map_or(Ok(()), std::fs::create_dir_all).and_then(|_| File::create(&path)).map_err(|err| common::describe(&err))?;
+            *extracting = Some(Extracting { path, file: BufWriter::new(file), len: 0 });
+        }
+        DecodeEvent::Data(len) => {
+            let extracted = extracting.as_mut().expect("data follows the begin line");
+            extracted.file.write_all(decoded).map_err(|err| common::describe(&err))?;
+            extracted.len += len as u64;
+        }
+        _ => {}
+    }
+    Ok(event)
+}
+
+/// Describes a decoding error, with its position counted from the start of the input rather than from the line
+/// `first_line` where decoding started.
+fn describe_error(err: &UUEncodeError, first_line: usize) -> String {
+    format!("{} (line {}, column {})", err.message(), first_line + err.line(), err.character() + 1)
+}
+
+/// Removes a file whose extraction failed part way, so that nothing corrupt is left behind.
+fn abandon(extracting: Option<Extracting>) {
+    if let Some(Extracting { path, file, .. }) = extracting {
+        drop(file);
+        let _ = std::fs::remove_file(path);
+    }
+}
+
+/// Prints a table of the files found by `--all`, with a count of those extracted and those which failed.
+fn print_summary(found: &[Found], stdout: &mut dyn Write) -> io::Result<()> {
+    if found.is_empty() {
+        return Ok(());
+    }
+    let input_width = found.iter().map(|file| file.input.len()).max().unwrap_or(0).max("INPUT".len());
+    writeln!(stdout, "{:<input_width$}  {:>7}  {:<6}  {:>4}  {:>10}  NAME", "INPUT", "LINE", "STATUS", "MODE", "SIZE")?;
+    for file in found {
+        let (name, mode) = file.header.as_ref().map_or(("-".to_string(), "-".to_string()), |(name, mode)| (format!("{:?}", name), format!("{:03o}", mode)));
+        let (status, size, note) = match &file.result {
+            Ok(len) => ("ok", len.to_string(), String::new()),
+            Err(msg) => ("FAILED", "-".to_string(), format!(": {}", msg)),
+        };
+        writeln!(stdout, "{:<input_width$}  {:>7}  {:<6}  {:>4}  {:>10}  {}{}", file.input, file.line, status, mode, size, name, note)?;
+    }
+    let failed = found.iter().filter(|file| file.result.is_err()).count();
+    writeln!(stdout, "{} found, {} extracted, {} failed", found.len(), found.len() - failed, failed)?;
+    stdout.flush()
+}
+
+fn main() {
+    let status = run(std::env::args_os().skip(1), &mut io::stdin().lock(), &mut io::stdout().lock(), &mut io::stderr());
+    std::process::exit(status);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use uuencode_lite::FileOptions;
+
+    /// Runs the program on the given standard input, returning its exit status and output.
+    fn run_with(args: &[&str], stdin: &[u8]) -> (i32, Vec<u8>, String) {
+        let (mut stdout, mut stderr) = (Vec::new(), Vec::new());
+        let status = run(args.iter().map(OsString::from), &mut &stdin[..], &mut stdout, &mut stderr);
+        (status, stdout, String::from_utf8(stderr).unwrap())
+    }
+
+    /// Tests decoding to standard output, to a named file, and the errors for bad input
+    #[test]
+    fn test_uudecode() {
+        let cat = b"begin 600 cat.txt\n#8V%T\n`\nend\n";
+        assert_eq!(run_with(&["-o", "-"], cat), (0, b"cat".to_vec(), String::new()), "decodes to standard output");
+        assert_eq!(run_with(&["--output-file=/dev/stdout", "-"], cat).1, b"cat", "treats /dev/stdout as standard output");
+
+        let source_data = std::fs::read("test_data/random_data.bin").expect("Can open test data");
+        let dir = std::env::temp_dir().join(format!("uudecode_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let input = dir.join("random_data.bin.uu");
+        std::fs::write(&input, uuencode_lite::uuencode_file(&source_data, &FileOptions::new("random_data.bin").mode(0o640)).unwrap()).unwrap();
+        let output = dir.join("random.bin");
+        let (status, _, stderr) = run_with(&["-o", output.to_str().unwrap(), input.to_str().unwrap()], b"");
+        assert_eq!((status, stderr.as_str()), (0, ""));
+        assert_eq!(std::fs::read(&output).unwrap(), source_data, "decodes a file");
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            assert_eq!(std::fs::metadata(&output).unwrap().permissions().mode() & 0o777, 0o640, "applies the mode");
+        }
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        let (status, _, stderr) = run_with(&["-o", "-"], b"begin 644 cat.txt\n#8V%T\n");
+        assert_eq!((status, stderr.as_str()), (1, "uudecode: standard input: Missing end line at line 2 character 0\n"), "reports a truncated file");
+        assert_eq!(run_with(&["-o"], b"").2, "uudecode: option requires an argument -- 'o'\nTry 'uudecode --help' for more information.\n");
+        assert_eq!(run_with(&["-o", "out", "a", "b"], b"").0, 1, "rejects an output file for several inputs");
+        assert_eq!(run_with(&["test_data/missing.uu"], b"").2, "uudecode: test_data/missing.uu: No such file or directory\n", "reports a missing file as strerror does");
+        assert_eq!(run_with(&["-o", "-"], b"begin-base64-encoded 644 Y2F0\nY2F0\n====\n").1, b"cat", "decodes a file with an encoded name");
+        let (status, _, stderr) = run_with(&["--progress", "-o", "-"], cat);
+        assert!(status == 0 && stderr.starts_with("uudecode: 30 B in, 3 B out, "), "prints a progress summary: {}", stderr);
+    }
+
+    /// Tests writing into an output directory, leaving the mode alone, and forcing unsafe names
+    #[test]
+    fn test_uudecode_output_dir() {
+        let dir = std::env::temp_dir().join(format!("uudecode_dir_{}", std::process::id()));
+        let out = dir.join("out");
+        let out_arg = format!("--output-dir={}", out.display());
+        assert_eq!(run_with(&[&out_arg], b"begin 600 cat.txt\n#8V%T\n`\nend\n"), (0, Vec::new(), String::new()));
+        assert_eq!(std::fs::read(out.join("cat.txt")).unwrap(), b"cat", "writes into the directory, creating it");
+        assert_eq!(run_with(&[&out_arg, "--no-mode"], b"begin 400 dog.txt\n#9&]G\n`\nend\n").0, 0);
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let mode = |name| std::fs::metadata(out.join(name)).unwrap().permissions().mode() & 0o777;
+            assert_eq!(mode("cat.txt"), 0o600, "applies the mode");
+            assert_ne!(mode("dog.txt"), 0o400, "leaves the mode alone with --no-mode");
+        }
+
+        let escape = b"begin 644 ../escape.txt\n#8V%T\n`\nend\n";
+        let (status, _, stderr) = run_with(&[&out_arg], escape);
+        assert_eq!((status, stderr.as_str()), (1, "uudecode: standard input: Unsafe file name in begin line: \"../escape.txt\" at line 0 character 10\n"), "refuses an unsafe name");
+        assert!(!dir.join("escape.txt").exists());
+        assert_eq!(run_with(&[&out_arg, "--force-name"], escape).0, 0, "accepts an unsafe name when forced");
+        assert_eq!(std::fs::read(dir.join("escape.txt")).unwrap(), b"cat");
+        assert_eq!(run_with(&[&out_arg, "-o", "-"], b"").0, 1, "rejects an output file with an output directory");
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    /// Tests extracting every file from a mailbox, carrying on past broken files, and the summary
+    #[test]
+    fn test_uudecode_all() {
+        let source_data = std::fs::read("test_data/random_data.bin").expect("Can open test data");
+        let mut mailbox = b"From alice Mon Jan  1 00:00:00 2024\nSubject: files\n\nHere they are.\n".to_vec();
+        mailbox.extend(uuencode_lite::uuencode_file(&source_data, &FileOptions::new("random.bin").mode(0o600)).unwrap().as_bytes());
+        mailbox.extend(b"\nbegin 644 broken.txt\n#8V\x01T\n#8V%T\n`\nend\n");
+        mailbox.extend(b"begin-base64 644 docs/cat.txt\nY2F0\n====\n");
+        mailbox.extend(b"begin 644 ../escape.txt\n#8V%T\n`\nend\n");
+        mailbox.extend(b"-- \nsignature\nbegin 644 cut.txt\n#8V%T\n");
+
+        let dir = std::env::temp_dir().join(format!("uudecode_all_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let (mut stdout, mut stderr) = (Vec::new(), Vec::new());
+        let options = Options { output: Output::Named, dir: Some(dir.clone()), apply_mode: true, force_name: false };
+        let status = extract_all(&["-".into()], &options, &mut &mailbox[..], &mut stdout, &mut stderr);
+        assert_eq!((status, String::from_utf8(stderr).unwrap().as_str()), (1, ""), "fails if any file failed");
+        assert_eq!(std::fs::read(dir.join("random.bin")).unwrap(), source_data, "extracts a uuencoded file");
+        assert_eq!(std::fs::read(dir.join("docs/cat.txt")).unwrap(), b"cat", "extracts a base64 file into a subdirectory");
+        assert!(!dir.join("broken.txt").exists() && !dir.join("cut.txt").exists(), "removes files which failed part way");
+        let summary = String::from_utf8(stdout).unwrap();
+        let lines = summary.lines().collect::<Vec<_>>();
+        assert_eq!(lines[0], "INPUT              LINE  STATUS  MODE        SIZE  NAME");
+        assert_eq!(lines[1], "standard input        5  ok       600        2048  \"random.bin\"");
+        assert_eq!(lines[2], "standard input       55  FAILED   644           -  \"broken.txt\": Invalid character in input: \x01 (line 56, column 4)");
+        assert_eq!(lines[3], "standard input       60  ok       644           3  \"docs/cat.txt\"");
+        assert_eq!(lines[4], "standard input       63  FAILED     -           -  -: Unsafe file name in begin line: \"../escape.txt\" (line 63, column 11)");
+        assert_eq!(lines[5], "standard input       69  FAILED   644           -  \"cut.txt\": Missing end line (line 71, column 1)");
+        assert_eq!(lines[6], "5 found, 2 extracted, 3 failed");
+
+        let mailbox = b"Subject: agenda\n\nbegin the meeting at 10\nbegin 644 cat.txt\n#8V%T\n`\nend\nbegin again later\n";
+        let (mut stdout, mut stderr) = (Vec::new(), Vec::new());
+        let status = extract_all(&["-".into()], &options, &mut &mailbox[..], &mut stdout, &mut stderr);
+        assert_eq!((status, String::from_utf8(stderr).unwrap().as_str()), (0, ""), "skips prose starting with begin");
+        assert_eq!(String::from_utf8(stdout).unwrap().lines().last(), Some("1 found, 1 extracted, 0 failed"), "lists only the real file");
+        assert_eq!(std::fs::read(dir.join("cat.txt")).unwrap(), b"cat", "extracts the real file");
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        let (status, stdout, stderr) = run_with(&["--all"], b"no files here\n");
+        assert_eq!((status, stdout.as_slice(), stderr.as_str()), (1, &b""[..], "uudecode: no encoded files found\n"));
+        assert_eq!(run_with(&["--all", "-o", "out"], b"").0, 1, "rejects an output file with --all");
+    }
+
+    /// Fails every write as a closed pipe does.
+    struct ClosedPipe;
+
+    impl Write for ClosedPipe {
+        fn write(&mut self, _: &[u8]) -> io::Result<usize> {
+            Err(io::ErrorKind::BrokenPipe.into())
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    /// Tests that a closed pipe on standard output stops decoding quietly
+    #[test]
+    fn test_uudecode_closed_pipe() {
+        let mut stderr = Vec::new();
+        let args = ["-o", "-"].map(OsString::from);
+        let status = run(args, &mut &b"begin 644 cat.txt\n#8V%T\n`\nend\n"[..], &mut ClosedPipe, &mut stderr);
+        assert_eq!((status, stderr.as_slice()), (common::BROKEN_PIPE_STATUS, &b""[..]));
+    }
+}
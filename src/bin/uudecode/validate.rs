@@ -0,0 +1,254 @@
+//! `uudecode validate [file]...`: checks that each file decodes cleanly without writing anything, printing every
+//! problem found with its line, column and severity, so that files can be vetted before they are ingested.
+
+use crate::common::{self, Arg, Parser};
+use crate::PROGRAM;
+use std::ffi::OsString;
+use std::fmt;
+use std::fs::File;
+use std::io::{self, BufRead, BufReader, Read, Write};
+use uuencode_lite::{DecodeEvent, DecodeOptions, Decoder, UUEncodeError};
+
+const USAGE: &str = "Usage: uudecode validate [OPTION]... [FILE]...
+Check that each uuencoded or base64 FILE, or standard input, decodes cleanly, without writing anything.
+
+  -h, --help              display this help and exit
+
+Each problem is printed as FILE:LINE:COLUMN: SEVERITY: MESSAGE, followed by a count of them. Errors stop a
+file from decoding; warnings are accepted by uudecode but suggest the file was damaged or badly made.
+With no FILE, or when FILE is -, read standard input. A file named validate can be given as ./validate.
+Exit status is 0 if no errors were found, and 1 if any were, or a file could not be read.
+";
+
+/// The most bytes a uuencoded line normally holds; only the last data line should hold fewer.
+const FULL_LINE: usize = 45;
+
+/// How serious a problem is.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Severity {
+    /// The file can't be decoded.
+    Error,
+    /// The file decodes, but probably not as its sender intended.
+    Warning,
+}
+
+impl fmt::Display for Severity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Severity::Error => "error",
+            Severity::Warning => "warning",
+        })
+    }
+}
+
+/// A problem found in an input.
+struct Diagnostic {
+    /// The line and column, from 1, or `None` for a problem with the input as a whole.
+    position: Option<(usize, usize)>,
+    severity: Severity,
+    message: String,
+}
+
+/// The problems found so far, printed as they are found.
+struct Report<'a> {
+    input: &'a str,
+    out: &'a mut dyn Write,
+    errors: usize,
+    warnings: usize,
+}
+
+impl Report<'_> {
+    fn add(&mut self, diagnostic: Diagnostic) -> io::Result<()> {
+        match diagnostic.severity {
+            Severity::Error => self.errors += 1,
+            Severity::Warning => self.warnings += 1,
+        }
+        match diagnostic.position {
+            Some((line, column)) => writeln!(self.out, "{}:{}:{}: {}: {}", self.input, line, column, diagnostic.severity, diagnostic.message),
+            None => writeln!(self.out, "{}: {}: {}", self.input, diagnostic.severity, diagnostic.message),
+        }
+    }
+
+    fn warn(&mut self, line: usize, column: usize, message: String) -> io::Result<()> {
+        self.add(Diagnostic { position: Some((line, column)), severity: Severity::Warning, message })
+    }
+}
+
+/// Runs `uudecode validate` with the arguments after `validate`, returning its exit status.
+pub(crate) fn run(args: impl IntoIterator<Item = OsString>, stdin: &mut dyn Read, stdout: &mut dyn Write, stderr: &mut dyn Write) -> i32 {
+    let mut parser = Parser::new(args);
+    let mut files = Vec::new();
+    loop {
+        let arg = match parser.next_arg() {
+            Ok(Some(arg)) => arg,
+            Ok(None) => break,
+            Err(msg) => return common::usage_error(PROGRAM, &msg, stderr),
+        };
+        match arg {
+            Arg::Short('h') => return common::help(USAGE, stdout),
+            Arg::Long(long) if long == "help" => return common::help(USAGE, stdout),
+            Arg::Positional(value) => files.push(value),
+            Arg::Short(ch) => return common::usage_error(PROGRAM, &format!("invalid option -- '{}'", ch), stderr),
+            Arg::Long(long) => return common::usage_error(PROGRAM, &format!("unrecognized option '--{}'", long), stderr),
+        }
+    }
+    if files.is_empty() {
+        files.push("-".into());
+    }
+
+    let (mut errors, mut warnings, mut status) = (0, 0, 0);
+    for path in &files {
+        let input_name = common::display_name(path);
+        let mut report = Report { input: &input_name, out: stdout, errors: 0, warnings: 0 };
+        let result = if path == "-" { check(stdin, &mut report) } else { File::open(path).and_then(|mut input| check(&mut input, &mut report)) };
+        errors += report.errors;
+        warnings += report.warnings;
+        if let Err(err) = result {
+            // a failure to write the report is reported against standard output, not the input
+            let file = if report.out.flush().is_err() { "standard output" } else { &input_name };
+            status = common::io_error(PROGRAM, file, &err, stderr);
+            if status == common::BROKEN_PIPE_STATUS {
+                return status;
+            }
+        }
+    }
+    let summary = writeln!(stdout, "{} {}, {} {}", errors, plural(errors, "error"), warnings, plural(warnings, "warning")).and_then(|_| stdout.flush());
+    if let Err(err) = summary {
+        return common::io_error(PROGRAM, "standard output", &err, stderr);
+    }
+    if errors > 0 { 1 } else { status }
+}
+
+fn plural(count: usize, word: &str) -> String {
+    if count == 1 { word.to_string() } else { format!("{}s", word) }
+}
+
+/// Checks every encoded file in the input a line at a time, reporting each problem. Decoding carries on past an
+/// error so that every problem in a file is found in one pass.
+fn check(input: &mut dyn Read, report: &mut Report) -> io::Result<()> {
+    let mut reader = BufReader::new(input);
+    let mut decoder = Decoder::new(DecodeOptions::new());
+    let (mut line, mut decoded) = (Vec::new(), Vec::new());
+    // the line numbers, from 1, of the current line and the first line given to the current decoder
+    let (mut line_number, mut first_line) = (0, 1);
+    let mut uuencoded = false;
+    // the line and length of a data line holding less than a full line, and whether the last body line held data
+    let mut short_line: Option<(usize, usize)> = None;
+    let mut last_was_data = false;
+    let mut found = false;
+    loop {
+        line.clear();
+        if reader.read_until(b'\n', &mut line)? == 0 {
+            break;
+        }
+        line_number += 1;
+        if !line.ends_with(b"\n") {
+            line.push(b'\n');
+        }
+        decoded.clear();
+        let event = match decoder.consume(&line, &mut decoded) {
+            Ok((_, event)) => event,
+            Err(err) => {
+                report.add(diagnose(&err, first_line))?;
+                continue;
+            }
+        };
+        let text = line.strip_suffix(b"\n").map(|text| text.strip_suffix(b"\r").unwrap_or(text)).unwrap_or(&line);
+        match event {
+            DecodeEvent::Begin => {
+                found = true;
+                uuencoded = !line.starts_with(b"begin-base64");
+                (short_line, last_was_data) = (None, false);
+                let (mode, _) = decoder.header().expect("a begin event has a header");
+                if mode & !0o777 != 0 {
+                    let column = text.iter().position(|&ch| ch == b' ').map_or(1, |pos| pos + 2);
+                    report.warn(line_number, column, format!("mode {:o} has setuid, setgid or sticky bits, which are not applied", mode))?;
+                }
+            }
+            DecodeEvent::Data(len) if uuencoded => {
+                if let Some((short, short_len)) = short_line.take() {
+                    report.warn(short, 1, format!("line holds {} bytes rather than {} but is followed by more data", short_len, FULL_LINE))?;
+                }
+                if len < FULL_LINE {
+                    short_line = Some((line_number, len));
+                }
+                // one character may follow the data, as a checksum
+                let used = 1 + len.div_ceil(3) * 4;
+                if text.len() > used + 1 {
+                    report.warn(line_number, used + 2, format!("{} unexpected characters after the encoded data", text.len() - used - 1))?;
+                }
+                last_was_data = true;
+            }
+            // a line of just a length character is the zero-length line
+            DecodeEvent::Line if uuencoded && decoder.has_started() && text.len() == 1 => last_was_data = false,
+            DecodeEvent::End => {
+                if uuencoded && last_was_data {
+                    report.warn(line_number, 1, "missing zero-length line before end".to_string())?;
+                }
+                decoder = Decoder::new(DecodeOptions::new());
+                first_line = line_number + 1;
+            }
+            _ => {}
+        }
+    }
+    if decoder.has_started() {
+        if let Err(err) = decoder.finish(&mut decoded) {
+            report.add(diagnose(&err, first_line))?;
+        }
+    } else if !found && report.errors == 0 {
+        report.add(Diagnostic { position: None, severity: Severity::Error, message: "no begin line found".to_string() })?;
+    }
+    Ok(())
+}
+
+/// Describes a decoding error, with its position counted from the start of the input rather than from the line
+/// `first_line` where decoding started, and an invalid character escaped so that it can be seen.
+fn diagnose(err: &UUEncodeError, first_line: usize) -> Diagnostic {
+    let message = match err.invalid_character() {
+        Some(ch) => format!("invalid character '{}' (0x{:02x})", ch.escape_ascii(), ch),
+        None => err.message().to_string(),
+    };
+    Diagnostic { position: Some((first_line + err.line(), err.character() + 1)), severity: Severity::Error, message }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Runs `uudecode validate` on the given standard input, returning its exit status and output.
+    fn validate(args: &[&str], stdin: &[u8]) -> (i32, String, String) {
+        let (mut stdout, mut stderr) = (Vec::new(), Vec::new());
+        let status = crate::run(["validate"].iter().chain(args).map(OsString::from), &mut &stdin[..], &mut stdout, &mut stderr);
+        (status, String::from_utf8(stdout).unwrap(), String::from_utf8(stderr).unwrap())
+    }
+
+    /// Tests that clean files pass, and that each problem is reported with its position and severity
+    #[test]
+    fn test_validate() {
+        let source_data = std::fs::read("test_data/random_data.bin").expect("Can open test data");
+        let mut clean = uuencode_lite::uuencode_file(&source_data, &uuencode_lite::FileOptions::new("random.bin")).unwrap().into_bytes();
+        clean.extend(b"begin-base64 644 cat.txt\nY2F0\n====\n");
+        assert_eq!(validate(&[], &clean), (0, "0 errors, 0 warnings\n".to_string(), String::new()), "passes clean files");
+
+        let input = b"Subject: files\n\nbegin 4755 cat.txt\n#8V\x01T\n#8V%T\n#8V%T!!!\nend\nbegin 644 ../escape.txt\nbegin 644 cut.txt\n#8V%T\n";
+        let (status, stdout, stderr) = validate(&[], input);
+        assert_eq!((status, stderr.as_str()), (1, ""), "fails on errors");
+        assert_eq!(
+            stdout.lines().collect::<Vec<_>>(),
+            [
+                "standard input:3:7: warning: mode 4755 has setuid, setgid or sticky bits, which are not applied",
+                "standard input:4:4: error: invalid character '\\x01' (0x01)",
+                "standard input:5:1: warning: line holds 3 bytes rather than 45 but is followed by more data",
+                "standard input:6:7: warning: 2 unexpected characters after the encoded data",
+                "standard input:7:1: warning: missing zero-length line before end",
+                "standard input:8:11: error: Unsafe file name in begin line: \"../escape.txt\"",
+                "standard input:11:1: error: Missing end line",
+                "3 errors, 4 warnings",
+            ]
+        );
+
+        assert_eq!(validate(&[], b"no files here\n").1, "standard input: error: no begin line found\n1 error, 0 warnings\n");
+        assert_eq!(validate(&["test_data/missing.uu"], b"").2, "uudecode: test_data/missing.uu: No such file or directory\n");
+        assert_eq!(validate(&["-x"], b"").0, 1, "rejects unknown options");
+    }
+}
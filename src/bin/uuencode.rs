@@ -0,0 +1,506 @@
+//! `uuencode [-me] [-o output] [file] name`: encodes a file, or standard input, as a complete uuencoded file on
+//! standard output, accepting the same options and giving the same exit statuses as the sharutils `uuencode`, so it
+//! can stand in for it in scripts. The data is streamed, so files of any size can be encoded. With `--split-lines`
+//! or `--split-bytes`, the output is split into numbered parts for posting to services which limit message size.
+
+mod common;
+
+use common::progress::{self, Meter, Show};
+use common::{Arg, Parser};
+use std::ffi::{OsStr, OsString};
+use std::fs::{File, OpenOptions};
+use std::io::{self, BufWriter, Read, Seek, SeekFrom, Write};
+use std::path::PathBuf;
+use std::time::{SystemTime, UNIX_EPOCH};
+use uuencode_lite::{EncodeReader, FileOptions, SplitOptions};
+
+const PROGRAM: &str = "uuencode";
+
+const USAGE: &str = "Usage: uuencode [OPTION]... [IN-FILE] REMOTE-FILE
+Encode IN-FILE, or standard input if it is omitted or -, as a uuencoded file named REMOTE-FILE,
+written to standard output.
+
+  -m, --base64            convert using base64
+  -e, --encode-file-name  encode the output file name
+  -o, --output-file=FILE  direct output to FILE
+      --split-lines=N     split the output into parts of at most N encoded lines, each starting
+                          with a section header; with -o, part n is written to FILE.00n
+      --split-bytes=N     split the output into parts of at most N bytes, headers included
+      --progress          print a summary of the bytes read and written and the rate at the end
+      --no-progress       never show progress, even on a terminal
+  -h, --help              display this help and exit
+  -v, --version           output version information and exit
+
+When standard error is a terminal, a progress bar is shown for inputs that take more than a second,
+followed by the summary. When splitting, standard input is first copied to a temporary file, to count the parts.
+Exit status is 0 on success, and 1 if the input could not be read, the output could not be written,
+or the command line was not valid.
+";
+
+/// The mode given to data from standard input.
+const STDIN_MODE: u32 = 0o644;
+
+/// The number of bytes encoded on each line.
+const LINE_BYTES: u64 = 45;
+
+/// How to split the output into parts.
+#[derive(Debug, Clone, Copy)]
+enum Split {
+    /// At most this many encoded lines in each part.
+    Lines(usize),
+    /// At most this many bytes in each part, including its header.
+    Bytes(usize),
+}
+
+/// Runs the program with the given arguments, without the program name, returning its exit status.
+fn run(args: impl IntoIterator<Item = OsString>, stdin: &mut dyn Read, stdout: &mut dyn Write, stderr: &mut dyn Write) -> i32 {
+    let mut parser = Parser::new(args);
+    let mut positional = Vec::new();
+    let (mut base64, mut encode_name, mut output, mut split) = (false, false, None, None);
+    let mut show = Show::Auto;
+    loop {
+        let arg = match parser.next_arg() {
+            Ok(Some(arg)) => arg,
+            Ok(None) => break,
+            Err(msg) => return common::usage_error(PROGRAM, &msg, stderr),
+        };
+        match arg {
+            Arg::Short('m') => base64 = true,
+            Arg::Long(long) if long == "base64" => base64 = true,
+            Arg::Short('e') => encode_name = true,
+            Arg::Long(long) if long == "encode-file-name" => encode_name = true,
+            Arg::Short('o') => match parser.value("o") {
+                Ok(path) => output = Some(path),
+                Err(msg) => return common::usage_error(PROGRAM, &msg, stderr),
+            },
+            Arg::Long(long) if long == "output-file" => match parser.value("output-file") {
+                Ok(path) => output = Some(path),
+                Err(msg) => return common::usage_error(PROGRAM, &msg, stderr),
+            },
+            Arg::Long(long) if long == "split-lines" || long == "split-bytes" => match parser.value(&long).and_then(|value| parse_count(&long, &value)) {
+                Ok(count) if long == "split-lines" => split = Some(Split::Lines(count)),
+                Ok(count) => split = Some(Split::Bytes(count)),
+                Err(msg) => return common::usage_error(PROGRAM, &msg, stderr),
+            },
+            Arg::Long(long) if long == "progress" => show = Show::Always,
+            Arg::Long(long) if long == "no-progress" => show = Show::Never,
+            Arg::Short('h') => return common::help(USAGE, stdout),
+            Arg::Long(long) if long == "help" => return common::help(USAGE, stdout),
+            Arg::Short('v') => return common::version(PROGRAM, stdout),
+            Arg::Long(long) if long == "version" => return common::version(PROGRAM, stdout),
+            Arg::Positional(value) => positional.push(value),
+            Arg::Short(ch) => return common::usage_error(PROGRAM, &format!("invalid option -- '{}'", ch), stderr),
+            Arg::Long(long) => return common::usage_error(PROGRAM, &format!("unrecognized option '--{}'", long), stderr),
+        }
+    }
+
+    if positional.len() > 2 {
+        return common::usage_error(PROGRAM, &format!("extra operand '{}'", positional[2].to_string_lossy()), stderr);
+    }
+    let Some(name) = positional.pop() else {
+        return common::usage_error(PROGRAM, "missing REMOTE-FILE operand", stderr);
+    };
+    let input = positional.pop().unwrap_or_else(|| "-".into());
+    let options = FileOptions::new(name.to_string_lossy()).base64(base64).encode_name(encode_name);
+
+    let (file, mode) = if input == "-" {
+        (None, STDIN_MODE)
+    } else {
+        match File::open(&input).and_then(|file| Ok((uuencode_lite::mode_from_permissions(&file.metadata()?.permissions()), file))) {
+            Ok((mode, file)) => (Some(file), mode),
+            Err(err) => return common::io_error(PROGRAM, &input.to_string_lossy(), &err, stderr),
+        }
+    };
+    let input_name = common::display_name(&input);
+    let options = options.mode(mode);
+    let output = output.filter(|path| path != "-");
+    let file_len = file.as_ref().and_then(|file| file.metadata().ok()).filter(|metadata| metadata.is_file()).map(|metadata| metadata.len());
+    let mut input_reader: Box<dyn Read + '_> = match file {
+        Some(file) => Box::new(file),
+        None => Box::new(stdin),
+    };
+
+    // the number of parts goes in every header, so it has to be known before the first is written
+    let (mut layout, mut input_len) = (None, file_len);
+    if let Some(split) = split {
+        let len = match file_len.map_or_else(|| spool(&mut input_reader), Ok) {
+            Ok(len) => len,
+            Err(err) => return common::io_error(PROGRAM, &input_name, &err, stderr),
+        };
+        match plan_parts(split, len, base64, &options) {
+            Ok(plan) => (layout, input_len) = (Some(plan), Some(len)),
+            Err(msg) => return common::error(PROGRAM, msg, stderr),
+        }
+    }
+
+    let meter = Meter::new(show, input_len);
+    let mut reader = match EncodeReader::new(input_reader, options.clone()) {
+        Ok(reader) => reader.on_progress(progress::INTERVAL, meter.callback()),
+        Err(err) => return common::error(PROGRAM, err, stderr),
+    };
+    let mut output_file = match (&output, layout) {
+        (Some(path), None) => match File::create(path) {
+            Ok(file) => Some(file),
+            Err(err) => return common::io_error(PROGRAM, &path.to_string_lossy(), &err, stderr),
+        },
+        _ => None,
+    };
+    let output_name = output.as_deref().map_or("standard output".into(), |path| path.to_string_lossy());
+    let result = match layout {
+        Some((lines_per_part, total)) => {
+            let mut parts = PartWriter { stdout, output: output.as_deref(), file: None, name: options.name(), lines_per_part, total, part: 0, lines: 0, at_line_start: true };
+            let result = common::copy(&mut reader, &mut parts);
+            if result.is_ok() && parts.part != total {
+                meter.finish(PROGRAM, false, stderr);
+                return common::error(PROGRAM, format_args!("{}: file changed size while being read", input_name), stderr);
+            }
+            result
+        }
+        None => {
+            let writer: &mut dyn Write = match &mut output_file {
+                Some(file) => file,
+                None => stdout,
+            };
+            common::copy(&mut reader, writer)
+        }
+    };
+    meter.finish(PROGRAM, result.is_ok(), stderr);
+    match result {
+        Ok(_) => 0,
+        Err(common::CopyError::Read(err)) => common::io_error(PROGRAM, &input_name, &err, stderr),
+        Err(common::CopyError::Write(err)) => common::io_error(PROGRAM, &output_name, &err, stderr),
+    }
+}
+
+/// Parses the count given to an option, which must be a positive number.
+fn parse_count(option: &str, value: &OsStr) -> Result<usize, String> {
+    value.to_str().and_then(|value| value.parse().ok()).filter(|&count| count > 0).ok_or_else(|| format!("invalid count for --{}: '{}'", option, value.to_string_lossy()))
+}
+
+/// Copies the input to a temporary file, for when its length can't be found from metadata, as for a pipe, so that
+/// the parts can be counted without holding the input in memory. The input is replaced with the copy, and its
+/// length returned.
+fn spool(input: &mut Box<dyn Read + '_>) -> io::Result<u64> {
+    let mut spool = Spool::new()?;
+    let len = io::copy(input, spool.file())?;
+    spool.file().seek(SeekFrom::Start(0))?;
+    *input = Box::new(spool);
+    Ok(len)
+}
+
+/// A temporary file holding a copy of standard input, removed once it has been read.
+struct Spool {
+    file: Option<File>,
+    path: PathBuf,
+}
+
+impl Spool {
+    /// Creates an empty temporary file, named so as not to clash with another run.
+    fn new() -> io::Result<Self> {
+        let nanos = SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |elapsed| elapsed.subsec_nanos());
+        let path = std::env::temp_dir().join(format!("uuencode-spool-{}-{}", std::process::id(), nanos));
+        let file = OpenOptions::new().read(true).write(true).create_new(true).open(&path)?;
+        Ok(Spool { file: Some(file), path })
+    }
+
+    fn file(&mut self) -> &mut File {
+        self.file.as_mut().expect("the file is open until the spool is dropped")
+    }
+}
+
+impl Read for Spool {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        self.file().read(buf)
+    }
+}
+
+impl Drop for Spool {
+    fn drop(&mut self) {
+        // closed first, as an open file can't be removed on Windows
+        self.file = None;
+        let _ = std::fs::remove_file(&self.path);
+    }
+}
+
+/// The header line starting a part.
+fn part_header(part: usize, total: usize, name: &str) -> String {
+    SplitOptions::DEFAULT_HEADER.replace("{part}", &part.to_string()).replace("{total}", &total.to_string()).replace("{name}", name)
+}
+
+/// Works out how many encoded lines go in each part, and how many parts there are, for input of the given length.
+fn plan_parts(split: Split, len: u64, base64: bool, options: &FileOptions) -> Result<(usize, usize), String> {
+    if options.name().contains(['\n', '\r']) {
+        return Err("can't split a file whose name contains a line break, as the name goes in each section header".to_string());
+    }
+    // the framing lines are those of an empty file
+    let framing = uuencode_lite::uuencode_file(b"", options).map_err(|err| err.to_string())?;
+    let total_lines = (len.div_ceil(LINE_BYTES) as usize).saturating_add(framing.lines().count());
+    let lines_per_part = match split {
+        Split::Lines(lines) => lines,
+        Split::Bytes(max_bytes) => {
+            // a full line is 60 characters, plus a length character for uuencode, and a newline
+            let body_line = if base64 { 61 } else { 62 };
+            let longest_line = framing.lines().map(|line| line.len() + 1).fold(body_line, usize::max);
+            fit_lines(max_bytes, total_lines, longest_line, options.name()).ok_or_else(|| format!("--split-bytes={} is too small to hold a line of {} bytes and its section header", max_bytes, longest_line))?
+        }
+    };
+    Ok((lines_per_part, total_lines.div_ceil(lines_per_part)))
+}
+
+/// The most lines of up to `longest_line` bytes which fit in parts of at most `max_bytes` bytes, counting their
+/// headers. Headers grow with the number of parts, which in turn depends on how many lines fit, so this settles on
+/// a number of digits for the part count first.
+fn fit_lines(max_bytes: usize, total_lines: usize, longest_line: usize, name: &str) -> Option<usize> {
+    let mut total = 1;
+    loop {
+        let header_len = part_header(total, total, name).len() + 1;
+        let lines_per_part = max_bytes.checked_sub(header_len)? / longest_line;
+        if lines_per_part == 0 {
+            return None;
+        }
+        let needed = total_lines.div_ceil(lines_per_part);
+        if needed.to_string().len() <= total.to_string().len() {
+            return Some(lines_per_part);
+        }
+        total = needed;
+    }
+}
+
+/// Writes encoded text as parts of at most `lines_per_part` lines, each starting with a section header: one after
+/// another to standard output, or each to its own numbered file.
+struct PartWriter<'a> {
+    stdout: &'a mut dyn Write,
+    /// The path the part files are numbered after, or `None` to write to standard output.
+    output: Option<&'a OsStr>,
+    /// The file the current part is written to.
+    file: Option<BufWriter<File>>,
+    name: &'a str,
+    lines_per_part: usize,
+    total: usize,
+    /// The number of the current part, from 1, or 0 before the first.
+    part: usize,
+    /// The number of lines in the current part.
+    lines: usize,
+    /// Whether the text so far ends with a complete line.
+    at_line_start: bool,
+}
+
+impl PartWriter<'_> {
+    /// Finishes the current part and starts the next, with its header.
+    fn start_part(&mut self) -> io::Result<()> {
+        self.part += 1;
+        self.lines = 0;
+        if let Some(output) = self.output {
+            if let Some(mut file) = self.file.take() {
+                file.flush()?;
+            }
+            let mut path = output.to_os_string();
+            path.push(format!(".{:03}", self.part));
+            self.file = Some(BufWriter::new(File::create(PathBuf::from(path))?));
+        }
+        let header = part_header(self.part, self.total, self.name);
+        writeln!(self.sink(), "{}", header)
+    }
+
+    fn sink(&mut self) -> &mut dyn Write {
+        match &mut self.file {
+            Some(file) => file,
+            None => self.stdout,
+        }
+    }
+}
+
+impl Write for PartWriter<'_> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let mut rest = buf;
+        while !rest.is_empty() {
+            if self.at_line_start && (self.part == 0 || self.lines == self.lines_per_part) {
+                self.start_part()?;
+            }
+            let len = rest.iter().position(|&ch| ch == b'\n').map_or(rest.len(), |idx| idx + 1);
+            self.sink().write_all(&rest[..len])?;
+            self.at_line_start = rest[len - 1] == b'\n';
+            if self.at_line_start {
+                self.lines += 1;
+            }
+            rest = &rest[len..];
+        }
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.sink().flush()
+    }
+}
+
+fn main() {
+    let status = run(std::env::args_os().skip(1), &mut io::stdin().lock(), &mut io::stdout().lock(), &mut io::stderr());
+    std::process::exit(status);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Runs the program on the given standard input, returning its exit status and output.
+    fn run_with(args: &[&str], stdin: &[u8]) -> (i32, String, String) {
+        let (mut stdout, mut stderr) = (Vec::new(), Vec::new());
+        let status = run(args.iter().map(OsString::from), &mut &stdin[..], &mut stdout, &mut stderr);
+        (status, String::from_utf8(stdout).unwrap(), String::from_utf8(stderr).unwrap())
+    }
+
+    /// Tests encoding standard input and a file, and the usage errors
+    #[test]
+    fn test_uuencode() {
+        assert_eq!(run_with(&["cat.txt"], b"cat"), (0, "begin 644 cat.txt\n#8V%T\n`\nend\n".to_string(), String::new()), "encodes standard input");
+
+        let (status, stdout, _) = run_with(&["test_data/random_data.bin", "random.bin"], b"");
+        assert_eq!(status, 0);
+        let file = uuencode_lite::uudecode_file(stdout.as_bytes()).unwrap();
+        assert_eq!((file.name.as_str(), file.data), ("random.bin", std::fs::read("test_data/random_data.bin").unwrap()), "encodes a file");
+
+        let (status, _, stderr) = run_with(&["test_data/missing.bin", "missing.bin"], b"");
+        assert_eq!((status, stderr.as_str()), (1, "uuencode: test_data/missing.bin: No such file or directory\n"), "reports a missing file as strerror does");
+        assert_eq!(run_with(&[], b"").0, 1, "requires a name");
+        assert_eq!(run_with(&["a", "b", "c"], b"").2, "uuencode: extra operand 'c'\nTry 'uuencode --help' for more information.\n");
+        assert_eq!(run_with(&["-x", "name"], b"").2, "uuencode: invalid option -- 'x'\nTry 'uuencode --help' for more information.\n");
+        assert!(run_with(&["--help"], b"").1.starts_with("Usage: uuencode"), "prints help");
+    }
+
+    /// Tests the sharutils options: base64, encoded names, an output file, and - for standard input
+    #[test]
+    fn test_uuencode_options() {
+        assert_eq!(run_with(&["-m", "-", "cat.txt"], b"cat").1, "begin-base64 644 cat.txt\nY2F0\n====\n", "encodes base64 from standard input");
+        assert_eq!(run_with(&["-e", "my cat.txt"], b"cat").1, "begin-encoded 644 *;7D@8V%T+G1X=```\n#8V%T\n`\nend\n", "encodes the name");
+        assert_eq!(run_with(&["-me", "cat"], b"cat").1, "begin-base64-encoded 644 Y2F0\nY2F0\n====\n", "combines options");
+        assert_eq!(run_with(&["--base64", "--encode-file-name", "cat"], b"cat").1, "begin-base64-encoded 644 Y2F0\nY2F0\n====\n", "accepts long options");
+
+        let path = std::env::temp_dir().join(format!("uuencode_{}.uu", std::process::id()));
+        let path_arg = path.to_str().unwrap();
+        assert_eq!(run_with(&["-o", path_arg, "cat.txt"], b"cat"), (0, String::new(), String::new()), "writes nothing to standard output");
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "begin 644 cat.txt\n#8V%T\n`\nend\n", "writes the output file");
+        std::fs::remove_file(&path).unwrap();
+
+        let (status, _, stderr) = run_with(&["cat\nname"], b"cat");
+        assert_eq!((status, stderr.as_str()), (1, "uuencode: Invalid file name: \"cat\\nname\" at line 0 character 0\n"), "rejects a name it cannot write");
+        assert_eq!(run_with(&["-e", "cat\nname"], b"cat").0, 0, "encodes a name it cannot otherwise write");
+    }
+
+    /// Tests the progress summary, and how the bar and byte counts are rendered
+    #[test]
+    fn test_uuencode_progress() {
+        let (status, stdout, stderr) = run_with(&["--progress", "cat.txt"], b"cat");
+        assert_eq!((status, stdout.as_str()), (0, "begin 644 cat.txt\n#8V%T\n`\nend\n"));
+        assert!(stderr.starts_with("uuencode: 3 B in, 30 B out, ") && stderr.ends_with(" MB/s\n"), "prints a summary: {}", stderr);
+        assert_eq!(run_with(&["--no-progress", "cat.txt"], b"cat").2, "", "prints nothing when asked not to");
+        assert_eq!(run_with(&["cat.txt"], b"cat").2, "", "prints nothing for a quick run");
+
+        assert_eq!(progress::format_bytes(999), "999 B");
+        assert_eq!(progress::format_bytes(1_500_000), "1.5 MB");
+        assert_eq!(progress::format_bytes(999_960_000), "1.0 GB");
+        let second = std::time::Duration::from_secs(1);
+        let half = uuencode_lite::Progress { consumed: 5_000_000, produced: 6_900_000 };
+        assert_eq!(progress::render_bar(half, Some(10_000_000), second), "[===============>              ]  50%  5.0 MB / 10.0 MB  5.0 MB/s  ETA 0:01");
+        assert_eq!(progress::render_bar(half, None, second), "5.0 MB read  5.0 MB/s");
+        assert_eq!(progress::render_summary(half, second * 2), "5.0 MB in, 6.9 MB out, 2.0 s, 2.5 MB/s");
+    }
+
+    /// Tests splitting into parts on standard output and into numbered files, by lines and by bytes
+    #[test]
+    fn test_uuencode_split() {
+        let source_data = std::fs::read("test_data/random_data.bin").expect("Can open test data");
+        let options = FileOptions::new("random.bin");
+        let expected = uuencode_lite::uuencode_parts(&source_data, &options, &SplitOptions::new(10)).unwrap().concat();
+        let (status, stdout, _) = run_with(&["--split-lines", "10", "random.bin"], &source_data);
+        assert_eq!((status, stdout), (0, expected), "splits standard input as uuencode_parts does");
+        let spool_prefix = format!("uuencode-spool-{}-", std::process::id());
+        let spools = std::fs::read_dir(std::env::temp_dir()).unwrap().filter(|entry| entry.as_ref().unwrap().file_name().to_string_lossy().starts_with(&spool_prefix));
+        assert_eq!(spools.count(), 0, "removes the copy of standard input");
+        let mode = uuencode_lite::mode_from_permissions(&std::fs::metadata("test_data/random_data.bin").unwrap().permissions());
+        let expected = uuencode_lite::uuencode_parts(&source_data, &options.clone().mode(mode), &SplitOptions::new(10)).unwrap().concat();
+        assert_eq!(run_with(&["--split-lines=10", "test_data/random_data.bin", "random.bin"], b"").1, expected, "splits a file");
+
+        let dir = std::env::temp_dir().join(format!("uuencode_split_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let output = dir.join("random.uu");
+        let (status, _, stderr) = run_with(&["-m", "--split-bytes=1000", "-o", output.to_str().unwrap(), "random.bin"], &source_data);
+        assert_eq!((status, stderr.as_str()), (0, ""));
+        let mut parts = std::fs::read_dir(&dir).unwrap().map(|entry| entry.unwrap().path()).collect::<Vec<_>>();
+        parts.sort();
+        assert_eq!(parts.len(), 4, "writes numbered part files: {:?}", parts);
+        assert_eq!(parts[0], dir.join("random.uu.001"));
+        let parts = parts.iter().map(|path| std::fs::read(path).unwrap()).collect::<Vec<_>>();
+        assert!(parts.iter().all(|part| part.len() <= 1000), "keeps parts within the limit");
+        assert!(parts[3].starts_with(b"section 4 of 4 of file random.bin\n"), "starts each part with a header");
+        let file = uuencode_lite::reassemble_parts(&parts.iter().map(Vec::as_slice).collect::<Vec<_>>(), &uuencode_lite::DecodeOptions::new()).unwrap();
+        assert_eq!(file.data, source_data, "can reassemble the parts");
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        let (status, _, stderr) = run_with(&["--split-bytes=50", "random.bin"], b"cat");
+        assert_eq!((status, stderr.as_str()), (1, "uuencode: --split-bytes=50 is too small to hold a line of 62 bytes and its section header\n"));
+        let (status, stdout, stderr) = run_with(&["-e", "--split-lines=20", "x\nbegin 644 evil"], &source_data);
+        assert_eq!((status, stdout.as_str()), (1, ""), "refuses to split a file whose name has a line break");
+        assert_eq!(stderr, "uuencode: can't split a file whose name contains a line break, as the name goes in each section header\n");
+        assert_eq!(run_with(&["--split-lines=0", "random.bin"], b"").2, "uuencode: invalid count for --split-lines: '0'\nTry 'uuencode --help' for more information.\n");
+    }
+
+    /// Reads a long run of zeros, noting how much has been read.
+    struct Zeros<'a> {
+        remaining: u64,
+        read: &'a std::cell::Cell<u64>,
+    }
+
+    impl Read for Zeros<'_> {
+        fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+            let len = buf.len().min(self.remaining as usize);
+            buf[..len].fill(0);
+            self.remaining -= len as u64;
+            self.read.set(self.read.get() + len as u64);
+            Ok(len)
+        }
+    }
+
+    /// Discards what is written, noting how much input had been read when output started, and failing as a closed
+    /// pipe does once a limit is reached.
+    struct Sink<'a> {
+        read: &'a std::cell::Cell<u64>,
+        read_at_first_write: Option<u64>,
+        written: u64,
+        limit: u64,
+    }
+
+    impl Write for Sink<'_> {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            if self.written >= self.limit {
+                return Err(io::ErrorKind::BrokenPipe.into());
+            }
+            self.read_at_first_write.get_or_insert(self.read.get());
+            self.written += buf.len() as u64;
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    /// Tests that standard input is encoded as it arrives rather than all at once, and that a closed pipe stops
+    /// encoding quietly
+    #[test]
+    fn test_uuencode_streaming() {
+        const LEN: u64 = 16 * 1024 * 1024;
+        let read = std::cell::Cell::new(0);
+        let mut sink = Sink { read: &read, read_at_first_write: None, written: 0, limit: u64::MAX };
+        let mut stderr = Vec::new();
+        let status = run(["zeros.bin".into()], &mut Zeros { remaining: LEN, read: &read }, &mut sink, &mut stderr);
+        assert_eq!(status, 0);
+        assert!(sink.read_at_first_write.unwrap() <= 64 * 1024, "starts writing after {:?} bytes", sink.read_at_first_write);
+        assert!(sink.written > LEN / 45 * 62, "writes every line");
+
+        let read = std::cell::Cell::new(0);
+        let mut sink = Sink { read: &read, read_at_first_write: None, written: 0, limit: 1024 * 1024 };
+        let status = run(["zeros.bin".into()], &mut Zeros { remaining: LEN, read: &read }, &mut sink, &mut stderr);
+        assert_eq!((status, stderr.as_slice()), (common::BROKEN_PIPE_STATUS, &b""[..]), "stops quietly at a closed pipe");
+        assert!(read.get() < 2 * 1024 * 1024, "stops reading at a closed pipe");
+    }
+}
@@ -0,0 +1,247 @@
+use alloc::format;
+use alloc::string::String;
+use alloc::vec::Vec;
+use crate::{Message, UUEncodeError};
+
+/// The BinHex 4.0 alphabet.
+const ALPHABET: &[u8; 64] = b"!\"#$%&'()*+,-012345689@ABCDEFGHIJKLMNPQRSTUVXYZ[`abcdefhijklmpqr";
+
+/// Marks a byte which is not part of the alphabet in `DECODE_TABLE`.
+const INVALID: u8 = 0xFF;
+
+const DECODE_TABLE: [u8; 256] = {
+    let mut table = [INVALID; 256];
+    let mut idx = 0;
+    while idx < 64 {
+        table[ALPHABET[idx] as usize] = idx as u8;
+        idx += 1;
+    }
+    table
+};
+
+/// The run-length encoding marker.
+const RLE_MARKER: u8 = 0x90;
+
+/// A classic Mac OS file decoded from BinHex 4.0.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BinHexFile {
+    /// The file name, decoded as Latin-1 (which matches Mac OS Roman for ASCII names).
+    pub name: String,
+    /// The four-character file type, such as `TEXT`.
+    pub file_type: [u8; 4],
+    /// The four-character creator code, such as `ttxt`.
+    pub creator: [u8; 4],
+    /// The Finder flags.
+    pub flags: u16,
+    /// The contents of the data fork.
+    pub data_fork: Vec<u8>,
+    /// The contents of the resource fork.
+    pub resource_fork: Vec<u8>,
+}
+
+/// Computes the CRC-16/XMODEM used by BinHex.
+pub(crate) fn crc16_xmodem(data: &[u8]) -> u16 {
+    data.iter().fold(0u16, |crc, &byte| {
+        let mut crc = crc ^ ((byte as u16) << 8);
+        for _ in 0..8 {
+            crc = if crc & 0x8000 != 0 { (crc << 1) ^ 0x1021 } else { crc << 1 };
+        }
+        crc
+    })
+}
+
+/// Decodes the 6-bit characters between the `:` markers, then expands the run-length encoding.
+fn decode_stream(data: &[u8]) -> Result<Vec<u8>, UUEncodeError> {
+    let mut cur_line = 0;
+    let mut line_start = 0;
+    // the encoded data starts with the first ':' at the start of a line
+    let mut idx = loop {
+        match data[line_start..].iter().position(|&ch| ch == b'\n') {
+            _ if data.get(line_start) == Some(&b':') => break line_start + 1,
+            Some(len) => {
+                line_start += len + 1;
+                cur_line += 1;
+            }
+            None => return Err(UUEncodeError::new(0, 0, "Missing start of BinHex data")),
+        }
+    };
+
+    let mut packed = Vec::with_capacity(data.len() / 4 * 3);
+    let mut bits = 0u32;
+    let mut bit_count = 0;
+    loop {
+        let Some(&ch) = data.get(idx) else {
+            return Err(UUEncodeError::new(cur_line, idx - line_start, "Missing end of BinHex data"));
+        };
+        match ch {
+            b':' => break,
+            b'\n' => {
+                cur_line += 1;
+                line_start = idx + 1;
+            }
+            b'\r' | b' ' | b'\t' => {}
+            _ => {
+                let value = match DECODE_TABLE[ch as usize] {
+                    INVALID => return Err(UUEncodeError::new(cur_line, idx - line_start, Message::InvalidCharacter(ch))),
+                    value => value,
+                };
+                bits = (bits << 6) | value as u32;
+                bit_count += 6;
+                if bit_count >= 8 {
+                    bit_count -= 8;
+                    packed.push((bits >> bit_count) as u8);
+                }
+            }
+        }
+        idx += 1;
+    }
+
+    let mut expanded = Vec::with_capacity(packed.len());
+    let mut bytes = packed.iter();
+    while let Some(&byte) = bytes.next() {
+        if byte != RLE_MARKER {
+            expanded.push(byte);
+            continue;
+        }
+        match bytes.next() {
+            Some(0) => expanded.push(RLE_MARKER),
+            Some(&count) => {
+                let Some(&previous) = expanded.last() else {
+                    return Err(UUEncodeError::new(cur_line, 0, "Run-length marker with nothing to repeat"));
+                };
+                expanded.extend(core::iter::repeat_n(previous, count as usize - 1));
+            }
+            None => return Err(UUEncodeError::new(cur_line, 0, "Run-length marker at end of data")),
+        }
+    }
+    Ok(expanded)
+}
+
+/// Splits `len` bytes and their trailing CRC off the front of `data`, verifying the CRC.
+fn take_checked<'a>(data: &mut &'a [u8], len: usize, what: &str) -> Result<&'a [u8], UUEncodeError> {
+    if data.len() < len + 2 {
+        return Err(UUEncodeError::new(0, 0, format!("BinHex data ended in the {}", what)));
+    }
+    let (section, rest) = data.split_at(len);
+    let expected = u16::from_be_bytes([rest[0], rest[1]]);
+    if crc16_xmodem(section) != expected {
+        return Err(UUEncodeError::new(0, 0, format!("CRC mismatch in the {}", what)));
+    }
+    *data = &rest[2..];
+    Ok(section)
+}
+
+/// Decodes a BinHex 4.0 (`.hqx`) file, verifying the CRC of the header and of each fork.
+/// Text before the data (such as the `(This file must be converted with BinHex 4.0)` banner) is skipped;
+/// the data starts at the first line beginning with `:` and ends at the next `:`.
+pub fn binhex_decode(data: &[u8]) -> Result<BinHexFile, UUEncodeError> {
+    let stream = decode_stream(data)?;
+    let mut rest = stream.as_slice();
+
+    let name_len = *rest.first().ok_or_else(|| UUEncodeError::new(0, 0, "BinHex data ended in the header"))? as usize;
+    let header = take_checked(&mut rest, 1 + name_len + 1 + 4 + 4 + 2 + 4 + 4, "header")?;
+    let name: String = header[1..=name_len].iter().map(|&ch| ch as char).collect();
+    let fields = &header[1 + name_len + 1..];
+    let data_len = u32::from_be_bytes([fields[10], fields[11], fields[12], fields[13]]) as usize;
+    let resource_len = u32::from_be_bytes([fields[14], fields[15], fields[16], fields[17]]) as usize;
+
+    let data_fork = take_checked(&mut rest, data_len, "data fork")?.to_vec();
+    let resource_fork = take_checked(&mut rest, resource_len, "resource fork")?.to_vec();
+    Ok(BinHexFile {
+        name,
+        file_type: [fields[0], fields[1], fields[2], fields[3]],
+        creator: [fields[4], fields[5], fields[6], fields[7]],
+        flags: u16::from_be_bytes([fields[8], fields[9]]),
+        data_fork,
+        resource_fork,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::string::ToString;
+    use alloc::vec;
+
+    /// Builds the BinHex stream for a file, without run-length compression beyond escaping the marker.
+    fn encode(file: &BinHexFile) -> String {
+        let mut stream = Vec::new();
+        let mut push_checked = |section: &[u8]| {
+            stream.extend_from_slice(section);
+            stream.extend_from_slice(&crc16_xmodem(section).to_be_bytes());
+        };
+        let mut header = vec![file.name.len() as u8];
+        header.extend(file.name.bytes());
+        header.push(0);
+        header.extend_from_slice(&file.file_type);
+        header.extend_from_slice(&file.creator);
+        header.extend_from_slice(&file.flags.to_be_bytes());
+        header.extend_from_slice(&(file.data_fork.len() as u32).to_be_bytes());
+        header.extend_from_slice(&(file.resource_fork.len() as u32).to_be_bytes());
+        push_checked(&header);
+        push_checked(&file.data_fork);
+        push_checked(&file.resource_fork);
+
+        let escaped: Vec<u8> = stream.iter().flat_map(|&byte| if byte == RLE_MARKER { vec![RLE_MARKER, 0] } else { vec![byte] }).collect();
+        let mut encoded = String::from("(This file must be converted with BinHex 4.0)\n\n:");
+        for (idx, chunk) in escaped.chunks(3).enumerate() {
+            let mut buffer = [0u8; 3];
+            buffer[..chunk.len()].copy_from_slice(chunk);
+            let values = [buffer[0] >> 2, ((buffer[0] << 4) | (buffer[1] >> 4)) & 0x3F, ((buffer[1] << 2) | (buffer[2] >> 6)) & 0x3F, buffer[2] & 0x3F];
+            encoded.extend(values[..chunk.len() + 1].iter().map(|&value| ALPHABET[value as usize] as char));
+            if idx % 16 == 15 {
+                encoded.push('\n');
+            }
+        }
+        encoded.push_str(":\n");
+        encoded
+    }
+
+    /// Tests decoding both forks of a file
+    #[test]
+    fn test_binhex_decode() {
+        let source_data = std::fs::read("test_data/random_data.bin").expect("Can open test data");
+        let file = BinHexFile {
+            name: "Random Data".to_string(),
+            file_type: *b"BINA",
+            creator: *b"????",
+            flags: 0x0100,
+            data_fork: source_data[..1000].to_vec(),
+            resource_fork: source_data[1000..1500].to_vec(),
+        };
+        assert_eq!(binhex_decode(encode(&file).as_bytes()).unwrap(), file, "can decode a binhex file");
+    }
+
+    /// Tests expansion of run-length encoded data
+    #[test]
+    fn test_binhex_rle() {
+        // "a", repeated 5 times, then a literal marker
+        let expanded = {
+            let packed = [b'a', RLE_MARKER, 5, RLE_MARKER, 0];
+            let mut encoded = String::from(":");
+            for chunk in packed.chunks(3) {
+                let mut buffer = [0u8; 3];
+                buffer[..chunk.len()].copy_from_slice(chunk);
+                let values = [buffer[0] >> 2, ((buffer[0] << 4) | (buffer[1] >> 4)) & 0x3F, ((buffer[1] << 2) | (buffer[2] >> 6)) & 0x3F, buffer[2] & 0x3F];
+                encoded.extend(values[..chunk.len() + 1].iter().map(|&value| ALPHABET[value as usize] as char));
+            }
+            encoded.push(':');
+            decode_stream(encoded.as_bytes()).unwrap()
+        };
+        assert_eq!(expanded, b"aaaaa\x90", "expands runs and literal markers");
+    }
+
+    /// Tests detection of corruption
+    #[test]
+    fn test_binhex_errors() {
+        let file = BinHexFile { name: "cat".to_string(), file_type: *b"TEXT", creator: *b"ttxt", flags: 0, data_fork: b"cat".to_vec(), resource_fork: Vec::new() };
+        let encoded = encode(&file);
+        let truncated = format!("{}:", &encoded[..encoded.len() - 6]);
+        assert!(binhex_decode(truncated.as_bytes()).is_err(), "rejects truncated data");
+        let corrupted = encoded.replacen(":", ":!", 1);
+        assert!(binhex_decode(corrupted.as_bytes()).is_err(), "rejects a corrupted header");
+        assert!(binhex_decode(b"no binhex here").is_err(), "requires binhex data");
+        let err = binhex_decode(b"banner\n:!!~").unwrap_err();
+        assert_eq!((err.line, err.character), (1, 3), "reports the position of an invalid character");
+    }
+}
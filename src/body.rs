@@ -0,0 +1,177 @@
+use crate::decoder::Decoder;
+use crate::{DecodeOptions, UUEncodeError};
+use bytes::{Buf, Bytes};
+use http_body::{Body, Frame};
+use std::pin::Pin;
+use std::task::{ready, Context, Poll};
+use std::vec::Vec;
+
+/// An error from a `DecodeBody`: either from the inner body, or from decoding it.
+#[derive(Debug)]
+pub enum DecodeBodyError<E> {
+    /// The inner body failed.
+    Body(E),
+    /// The inner body was not a valid uuencoded file.
+    Decode(UUEncodeError),
+}
+
+impl<E: std::fmt::Display> std::fmt::Display for DecodeBodyError<E> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DecodeBodyError::Body(err) => write!(f, "Body error: {}", err),
+            DecodeBodyError::Decode(err) => write!(f, "Decode error: {}", err),
+        }
+    }
+}
+
+impl<E: std::error::Error + 'static> std::error::Error for DecodeBodyError<E> {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            DecodeBodyError::Body(err) => Some(err),
+            DecodeBodyError::Decode(err) => Some(err),
+        }
+    }
+}
+
+/// An HTTP body which decodes a complete uuencoded file, as `uudecode_file_with` does, from the inner body as its
+/// frames arrive, such as an upload sent with `Content-Transfer-Encoding: x-uuencode`. Each data frame holds the
+/// decoded bytes of the lines completed by one inner frame, and trailers are passed through.
+/// Data after the end of the file is read and ignored.
+/// The inner body must be `Unpin`; use `Box::pin` for bodies which are not.
+/// Example:
+/// ```rust
+/// fn decode() -> Result<(), Box<dyn std::error::Error>> {
+///     use http_body::Body;
+///     let mut body = uuencode_lite::DecodeBody::new(String::from("begin 644 cat.txt\n#8V%T\n`\nend\n"), uuencode_lite::DecodeOptions::new());
+///     let frame = futures::executor::block_on(std::future::poll_fn(|cx| std::pin::Pin::new(&mut body).poll_frame(cx)));
+///     assert_eq!(frame.unwrap()?.into_data().unwrap(), "cat");
+///     assert_eq!(body.name(), Some("cat.txt"));
+///     Ok(())
+/// }
+/// ```
+#[derive(Debug)]
+pub struct DecodeBody<B> {
+    inner: B,
+    decoder: Decoder,
+    /// Whether the body has ended.
+    done: bool,
+}
+
+impl<B> DecodeBody<B> {
+    /// Creates a body which decodes a file with the given options from `inner`.
+    pub fn new(inner: B, options: DecodeOptions) -> Self {
+        DecodeBody { inner, decoder: Decoder::new(options), done: false }
+    }
+
+    /// The file name from the `begin` line, once it has been decoded.
+    pub fn name(&self) -> Option<&str> {
+        self.decoder.header().map(|(_, name)| name)
+    }
+
+    /// The permission bits from the `begin` line, once it has been decoded.
+    pub fn mode(&self) -> Option<u32> {
+        self.decoder.header().map(|(mode, _)| mode)
+    }
+
+    /// Returns the inner body.
+    pub fn into_inner(self) -> B {
+        self.inner
+    }
+}
+
+impl<B: Body + Unpin> Body for DecodeBody<B> {
+    type Data = Bytes;
+    type Error = DecodeBodyError<B::Error>;
+
+    fn poll_frame(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Result<Frame<Bytes>, Self::Error>>> {
+        let this = self.get_mut();
+        let mut decoded = Vec::new();
+        while !this.done {
+            let result = match ready!(Pin::new(&mut this.inner).poll_frame(cx)) {
+                Some(Ok(frame)) => match frame.into_data() {
+                    Ok(mut data) => {
+                        let mut result = Ok(());
+                        while data.has_remaining() && result.is_ok() {
+                            let chunk = data.chunk();
+                            result = this.decoder.push(chunk, &mut decoded);
+                            let len = chunk.len();
+                            data.advance(len);
+                        }
+                        result.map_err(DecodeBodyError::Decode)
+                    }
+                    Err(frame) => {
+                        let trailers = frame.into_trailers().unwrap_or_default();
+                        return Poll::Ready(Some(Ok(Frame::trailers(trailers))));
+                    }
+                },
+                Some(Err(err)) => Err(DecodeBodyError::Body(err)),
+                None => {
+                    this.done = true;
+                    this.decoder.finish(&mut decoded).map_err(DecodeBodyError::Decode)
+                }
+            };
+            if let Err(err) = result {
+                this.done = true;
+                return Poll::Ready(Some(Err(err)));
+            }
+            if !decoded.is_empty() {
+                return Poll::Ready(Some(Ok(Frame::data(decoded.into()))));
+            }
+        }
+        Poll::Ready(None)
+    }
+
+    fn is_end_stream(&self) -> bool {
+        self.done
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{uuencode_file, FileOptions};
+    use std::collections::VecDeque;
+
+    /// A body which yields the given frames.
+    struct Frames(VecDeque<Result<Frame<Bytes>, std::io::Error>>);
+
+    impl Body for Frames {
+        type Data = Bytes;
+        type Error = std::io::Error;
+
+        fn poll_frame(mut self: Pin<&mut Self>, _: &mut Context<'_>) -> Poll<Option<Result<Frame<Bytes>, Self::Error>>> {
+            Poll::Ready(self.0.pop_front())
+        }
+    }
+
+    fn collect(mut body: DecodeBody<Frames>) -> Vec<Result<Frame<Bytes>, DecodeBodyError<std::io::Error>>> {
+        let mut frames = Vec::new();
+        while let Some(frame) = futures::executor::block_on(std::future::poll_fn(|cx| Pin::new(&mut body).poll_frame(cx))) {
+            frames.push(frame);
+        }
+        assert!(body.is_end_stream(), "ends the stream");
+        frames
+    }
+
+    /// Tests decoding a body split into frames, and reporting errors
+    #[test]
+    fn test_decode_body() {
+        let source_data = std::fs::read("test_data/the_machine_stops.txt").expect("Can open test data");
+        let encoded = uuencode_file(&source_data, &FileOptions::new("the_machine_stops.txt")).unwrap();
+        let mut frames: VecDeque<_> = encoded.as_bytes().chunks(999).map(|chunk| Ok(Frame::data(Bytes::copy_from_slice(chunk)))).collect();
+        frames.push_back(Ok(Frame::trailers(Default::default())));
+        let frames = collect(DecodeBody::new(Frames(frames), DecodeOptions::new()));
+        let (trailers, data) = frames.split_last().unwrap();
+        assert!(trailers.as_ref().unwrap().is_trailers(), "passes trailers through");
+        let decoded: Vec<u8> = data.iter().flat_map(|frame| frame.as_ref().unwrap().data_ref().unwrap().to_vec()).collect();
+        assert_eq!(decoded, source_data, "can decode a body in frames");
+
+        let truncated = VecDeque::from([Ok(Frame::data(Bytes::copy_from_slice(&encoded.as_bytes()[..1000])))]);
+        let frames = collect(DecodeBody::new(Frames(truncated), DecodeOptions::new()));
+        assert!(matches!(frames.last(), Some(Err(DecodeBodyError::Decode(_)))), "reports a truncated body");
+
+        let failing = VecDeque::from([Err(std::io::Error::other("connection reset"))]);
+        let frames = collect(DecodeBody::new(Frames(failing), DecodeOptions::new()));
+        assert!(matches!(frames.last(), Some(Err(DecodeBodyError::Body(_)))), "passes body errors through");
+    }
+}
@@ -0,0 +1,189 @@
+use alloc::format;
+use alloc::string::String;
+use alloc::vec::Vec;
+use crate::file::numbered_lines;
+use crate::{Message, UUEncodeError};
+
+/// The number of characters per line written by `btoa`.
+const LINE_LEN: usize = 78;
+
+/// The running checksums kept by `btoa` and `atob`, over every byte including the final group's padding.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+struct Checksums {
+    eor: u32,
+    sum: u32,
+    rot: u32,
+}
+
+impl Checksums {
+    fn update(&mut self, byte: u8) {
+        self.eor ^= byte as u32;
+        self.sum = self.sum.wrapping_add(byte as u32).wrapping_add(1);
+        self.rot = self.rot.rotate_left(1).wrapping_add(byte as u32);
+    }
+}
+
+/// Encodes the input data in the format of the Unix `btoa` utility, between `xbtoa Begin` and `xbtoa End` lines.
+/// The end line records the length and checksums of the data, which `btoa_decode` verifies.
+/// Example:
+/// ```rust
+/// let encoded = uuencode_lite::btoa_encode(b"cat");
+/// assert!(encoded.starts_with("xbtoa Begin\n"));
+/// assert!(encoded.contains("xbtoa End N 3 3 "));
+/// ```
+pub fn btoa_encode(data: &[u8]) -> String {
+    let mut encoded = String::with_capacity(data.len().div_ceil(4) * 5 + data.len() / 60 + 80);
+    encoded.push_str("xbtoa Begin\n");
+    let mut checksums = Checksums::default();
+    let mut line_len = 0;
+    for chunk in data.chunks(4) {
+        // the final group is padded with zeros, which are covered by the checksums
+        let mut buffer = [0u8; 4];
+        buffer[..chunk.len()].copy_from_slice(chunk);
+        buffer.iter().for_each(|&byte| checksums.update(byte));
+        let mut value = u32::from_be_bytes(buffer);
+        let mut digits = [b'z'; 5];
+        let digits = if value == 0 {
+            &digits[..1]
+        } else {
+            for digit in digits.iter_mut().rev() {
+                *digit = (value % 85) as u8 + b'!';
+                value /= 85;
+            }
+            &digits[..]
+        };
+        for &digit in digits {
+            encoded.push(digit as char);
+            line_len += 1;
+            if line_len == LINE_LEN {
+                encoded.push('\n');
+                line_len = 0;
+            }
+        }
+    }
+    if line_len > 0 {
+        encoded.push('\n');
+    }
+    encoded.push_str(&format!("xbtoa End N {} {:x} E {:x} S {:x} R {:x}\n", data.len(), data.len(), checksums.eor, checksums.sum, checksums.rot));
+    encoded
+}
+
+/// Parses the values from an `xbtoa End N <len> <hex len> E <eor> S <sum> R <rot>` line.
+fn parse_end(line: &str) -> Option<(usize, Checksums)> {
+    let words: Vec<&str> = line.split_whitespace().collect();
+    let [_, _, "N", len, hex_len, "E", eor, "S", sum, "R", rot] = words[..] else {
+        return None;
+    };
+    let len = len.parse::<usize>().ok()?;
+    let hex = |value: &str| u32::from_str_radix(value, 16).ok();
+    let checksums = Checksums { eor: hex(eor)?, sum: hex(sum)?, rot: hex(rot)? };
+    (usize::from_str_radix(hex_len, 16).ok()? == len).then_some((len, checksums))
+}
+
+/// Decodes the output of the Unix `btoa` utility, as `atob` would, verifying the length and checksums.
+/// Text before the `xbtoa Begin` line is skipped, and `y` is accepted for four spaces as written by later versions.
+/// Errors report the line and character of the offending input.
+/// Example:
+/// ```rust
+/// fn decode() -> Result<(), uuencode_lite::UUEncodeError> {
+///     let encoded = uuencode_lite::btoa_encode(b"cat");
+///     assert_eq!(uuencode_lite::btoa_decode(encoded.as_bytes())?, b"cat");
+///     Ok(())
+/// }
+/// ```
+pub fn btoa_decode(data: &[u8]) -> Result<Vec<u8>, UUEncodeError> {
+    let mut lines = numbered_lines(data).skip_while(|(_, line)| line.trim_ascii_end() != b"xbtoa Begin");
+    if lines.next().is_none() {
+        return Err(UUEncodeError::new(0, 0, "Missing xbtoa Begin line"));
+    }
+
+    let mut decoded = Vec::with_capacity(data.len() / 5 * 4);
+    let mut checksums = Checksums::default();
+    let mut group = [0u8; 5];
+    let mut filled = 0;
+    let mut end_line = 0;
+    let mut end = None;
+    for (cur_line, line) in lines.by_ref() {
+        end_line = cur_line;
+        if line.starts_with(b"xbtoa End") {
+            end = Some(String::from_utf8_lossy(line).into_owned());
+            break;
+        }
+        for (cur_char, &ch) in line.iter().enumerate() {
+            let value = match ch {
+                b'z' | b'y' if filled == 0 => {
+                    if ch == b'z' { 0 } else { 0x20202020 }
+                }
+                b'!'..=b'u' => {
+                    group[filled] = ch - b'!';
+                    filled += 1;
+                    if filled < 5 {
+                        continue;
+                    }
+                    filled = 0;
+                    match group.iter().try_fold(0u32, |value, &digit| value.checked_mul(85)?.checked_add(digit as u32)) {
+                        Some(value) => value,
+                        None => return Err(UUEncodeError::new(cur_line, cur_char, "btoa group out of range")),
+                    }
+                }
+                b' ' | b'\t' => continue,
+                _ => return Err(UUEncodeError::new(cur_line, cur_char, Message::InvalidCharacter(ch))),
+            };
+            let bytes = value.to_be_bytes();
+            bytes.iter().for_each(|&byte| checksums.update(byte));
+            decoded.extend_from_slice(&bytes);
+        }
+    }
+
+    let Some(end) = end else {
+        return Err(UUEncodeError::new(end_line, 0, "Missing xbtoa End line"));
+    };
+    if filled != 0 {
+        return Err(UUEncodeError::new(end_line, 0, "Incomplete btoa group at end of input"));
+    }
+    let Some((len, expected)) = parse_end(&end) else {
+        return Err(UUEncodeError::new(end_line, 0, "Malformed xbtoa End line"));
+    };
+    // only the final group may be padding
+    if len > decoded.len() || decoded.len() - len >= 4 {
+        return Err(UUEncodeError::new(end_line, 0, format!("Length mismatch: expected {}, decoded {}", len, decoded.len())));
+    }
+    if checksums != expected {
+        return Err(UUEncodeError::new(end_line, 0, "btoa checksum mismatch"));
+    }
+    decoded.truncate(len);
+    Ok(decoded)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Tests round-tripping random data
+    #[test]
+    fn test_btoa_rt() {
+        let source_data = std::fs::read("test_data/random_data.bin").expect("Can open test data");
+        for len in [0, 1, 3, 4, 5, source_data.len()] {
+            let encoded = btoa_encode(&source_data[..len]);
+            assert!(encoded.lines().all(|line| line.len() <= LINE_LEN || line.starts_with("xbtoa End")), "wraps lines at 78 characters");
+            assert_eq!(btoa_decode(encoded.as_bytes()).unwrap(), &source_data[..len], "can round-trip {} bytes", len);
+        }
+        let zeros = btoa_encode(&[0; 8]);
+        assert!(zeros.contains("\nzz\n"), "abbreviates zero groups");
+    }
+
+    /// Tests detection of corruption
+    #[test]
+    fn test_btoa_errors() {
+        let encoded = btoa_encode(b"the machine stops");
+        let corrupted = encoded.replacen("xbtoa Begin\n", "xbtoa Begin\nz", 1);
+        assert!(btoa_decode(corrupted.as_bytes()).is_err(), "rejects corrupted data");
+        let truncated = &encoded[..encoded.find("xbtoa End").unwrap()];
+        assert!(btoa_decode(truncated.as_bytes()).is_err(), "requires the end line");
+        let bad_length = encoded.replace("N 17 11", "N 16 10");
+        assert!(btoa_decode(bad_length.as_bytes()).is_err(), "verifies the length");
+        assert!(btoa_decode(b"no btoa here").is_err(), "requires the begin line");
+        let err = btoa_decode(b"header\nxbtoa Begin\n!!{\n").unwrap_err();
+        assert_eq!((err.line, err.character), (2, 2), "reports the position of an invalid character");
+    }
+}
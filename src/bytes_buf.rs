@@ -0,0 +1,112 @@
+use crate::{uuencoded_len, DecodeOptions, EncodeOptions, SliceDecoder, SliceEncoder, UUEncodeError};
+use bytes::{Buf, BytesMut};
+
+/// The most bytes one decoded line holds.
+const DECODED_LEN: usize = 63;
+
+/// Encodes the input data as `uuencode` does, appending the text to `encoded`. The input may be split across
+/// several chunks, such as one `Bytes` chained onto another, and is encoded straight into `encoded`.
+/// Example:
+/// ```rust
+/// fn encode() -> Result<(), uuencode_lite::UUEncodeError> {
+///     use bytes::Buf;
+///     let mut encoded = bytes::BytesMut::new();
+///     uuencode_lite::uuencode_to_bytes((&b"c"[..]).chain(&b"at"[..]), &mut encoded)?;
+///     assert_eq!(encoded, &b"#8V%T"[..]);
+///     Ok(())
+/// }
+/// ```
+pub fn uuencode_to_bytes(data: impl Buf, encoded: &mut BytesMut) -> Result<(), UUEncodeError> {
+    uuencode_to_bytes_with(data, &EncodeOptions::default(), encoded)
+}
+
+/// Encodes the input data as `uuencode_to_bytes`, using the given options.
+pub fn uuencode_to_bytes_with(mut data: impl Buf, options: &EncodeOptions, encoded: &mut BytesMut) -> Result<(), UUEncodeError> {
+    let data_len = data.remaining();
+    if data_len == 0 {
+        return Ok(());
+    }
+    // the encoder ends every line with a newline, where `uuencode` leaves the last one off
+    let start = encoded.len();
+    encoded.resize(start + uuencoded_len(data_len, options) + 1, 0);
+    let mut encoder = SliceEncoder::new(options);
+    let mut written = start;
+    while data.has_remaining() {
+        let (consumed, len) = encoder.push(data.chunk(), &mut encoded[written..]);
+        data.advance(consumed);
+        written += len;
+    }
+    written += encoder.finish(&mut encoded[written..])?;
+    encoded.truncate(written - 1);
+    Ok(())
+}
+
+/// Decodes uuencoded data as `uudecode_with` does, appending the bytes to `decoded`. The input may be split across
+/// several chunks, with lines split between them, and is decoded straight into `decoded`.
+/// Example:
+/// ```rust
+/// fn decode() -> Result<(), uuencode_lite::UUEncodeError> {
+///     use bytes::Buf;
+///     let mut decoded = bytes::BytesMut::new();
+///     uuencode_lite::uudecode_to_bytes((&b"#8V"[..]).chain(&b"%T\n"[..]), &mut decoded)?;
+///     assert_eq!(decoded, &b"cat"[..]);
+///     Ok(())
+/// }
+/// ```
+pub fn uudecode_to_bytes(data: impl Buf, decoded: &mut BytesMut) -> Result<(), UUEncodeError> {
+    uudecode_to_bytes_with(data, &DecodeOptions::default(), decoded)
+}
+
+/// Decodes uuencoded data as `uudecode_to_bytes`, using the given options. On error, `decoded` is left as it was.
+pub fn uudecode_to_bytes_with(mut data: impl Buf, options: &DecodeOptions, decoded: &mut BytesMut) -> Result<(), UUEncodeError> {
+    let original_len = decoded.len();
+    let mut decoder = SliceDecoder::new(options);
+    let mut written = original_len;
+    let result = loop {
+        let chunk = data.chunk();
+        // decoding never grows the text, except for the line carried over from the last chunk
+        decoded.resize(written + chunk.len() + DECODED_LEN, 0);
+        if chunk.is_empty() {
+            break decoder.finish(&mut decoded[written..]).map(|len| written += len);
+        }
+        match decoder.push(chunk, &mut decoded[written..]) {
+            Ok((consumed, len)) => {
+                data.advance(consumed);
+                written += len;
+            }
+            Err(err) => break Err(err),
+        }
+    };
+    decoded.truncate(if result.is_ok() { written } else { original_len });
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Tests encoding and decoding input split into chunks which do not line up with lines
+    #[test]
+    fn test_bytes() {
+        let source_data = std::fs::read("test_data/random_data.bin").expect("Can open test data");
+        let expected = std::fs::read("test_data/random_data.bin.uu").expect("Can open test data");
+        let expected = expected.strip_suffix(b"\n").unwrap_or(&expected);
+
+        let mut encoded = BytesMut::from(&b"prefix "[..]);
+        uuencode_to_bytes(source_data[..100].chain(&source_data[100..]), &mut encoded).unwrap();
+        assert_eq!(&encoded[7..], expected, "can encode a chained buffer");
+        let mut encoded = BytesMut::new();
+        uuencode_to_bytes(&b""[..], &mut encoded).unwrap();
+        assert!(encoded.is_empty(), "encodes nothing to nothing");
+
+        // chunks which split lines, including a one-byte chunk
+        let chunks = expected[..1000].chain(&expected[1000..1001]).chain(&expected[1001..]);
+        let mut decoded = BytesMut::new();
+        uudecode_to_bytes(chunks, &mut decoded).unwrap();
+        assert_eq!(decoded, source_data, "can decode a chained buffer");
+
+        let mut decoded = BytesMut::from(&b"cat"[..]);
+        assert!(uudecode_to_bytes(&b"#8V%T\n#8V\x01T\n"[..], &mut decoded).is_err(), "reports invalid input");
+        assert_eq!(decoded, &b"cat"[..], "leaves the output as it was on error");
+    }
+}
@@ -0,0 +1,44 @@
+use alloc::sync::Arc;
+use core::sync::atomic::{AtomicBool, Ordering};
+
+/// A shared flag for stopping a long-running encode or decode, such as when a client disconnects.
+/// Clones share the same flag, so one can be given to the options while another is kept to cancel with.
+/// The flag is checked between lines; once it is set, the operation fails with an error of kind `ErrorKind::Cancelled`.
+/// Example:
+/// ```rust
+/// let token = uuencode_lite::CancelToken::new();
+/// let options = uuencode_lite::DecodeOptions::new().cancel_token(token.clone());
+/// token.cancel();
+/// let err = uuencode_lite::uudecode_file_with(b"begin 644 cat.txt\n#8V%T\n`\nend\n", &options).unwrap_err();
+/// assert_eq!(err.kind(), uuencode_lite::ErrorKind::Cancelled);
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct CancelToken {
+    cancelled: Arc<AtomicBool>,
+}
+
+impl CancelToken {
+    /// Creates a token which has not been cancelled.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Cancels every operation using this token, or a clone of it.
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::Relaxed);
+    }
+
+    /// Whether the token has been cancelled.
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::Relaxed)
+    }
+}
+
+/// Tokens are equal if they share the same flag.
+impl PartialEq for CancelToken {
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.cancelled, &other.cancelled)
+    }
+}
+
+impl Eq for CancelToken {}
@@ -0,0 +1,140 @@
+use alloc::format;
+use alloc::vec::Vec;
+use crate::{Message, Table, UUEncodeError};
+
+/// Identifies a decoder checkpoint, followed by the format version.
+const MAGIC: &[u8; 4] = b"UUck";
+const VERSION: u8 = 1;
+
+/// Writes the fields of a checkpoint in a compact little-endian format.
+pub(crate) struct CheckpointWriter {
+    data: Vec<u8>,
+}
+
+impl CheckpointWriter {
+    pub(crate) fn new() -> Self {
+        let mut data = MAGIC.to_vec();
+        data.push(VERSION);
+        CheckpointWriter { data }
+    }
+
+    pub(crate) fn u8(&mut self, value: u8) {
+        self.data.push(value);
+    }
+
+    pub(crate) fn bool(&mut self, value: bool) {
+        self.data.push(value as u8);
+    }
+
+    pub(crate) fn u32(&mut self, value: u32) {
+        self.data.extend_from_slice(&value.to_le_bytes());
+    }
+
+    pub(crate) fn u64(&mut self, value: u64) {
+        self.data.extend_from_slice(&value.to_le_bytes());
+    }
+
+    /// Writes a length-prefixed byte string.
+    pub(crate) fn bytes(&mut self, value: &[u8]) {
+        self.u64(value.len() as u64);
+        self.data.extend_from_slice(value);
+    }
+
+    /// Writes a table as its alphabet, or a marker for the standard table, whose decoding is more lenient.
+    pub(crate) fn table(&mut self, table: &Table) {
+        if *table == Table::STANDARD {
+            self.u8(0);
+        } else {
+            self.u8(1);
+            self.data.extend_from_slice(table.alphabet());
+        }
+    }
+
+    pub(crate) fn finish(self) -> Vec<u8> {
+        self.data
+    }
+}
+
+/// Reads the fields written by a `CheckpointWriter`, reporting the byte offset of anything malformed.
+pub(crate) struct CheckpointReader<'a> {
+    data: &'a [u8],
+    position: usize,
+}
+
+impl<'a> CheckpointReader<'a> {
+    pub(crate) fn new(data: &'a [u8]) -> Result<Self, UUEncodeError> {
+        let mut reader = CheckpointReader { data, position: 0 };
+        if reader.take(MAGIC.len())? != MAGIC {
+            return Err(UUEncodeError::new(0, 0, "Not a decoder checkpoint"));
+        }
+        let version = reader.u8()?;
+        if version != VERSION {
+            return Err(UUEncodeError::new(0, MAGIC.len(), format!("Unsupported checkpoint version {}", version)));
+        }
+        Ok(reader)
+    }
+
+    /// An error at the current position.
+    pub(crate) fn error(&self, msg: impl Into<Message>) -> UUEncodeError {
+        UUEncodeError::new(0, self.position, msg)
+    }
+
+    fn take(&mut self, len: usize) -> Result<&'a [u8], UUEncodeError> {
+        if self.data.len() - self.position < len {
+            return Err(UUEncodeError::new(0, self.data.len(), "Truncated checkpoint"));
+        }
+        let value = &self.data[self.position..self.position + len];
+        self.position += len;
+        Ok(value)
+    }
+
+    pub(crate) fn u8(&mut self) -> Result<u8, UUEncodeError> {
+        Ok(self.take(1)?[0])
+    }
+
+    pub(crate) fn bool(&mut self) -> Result<bool, UUEncodeError> {
+        match self.u8()? {
+            0 => Ok(false),
+            1 => Ok(true),
+            _ => Err(UUEncodeError::new(0, self.position - 1, "Invalid flag in checkpoint")),
+        }
+    }
+
+    pub(crate) fn u32(&mut self) -> Result<u32, UUEncodeError> {
+        Ok(u32::from_le_bytes(self.take(4)?.try_into().expect("took 4 bytes")))
+    }
+
+    pub(crate) fn u64(&mut self) -> Result<u64, UUEncodeError> {
+        Ok(u64::from_le_bytes(self.take(8)?.try_into().expect("took 8 bytes")))
+    }
+
+    /// Reads a count, such as a line number, which must fit in a `usize`.
+    pub(crate) fn usize(&mut self) -> Result<usize, UUEncodeError> {
+        let value = self.u64()?;
+        usize::try_from(value).map_err(|_| UUEncodeError::new(0, self.position - 8, "Count too large in checkpoint"))
+    }
+
+    pub(crate) fn bytes(&mut self) -> Result<&'a [u8], UUEncodeError> {
+        let len = self.usize()?;
+        self.take(len)
+    }
+
+    pub(crate) fn table(&mut self) -> Result<Table, UUEncodeError> {
+        match self.u8()? {
+            0 => Ok(Table::STANDARD),
+            1 => {
+                let start = self.position;
+                Table::from_alphabet(self.take(64)?).map_err(|err| UUEncodeError::new(0, start + err.character, err.msg))
+            }
+            _ => Err(UUEncodeError::new(0, self.position - 1, "Invalid table in checkpoint")),
+        }
+    }
+
+    /// Checks that the whole checkpoint was read.
+    pub(crate) fn finish(self) -> Result<(), UUEncodeError> {
+        if self.position != self.data.len() {
+            return Err(self.error("Trailing data in checkpoint"));
+        }
+        Ok(())
+    }
+}
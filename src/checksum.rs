@@ -0,0 +1,194 @@
+use alloc::format;
+use alloc::string::String;
+use crate::checkpoint::{CheckpointReader, CheckpointWriter};
+use crate::UUEncodeError;
+
+/// A whole-file checksum, written on a trailer line after the end of an encoded file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Checksum {
+    /// The 16-bit BSD checksum, as printed by `sum -r`, written as `sum -r/size <sum>/<size> entire input file`.
+    BsdSum,
+    /// The IEEE CRC-32 used by zip and gzip, written as `crc32/size <crc>/<size> entire input file`.
+    Crc32,
+}
+
+impl Checksum {
+    /// Computes the checksum of the data.
+    pub fn compute(self, data: &[u8]) -> u32 {
+        match self {
+            Checksum::BsdSum => bsd_sum(data) as u32,
+            Checksum::Crc32 => {
+                let mut crc = Crc32::new();
+                crc.update(data);
+                crc.finish()
+            }
+        }
+    }
+
+    /// Parses a trailer line into its checksum kind, checksum and size.
+    pub(crate) fn parse_trailer(line: &[u8]) -> Option<(Checksum, u32, usize)> {
+        let line = core::str::from_utf8(line).ok()?;
+        let (checksum, rest) = if let Some(rest) = line.strip_prefix("sum -r/size ") {
+            (Checksum::BsdSum, rest)
+        } else if let Some(rest) = line.strip_prefix("crc32/size ") {
+            (Checksum::Crc32, rest)
+        } else {
+            return None;
+        };
+        let (value, size) = rest.split_whitespace().next()?.split_once('/')?;
+        let value = match checksum {
+            Checksum::BsdSum => value.parse().ok()?,
+            Checksum::Crc32 => u32::from_str_radix(value, 16).ok()?,
+        };
+        Some((checksum, value, size.parse().ok()?))
+    }
+}
+
+/// Computes the 16-bit BSD checksum, as printed by `sum -r`.
+pub(crate) fn bsd_sum(data: &[u8]) -> u16 {
+    bsd_sum_update(0, data)
+}
+
+/// Continues a 16-bit BSD checksum over more data.
+fn bsd_sum_update(sum: u16, data: &[u8]) -> u16 {
+    data.iter().fold(sum, |sum, &byte| sum.rotate_right(1).wrapping_add(byte as u16))
+}
+
+/// A whole-file checksum computed incrementally, for data that arrives in pieces.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct ChecksumState {
+    checksum: Checksum,
+    sum: u16,
+    crc: Crc32,
+    len: usize,
+}
+
+impl ChecksumState {
+    pub(crate) fn new(checksum: Checksum) -> Self {
+        ChecksumState { checksum, sum: 0, crc: Crc32::new(), len: 0 }
+    }
+
+    pub(crate) fn update(&mut self, data: &[u8]) {
+        match self.checksum {
+            Checksum::BsdSum => self.sum = bsd_sum_update(self.sum, data),
+            Checksum::Crc32 => self.crc.update(data),
+        }
+        self.len += data.len();
+    }
+
+    /// The number of bytes seen so far.
+    pub(crate) fn len(&self) -> usize {
+        self.len
+    }
+
+    /// The checksum of the data seen so far.
+    pub(crate) fn value(&self) -> u32 {
+        match self.checksum {
+            Checksum::BsdSum => self.sum as u32,
+            Checksum::Crc32 => self.crc.finish(),
+        }
+    }
+
+    /// Writes the state to a checkpoint.
+    pub(crate) fn save(&self, writer: &mut CheckpointWriter) {
+        writer.u8(self.checksum as u8);
+        writer.u32(self.sum as u32);
+        writer.u32(self.crc.state);
+        writer.u64(self.len as u64);
+    }
+
+    /// Reads a state from a checkpoint.
+    pub(crate) fn load(reader: &mut CheckpointReader) -> Result<Self, UUEncodeError> {
+        let checksum = match reader.u8()? {
+            0 => Checksum::BsdSum,
+            1 => Checksum::Crc32,
+            _ => return Err(reader.error("Invalid checksum in checkpoint")),
+        };
+        let sum = u16::try_from(reader.u32()?).map_err(|_| reader.error("Invalid checksum in checkpoint"))?;
+        let crc = Crc32 { state: reader.u32()? };
+        Ok(ChecksumState { checksum, sum, crc, len: reader.usize()? })
+    }
+
+    /// Formats the trailer line for the data seen so far, without a newline.
+    pub(crate) fn trailer(&self) -> String {
+        format_trailer(self.checksum, self.value(), self.len)
+    }
+
+    /// The length of the longest trailer line for a file of `len` bytes, without a newline.
+    pub(crate) fn max_trailer_len(&self, len: usize) -> usize {
+        // the widest BSD sum has five digits, and a CRC is always eight hex digits
+        format_trailer(self.checksum, u16::MAX.into(), len).len()
+    }
+}
+
+/// Formats a trailer line for a file of `len` bytes with the given checksum value.
+fn format_trailer(checksum: Checksum, value: u32, len: usize) -> String {
+    match checksum {
+        Checksum::BsdSum => format!("sum -r/size {}/{} entire input file", value, len),
+        Checksum::Crc32 => format!("crc32/size {:08x}/{} entire input file", value, len),
+    }
+}
+
+const CRC32_TABLE: [u32; 256] = {
+    let mut table = [0u32; 256];
+    let mut idx = 0;
+    while idx < 256 {
+        let mut crc = idx as u32;
+        let mut bit = 0;
+        while bit < 8 {
+            crc = if crc & 1 == 1 { (crc >> 1) ^ 0xEDB8_8320 } else { crc >> 1 };
+            bit += 1;
+        }
+        table[idx] = crc;
+        idx += 1;
+    }
+    table
+};
+
+/// An incremental IEEE CRC-32.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct Crc32 {
+    state: u32,
+}
+
+impl Crc32 {
+    pub(crate) fn new() -> Self {
+        Crc32 { state: 0xFFFF_FFFF }
+    }
+
+    pub(crate) fn update(&mut self, data: &[u8]) {
+        for &byte in data {
+            self.state = CRC32_TABLE[((self.state ^ byte as u32) & 0xFF) as usize] ^ (self.state >> 8);
+        }
+    }
+
+    pub(crate) fn finish(&self) -> u32 {
+        !self.state
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Tests the checksums against known values
+    #[test]
+    fn test_checksums() {
+        assert_eq!(Checksum::Crc32.compute(b"123456789"), 0xCBF4_3926, "can compute a crc32");
+        // from `printf 'cat' | sum -r`
+        assert_eq!(Checksum::BsdSum.compute(b"cat"), 16573, "can compute a bsd sum");
+    }
+
+    /// Tests that trailers round-trip through formatting and parsing
+    #[test]
+    fn test_trailers() {
+        for checksum in [Checksum::BsdSum, Checksum::Crc32] {
+            let mut state = ChecksumState::new(checksum);
+            state.update(b"c");
+            state.update(b"at");
+            let trailer = state.trailer();
+            assert_eq!(Checksum::parse_trailer(trailer.as_bytes()), Some((checksum, checksum.compute(b"cat"), 3)), "can parse {}", trailer);
+        }
+        assert_eq!(Checksum::parse_trailer(b"sum -r/size abc/3"), None, "rejects a malformed trailer");
+    }
+}
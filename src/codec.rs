@@ -0,0 +1,172 @@
+use crate::decoder::Decoder;
+use crate::encoder::Encoder;
+use crate::{DecodeEvent, DecodeOptions, FileOptions, UUEncodeError};
+use bytes::{Buf, Bytes, BytesMut};
+use std::io;
+use std::string::String;
+use std::vec::Vec;
+
+/// A `tokio_util` codec encoder which uuencodes outgoing bytes as a complete file, as `uuencode_file` does,
+/// writing each completed line to the frame buffer. Send an empty item to end the file, writing the last
+/// line, the terminator and `end`; anything sent afterwards is ignored.
+/// Example:
+/// ```rust
+/// fn encode() -> std::io::Result<()> {
+///     use tokio_util::codec::Encoder;
+///     let mut codec = uuencode_lite::UUEncodeCodec::new(uuencode_lite::FileOptions::new("cat.txt"))?;
+///     let mut encoded = bytes::BytesMut::new();
+///     codec.encode(bytes::Bytes::from_static(b"cat"), &mut encoded)?;
+///     codec.encode(bytes::Bytes::new(), &mut encoded)?;
+///     assert_eq!(encoded, &b"begin 644 cat.txt\n#8V%T\n`\nend\n"[..]);
+///     Ok(())
+/// }
+/// ```
+#[derive(Debug)]
+pub struct UUEncodeCodec {
+    encoder: Encoder,
+    /// Encoded text waiting to be copied into the frame buffer.
+    buffer: String,
+}
+
+impl UUEncodeCodec {
+    /// Creates a codec that encodes a file with the given options.
+    /// Fails if the file name is invalid, as `uuencode_file` does.
+    pub fn new(options: FileOptions) -> Result<Self, UUEncodeError> {
+        Ok(UUEncodeCodec { encoder: Encoder::new(options)?, buffer: String::new() })
+    }
+}
+
+impl tokio_util::codec::Encoder<Bytes> for UUEncodeCodec {
+    type Error = io::Error;
+
+    fn encode(&mut self, item: Bytes, dst: &mut BytesMut) -> Result<(), Self::Error> {
+        if item.is_empty() {
+            self.encoder.finish(&mut self.buffer)?;
+        } else {
+            self.encoder.push(&item, &mut self.buffer)?;
+        }
+        dst.extend_from_slice(self.buffer.as_bytes());
+        self.buffer.clear();
+        Ok(())
+    }
+}
+
+/// A `tokio_util` codec decoder which decodes a complete uuencoded file, as `uudecode_file_with` does,
+/// yielding the decoded bytes of every line that has completely arrived as one frame.
+/// Text after the end of the file is left in the buffer, and the end of the stream must not come before the
+/// end of the file. Decoding errors are `io::Error`s of kind `InvalidData`, with the `UUEncodeError` as their source.
+/// Example:
+/// ```rust
+/// fn decode() -> std::io::Result<()> {
+///     use tokio_util::codec::Decoder;
+///     let mut codec = uuencode_lite::UUDecodeCodec::new(uuencode_lite::DecodeOptions::new());
+///     let mut encoded = bytes::BytesMut::from(&b"begin 644 cat.txt\n#8V%T\n#8V"[..]);
+///     assert_eq!(codec.decode(&mut encoded)?.as_deref(), Some(&b"cat"[..]));
+///     encoded.extend_from_slice(b"%T\n`\nend\n");
+///     assert_eq!(codec.decode(&mut encoded)?.as_deref(), Some(&b"cat"[..]));
+///     assert_eq!(codec.decode_eof(&mut encoded)?, None);
+///     assert_eq!(codec.name(), Some("cat.txt"));
+///     Ok(())
+/// }
+/// ```
+#[derive(Debug)]
+pub struct UUDecodeCodec {
+    decoder: Decoder,
+    /// Whether the end of the stream has been checked.
+    finished: bool,
+}
+
+impl UUDecodeCodec {
+    /// Creates a codec that decodes a file with the given options.
+    pub fn new(options: DecodeOptions) -> Self {
+        UUDecodeCodec { decoder: Decoder::new(options), finished: false }
+    }
+
+    /// The file name from the `begin` line, once it has been decoded.
+    pub fn name(&self) -> Option<&str> {
+        self.decoder.header().map(|(_, name)| name)
+    }
+
+    /// The permission bits from the `begin` line, once it has been decoded.
+    pub fn mode(&self) -> Option<u32> {
+        self.decoder.header().map(|(mode, _)| mode)
+    }
+}
+
+impl tokio_util::codec::Decoder for UUDecodeCodec {
+    type Item = Bytes;
+    type Error = io::Error;
+
+    fn decode(&mut self, src: &mut BytesMut) -> Result<Option<Bytes>, Self::Error> {
+        let mut decoded = Vec::new();
+        let mut consumed = 0;
+        loop {
+            let (len, event) = self.decoder.consume(&src[consumed..], &mut decoded)?;
+            consumed += len;
+            if matches!(event, DecodeEvent::NeedMore | DecodeEvent::End) {
+                break;
+            }
+        }
+        src.advance(consumed);
+        Ok((!decoded.is_empty()).then(|| decoded.into()))
+    }
+
+    fn decode_eof(&mut self, src: &mut BytesMut) -> Result<Option<Bytes>, Self::Error> {
+        if let Some(frame) = self.decode(src)? {
+            return Ok(Some(frame));
+        }
+        if self.finished {
+            return Ok(None);
+        }
+        self.finished = true;
+        let mut decoded = Vec::new();
+        if !self.decoder.is_done() {
+            self.decoder.push(src, &mut decoded)?;
+        }
+        src.clear();
+        self.decoder.finish(&mut decoded)?;
+        Ok((!decoded.is_empty()).then(|| decoded.into()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{uuencode_file, Checksum};
+    use tokio_util::codec::{Decoder as _, Encoder as _};
+
+    /// Tests encoding and decoding a file through the codecs, in chunks
+    #[test]
+    fn test_codec_rt() {
+        let source_data = std::fs::read("test_data/the_machine_stops.txt").expect("Can open test data");
+        let options = FileOptions::new("the_machine_stops.txt").trailer(Some(Checksum::BsdSum));
+        let expected = uuencode_file(&source_data, &options).unwrap();
+
+        let mut codec = UUEncodeCodec::new(options).unwrap();
+        let mut encoded = BytesMut::new();
+        for chunk in source_data.chunks(1000) {
+            codec.encode(Bytes::copy_from_slice(chunk), &mut encoded).unwrap();
+        }
+        codec.encode(Bytes::new(), &mut encoded).unwrap();
+        assert_eq!(encoded, expected.as_bytes(), "can encode in chunks");
+
+        let mut codec = UUDecodeCodec::new(DecodeOptions::new().verify_trailer(true));
+        let mut src = BytesMut::new();
+        let mut decoded = Vec::new();
+        for chunk in expected.as_bytes().chunks(999) {
+            src.extend_from_slice(chunk);
+            while let Some(frame) = codec.decode(&mut src).unwrap() {
+                decoded.extend_from_slice(&frame);
+            }
+        }
+        while let Some(frame) = codec.decode_eof(&mut src).unwrap() {
+            decoded.extend_from_slice(&frame);
+        }
+        assert_eq!(decoded, source_data, "can decode in chunks");
+
+        let mut codec = UUDecodeCodec::new(DecodeOptions::new());
+        let mut src = BytesMut::from(&expected.as_bytes()[..1000]);
+        codec.decode(&mut src).unwrap();
+        assert_eq!(codec.decode_eof(&mut src).unwrap_err().kind(), io::ErrorKind::InvalidData, "rejects a truncated stream");
+    }
+}
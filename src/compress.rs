@@ -0,0 +1,368 @@
+use crate::{DecodeOptions, DecodeReader, DecodeWriter, EncodeReader, EncodeWriter, FileOptions, UUEncodeError};
+use flate2::Compression;
+use std::io::{self, Read, Write};
+
+/// The compressed format used by the compressing encoders and decompressing decoders.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CompressionFormat {
+    /// gzip, as `gzip` writes it. Decoding accepts several concatenated gzip members.
+    Gzip,
+    /// zlib, from RFC 1950.
+    Zlib,
+    /// Raw DEFLATE, from RFC 1951, with no header or checksum.
+    Deflate,
+}
+
+/// A compressing writer of any of the formats.
+#[derive(Debug)]
+enum Compressor<W: Write> {
+    Gzip(flate2::write::GzEncoder<W>),
+    Zlib(flate2::write::ZlibEncoder<W>),
+    Deflate(flate2::write::DeflateEncoder<W>),
+}
+
+impl<W: Write> Compressor<W> {
+    fn new(inner: W, format: CompressionFormat, level: Compression) -> Self {
+        match format {
+            CompressionFormat::Gzip => Compressor::Gzip(flate2::write::GzEncoder::new(inner, level)),
+            CompressionFormat::Zlib => Compressor::Zlib(flate2::write::ZlibEncoder::new(inner, level)),
+            CompressionFormat::Deflate => Compressor::Deflate(flate2::write::DeflateEncoder::new(inner, level)),
+        }
+    }
+
+    fn finish(self) -> io::Result<W> {
+        match self {
+            Compressor::Gzip(writer) => writer.finish(),
+            Compressor::Zlib(writer) => writer.finish(),
+            Compressor::Deflate(writer) => writer.finish(),
+        }
+    }
+
+    fn writer(&mut self) -> &mut dyn Write {
+        match self {
+            Compressor::Gzip(writer) => writer,
+            Compressor::Zlib(writer) => writer,
+            Compressor::Deflate(writer) => writer,
+        }
+    }
+}
+
+/// A decompressing writer of any of the formats.
+#[derive(Debug)]
+enum Decompressor<W: Write> {
+    Gzip(flate2::write::MultiGzDecoder<W>),
+    Zlib(flate2::write::ZlibDecoder<W>),
+    Deflate(flate2::write::DeflateDecoder<W>),
+}
+
+impl<W: Write> Decompressor<W> {
+    fn new(inner: W, format: CompressionFormat) -> Self {
+        match format {
+            CompressionFormat::Gzip => Decompressor::Gzip(flate2::write::MultiGzDecoder::new(inner)),
+            CompressionFormat::Zlib => Decompressor::Zlib(flate2::write::ZlibDecoder::new(inner)),
+            CompressionFormat::Deflate => Decompressor::Deflate(flate2::write::DeflateDecoder::new(inner)),
+        }
+    }
+
+    fn finish(self) -> io::Result<W> {
+        match self {
+            Decompressor::Gzip(writer) => writer.finish(),
+            Decompressor::Zlib(writer) => writer.finish(),
+            Decompressor::Deflate(writer) => writer.finish(),
+        }
+    }
+
+    fn writer(&mut self) -> &mut dyn Write {
+        match self {
+            Decompressor::Gzip(writer) => writer,
+            Decompressor::Zlib(writer) => writer,
+            Decompressor::Deflate(writer) => writer,
+        }
+    }
+}
+
+impl<W: Write> Write for Decompressor<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.writer().write(buf)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.writer().flush()
+    }
+}
+
+/// A compressing or decompressing reader of any of the formats.
+#[derive(Debug)]
+enum CodecReader<R: Read> {
+    GzipEncoder(flate2::read::GzEncoder<R>),
+    ZlibEncoder(flate2::read::ZlibEncoder<R>),
+    DeflateEncoder(flate2::read::DeflateEncoder<R>),
+    GzipDecoder(flate2::read::MultiGzDecoder<R>),
+    ZlibDecoder(flate2::read::ZlibDecoder<R>),
+    DeflateDecoder(flate2::read::DeflateDecoder<R>),
+}
+
+impl<R: Read> CodecReader<R> {
+    fn compressor(inner: R, format: CompressionFormat, level: Compression) -> Self {
+        match format {
+            CompressionFormat::Gzip => CodecReader::GzipEncoder(flate2::read::GzEncoder::new(inner, level)),
+            CompressionFormat::Zlib => CodecReader::ZlibEncoder(flate2::read::ZlibEncoder::new(inner, level)),
+            CompressionFormat::Deflate => CodecReader::DeflateEncoder(flate2::read::DeflateEncoder::new(inner, level)),
+        }
+    }
+
+    fn decompressor(inner: R, format: CompressionFormat) -> Self {
+        match format {
+            CompressionFormat::Gzip => CodecReader::GzipDecoder(flate2::read::MultiGzDecoder::new(inner)),
+            CompressionFormat::Zlib => CodecReader::ZlibDecoder(flate2::read::ZlibDecoder::new(inner)),
+            CompressionFormat::Deflate => CodecReader::DeflateDecoder(flate2::read::DeflateDecoder::new(inner)),
+        }
+    }
+
+    fn get_ref(&self) -> &R {
+        match self {
+            CodecReader::GzipEncoder(reader) => reader.get_ref(),
+            CodecReader::ZlibEncoder(reader) => reader.get_ref(),
+            CodecReader::DeflateEncoder(reader) => reader.get_ref(),
+            CodecReader::GzipDecoder(reader) => reader.get_ref(),
+            CodecReader::ZlibDecoder(reader) => reader.get_ref(),
+            CodecReader::DeflateDecoder(reader) => reader.get_ref(),
+        }
+    }
+
+    fn into_inner(self) -> R {
+        match self {
+            CodecReader::GzipEncoder(reader) => reader.into_inner(),
+            CodecReader::ZlibEncoder(reader) => reader.into_inner(),
+            CodecReader::DeflateEncoder(reader) => reader.into_inner(),
+            CodecReader::GzipDecoder(reader) => reader.into_inner(),
+            CodecReader::ZlibDecoder(reader) => reader.into_inner(),
+            CodecReader::DeflateDecoder(reader) => reader.into_inner(),
+        }
+    }
+}
+
+impl<R: Read> Read for CodecReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        match self {
+            CodecReader::GzipEncoder(reader) => reader.read(buf),
+            CodecReader::ZlibEncoder(reader) => reader.read(buf),
+            CodecReader::DeflateEncoder(reader) => reader.read(buf),
+            CodecReader::GzipDecoder(reader) => reader.read(buf),
+            CodecReader::ZlibDecoder(reader) => reader.read(buf),
+            CodecReader::DeflateDecoder(reader) => reader.read(buf),
+        }
+    }
+}
+
+/// A writer that compresses everything written to it and uuencodes the compressed data as a complete file, as
+/// `EncodeWriter` does, like piping `gzip` into `uuencode`. Call `finish` once all the data is written.
+/// Example:
+/// ```rust
+/// fn encode() -> std::io::Result<()> {
+///     use std::io::Write;
+///     let options = uuencode_lite::FileOptions::new("cat.txt.gz");
+///     let mut writer = uuencode_lite::CompressEncodeWriter::new(Vec::new(), options, uuencode_lite::CompressionFormat::Gzip, flate2::Compression::best())?;
+///     writer.write_all(&b"cat".repeat(1000))?;
+///     let encoded = writer.finish()?;
+///     assert!(encoded.len() < 3000);
+///     Ok(())
+/// }
+/// ```
+#[derive(Debug)]
+pub struct CompressEncodeWriter<W: Write> {
+    compressor: Compressor<EncodeWriter<W>>,
+}
+
+impl<W: Write> CompressEncodeWriter<W> {
+    /// Creates a writer that compresses with the given format and level, and encodes a file with the given options
+    /// into `inner`. Fails if the file name is invalid, as `uuencode_file` does.
+    pub fn new(inner: W, options: FileOptions, format: CompressionFormat, level: Compression) -> Result<Self, UUEncodeError> {
+        Ok(CompressEncodeWriter { compressor: Compressor::new(EncodeWriter::new(inner, options)?, format, level) })
+    }
+
+    /// Writes the end of the compressed data, then the final partial line and the end of the file, flushes the inner
+    /// writer, and returns it.
+    pub fn finish(self) -> io::Result<W> {
+        self.compressor.finish()?.finish()
+    }
+}
+
+impl<W: Write> Write for CompressEncodeWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.compressor.writer().write(buf)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.compressor.writer().flush()
+    }
+}
+
+/// A writer that decodes a complete uuencoded file written to it, as `DecodeWriter` does, and decompresses the
+/// decoded data into the inner writer, like piping `uudecode` into `gunzip`. Call `finish` once all the text is
+/// written, to check that both the file and the compressed data were complete.
+#[derive(Debug)]
+pub struct DecompressDecodeWriter<W: Write> {
+    writer: DecodeWriter<Decompressor<W>>,
+}
+
+impl<W: Write> DecompressDecodeWriter<W> {
+    /// Creates a writer that decodes a file with the given options, and decompresses it from the given format into
+    /// `inner`.
+    pub fn new(inner: W, options: DecodeOptions, format: CompressionFormat) -> Self {
+        DecompressDecodeWriter { writer: DecodeWriter::new(Decompressor::new(inner, format), options) }
+    }
+
+    /// The file name from the `begin` line, once it has been written.
+    pub fn name(&self) -> Option<&str> {
+        self.writer.name()
+    }
+
+    /// The permission bits from the `begin` line, once it has been written.
+    pub fn mode(&self) -> Option<u32> {
+        self.writer.mode()
+    }
+
+    /// Checks that the file and the compressed data were complete, flushes the inner writer, and returns it.
+    pub fn finish(self) -> io::Result<W> {
+        let mut inner = self.writer.finish()?.finish()?;
+        inner.flush()?;
+        Ok(inner)
+    }
+}
+
+impl<W: Write> Write for DecompressDecodeWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.writer.write(buf)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.writer.flush()
+    }
+}
+
+/// A reader that compresses the data read from the inner reader and uuencodes the compressed data as a complete
+/// file, as `EncodeReader` does.
+#[derive(Debug)]
+pub struct CompressEncodeReader<R: Read> {
+    reader: EncodeReader<CodecReader<R>>,
+}
+
+impl<R: Read> CompressEncodeReader<R> {
+    /// Creates a reader that compresses the data from `inner` with the given format and level, and encodes it as a
+    /// file with the given options. Fails if the file name is invalid, as `uuencode_file` does.
+    pub fn new(inner: R, options: FileOptions, format: CompressionFormat, level: Compression) -> Result<Self, UUEncodeError> {
+        Ok(CompressEncodeReader { reader: EncodeReader::new(CodecReader::compressor(inner, format, level), options)? })
+    }
+
+    /// Returns the inner reader.
+    pub fn into_inner(self) -> R {
+        self.reader.into_inner().into_inner()
+    }
+}
+
+impl<R: Read> Read for CompressEncodeReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        self.reader.read(buf)
+    }
+}
+
+/// A reader that decodes a complete uuencoded file from the inner reader, as `DecodeReader` does, and decompresses
+/// the decoded data, like piping `uudecode` into `gunzip`. Corrupt compressed data is reported as an `io::Error`.
+/// Example:
+/// ```rust
+/// fn decode(encoded: &[u8]) -> std::io::Result<Vec<u8>> {
+///     use std::io::Read;
+///     let options = uuencode_lite::DecodeOptions::new();
+///     let mut reader = uuencode_lite::DecompressDecodeReader::new(encoded, options, uuencode_lite::CompressionFormat::Gzip);
+///     let mut data = Vec::new();
+///     reader.read_to_end(&mut data)?;
+///     Ok(data)
+/// }
+/// ```
+#[derive(Debug)]
+pub struct DecompressDecodeReader<R: Read> {
+    reader: CodecReader<DecodeReader<R>>,
+}
+
+impl<R: Read> DecompressDecodeReader<R> {
+    /// Creates a reader that decodes a file with the given options from `inner`, and decompresses it from the given
+    /// format.
+    pub fn new(inner: R, options: DecodeOptions, format: CompressionFormat) -> Self {
+        DecompressDecodeReader { reader: CodecReader::decompressor(DecodeReader::new(inner, options), format) }
+    }
+
+    /// The file name from the `begin` line, once it has been read.
+    pub fn name(&self) -> Option<&str> {
+        self.reader.get_ref().name()
+    }
+
+    /// The permission bits from the `begin` line, once it has been read.
+    pub fn mode(&self) -> Option<u32> {
+        self.reader.get_ref().mode()
+    }
+
+    /// Returns the inner reader.
+    pub fn into_inner(self) -> R {
+        self.reader.into_inner().into_inner()
+    }
+}
+
+impl<R: Read> Read for DecompressDecodeReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        self.reader.read(buf)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::vec::Vec;
+
+    /// Tests round trips through every pairing of writer and reader, in every format
+    #[test]
+    fn test_compress_round_trip() {
+        let source_data = std::fs::read("test_data/the_machine_stops.txt").expect("Can open test data");
+        let options = FileOptions::new("the_machine_stops.txt.gz");
+        for format in [CompressionFormat::Gzip, CompressionFormat::Zlib, CompressionFormat::Deflate] {
+            let mut writer = CompressEncodeWriter::new(Vec::new(), options.clone(), format, Compression::default()).unwrap();
+            for chunk in source_data.chunks(1000) {
+                writer.write_all(chunk).unwrap();
+            }
+            let encoded = writer.finish().unwrap();
+            assert!(encoded.len() < source_data.len(), "compresses {:?}", format);
+
+            let mut reencoded = Vec::new();
+            CompressEncodeReader::new(&source_data[..], options.clone(), format, Compression::default()).unwrap().read_to_end(&mut reencoded).unwrap();
+            assert_eq!(reencoded, encoded, "reads the same text as is written for {:?}", format);
+
+            let mut reader = DecompressDecodeReader::new(&encoded[..], DecodeOptions::new(), format);
+            let mut decoded = Vec::new();
+            reader.read_to_end(&mut decoded).unwrap();
+            assert_eq!(decoded, source_data, "can read back {:?}", format);
+            assert_eq!(reader.name(), Some("the_machine_stops.txt.gz"), "reports the begin line");
+
+            let mut writer = DecompressDecodeWriter::new(Vec::new(), DecodeOptions::new(), format);
+            for chunk in encoded.chunks(100) {
+                writer.write_all(chunk).unwrap();
+            }
+            assert_eq!(writer.finish().unwrap(), source_data, "can write back {:?}", format);
+        }
+    }
+
+    /// Tests that uncompressed or truncated payloads are reported
+    #[test]
+    fn test_decompress_errors() {
+        let encoded = crate::uuencode_file(b"not compressed", &FileOptions::new("cat.txt")).unwrap();
+        let mut reader = DecompressDecodeReader::new(encoded.as_bytes(), DecodeOptions::new(), CompressionFormat::Gzip);
+        assert!(reader.read_to_end(&mut Vec::new()).is_err(), "rejects data which is not gzip");
+
+        let mut compressed = flate2::write::GzEncoder::new(Vec::new(), Compression::default());
+        compressed.write_all(b"cat").unwrap();
+        let compressed = compressed.finish().unwrap();
+        let encoded = crate::uuencode_file(&compressed[..compressed.len() - 4], &FileOptions::new("cat.txt.gz")).unwrap();
+        let mut writer = DecompressDecodeWriter::new(Vec::new(), DecodeOptions::new(), CompressionFormat::Gzip);
+        writer.write_all(encoded.as_bytes()).unwrap();
+        assert!(writer.finish().is_err(), "rejects truncated gzip data");
+    }
+}
@@ -0,0 +1,563 @@
+use alloc::boxed::Box;
+use alloc::format;
+use alloc::string::String;
+use alloc::vec::Vec;
+use crate::base64::Base64Decoder;
+use crate::checkpoint::{CheckpointReader, CheckpointWriter};
+use crate::checksum::ChecksumState;
+use crate::file::{parse_begin, Framing};
+use crate::{decode_line, Checksum, DecodeOptions, Table, UUEncodeError};
+
+/// Where the decoder is within a file.
+#[derive(Debug, Clone)]
+enum State {
+    /// Skipping text while looking for a `table` directive or `begin` line.
+    Seeking,
+    /// Collecting the characters of a `table` directive.
+    Table(Vec<u8>),
+    /// Decoding a uuencoded body, noting whether the zero-length terminator line has been seen.
+    UUEncodeBody { terminated: bool },
+    /// Decoding a Base64 body.
+    Base64Body(Box<Base64Decoder>),
+    /// Looking for the checksum trailer after the end of the file, which ended on the given line.
+    Trailer(usize),
+    /// The file is complete; further input is ignored.
+    Done,
+}
+
+/// What a line consumed by `Decoder::consume` held.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DecodeEvent {
+    /// The input holds no complete line; nothing was consumed.
+    NeedMore,
+    /// The `begin` line was decoded, so `Decoder::header` is now available.
+    Begin,
+    /// The given number of decoded bytes were appended to the output.
+    Data(usize),
+    /// A line that produced no data, such as text before the `begin` line, part of a `table` directive,
+    /// or the zero-length terminator line.
+    Line,
+    /// The file is complete, including any trailer being verified.
+    End,
+}
+
+/// Incrementally decodes a complete file, as `uudecode_file_with`, from text that arrives in pieces.
+/// Lines may be split across pieces at any point, and decoded bytes are appended to a caller-provided buffer
+/// as each line completes. Errors report the line and character within the whole input, not within a piece.
+/// Example:
+/// ```rust
+/// fn decode() -> Result<(), uuencode_lite::UUEncodeError> {
+///     let mut decoder = uuencode_lite::Decoder::new(uuencode_lite::DecodeOptions::new());
+///     let mut decoded = Vec::new();
+///     for piece in ["begin 644 cat.t", "xt\n#8V", "%T\n`\nend\n"] {
+///         decoder.push(piece.as_bytes(), &mut decoded)?;
+///     }
+///     decoder.finish(&mut decoded)?;
+///     assert_eq!(decoder.header(), Some((0o644, "cat.txt")));
+///     assert_eq!(decoded, b"cat");
+///     Ok(())
+/// }
+/// ```
+#[derive(Debug, Clone)]
+pub struct Decoder {
+    options: DecodeOptions,
+    /// The alphabet for a uuencoded body, which a `table` directive may replace.
+    table: Table,
+    state: State,
+    /// The start of a line whose end has not arrived yet.
+    partial: Vec<u8>,
+    /// The number of the next line to be completed.
+    cur_line: usize,
+    /// The mode and name from the `begin` line, once it has been seen.
+    header: Option<(u32, String)>,
+    /// Running checksums of the decoded data, if the trailer is being verified.
+    checksums: Option<[ChecksumState; 2]>,
+    /// The number of input bytes in the lines completed so far.
+    line_offset: u64,
+    /// The number of decoded bytes produced so far.
+    decoded_len: u64,
+}
+
+impl Decoder {
+    /// Creates a decoder with the given options.
+    pub fn new(options: DecodeOptions) -> Self {
+        let checksums = options.verify_trailer.then(|| [ChecksumState::new(Checksum::BsdSum), ChecksumState::new(Checksum::Crc32)]);
+        Decoder { table: options.table.clone(), options, state: State::Seeking, partial: Vec::new(), cur_line: 0, header: None, checksums, line_offset: 0, decoded_len: 0 }
+    }
+
+    /// The number of input bytes taken so far, including the start of any incomplete line.
+    /// After resuming from a checkpoint, input should continue from this offset.
+    pub fn input_offset(&self) -> u64 {
+        self.line_offset + self.partial.len() as u64
+    }
+
+    /// The number of decoded bytes produced so far.
+    pub fn decoded_len(&self) -> u64 {
+        self.decoded_len
+    }
+
+    /// Saves the decoder's state, including its options and any incomplete line, as a compact binary checkpoint.
+    /// A decoder resumed from the checkpoint continues exactly where this one left off, given the input from
+    /// `input_offset` onwards; the `decoded_len` bytes already produced are not part of the checkpoint.
+    /// Example:
+    /// ```rust
+    /// fn resume() -> Result<(), uuencode_lite::UUEncodeError> {
+    ///     let input = b"begin 644 cat.txt\n#8V%T\n#8V%T\n`\nend\n";
+    ///     let mut decoder = uuencode_lite::Decoder::new(uuencode_lite::DecodeOptions::new());
+    ///     let mut decoded = Vec::new();
+    ///     decoder.push(&input[..30], &mut decoded)?;
+    ///     let checkpoint = decoder.checkpoint();
+    ///
+    ///     let mut resumed = uuencode_lite::Decoder::resume(&checkpoint)?;
+    ///     resumed.push(&input[resumed.input_offset() as usize..], &mut decoded)?;
+    ///     resumed.finish(&mut decoded)?;
+    ///     assert_eq!(decoded, b"catcat");
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn checkpoint(&self) -> Vec<u8> {
+        let mut writer = CheckpointWriter::new();
+        let options = &self.options;
+        writer.bool(options.raw_names);
+        writer.table(&options.table);
+        writer.bool(options.verify_checksums);
+        writer.bool(options.verify_trailer);
+        writer.table(&self.table);
+        match &self.state {
+            State::Seeking => writer.u8(0),
+            State::Table(alphabet) => {
+                writer.u8(1);
+                writer.bytes(alphabet);
+            }
+            State::UUEncodeBody { terminated } => {
+                writer.u8(2);
+                writer.bool(*terminated);
+            }
+            State::Base64Body(decoder) => {
+                writer.u8(3);
+                decoder.save(&mut writer);
+            }
+            State::Trailer(end_line) => {
+                writer.u8(4);
+                writer.u64(*end_line as u64);
+            }
+            State::Done => writer.u8(5),
+        }
+        writer.bytes(&self.partial);
+        writer.u64(self.cur_line as u64);
+        writer.bool(self.header.is_some());
+        if let Some((mode, name)) = &self.header {
+            writer.u32(*mode);
+            writer.bytes(name.as_bytes());
+        }
+        if let Some(checksums) = &self.checksums {
+            checksums.iter().for_each(|checksum| checksum.save(&mut writer));
+        }
+        writer.u64(self.line_offset);
+        writer.u64(self.decoded_len);
+        writer.finish()
+    }
+
+    /// Restores a decoder from a checkpoint made by `checkpoint`. Cancellation tokens are not saved.
+    /// Fails if the checkpoint is malformed, reporting the offset within it as the error's character.
+    pub fn resume(checkpoint: &[u8]) -> Result<Self, UUEncodeError> {
+        let mut reader = CheckpointReader::new(checkpoint)?;
+        let options = DecodeOptions { raw_names: reader.bool()?, table: reader.table()?, verify_checksums: reader.bool()?, verify_trailer: reader.bool()?, cancel: None };
+        let table = reader.table()?;
+        let state = match reader.u8()? {
+            0 => State::Seeking,
+            1 => {
+                let alphabet = reader.bytes()?;
+                if alphabet.len() >= 64 {
+                    return Err(reader.error("Invalid table state in checkpoint"));
+                }
+                State::Table(alphabet.to_vec())
+            }
+            2 => State::UUEncodeBody { terminated: reader.bool()? },
+            3 => State::Base64Body(Box::new(Base64Decoder::load(&mut reader)?)),
+            4 if !options.verify_trailer => return Err(reader.error("Trailer state in checkpoint without trailer verification")),
+            4 => State::Trailer(reader.usize()?),
+            5 => State::Done,
+            _ => return Err(reader.error("Invalid state in checkpoint")),
+        };
+        let partial = reader.bytes()?.to_vec();
+        let cur_line = reader.usize()?;
+        let header = if reader.bool()? {
+            let mode = reader.u32()?;
+            let name = String::from_utf8(reader.bytes()?.to_vec()).map_err(|_| reader.error("Invalid file name in checkpoint"))?;
+            Some((mode, name))
+        } else {
+            None
+        };
+        let checksums = if options.verify_trailer { Some([ChecksumState::load(&mut reader)?, ChecksumState::load(&mut reader)?]) } else { None };
+        let line_offset = reader.u64()?;
+        let decoded_len = reader.u64()?;
+        reader.finish()?;
+        Ok(Decoder { options, table, state, partial, cur_line, header, checksums, line_offset, decoded_len })
+    }
+
+    /// The number of complete lines taken so far.
+    #[cfg(feature = "tracing")]
+    pub(crate) fn lines(&self) -> usize {
+        self.cur_line
+    }
+
+    /// The permission bits and file name from the `begin` line, once it has been decoded.
+    pub fn header(&self) -> Option<(u32, &str)> {
+        self.header.as_ref().map(|(mode, name)| (*mode, name.as_str()))
+    }
+
+    /// Whether the whole file (including any trailer being verified) has been decoded.
+    pub fn is_done(&self) -> bool {
+        matches!(self.state, State::Done)
+    }
+
+    /// Whether a `begin` line (or `table` directive) has been seen.
+    pub fn has_started(&self) -> bool {
+        !matches!(self.state, State::Seeking)
+    }
+
+    /// Decodes the text, appending decoded bytes to `decoded` as each line completes.
+    /// The text may end part way through a line, which is kept until the rest of it arrives.
+    /// Text after the end of the file is ignored.
+    pub fn push(&mut self, mut data: &[u8], decoded: &mut Vec<u8>) -> Result<(), UUEncodeError> {
+        if !self.partial.is_empty() {
+            let Some(len) = data.iter().position(|&ch| ch == b'\n') else {
+                self.partial.extend_from_slice(data);
+                return Ok(());
+            };
+            let mut partial = core::mem::take(&mut self.partial);
+            partial.extend_from_slice(&data[..=len]);
+            data = &data[len + 1..];
+            let result = self.consume(&partial, decoded);
+            partial.clear();
+            self.partial = partial;
+            result?;
+        }
+        loop {
+            match self.consume(data, decoded)? {
+                (_, DecodeEvent::End) => return Ok(()),
+                (_, DecodeEvent::NeedMore) => break,
+                (consumed, _) => data = &data[consumed..],
+            }
+        }
+        self.partial.extend_from_slice(data);
+        Ok(())
+    }
+
+    /// Decodes the first complete line of `input`, without buffering any of it, and returns the number of bytes
+    /// consumed along with what the line held. Decoded bytes are appended to `decoded`.
+    /// If `input` holds no complete line, nothing is consumed and `DecodeEvent::NeedMore` is returned; the caller
+    /// keeps the bytes and tries again once more have arrived. Once the file is complete, nothing more is consumed
+    /// and `DecodeEvent::End` is returned. `push`, and so the streaming adapters, are built on this.
+    /// It still allocates: decoded bytes are appended to a `Vec`, and the decoder keeps the name from the `begin`
+    /// line and any `table` directive. Without an allocator, `SliceDecoder` decodes bare lines into a fixed buffer.
+    /// Example:
+    /// ```rust
+    /// fn decode() -> Result<(), uuencode_lite::UUEncodeError> {
+    ///     use uuencode_lite::DecodeEvent;
+    ///     let mut decoder = uuencode_lite::Decoder::new(uuencode_lite::DecodeOptions::new());
+    ///     let mut input: &[u8] = b"begin 644 cat.txt\n#8V%T\n`\nend\ntrailing text";
+    ///     let mut decoded = Vec::new();
+    ///     loop {
+    ///         let (consumed, event) = decoder.consume(input, &mut decoded)?;
+    ///         input = &input[consumed..];
+    ///         match event {
+    ///             DecodeEvent::Begin => assert_eq!(decoder.header(), Some((0o644, "cat.txt"))),
+    ///             DecodeEvent::Data(len) => assert_eq!(len, 3),
+    ///             DecodeEvent::Line => {}
+    ///             DecodeEvent::End | DecodeEvent::NeedMore => break,
+    ///         }
+    ///     }
+    ///     assert_eq!(decoded, b"cat");
+    ///     assert_eq!(input, b"trailing text");
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn consume(&mut self, input: &[u8], decoded: &mut Vec<u8>) -> Result<(usize, DecodeEvent), UUEncodeError> {
+        if self.is_done() {
+            return Ok((0, DecodeEvent::End));
+        }
+        let Some(len) = input.iter().position(|&ch| ch == b'\n') else {
+            return Ok((0, DecodeEvent::NeedMore));
+        };
+        let line = &input[..len];
+        let cur_line = self.cur_line;
+        self.cur_line += 1;
+        let had_header = self.header.is_some();
+        let start = decoded.len();
+        self.push_line(cur_line, line.strip_suffix(b"\r").unwrap_or(line), decoded)?;
+        let event = if !had_header && self.header.is_some() {
+            DecodeEvent::Begin
+        } else if decoded.len() > start {
+            DecodeEvent::Data(decoded.len() - start)
+        } else if self.is_done() {
+            DecodeEvent::End
+        } else {
+            DecodeEvent::Line
+        };
+        self.line_offset += len as u64 + 1;
+        Ok((len + 1, event))
+    }
+
+    /// Decodes any final line without a newline, then checks that the file was complete.
+    /// Fails if the input ended before the `end` line (or `====` line, or the trailer being verified).
+    pub fn finish(&mut self, decoded: &mut Vec<u8>) -> Result<(), UUEncodeError> {
+        if !self.partial.is_empty() {
+            let partial = core::mem::take(&mut self.partial);
+            let cur_line = self.cur_line;
+            self.cur_line += 1;
+            self.line_offset += partial.len() as u64;
+            self.push_line(cur_line, partial.strip_suffix(b"\r").unwrap_or(&partial), decoded)?;
+        }
+        self.end_of_input(self.cur_line.saturating_sub(1))
+    }
+
+    /// Reports what was missing if the input ended before the file was complete, where `last_line` was the last line seen.
+    pub(crate) fn end_of_input(&self, last_line: usize) -> Result<(), UUEncodeError> {
+        let result = match self.state {
+            State::Done => Ok(()),
+            State::Seeking => Err(UUEncodeError::new(0, 0, "Missing begin line")),
+            State::Table(_) => Err(UUEncodeError::new(last_line + 1, 0, "Table ended before 64 characters")),
+            State::UUEncodeBody { .. } => Err(UUEncodeError::new(last_line + 1, 0, "Missing end line")),
+            State::Base64Body(_) => Err(UUEncodeError::new(last_line + 1, 0, "Missing ==== line")),
+            State::Trailer(end_line) => Err(UUEncodeError::new(end_line + 1, 0, "Missing checksum trailer")),
+        };
+        #[cfg(feature = "metrics")]
+        let result = crate::metrics::check(result);
+        result
+    }
+
+    /// Fails if the cancellation token has been cancelled.
+    fn check_cancelled(&self, cur_line: usize) -> Result<(), UUEncodeError> {
+        match &self.options.cancel {
+            Some(token) if token.is_cancelled() => Err(UUEncodeError::cancelled(cur_line)),
+            _ => Ok(()),
+        }
+    }
+
+    /// Ends the body on the given line, moving on to the trailer if it is being verified.
+    fn end_body(&mut self, cur_line: usize) {
+        self.state = if self.options.verify_trailer { State::Trailer(cur_line) } else { State::Done };
+    }
+
+    /// Decodes one complete line, without its line ending, appending any decoded bytes to `decoded`.
+    pub(crate) fn push_line(&mut self, cur_line: usize, line: &[u8], decoded: &mut Vec<u8>) -> Result<(), UUEncodeError> {
+        let start = decoded.len();
+        let result = self.check_cancelled(cur_line).and_then(|()| self.decode_line(cur_line, line, decoded));
+        self.decoded_len += (decoded.len() - start) as u64;
+        if let Some(checksums) = &mut self.checksums {
+            checksums.iter_mut().for_each(|checksum| checksum.update(&decoded[start..]));
+        }
+        #[cfg(feature = "metrics")]
+        if let Some(metrics) = crate::metrics::installed() {
+            match &result {
+                Ok(()) => metrics.decoded((decoded.len() - start) as u64, 1),
+                Err(err) => metrics.error(err),
+            }
+        }
+        result
+    }
+
+    fn decode_line(&mut self, cur_line: usize, line: &[u8], decoded: &mut Vec<u8>) -> Result<(), UUEncodeError> {
+        match &mut self.state {
+            State::Seeking => {
+                if line.trim_ascii_end() == b"table" {
+                    self.state = State::Table(Vec::with_capacity(64));
+                    return Ok(());
+                }
+                let Some(framing) = Framing::of_begin_line(line) else {
+                    return Ok(());
+                };
+                let header = parse_begin(line, cur_line, &self.options)?;
+                self.header = Some(header);
+                self.state = match framing {
+                    Framing::UUEncode => State::UUEncodeBody { terminated: false },
+                    Framing::Base64 => State::Base64Body(Box::default()),
+                };
+            }
+            State::Table(alphabet) => {
+                if alphabet.len() + line.len() > 64 {
+                    return Err(UUEncodeError::new(cur_line, 64 - alphabet.len(), "Table has more than 64 characters"));
+                }
+                alphabet.extend_from_slice(line);
+                if alphabet.len() == 64 {
+                    self.table = Table::from_alphabet(alphabet).map_err(|err| UUEncodeError::new(cur_line, 0, err.msg))?;
+                    self.state = State::Seeking;
+                }
+            }
+            State::UUEncodeBody { terminated } => {
+                if line.trim_ascii_end() == b"end" {
+                    self.end_body(cur_line);
+                    return Ok(());
+                }
+                if line.is_empty() {
+                    return Ok(());
+                }
+                if *terminated {
+                    return Err(UUEncodeError::new(cur_line, 0, "Expected end line after zero-length line"));
+                }
+                let start = decoded.len();
+                decode_line(line, cur_line, &self.table, self.options.verify_checksums, decoded)?;
+                *terminated = decoded.len() == start;
+            }
+            State::Base64Body(decoder) => {
+                if line.trim_ascii_end() == b"====" {
+                    decoder.finish(cur_line, decoded)?;
+                    self.end_body(cur_line);
+                    return Ok(());
+                }
+                decoder.push_line(line, cur_line, b" \t", decoded)?;
+            }
+            State::Trailer(_) => {
+                if line.trim_ascii().is_empty() {
+                    return Ok(());
+                }
+                let Some((checksum, value, size)) = Checksum::parse_trailer(line) else {
+                    return Err(UUEncodeError::new(cur_line, 0, "Missing checksum trailer"));
+                };
+                let Some(checksums) = &self.checksums else {
+                    return Err(UUEncodeError::new(cur_line, 0, "Checksum trailer is not being verified"));
+                };
+                let state = match checksum {
+                    Checksum::BsdSum => &checksums[0],
+                    Checksum::Crc32 => &checksums[1],
+                };
+                if size != state.len() {
+                    return Err(UUEncodeError::new(cur_line, 0, format!("Size mismatch: trailer declares {} bytes, decoded {}", size, state.len())));
+                }
+                if value != state.value() {
+                    return Err(UUEncodeError::new(cur_line, 0, "Checksum trailer mismatch"));
+                }
+                self.state = State::Done;
+            }
+            State::Done => {}
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::string::ToString;
+    use crate::{uuencode_file, uudecode_file, FileOptions};
+
+    /// Tests the events for each line consumed
+    #[test]
+    fn test_decoder_consume() {
+        let mut decoder = Decoder::new(DecodeOptions::new());
+        let mut decoded = Vec::new();
+        let mut input: &[u8] = b"hello\r\nbegin 644 cat.txt\n#8V%T\n`\nend\n\nbegin";
+        let mut events = Vec::new();
+        loop {
+            let (consumed, event) = decoder.consume(input, &mut decoded).unwrap();
+            input = &input[consumed..];
+            events.push(event);
+            if matches!(event, DecodeEvent::NeedMore | DecodeEvent::End) {
+                break;
+            }
+        }
+        use DecodeEvent::*;
+        assert_eq!(events, [Line, Begin, Data(3), Line, End], "reports each line");
+        assert_eq!(input, b"\nbegin", "doesn't consume past the end");
+        assert_eq!(decoder.consume(b"#8V%T", &mut decoded).unwrap(), (0, End), "ignores input after the end");
+
+        let mut decoder = Decoder::new(DecodeOptions::new());
+        assert_eq!(decoder.consume(b"begin 644 cat", &mut decoded).unwrap(), (0, NeedMore), "needs a complete line");
+    }
+
+    /// Tests that decoding text split at every possible size matches decoding it all at once
+    #[test]
+    fn test_decoder_pieces() {
+        let source_data = std::fs::read("test_data/random_data.bin").expect("Can open test data");
+        let options = FileOptions::new("random_data.bin").trailer(Some(Checksum::Crc32));
+        let encoded = uuencode_file(&source_data, &options).unwrap().replace('\n', "\r\n");
+        assert_eq!(uudecode_file(encoded.as_bytes()).unwrap().data, source_data, "can decode at once");
+        for piece_size in [1, 2, 7, 61, 62, 63, 1000] {
+            let mut decoder = Decoder::new(DecodeOptions::new().verify_trailer(true));
+            let mut decoded = Vec::new();
+            for piece in encoded.as_bytes().chunks(piece_size) {
+                decoder.push(piece, &mut decoded).unwrap();
+            }
+            decoder.finish(&mut decoded).unwrap();
+            assert!(decoder.is_done(), "finishes the file");
+            assert_eq!(decoder.header(), Some((0o644, "random_data.bin")), "can read the header");
+            assert_eq!(decoded, source_data, "can decode pieces of {} bytes", piece_size);
+        }
+    }
+
+    /// Tests that resuming from a checkpoint at any point gives the same result as decoding without stopping
+    #[test]
+    fn test_decoder_checkpoint() {
+        let source_data = std::fs::read("test_data/random_data.bin").expect("Can open test data");
+        let all_options = [
+            (FileOptions::new("random_data.bin").encoding(crate::EncodeOptions::new().table(Table::XX)).emit_table(true), DecodeOptions::new()),
+            (FileOptions::new("random_data.bin").base64(true).trailer(Some(Checksum::BsdSum)), DecodeOptions::new().verify_trailer(true)),
+        ];
+        for (file_options, options) in all_options {
+            let encoded = uuencode_file(&source_data, &file_options).unwrap();
+            let encoded = encoded.as_bytes();
+            for split in [0, 1, 6, 70, 71, 500, encoded.len() - 3, encoded.len()] {
+                let mut decoder = Decoder::new(options.clone());
+                let mut decoded = Vec::new();
+                decoder.push(&encoded[..split], &mut decoded).unwrap();
+                let mut resumed = Decoder::resume(&decoder.checkpoint()).unwrap();
+                assert_eq!(resumed.input_offset(), split as u64, "resumes from where the input stopped at {}", split);
+                assert_eq!(resumed.decoded_len(), decoded.len() as u64, "counts the decoded bytes at {}", split);
+                resumed.push(&encoded[split..], &mut decoded).unwrap();
+                resumed.finish(&mut decoded).unwrap();
+                assert_eq!(decoded, source_data, "can resume from a checkpoint at {}", split);
+            }
+        }
+
+        let checkpoint = Decoder::new(DecodeOptions::new()).checkpoint();
+        assert!(Decoder::resume(&checkpoint[..checkpoint.len() - 1]).is_err(), "rejects a truncated checkpoint");
+        assert!(Decoder::resume(b"not a checkpoint").is_err(), "rejects other data");
+    }
+
+    /// Tests that a crafted checkpoint can't put a decoder in the trailer state without the checksums to verify it
+    #[test]
+    fn test_decoder_crafted_checkpoint() {
+        let checkpoint = Decoder::new(DecodeOptions::new()).checkpoint();
+        // magic, version, three flags and two standard tables come before the state
+        let state = 10;
+        assert_eq!(checkpoint[state], 0, "finds the seeking state");
+        let crafted = [&checkpoint[..state], &[4], &0u64.to_le_bytes(), &checkpoint[state + 1..]].concat();
+        let err = Decoder::resume(&crafted).unwrap_err();
+        assert_eq!(err.character, state + 1, "rejects the trailer state");
+
+        let mut decoder = Decoder { state: State::Trailer(0), ..Decoder::new(DecodeOptions::new()) };
+        let mut decoded = Vec::new();
+        let err = decoder.push(b"crc32/size 00000000/0 entire input file\n", &mut decoded).unwrap_err();
+        assert_eq!(err.message().to_string(), "Checksum trailer is not being verified", "fails rather than panics");
+    }
+
+    /// Tests that a cancelled token stops decoding between lines
+    #[test]
+    fn test_decoder_cancel() {
+        let token = crate::CancelToken::new();
+        let mut decoder = Decoder::new(DecodeOptions::new().cancel_token(token.clone()));
+        let mut decoded = Vec::new();
+        decoder.push(b"begin 644 cat.txt\n#8V%T\n#8", &mut decoded).unwrap();
+        token.cancel();
+        let err = decoder.push(b"V%T\n`\nend\n", &mut decoded).unwrap_err();
+        assert_eq!((err.kind(), err.line), (crate::ErrorKind::Cancelled, 2), "stops before the next line");
+        assert_eq!(decoded, b"cat", "keeps the lines decoded before cancelling");
+    }
+
+    /// Tests that errors report positions within the whole input
+    #[test]
+    fn test_decoder_errors() {
+        let mut decoder = Decoder::new(DecodeOptions::new());
+        let mut decoded = Vec::new();
+        decoder.push(b"begin 644 cat.txt\n#8", &mut decoded).unwrap();
+        let err = decoder.push(b"V%T\n#8V\x01T\n", &mut decoded).unwrap_err();
+        assert_eq!((err.line, err.character), (2, 3), "reports the line and character of an invalid character");
+
+        let mut decoder = Decoder::new(DecodeOptions::new());
+        decoder.push(b"begin 644 cat.txt\n#8V%T\n", &mut decoded).unwrap();
+        let err = decoder.finish(&mut decoded).unwrap_err();
+        assert_eq!(err.line, 2, "reports a missing end line after the last line");
+    }
+}
@@ -0,0 +1,307 @@
+use crate::{DecodedFile, UUEncodeError};
+use std::format;
+use std::io::Read;
+use std::string::String;
+use std::vec::Vec;
+
+/// A compression format recognized in a decoded payload by `detect_compression`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum PayloadCompression {
+    /// gzip, usually named `.gz`.
+    Gzip,
+    /// The LZW format of Unix `compress`, usually named `.Z`.
+    Compress,
+    /// bzip2, usually named `.bz2`.
+    Bzip2,
+}
+
+impl PayloadCompression {
+    /// The file name suffixes this format is stored under, and what each is replaced with once decompressed.
+    fn suffixes(self) -> &'static [(&'static str, &'static str)] {
+        match self {
+            PayloadCompression::Gzip => &[(".gz", ""), (".tgz", ".tar")],
+            PayloadCompression::Compress => &[(".Z", ""), (".taz", ".tar")],
+            PayloadCompression::Bzip2 => &[(".bz2", ""), (".tbz2", ".tar"), (".tbz", ".tar")],
+        }
+    }
+}
+
+/// Identifies a compressed payload from its magic bytes. Returns `None` for anything else.
+/// Example:
+/// ```rust
+/// assert_eq!(uuencode_lite::detect_compression(b"\x1f\x8b\x08\x00"), Some(uuencode_lite::PayloadCompression::Gzip));
+/// assert_eq!(uuencode_lite::detect_compression(b"cat"), None);
+/// ```
+pub fn detect_compression(data: &[u8]) -> Option<PayloadCompression> {
+    match data {
+        [0x1f, 0x8b, ..] => Some(PayloadCompression::Gzip),
+        [0x1f, 0x9d, ..] => Some(PayloadCompression::Compress),
+        [b'B', b'Z', b'h', b'1'..=b'9', ..] => Some(PayloadCompression::Bzip2),
+        _ => None,
+    }
+}
+
+/// A decoded file together with its decompressed contents, from `decompress_payload`.
+#[derive(Debug)]
+pub struct DecompressedFile {
+    /// The file as it was decoded, still compressed.
+    pub file: DecodedFile,
+    /// The compression recognized in the file, if any.
+    pub compression: Option<PayloadCompression>,
+    /// The decompressed contents, or the error which stopped decompression. `None` if the file is not compressed.
+    pub decompressed: Option<Result<Vec<u8>, UUEncodeError>>,
+}
+
+impl DecompressedFile {
+    /// The file name with the compression suffix removed, such as `cat.txt` for `cat.txt.gz`, or `cat.tar` for
+    /// `cat.tgz`. The name is unchanged if the file is not compressed or has no matching suffix.
+    pub fn name(&self) -> String {
+        let name = &self.file.name;
+        for &(suffix, replacement) in self.compression.map_or(&[][..], PayloadCompression::suffixes) {
+            if let Some(stem) = name.strip_suffix(suffix).filter(|stem| !stem.is_empty() && !stem.ends_with(['/', '\\'])) {
+                return format!("{}{}", stem, replacement);
+            }
+        }
+        name.clone()
+    }
+}
+
+/// Detects whether a decoded file is gzip, `compress`, or bzip2 data, as archives were often compressed before being
+/// uuencoded, and if so decompresses it. The decoded file is kept alongside the decompressed contents, so it is not
+/// lost if the contents turn out to be corrupt, or only happen to start with a magic number.
+/// Concatenated gzip members and bzip2 streams are decompressed as one file, as `gunzip` and `bunzip2` do.
+/// Decompression stops with an error once the contents would grow past `max_len` bytes, so that a small payload
+/// which decompresses to something huge can't exhaust memory.
+/// Example:
+/// ```rust
+/// let file = uuencode_lite::uudecode_file(b"begin 644 cat.txt\n#8V%T\n`\nend\n").unwrap();
+/// let payload = uuencode_lite::decompress_payload(file, 1 << 30);
+/// assert_eq!(payload.compression, None);
+/// assert_eq!(payload.name(), "cat.txt");
+/// ```
+pub fn decompress_payload(file: DecodedFile, max_len: usize) -> DecompressedFile {
+    let compression = detect_compression(&file.data);
+    let decompressed = compression.map(|compression| match compression {
+        PayloadCompression::Gzip => read_all(flate2::read::MultiGzDecoder::new(&file.data[..]), "gzip", max_len),
+        PayloadCompression::Compress => decompress_lzw(&file.data, max_len),
+        PayloadCompression::Bzip2 => read_all(bzip2::read::MultiBzDecoder::new(&file.data[..]), "bzip2", max_len),
+    });
+    DecompressedFile { file, compression, decompressed }
+}
+
+/// The error for contents which grow past the limit.
+fn too_large(max_len: usize) -> UUEncodeError {
+    UUEncodeError::new(0, 0, format!("Decompressed payload is larger than {} bytes", max_len))
+}
+
+/// Reads a decompressor to the end, reporting any failure as corrupt data, and stopping once it has produced more
+/// than `max_len` bytes.
+fn read_all(reader: impl Read, format: &str, max_len: usize) -> Result<Vec<u8>, UUEncodeError> {
+    let mut data = Vec::new();
+    reader.take((max_len as u64).saturating_add(1)).read_to_end(&mut data).map_err(|err| UUEncodeError::new(0, 0, format!("Corrupt {} payload: {}", format, err)))?;
+    if data.len() > max_len {
+        return Err(too_large(max_len));
+    }
+    Ok(data)
+}
+
+/// The code which clears the table, in block mode.
+const LZW_CLEAR: usize = 256;
+
+/// Decompresses the output of Unix `compress`, following `ncompress`: codes are packed least significant bit first,
+/// starting at 9 bits wide, and whenever the width changes or the table is cleared, the rest of the current group
+/// of eight codes is skipped. Fails once the output would grow past `max_len` bytes.
+fn decompress_lzw(data: &[u8], max_len: usize) -> Result<Vec<u8>, UUEncodeError> {
+    let corrupt = |msg: &'static str| UUEncodeError::new(0, 0, msg);
+    let flags = *data.get(2).ok_or_else(|| corrupt("Truncated compress header"))?;
+    let max_bits = (flags & 0x1f) as u32;
+    let block_mode = flags & 0x80 != 0;
+    if !(9..=16).contains(&max_bits) {
+        return Err(corrupt("Unsupported compress code width"));
+    }
+    let max_max_code = 1usize << max_bits;
+    let first = if block_mode { LZW_CLEAR + 1 } else { LZW_CLEAR };
+    let body = &data[3..];
+    let total_bits = body.len() * 8;
+
+    let mut prefix = std::vec![0u16; max_max_code];
+    let mut suffix: Vec<u8> = (0..max_max_code).map(|code| code as u8).collect();
+    let mut stack = Vec::new();
+    let mut output = Vec::new();
+    // the largest code of each width; at the widest, every code up to the size of the table
+    let width_max_code = |bits: u32| if bits == max_bits { max_max_code } else { (1 << bits) - 1 };
+    let (mut bits, mut max_code, mut free) = (9u32, width_max_code(9), first);
+    let (mut position, mut group_start) = (0usize, 0usize);
+    let mut previous: Option<usize> = None;
+    let mut last_char = 0u8;
+    // skips to the end of the current group of eight codes
+    let skip_group = |position: usize, group_start: usize, bits: u32| group_start + (position - group_start).next_multiple_of(bits as usize * 8);
+
+    loop {
+        if free > max_code {
+            position = skip_group(position, group_start, bits);
+            group_start = position;
+            bits += 1;
+            max_code = width_max_code(bits);
+        }
+        if position + bits as usize > total_bits {
+            break;
+        }
+        let mut code = 0;
+        for bit in 0..bits as usize {
+            let index = position + bit;
+            code |= ((body[index / 8] >> (index % 8)) as usize & 1) << bit;
+        }
+        position += bits as usize;
+
+        let Some(old) = previous else {
+            if code > 255 {
+                return Err(corrupt("Corrupt compress payload"));
+            }
+            if max_len == 0 {
+                return Err(too_large(max_len));
+            }
+            last_char = code as u8;
+            output.push(last_char);
+            previous = Some(code);
+            continue;
+        };
+        if code == LZW_CLEAR && block_mode {
+            position = skip_group(position, group_start, bits);
+            group_start = position;
+            bits = 9;
+            max_code = width_max_code(9);
+            free = first - 1;
+            continue;
+        }
+
+        let incoming = code;
+        // once the table is full, `free` is its size, so a code equal to it can't be defined either
+        if code >= max_max_code {
+            return Err(corrupt("Corrupt compress payload"));
+        }
+        if code >= free {
+            // the code being defined by this very step: the previous string followed by its own first character
+            if code > free {
+                return Err(corrupt("Corrupt compress payload"));
+            }
+            stack.push(last_char);
+            code = old;
+        }
+        while code > 255 {
+            stack.push(suffix[code]);
+            code = prefix[code] as usize;
+        }
+        last_char = suffix[code];
+        stack.push(last_char);
+        if output.len() + stack.len() > max_len {
+            return Err(too_large(max_len));
+        }
+        output.extend(stack.drain(..).rev());
+
+        if free < max_max_code {
+            prefix[free] = old as u16;
+            suffix[free] = last_char;
+            free += 1;
+        }
+        previous = Some(incoming);
+    }
+    Ok(output)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+    use std::string::ToString;
+
+    /// Tests decompressing each format, and keeping the raw data
+    #[test]
+    fn test_decompress_payload() {
+        let source_data = std::fs::read("test_data/the_machine_stops.txt").expect("Can open test data");
+        let compressed = std::fs::read("test_data/the_machine_stops.txt.Z").expect("Can open test data");
+        let payload = decompress_payload(DecodedFile { name: "the_machine_stops.txt.Z".to_string(), mode: 0o644, data: compressed.clone() }, usize::MAX);
+        assert_eq!(payload.compression, Some(PayloadCompression::Compress));
+        assert_eq!(payload.decompressed.unwrap().unwrap(), source_data, "decompresses compress data with table clears");
+        assert_eq!(payload.file.data, compressed, "keeps the raw data");
+
+        // two members, as `cat a.gz b.gz` produces
+        let mut gzip = Vec::new();
+        for half in source_data.chunks(source_data.len() / 2 + 1) {
+            let mut writer = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default());
+            writer.write_all(half).unwrap();
+            gzip.extend(writer.finish().unwrap());
+        }
+        let payload = decompress_payload(DecodedFile { name: "machine.tgz".to_string(), mode: 0o644, data: gzip }, usize::MAX);
+        assert_eq!(payload.compression, Some(PayloadCompression::Gzip));
+        assert_eq!(payload.name(), "machine.tar");
+        assert_eq!(payload.decompressed.unwrap().unwrap(), source_data, "decompresses every gzip member");
+
+        let mut writer = bzip2::write::BzEncoder::new(Vec::new(), bzip2::Compression::default());
+        writer.write_all(&source_data).unwrap();
+        let payload = decompress_payload(DecodedFile { name: "machine.txt.bz2".to_string(), mode: 0o644, data: writer.finish().unwrap() }, usize::MAX);
+        assert_eq!(payload.compression, Some(PayloadCompression::Bzip2));
+        assert_eq!(payload.name(), "machine.txt");
+        assert_eq!(payload.decompressed.unwrap().unwrap(), source_data, "decompresses bzip2");
+    }
+
+    /// Tests that corrupt payloads are reported, while keeping the raw data
+    #[test]
+    fn test_corrupt_payload() {
+        let compressed = std::fs::read("test_data/the_machine_stops.txt.Z").expect("Can open test data");
+        for data in [&b"\x1f\x8bnot gzip"[..], b"BZh9not bzip2", b"\x1f\x9d", b"\x1f\x9d\x11", &compressed[..3000]] {
+            let payload = decompress_payload(DecodedFile { name: ".gz".to_string(), mode: 0o644, data: data.to_vec() }, usize::MAX);
+            assert_eq!(payload.name(), ".gz", "does not strip the whole name");
+            if data.len() < 3000 {
+                assert!(payload.decompressed.unwrap().is_err(), "rejects {:?}", data);
+            } else {
+                assert!(payload.decompressed.unwrap().is_ok(), "decompresses a truncated compress file as far as it goes");
+            }
+            assert_eq!(payload.file.data, data, "keeps the raw data");
+        }
+        let payload = decompress_payload(DecodedFile { name: "cat.gz".to_string(), mode: 0o644, data: b"cat".to_vec() }, usize::MAX);
+        assert_eq!((payload.compression, payload.name()), (None, "cat.gz".to_string()), "leaves uncompressed data alone");
+        assert!(payload.decompressed.is_none());
+    }
+
+    /// Tests that decompression stops at the limit, for a highly compressible payload in each format
+    #[test]
+    fn test_decompress_limit() {
+        let zeros = std::vec![0u8; 1 << 24];
+        let mut gzip = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::best());
+        gzip.write_all(&zeros).unwrap();
+        let mut bzip2 = bzip2::write::BzEncoder::new(Vec::new(), bzip2::Compression::best());
+        bzip2.write_all(&zeros).unwrap();
+        let compress = std::fs::read("test_data/the_machine_stops.txt.Z").expect("Can open test data");
+        let source_len = std::fs::read("test_data/the_machine_stops.txt").expect("Can open test data").len();
+        for (data, len) in [(gzip.finish().unwrap(), zeros.len()), (bzip2.finish().unwrap(), zeros.len()), (compress, source_len)] {
+            let file = DecodedFile { name: "bomb".to_string(), mode: 0o644, data };
+            let payload = decompress_payload(file.clone(), len - 1);
+            assert_eq!(payload.decompressed.unwrap().unwrap_err().message().to_string(), format!("Decompressed payload is larger than {} bytes", len - 1), "stops past the limit");
+            assert_eq!(payload.file, file, "keeps the raw data");
+            assert_eq!(decompress_payload(file, len).decompressed.unwrap().unwrap().len(), len, "decompresses up to the limit");
+        }
+    }
+
+    /// Tests that codes never grow past the width the header declares, so that when it is 9 bits, a full table is
+    /// not followed by wider codes naming the entry past its end
+    #[test]
+    fn test_lzw_table_bounds() {
+        // 256 nine-bit codes fill the table, then two ten-bit codes name the entry past its end
+        let codes = (0..256).map(|code| (code, 9)).chain([(512, 10), (512, 10)]);
+        let mut data = std::vec![0x1f, 0x9d, 0x89];
+        let (mut bits, mut len) = (0u32, 0u32);
+        for (code, width) in codes {
+            bits |= code << len;
+            len += width;
+            while len >= 8 {
+                data.push(bits as u8);
+                bits >>= 8;
+                len -= 8;
+            }
+        }
+        data.push(bits as u8);
+        let output = decompress_lzw(&data, usize::MAX).expect("reads the codes as 9 bits wide, as the header declares");
+        assert_eq!(output[..256], (0..=255).collect::<Vec<u8>>()[..], "decodes the literal codes");
+    }
+}
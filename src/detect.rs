@@ -0,0 +1,147 @@
+use alloc::vec::Vec;
+use crate::file::numbered_lines;
+use crate::{ascii85_decode, base64_decode, binhex_decode, btoa_decode, uudecode_file_with, uudecode_with, yenc_decode, DecodeOptions, Table, UUEncodeError};
+
+/// An encoding recognized by `detect_format`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Format {
+    /// uuencoded data, either a `begin` ... `end` file or bare lines.
+    UUEncode,
+    /// xxencoded data in a `begin` ... `end` file.
+    XXEncode,
+    /// Base64 data, either a `begin-base64` ... `====` file or bare lines.
+    Base64,
+    /// A yEnc file, starting with `=ybegin`.
+    YEnc,
+    /// Adobe Ascii85, delimited by `<~` and `~>`.
+    Ascii85,
+    /// The Unix `btoa` format, starting with `xbtoa Begin`.
+    Btoa,
+    /// A BinHex 4.0 file.
+    BinHex,
+}
+
+/// Whether a `begin` line names a uuencoded or xxencoded file.
+fn is_begin_line(line: &[u8]) -> bool {
+    let mut words = line.splitn(3, |&ch| ch == b' ');
+    words.next().is_some_and(|keyword| keyword == b"begin" || keyword == b"begin-encoded") && words.next().is_some_and(|mode| !mode.is_empty() && mode.iter().all(|ch| (b'0'..=b'7').contains(ch))) && words.next().is_some()
+}
+
+/// Decides between uuencode and xxencode by counting the body characters that only one of the alphabets contains.
+fn uu_or_xx<'a>(body: impl Iterator<Item = &'a [u8]>) -> Format {
+    let (mut uu_only, mut xx_only) = (0, 0);
+    for line in body.take_while(|line| *line != b"end") {
+        for &ch in line {
+            match ((b' '..=b'`').contains(&ch), Table::XX.decode(ch).is_some()) {
+                (true, false) => uu_only += 1,
+                (false, true) => xx_only += 1,
+                _ => {}
+            }
+        }
+    }
+    if xx_only > uu_only { Format::XXEncode } else { Format::UUEncode }
+}
+
+/// Identifies the encoding of the input from its headers, or failing that, from the characters of its lines.
+/// Text before a header (such as mail headers) is skipped. Returns `None` if no supported encoding is recognized.
+/// Example:
+/// ```rust
+/// assert_eq!(uuencode_lite::detect_format(b"begin 644 cat.txt\n#8V%T\n`\nend\n"), Some(uuencode_lite::Format::UUEncode));
+/// assert_eq!(uuencode_lite::detect_format(b"begin 644 cat.txt\n1Mq3o\n+\nend\n"), Some(uuencode_lite::Format::XXEncode));
+/// assert_eq!(uuencode_lite::detect_format(b"<~@psH~>"), Some(uuencode_lite::Format::Ascii85));
+/// ```
+pub fn detect_format(data: &[u8]) -> Option<Format> {
+    let mut lines = numbered_lines(data).map(|(_, line)| line);
+    while let Some(line) = lines.next() {
+        if line.starts_with(b"=ybegin ") {
+            return Some(Format::YEnc);
+        } else if line.starts_with(b"xbtoa Begin") {
+            return Some(Format::Btoa);
+        } else if line.starts_with(b"(This file must be converted with BinHex") {
+            return Some(Format::BinHex);
+        } else if line.starts_with(b"begin-base64 ") || line.starts_with(b"begin-base64-encoded ") {
+            return Some(Format::Base64);
+        } else if is_begin_line(line) {
+            return Some(uu_or_xx(lines));
+        } else if line.trim_ascii_start().starts_with(b"<~") {
+            return Some(Format::Ascii85);
+        }
+    }
+
+    // without a header, look at the characters of the lines themselves
+    let body: Vec<&[u8]> = numbered_lines(data).map(|(_, line)| line).filter(|line| !line.is_empty()).collect();
+    let first = body.first()?;
+    let is_uu_line = |line: &[u8]| line.iter().all(|&ch| (b' '..=b'`').contains(&ch));
+    let is_base64_line = |line: &[u8]| line.iter().all(|&ch| ch.is_ascii_alphanumeric() || matches!(ch, b'+' | b'/' | b'='));
+    if first.first() == Some(&b'M') && first.len() == 61 && body.iter().all(|line| is_uu_line(line)) {
+        Some(Format::UUEncode)
+    } else if body.iter().all(|line| is_base64_line(line)) {
+        Some(Format::Base64)
+    } else {
+        None
+    }
+}
+
+/// Identifies the encoding of the input with `detect_format`, then decodes it, returning the format and the data.
+/// For files that carry a name and mode, only the data of the first file is returned; for BinHex, the data fork.
+/// Example:
+/// ```rust
+/// fn decode() -> Result<(), uuencode_lite::UUEncodeError> {
+///     let (format, decoded) = uuencode_lite::decode_auto(b"Subject: cat\n\nbegin-base64 644 cat.txt\nY2F0\n====\n")?;
+///     assert_eq!(format, uuencode_lite::Format::Base64);
+///     assert_eq!(decoded, b"cat");
+///     Ok(())
+/// }
+/// ```
+pub fn decode_auto(data: &[u8]) -> Result<(Format, Vec<u8>), UUEncodeError> {
+    let Some(format) = detect_format(data) else {
+        return Err(UUEncodeError::new(0, 0, "Unrecognized encoding"));
+    };
+    let framed = numbered_lines(data).any(|(_, line)| line.starts_with(b"begin"));
+    let decoded = match format {
+        Format::UUEncode if framed => uudecode_file_with(data, &DecodeOptions::new())?.data,
+        Format::UUEncode => uudecode_with(data, &DecodeOptions::new())?,
+        Format::XXEncode => uudecode_file_with(data, &DecodeOptions::new().table(Table::XX))?.data,
+        Format::Base64 if framed => uudecode_file_with(data, &DecodeOptions::new())?.data,
+        Format::Base64 => base64_decode(data)?,
+        Format::YEnc => yenc_decode(data)?.data,
+        Format::Ascii85 => ascii85_decode(data.trim_ascii())?,
+        Format::Btoa => btoa_decode(data)?,
+        Format::BinHex => binhex_decode(data)?.data_fork,
+    };
+    Ok((format, decoded))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ascii85_encode, base64_encode, btoa_encode, uuencode, uuencode_file, yenc_encode, EncodeOptions, FileOptions, YEncOptions};
+
+    /// Tests detecting and decoding each format
+    #[test]
+    fn test_decode_auto() {
+        let source_data = std::fs::read("test_data/random_data.bin").expect("Can open test data");
+        let file = FileOptions::new("random_data.bin");
+        let encoded = [
+            (Format::UUEncode, std::fs::read("test_data/random_data.bin.uu").unwrap()),
+            (Format::UUEncode, uuencode(&source_data).unwrap().into_bytes()),
+            (Format::XXEncode, uuencode_file(&source_data, &file.clone().encoding(EncodeOptions::new().table(Table::XX))).unwrap().into_bytes()),
+            (Format::Base64, uuencode_file(&source_data, &file.clone().base64(true)).unwrap().into_bytes()),
+            (Format::Base64, base64_encode(&source_data).into_bytes()),
+            (Format::YEnc, yenc_encode(&source_data, &YEncOptions::new("random_data.bin"))),
+            (Format::Ascii85, ascii85_encode(&source_data).into_bytes()),
+            (Format::Btoa, btoa_encode(&source_data).into_bytes()),
+        ];
+        for (format, encoded) in encoded {
+            assert_eq!(decode_auto(&encoded).unwrap(), (format, source_data.clone()), "can detect and decode {:?}", format);
+        }
+    }
+
+    /// Tests that unrecognized input is rejected
+    #[test]
+    fn test_detect_unknown() {
+        assert_eq!(detect_format(b""), None, "recognizes nothing in empty input");
+        assert_eq!(detect_format(b"Hello, world!\nThis is plain text.\n"), None, "does not mistake prose for an encoding");
+        assert!(decode_auto(b"Hello, world!\n").is_err(), "refuses to decode unrecognized input");
+    }
+}
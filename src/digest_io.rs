@@ -0,0 +1,145 @@
+use digest::Update;
+use std::io::{self, Read, Write};
+
+/// A writer that feeds everything written through it to a hasher, such as a `sha2::Sha256`, before passing it on to
+/// the inner writer. Put it inside a `DecodeWriter` to checksum the decoded file, or around an `EncodeWriter` to
+/// checksum the data being encoded, in the same pass as the decoding or encoding.
+/// Example:
+/// ```rust
+/// fn decode() -> std::io::Result<()> {
+///     use sha2::Digest;
+///     let hashed = uuencode_lite::DigestWriter::new(Vec::new(), sha2::Sha256::new());
+///     let mut writer = uuencode_lite::DecodeWriter::new(hashed, uuencode_lite::DecodeOptions::new());
+///     std::io::copy(&mut &b"begin 644 cat.txt\n#8V%T\n`\nend\n"[..], &mut writer)?;
+///     let (decoded, hasher) = writer.finish()?.into_parts();
+///     assert_eq!(decoded, b"cat");
+///     assert_eq!(hasher.finalize()[..], sha2::Sha256::digest(b"cat")[..]);
+///     Ok(())
+/// }
+/// ```
+#[derive(Debug)]
+pub struct DigestWriter<W: Write, D: Update> {
+    inner: W,
+    digest: D,
+}
+
+impl<W: Write, D: Update> DigestWriter<W, D> {
+    /// Creates a writer which feeds `digest` with everything written to `inner`.
+    pub fn new(inner: W, digest: D) -> Self {
+        DigestWriter { inner, digest }
+    }
+
+    /// The hasher, fed with everything written so far.
+    pub fn digest(&self) -> &D {
+        &self.digest
+    }
+
+    /// The inner writer.
+    pub fn get_ref(&self) -> &W {
+        &self.inner
+    }
+
+    /// Returns the inner writer and the hasher.
+    pub fn into_parts(self) -> (W, D) {
+        (self.inner, self.digest)
+    }
+}
+
+impl<W: Write, D: Update> Write for DigestWriter<W, D> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let written = self.inner.write(buf)?;
+        self.digest.update(&buf[..written]);
+        Ok(written)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+/// A reader that feeds everything read through it to a hasher, such as a `sha2::Sha256`. Put it around a
+/// `DecodeReader` to checksum the decoded file, or inside an `EncodeReader` to checksum the data being encoded, in
+/// the same pass as the decoding or encoding.
+/// Example:
+/// ```rust
+/// fn decode() -> std::io::Result<()> {
+///     use sha2::Digest;
+///     use std::io::Read;
+///     let reader = uuencode_lite::DecodeReader::new(&b"begin 644 cat.txt\n#8V%T\n`\nend\n"[..], uuencode_lite::DecodeOptions::new());
+///     let mut hashed = uuencode_lite::DigestReader::new(reader, sha2::Sha256::new());
+///     std::io::copy(&mut hashed, &mut std::io::sink())?;
+///     let (_, hasher) = hashed.into_parts();
+///     assert_eq!(hasher.finalize()[..], sha2::Sha256::digest(b"cat")[..]);
+///     Ok(())
+/// }
+/// ```
+#[derive(Debug)]
+pub struct DigestReader<R: Read, D: Update> {
+    inner: R,
+    digest: D,
+}
+
+impl<R: Read, D: Update> DigestReader<R, D> {
+    /// Creates a reader which feeds `digest` with everything read from `inner`.
+    pub fn new(inner: R, digest: D) -> Self {
+        DigestReader { inner, digest }
+    }
+
+    /// The hasher, fed with everything read so far.
+    pub fn digest(&self) -> &D {
+        &self.digest
+    }
+
+    /// The inner reader.
+    pub fn get_ref(&self) -> &R {
+        &self.inner
+    }
+
+    /// Returns the inner reader and the hasher.
+    pub fn into_parts(self) -> (R, D) {
+        (self.inner, self.digest)
+    }
+}
+
+impl<R: Read, D: Update> Read for DigestReader<R, D> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let len = self.inner.read(buf)?;
+        self.digest.update(&buf[..len]);
+        Ok(len)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{DecodeOptions, DecodeReader, EncodeReader, EncodeWriter, FileOptions};
+    use sha2::{Digest, Sha256};
+    use std::vec::Vec;
+
+    /// Tests hashing the data on both sides of encoding and decoding, in one pass each
+    #[test]
+    fn test_digest_io() {
+        let source_data = std::fs::read("test_data/random_data.bin").expect("Can open test data");
+        let expected = Sha256::digest(&source_data);
+
+        let mut writer = DigestWriter::new(EncodeWriter::new(Vec::new(), FileOptions::new("random_data.bin")).unwrap(), Sha256::new());
+        for chunk in source_data.chunks(100) {
+            writer.write_all(chunk).unwrap();
+        }
+        let (writer, hasher) = writer.into_parts();
+        let encoded = writer.finish().unwrap();
+        assert_eq!(hasher.finalize(), expected, "hashes the data written to an encoder");
+
+        let mut reader = EncodeReader::new(DigestReader::new(&source_data[..], Sha256::new()), FileOptions::new("random_data.bin")).unwrap();
+        let mut reencoded = Vec::new();
+        reader.read_to_end(&mut reencoded).unwrap();
+        assert_eq!(reencoded, encoded);
+        assert_eq!(reader.into_inner().into_parts().1.finalize(), expected, "hashes the data read by an encoder");
+
+        let mut reader = DigestReader::new(DecodeReader::new(&encoded[..], DecodeOptions::new()), Sha256::new());
+        let mut decoded = Vec::new();
+        reader.read_to_end(&mut decoded).unwrap();
+        assert_eq!(decoded, source_data);
+        assert_eq!(reader.into_parts().1.finalize(), expected, "hashes the data read from a decoder");
+    }
+}
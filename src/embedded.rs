@@ -0,0 +1,494 @@
+use crate::{DecodeOptions, EncodeOptions, SliceDecoder, SliceEncoder, UUEncodeError};
+use embedded_io::{ErrorType, Read, Write};
+
+/// The most text one encoded line takes, including its checksum character and newline.
+const LINE_LEN: usize = 64;
+
+/// The most bytes one decoded line holds.
+const DECODED_LEN: usize = 63;
+
+/// An error from an embedded-io adapter: either from the inner reader or writer, or from decoding.
+#[derive(Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum EmbeddedIoError<E> {
+    /// The inner reader or writer failed.
+    Io(E),
+    /// The input was not valid uuencoded text.
+    Decode(UUEncodeError),
+}
+
+impl<E: core::fmt::Debug> core::fmt::Display for EmbeddedIoError<E> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            EmbeddedIoError::Io(err) => write!(f, "I/O error: {:?}", err),
+            EmbeddedIoError::Decode(err) => write!(f, "Decode error: {}", err),
+        }
+    }
+}
+
+impl<E: core::fmt::Debug> core::error::Error for EmbeddedIoError<E> {}
+
+/// Errors from the inner reader or writer keep their kind; decoding errors are `InvalidData`.
+impl<E: embedded_io::Error> embedded_io::Error for EmbeddedIoError<E> {
+    fn kind(&self) -> embedded_io::ErrorKind {
+        match self {
+            EmbeddedIoError::Io(err) => err.kind(),
+            EmbeddedIoError::Decode(_) => embedded_io::ErrorKind::InvalidData,
+        }
+    }
+}
+
+/// An `embedded_io::Write` adapter which uuencodes everything written to it as bare lines, as `uuencode` does,
+/// writing each line, followed by a newline, to the inner writer once it is complete. Nothing is allocated.
+/// Call `finish` once all the data is written, to write the last line.
+/// Example:
+/// ```rust
+/// fn encode() -> Result<(), embedded_io::SliceWriteError> {
+///     use embedded_io::Write;
+///     let options = uuencode_lite::EncodeOptions::new();
+///     let mut encoded = [0u8; 8];
+///     let mut writer = uuencode_lite::EmbeddedEncodeWriter::new(&mut encoded[..], &options);
+///     writer.write_all(b"cat")?;
+///     writer.finish()?;
+///     assert_eq!(&encoded[..6], b"#8V%T\n");
+///     Ok(())
+/// }
+/// ```
+#[derive(Debug)]
+pub struct EmbeddedEncodeWriter<'a, W> {
+    inner: W,
+    encoder: SliceEncoder<'a>,
+    /// Encoded text on its way to `inner`.
+    line: [u8; LINE_LEN],
+}
+
+impl<'a, W> EmbeddedEncodeWriter<'a, W> {
+    /// Creates a writer which encodes into `inner` with the given options.
+    pub fn new(inner: W, options: &'a EncodeOptions) -> Self {
+        EmbeddedEncodeWriter { inner, encoder: SliceEncoder::new(options), line: [0; LINE_LEN] }
+    }
+
+    /// Returns the inner writer. Any partial line not yet finished is lost.
+    pub fn into_inner(self) -> W {
+        self.inner
+    }
+}
+
+impl<W: Write> EmbeddedEncodeWriter<'_, W> {
+    /// Writes the last, partial, line and flushes the inner writer.
+    pub fn finish(&mut self) -> Result<(), W::Error> {
+        let written = self.encoder.finish(&mut self.line).expect("a line fits in the buffer");
+        self.inner.write_all(&self.line[..written])?;
+        self.inner.flush()
+    }
+}
+
+impl<W: ErrorType> ErrorType for EmbeddedEncodeWriter<'_, W> {
+    type Error = W::Error;
+}
+
+impl<W: Write> Write for EmbeddedEncodeWriter<'_, W> {
+    fn write(&mut self, buf: &[u8]) -> Result<usize, Self::Error> {
+        let (consumed, written) = self.encoder.push(buf, &mut self.line);
+        self.inner.write_all(&self.line[..written])?;
+        Ok(consumed)
+    }
+
+    /// Flushes the inner writer. A partial line is held back until it is complete, or until `finish`.
+    fn flush(&mut self) -> Result<(), Self::Error> {
+        self.inner.flush()
+    }
+}
+
+/// An `embedded_io::Write` adapter which decodes the bare uuencoded lines written to it, as `uudecode_with` does,
+/// writing each line's bytes to the inner writer once its newline arrives. Nothing is allocated.
+/// Call `finish` once all the text is written, to decode a last line without a newline.
+/// Example:
+/// ```rust
+/// fn decode() -> Result<(), uuencode_lite::EmbeddedIoError<embedded_io::SliceWriteError>> {
+///     use embedded_io::Write;
+///     let options = uuencode_lite::DecodeOptions::new();
+///     let mut decoded = [0u8; 3];
+///     let mut writer = uuencode_lite::EmbeddedDecodeWriter::new(&mut decoded[..], &options);
+///     writer.write_all(b"#8V%T\n")?;
+///     writer.finish()?;
+///     assert_eq!(&decoded, b"cat");
+///     Ok(())
+/// }
+/// ```
+#[derive(Debug)]
+pub struct EmbeddedDecodeWriter<'a, W> {
+    inner: W,
+    decoder: SliceDecoder<'a>,
+    /// Decoded bytes on their way to `inner`.
+    decoded: [u8; DECODED_LEN],
+}
+
+impl<'a, W> EmbeddedDecodeWriter<'a, W> {
+    /// Creates a writer which decodes into `inner` with the given options.
+    pub fn new(inner: W, options: &'a DecodeOptions) -> Self {
+        EmbeddedDecodeWriter { inner, decoder: SliceDecoder::new(options), decoded: [0; DECODED_LEN] }
+    }
+
+    /// Returns the inner writer. Any partial line not yet finished is lost.
+    pub fn into_inner(self) -> W {
+        self.inner
+    }
+}
+
+impl<W: Write> EmbeddedDecodeWriter<'_, W> {
+    /// Decodes a last line without a newline, if there is one, and flushes the inner writer.
+    pub fn finish(&mut self) -> Result<(), EmbeddedIoError<W::Error>> {
+        let written = self.decoder.finish(&mut self.decoded).map_err(EmbeddedIoError::Decode)?;
+        self.inner.write_all(&self.decoded[..written]).map_err(EmbeddedIoError::Io)?;
+        self.inner.flush().map_err(EmbeddedIoError::Io)
+    }
+}
+
+impl<W: ErrorType> ErrorType for EmbeddedDecodeWriter<'_, W> {
+    type Error = EmbeddedIoError<W::Error>;
+}
+
+impl<W: Write> Write for EmbeddedDecodeWriter<'_, W> {
+    fn write(&mut self, buf: &[u8]) -> Result<usize, Self::Error> {
+        let (consumed, written) = self.decoder.push(buf, &mut self.decoded).map_err(EmbeddedIoError::Decode)?;
+        self.inner.write_all(&self.decoded[..written]).map_err(EmbeddedIoError::Io)?;
+        Ok(consumed)
+    }
+
+    /// Flushes the inner writer. A partial line is held back until its newline arrives, or until `finish`.
+    fn flush(&mut self) -> Result<(), Self::Error> {
+        self.inner.flush().map_err(EmbeddedIoError::Io)
+    }
+}
+
+/// An `embedded_io::Read` adapter which uuencodes everything read from the inner reader as bare lines,
+/// as `uuencode` does, each followed by a newline. Nothing is allocated.
+/// Example:
+/// ```rust
+/// fn encode() -> Result<(), embedded_io::ReadExactError<core::convert::Infallible>> {
+///     use embedded_io::Read;
+///     let options = uuencode_lite::EncodeOptions::new();
+///     let mut reader = uuencode_lite::EmbeddedEncodeReader::new(&b"cat"[..], &options);
+///     let mut encoded = [0u8; 6];
+///     reader.read_exact(&mut encoded)?;
+///     assert_eq!(&encoded, b"#8V%T\n");
+///     Ok(())
+/// }
+/// ```
+#[derive(Debug)]
+pub struct EmbeddedEncodeReader<'a, R> {
+    inner: R,
+    encoder: SliceEncoder<'a>,
+    /// Input read from `inner`, at most one line's worth.
+    input: [u8; 45],
+    /// Encoded text not yet read.
+    line: [u8; LINE_LEN],
+    /// The range of `line` not yet read.
+    position: usize,
+    line_len: usize,
+    /// Whether the inner reader has ended and the last line has been encoded.
+    finished: bool,
+}
+
+impl<'a, R> EmbeddedEncodeReader<'a, R> {
+    /// Creates a reader which encodes what is read from `inner` with the given options.
+    pub fn new(inner: R, options: &'a EncodeOptions) -> Self {
+        EmbeddedEncodeReader { inner, encoder: SliceEncoder::new(options), input: [0; 45], line: [0; LINE_LEN], position: 0, line_len: 0, finished: false }
+    }
+
+    /// Returns the inner reader.
+    pub fn into_inner(self) -> R {
+        self.inner
+    }
+
+    /// Copies out encoded text not yet read, if there is any.
+    fn copy_out(&mut self, buf: &mut [u8]) -> Option<usize> {
+        let len = buf.len().min(self.line_len - self.position);
+        buf[..len].copy_from_slice(&self.line[self.position..self.position + len]);
+        self.position += len;
+        (len > 0 || buf.is_empty() || self.finished).then_some(len)
+    }
+
+    /// Encodes `len` bytes just read into `input`, or the last line if the inner reader has ended.
+    fn encode_input(&mut self, len: usize) {
+        self.position = 0;
+        if len == 0 {
+            self.line_len = self.encoder.finish(&mut self.line).expect("a line fits in the buffer");
+            self.finished = true;
+        } else {
+            let (consumed, written) = self.encoder.push(&self.input[..len], &mut self.line);
+            debug_assert_eq!(consumed, len, "one line of input always fits");
+            self.line_len = written;
+        }
+    }
+}
+
+impl<R: ErrorType> ErrorType for EmbeddedEncodeReader<'_, R> {
+    type Error = R::Error;
+}
+
+impl<R: Read> Read for EmbeddedEncodeReader<'_, R> {
+    fn read(&mut self, buf: &mut [u8]) -> Result<usize, Self::Error> {
+        loop {
+            if let Some(len) = self.copy_out(buf) {
+                return Ok(len);
+            }
+            let len = self.inner.read(&mut self.input)?;
+            self.encode_input(len);
+        }
+    }
+}
+
+/// An `embedded_io::Read` adapter which decodes bare uuencoded lines read from the inner reader, as `uudecode_with`
+/// does, such as text arriving over a UART. Nothing is allocated.
+/// Example:
+/// ```rust
+/// fn decode() -> Result<(), embedded_io::ReadExactError<uuencode_lite::EmbeddedIoError<core::convert::Infallible>>> {
+///     use embedded_io::Read;
+///     let options = uuencode_lite::DecodeOptions::new();
+///     let mut reader = uuencode_lite::EmbeddedDecodeReader::new(&b"#8V%T\r\n"[..], &options);
+///     let mut decoded = [0u8; 3];
+///     reader.read_exact(&mut decoded)?;
+///     assert_eq!(&decoded, b"cat");
+///     Ok(())
+/// }
+/// ```
+#[derive(Debug)]
+pub struct EmbeddedDecodeReader<'a, R> {
+    inner: R,
+    decoder: SliceDecoder<'a>,
+    /// Text read from `inner`.
+    input: [u8; LINE_LEN],
+    /// The range of `input` not yet decoded.
+    input_position: usize,
+    input_len: usize,
+    /// Decoded bytes not yet read.
+    decoded: [u8; DECODED_LEN],
+    /// The range of `decoded` not yet read.
+    position: usize,
+    decoded_len: usize,
+    /// Whether the inner reader has ended and the last line has been decoded.
+    finished: bool,
+}
+
+impl<'a, R> EmbeddedDecodeReader<'a, R> {
+    /// Creates a reader which decodes what is read from `inner` with the given options.
+    pub fn new(inner: R, options: &'a DecodeOptions) -> Self {
+        EmbeddedDecodeReader {
+            inner,
+            decoder: SliceDecoder::new(options),
+            input: [0; LINE_LEN],
+            input_position: 0,
+            input_len: 0,
+            decoded: [0; DECODED_LEN],
+            position: 0,
+            decoded_len: 0,
+            finished: false,
+        }
+    }
+
+    /// Returns the inner reader.
+    pub fn into_inner(self) -> R {
+        self.inner
+    }
+
+    /// Copies out decoded bytes not yet read, if there are any.
+    fn copy_out(&mut self, buf: &mut [u8]) -> Option<usize> {
+        let len = buf.len().min(self.decoded_len - self.position);
+        buf[..len].copy_from_slice(&self.decoded[self.position..self.position + len]);
+        self.position += len;
+        (len > 0 || buf.is_empty() || self.finished).then_some(len)
+    }
+
+    /// Whether all the text read from `inner` has been decoded.
+    fn needs_input(&self) -> bool {
+        self.input_position == self.input_len
+    }
+
+    /// Decodes text read into `input`, after `len` more bytes have been read, or the last line if the inner reader has ended.
+    fn decode_input(&mut self, len: Option<usize>) -> Result<(), UUEncodeError> {
+        self.position = 0;
+        if let Some(len) = len {
+            self.input_position = 0;
+            self.input_len = len;
+        }
+        if self.input_len == 0 {
+            self.decoded_len = self.decoder.finish(&mut self.decoded)?;
+            self.finished = true;
+        } else {
+            let (consumed, written) = self.decoder.push(&self.input[self.input_position..self.input_len], &mut self.decoded)?;
+            self.input_position += consumed;
+            self.decoded_len = written;
+        }
+        Ok(())
+    }
+}
+
+impl<R: ErrorType> ErrorType for EmbeddedDecodeReader<'_, R> {
+    type Error = EmbeddedIoError<R::Error>;
+}
+
+impl<R: Read> Read for EmbeddedDecodeReader<'_, R> {
+    fn read(&mut self, buf: &mut [u8]) -> Result<usize, Self::Error> {
+        loop {
+            if let Some(len) = self.copy_out(buf) {
+                return Ok(len);
+            }
+            let len = if self.needs_input() { Some(self.inner.read(&mut self.input).map_err(EmbeddedIoError::Io)?) } else { None };
+            self.decode_input(len).map_err(EmbeddedIoError::Decode)?;
+        }
+    }
+}
+
+#[cfg(feature = "embedded-io-async")]
+mod async_impls {
+    use super::{EmbeddedDecodeReader, EmbeddedDecodeWriter, EmbeddedEncodeReader, EmbeddedEncodeWriter, EmbeddedIoError};
+    use embedded_io_async::{Read, Write};
+
+    impl<W: Write> EmbeddedEncodeWriter<'_, W> {
+        /// Writes the last, partial, line and flushes the inner writer, as `finish` does, asynchronously.
+        pub async fn finish_async(&mut self) -> Result<(), W::Error> {
+            let written = self.encoder.finish(&mut self.line).expect("a line fits in the buffer");
+            self.inner.write_all(&self.line[..written]).await?;
+            self.inner.flush().await
+        }
+    }
+
+    impl<W: Write> Write for EmbeddedEncodeWriter<'_, W> {
+        async fn write(&mut self, buf: &[u8]) -> Result<usize, Self::Error> {
+            let (consumed, written) = self.encoder.push(buf, &mut self.line);
+            self.inner.write_all(&self.line[..written]).await?;
+            Ok(consumed)
+        }
+
+        async fn flush(&mut self) -> Result<(), Self::Error> {
+            self.inner.flush().await
+        }
+    }
+
+    impl<W: Write> EmbeddedDecodeWriter<'_, W> {
+        /// Decodes a last line without a newline and flushes the inner writer, as `finish` does, asynchronously.
+        pub async fn finish_async(&mut self) -> Result<(), EmbeddedIoError<W::Error>> {
+            let written = self.decoder.finish(&mut self.decoded).map_err(EmbeddedIoError::Decode)?;
+            self.inner.write_all(&self.decoded[..written]).await.map_err(EmbeddedIoError::Io)?;
+            self.inner.flush().await.map_err(EmbeddedIoError::Io)
+        }
+    }
+
+    impl<W: Write> Write for EmbeddedDecodeWriter<'_, W> {
+        async fn write(&mut self, buf: &[u8]) -> Result<usize, Self::Error> {
+            let (consumed, written) = self.decoder.push(buf, &mut self.decoded).map_err(EmbeddedIoError::Decode)?;
+            self.inner.write_all(&self.decoded[..written]).await.map_err(EmbeddedIoError::Io)?;
+            Ok(consumed)
+        }
+
+        async fn flush(&mut self) -> Result<(), Self::Error> {
+            self.inner.flush().await.map_err(EmbeddedIoError::Io)
+        }
+    }
+
+    impl<R: Read> Read for EmbeddedEncodeReader<'_, R> {
+        async fn read(&mut self, buf: &mut [u8]) -> Result<usize, Self::Error> {
+            loop {
+                if let Some(len) = self.copy_out(buf) {
+                    return Ok(len);
+                }
+                let len = self.inner.read(&mut self.input).await?;
+                self.encode_input(len);
+            }
+        }
+    }
+
+    impl<R: Read> Read for EmbeddedDecodeReader<'_, R> {
+        async fn read(&mut self, buf: &mut [u8]) -> Result<usize, Self::Error> {
+            loop {
+                if let Some(len) = self.copy_out(buf) {
+                    return Ok(len);
+                }
+                let len = if self.needs_input() { Some(self.inner.read(&mut self.input).await.map_err(EmbeddedIoError::Io)?) } else { None };
+                self.decode_input(len).map_err(EmbeddedIoError::Decode)?;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Tests encoding and decoding through the writers and readers, in small pieces
+    #[test]
+    fn test_embedded_io_rt() {
+        let source_data = std::fs::read("test_data/random_data.bin").expect("Can open test data");
+        let mut expected = std::fs::read("test_data/random_data.bin.uu").expect("Can open test data");
+        expected.push(b'\n');
+        let encode_options = EncodeOptions::new();
+        let decode_options = DecodeOptions::new();
+
+        let mut encoded = [0u8; 4096];
+        let mut writer = EmbeddedEncodeWriter::new(&mut encoded[..], &encode_options);
+        for chunk in source_data.chunks(100) {
+            writer.write_all(chunk).unwrap();
+        }
+        writer.finish().unwrap();
+        let remaining = writer.into_inner().len();
+        let len = encoded.len() - remaining;
+        assert_eq!(&encoded[..len], expected, "can encode through a writer");
+
+        let mut reader = EmbeddedEncodeReader::new(&source_data[..], &encode_options);
+        let mut read = [0u8; 4096];
+        let mut len = 0;
+        loop {
+            match reader.read(&mut read[len..len + 7]).unwrap() {
+                0 => break,
+                n => len += n,
+            }
+        }
+        assert_eq!(&read[..len], expected, "can encode through a reader");
+
+        let mut decoded = [0u8; 2048];
+        let mut writer = EmbeddedDecodeWriter::new(&mut decoded[..], &decode_options);
+        for chunk in expected.chunks(99) {
+            writer.write_all(chunk).unwrap();
+        }
+        writer.finish().unwrap();
+        assert_eq!(decoded[..], source_data, "can decode through a writer");
+
+        let mut reader = EmbeddedDecodeReader::new(&expected[..], &decode_options);
+        let mut decoded = [0u8; 2048];
+        reader.read_exact(&mut decoded).unwrap();
+        assert_eq!(reader.read(&mut [0u8; 1]).unwrap(), 0, "ends with the input");
+        assert_eq!(decoded[..], source_data, "can decode through a reader");
+
+        let mut reader = EmbeddedDecodeReader::new(&b"#8V\x01T\n"[..], &decode_options);
+        let err = reader.read(&mut decoded).unwrap_err();
+        assert_eq!(embedded_io::Error::kind(&err), embedded_io::ErrorKind::InvalidData, "reports invalid input");
+    }
+
+    /// Tests the async writers and readers
+    #[cfg(feature = "embedded-io-async")]
+    #[test]
+    fn test_embedded_io_async_rt() {
+        let source_data = std::fs::read("test_data/random_data.bin").expect("Can open test data");
+        let mut expected = std::fs::read("test_data/random_data.bin.uu").expect("Can open test data");
+        expected.push(b'\n');
+        let encode_options = EncodeOptions::new();
+        let decode_options = DecodeOptions::new();
+
+        futures::executor::block_on(async {
+            let mut encoded = [0u8; 4096];
+            let mut writer = EmbeddedEncodeWriter::new(&mut encoded[..], &encode_options);
+            embedded_io_async::Write::write_all(&mut writer, &source_data).await.unwrap();
+            writer.finish_async().await.unwrap();
+            let remaining = writer.into_inner().len();
+            let len = encoded.len() - remaining;
+            assert_eq!(&encoded[..len], expected, "can encode through an async writer");
+
+            let mut reader = EmbeddedDecodeReader::new(&expected[..], &decode_options);
+            let mut decoded = [0u8; 2048];
+            embedded_io_async::Read::read_exact(&mut reader, &mut decoded).await.unwrap();
+            assert_eq!(decoded[..], source_data, "can decode through an async reader");
+        });
+    }
+}
@@ -0,0 +1,283 @@
+use alloc::format;
+use alloc::string::String;
+use alloc::vec::Vec;
+use crate::base64;
+use crate::checksum::ChecksumState;
+use crate::file::begin_line;
+use crate::{encode_line, push_encoded, uuencoded_len, FileOptions, UUEncodeError};
+
+/// The number of bytes encoded on each full line, for both uuencode and Base64 bodies.
+const LINE_BYTES: usize = 45;
+
+/// Incrementally encodes a complete file, as `uuencode_file`, from data that arrives in pieces of any size.
+/// Each call to `push` appends the lines it completed to a caller-provided buffer, so the whole input never
+/// needs to be held in memory; `finish` appends the last partial line and the end of the file.
+/// Example:
+/// ```rust
+/// fn encode() -> Result<(), uuencode_lite::UUEncodeError> {
+///     let mut encoder = uuencode_lite::Encoder::new(uuencode_lite::FileOptions::new("cat.txt"))?;
+///     let mut encoded = String::new();
+///     encoder.push(b"c", &mut encoded)?;
+///     encoder.push(b"at", &mut encoded)?;
+///     assert_eq!(encoded, "begin 644 cat.txt\n");
+///     encoder.finish(&mut encoded)?;
+///     assert_eq!(encoded, "begin 644 cat.txt\n#8V%T\n`\nend\n");
+///     Ok(())
+/// }
+/// ```
+#[derive(Debug, Clone)]
+pub struct Encoder {
+    options: FileOptions,
+    /// Bytes waiting for a full line.
+    pending: Vec<u8>,
+    /// Whether the header lines have been written.
+    started: bool,
+    /// Whether the footer lines have been written.
+    finished: bool,
+    /// The running whole-file checksum, if a trailer was requested.
+    checksum: Option<ChecksumState>,
+}
+
+impl Encoder {
+    /// Creates an encoder for a file with the given options.
+    /// Fails if the file name is invalid, as `uuencode_file` does.
+    pub fn new(options: FileOptions) -> Result<Self, UUEncodeError> {
+        if options.name.is_empty() || (!options.encode_name && options.name.contains(['\n', '\r'])) {
+            let err = UUEncodeError::new(0, 0, format!("Invalid file name: {:?}", options.name));
+            #[cfg(feature = "metrics")]
+            if let Some(metrics) = crate::metrics::installed() {
+                metrics.error(&err);
+            }
+            return Err(err);
+        }
+        let checksum = options.trailer.map(ChecksumState::new);
+        Ok(Encoder { options, pending: Vec::with_capacity(LINE_BYTES), started: false, finished: false, checksum })
+    }
+
+    /// Writes the `table` directive and `begin` line, if not already written.
+    fn start(&mut self, encoded: &mut String) {
+        if self.started {
+            return;
+        }
+        self.started = true;
+        self.header(encoded);
+    }
+
+    /// Writes the `table` directive, if one was asked for, and the `begin` line.
+    fn header(&self, encoded: &mut String) {
+        let options = &self.options;
+        if options.emit_table && !options.base64 {
+            let alphabet = options.encoding.table.alphabet();
+            encoded.push_str("table\n");
+            push_encoded(encoded, &alphabet[..32]);
+            encoded.push('\n');
+            push_encoded(encoded, &alphabet[32..]);
+            encoded.push('\n');
+        }
+        begin_line(options, encoded);
+    }
+
+    /// The length of the text that encoding `len` more bytes and then finishing will append, so that it can be
+    /// allocated up front. This is exact, except that room is left for the longest possible checksum trailer.
+    pub(crate) fn encoded_len(&self, len: usize) -> usize {
+        if self.finished {
+            return 0;
+        }
+        let header_len = if self.started {
+            0
+        } else {
+            let mut header = String::new();
+            self.header(&mut header);
+            header.len()
+        };
+        let trailer_len = self.checksum.as_ref().map_or(0, |checksum| checksum.max_trailer_len(checksum.len() + len) + 1);
+        header_len + self.body_len(self.pending.len() + len) + self.footer_len() + trailer_len
+    }
+
+    /// The length of the body lines, with their newlines, that encode `len` bytes.
+    fn body_len(&self, len: usize) -> usize {
+        if self.options.base64 {
+            len.div_ceil(3) * 4 + len.div_ceil(LINE_BYTES)
+        } else {
+            uuencoded_len(len, &self.options.encoding) + usize::from(len > 0)
+        }
+    }
+
+    /// The length of the terminator and `end` lines, or the `====` line.
+    fn footer_len(&self) -> usize {
+        if self.options.base64 { "====\n".len() } else { "`\nend\n".len() }
+    }
+
+    /// Encodes one line of at most 45 bytes, with its newline.
+    fn push_line(&self, line: &[u8], encoded: &mut String) {
+        if self.options.base64 {
+            base64::encode_into(line, encoded);
+        } else {
+            encode_line(line, &self.options.encoding, encoded);
+        }
+        encoded.push('\n');
+    }
+
+    /// Encodes the data, appending the header lines (on the first call) and every completed line to `encoded`.
+    /// Bytes that don't fill a line are kept until more data arrives or the encoder is finished.
+    /// Data pushed after `finish` is ignored. Fails if the cancellation token has been cancelled, after which
+    /// the encoder should not be used further.
+    pub fn push(&mut self, data: &[u8], encoded: &mut String) -> Result<(), UUEncodeError> {
+        #[cfg(feature = "metrics")]
+        let (start, consumed) = (encoded.len(), if self.finished { 0 } else { data.len() });
+        let result = self.push_lines(data, encoded);
+        #[cfg(feature = "metrics")]
+        record_metrics(&result, consumed, &encoded[start..]);
+        result
+    }
+
+    fn push_lines(&mut self, mut data: &[u8], encoded: &mut String) -> Result<(), UUEncodeError> {
+        if self.finished {
+            return Ok(());
+        }
+        self.start(encoded);
+        if let Some(checksum) = &mut self.checksum {
+            checksum.update(data);
+        }
+        if !self.pending.is_empty() {
+            let take = (LINE_BYTES - self.pending.len()).min(data.len());
+            self.pending.extend_from_slice(&data[..take]);
+            data = &data[take..];
+            if self.pending.len() < LINE_BYTES {
+                return Ok(());
+            }
+            self.check_cancelled()?;
+            let line = core::mem::take(&mut self.pending);
+            self.push_line(&line, encoded);
+            self.pending = line;
+            self.pending.clear();
+        }
+        let mut lines = data.chunks_exact(LINE_BYTES);
+        encoded.reserve(self.body_len(data.len() - lines.remainder().len()));
+        for line in lines.by_ref() {
+            self.check_cancelled()?;
+            self.push_line(line, encoded);
+        }
+        self.pending.extend_from_slice(lines.remainder());
+        Ok(())
+    }
+
+    /// Encodes any remaining bytes, then appends the terminator, `end` (or `====`) and trailer lines to `encoded`.
+    /// Does nothing if the encoder was already finished. Fails if the cancellation token has been cancelled.
+    pub fn finish(&mut self, encoded: &mut String) -> Result<(), UUEncodeError> {
+        #[cfg(feature = "metrics")]
+        let start = encoded.len();
+        let result = self.finish_lines(encoded);
+        #[cfg(feature = "metrics")]
+        record_metrics(&result, 0, &encoded[start..]);
+        result
+    }
+
+    fn finish_lines(&mut self, encoded: &mut String) -> Result<(), UUEncodeError> {
+        if self.finished {
+            return Ok(());
+        }
+        self.check_cancelled()?;
+        self.start(encoded);
+        self.finished = true;
+        let trailer = self.checksum.as_ref().map(ChecksumState::trailer);
+        encoded.reserve(self.body_len(self.pending.len()) + self.footer_len() + trailer.as_ref().map_or(0, |trailer| trailer.len() + 1));
+        if !self.pending.is_empty() {
+            let line = core::mem::take(&mut self.pending);
+            self.push_line(&line, encoded);
+        }
+        if self.options.base64 {
+            encoded.push_str("====\n");
+        } else {
+            encoded.push(self.options.encoding.table.encode(0) as char);
+            encoded.push_str("\nend\n");
+        }
+        if let Some(trailer) = trailer {
+            encoded.push_str(&trailer);
+            encoded.push('\n');
+        }
+        Ok(())
+    }
+
+    /// Fails if the cancellation token has been cancelled.
+    fn check_cancelled(&self) -> Result<(), UUEncodeError> {
+        match &self.options.cancel {
+            Some(token) if token.is_cancelled() => Err(UUEncodeError::cancelled(0)),
+            _ => Ok(()),
+        }
+    }
+}
+
+/// Counts the data taken and lines written by a call to `push` or `finish`, or the error it failed with.
+#[cfg(feature = "metrics")]
+fn record_metrics(result: &Result<(), UUEncodeError>, consumed: usize, written: &str) {
+    if let Some(metrics) = crate::metrics::installed() {
+        match result {
+            Ok(()) => metrics.encoded(consumed as u64, written.bytes().filter(|&ch| ch == b'\n').count() as u64),
+            Err(err) => metrics.error(err),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{uuencode_file, Checksum, EncodeOptions};
+
+    /// Tests that pushing data in pieces matches encoding it all at once
+    #[test]
+    fn test_encoder_pieces() {
+        let source_data = std::fs::read("test_data/random_data.bin").expect("Can open test data");
+        let all_options = [
+            FileOptions::new("random_data.bin").encoding(EncodeOptions::new().line_checksums(true)),
+            FileOptions::new("random_data.bin").base64(true).trailer(Some(Checksum::BsdSum)),
+        ];
+        for options in all_options {
+            let expected = uuencode_file(&source_data, &options).unwrap();
+            for piece_size in [1, 3, 44, 45, 46, 90, 1000] {
+                let mut encoder = Encoder::new(options.clone()).unwrap();
+                let mut encoded = String::new();
+                for piece in source_data.chunks(piece_size) {
+                    encoder.push(piece, &mut encoded).unwrap();
+                    assert!(encoded.ends_with('\n'), "only emits complete lines");
+                }
+                encoder.finish(&mut encoded).unwrap();
+                encoder.push(b"ignored", &mut encoded).unwrap();
+                encoder.finish(&mut encoded).unwrap();
+                assert_eq!(encoded, expected, "encodes pieces of {} bytes", piece_size);
+            }
+        }
+    }
+
+    /// Tests that the space reserved for a whole file is exactly what it takes
+    #[test]
+    fn test_encoder_capacity() {
+        let all_options = [
+            FileOptions::new("cat.txt"),
+            FileOptions::new("cat.txt").encoding(EncodeOptions::new().line_checksums(true)).emit_table(true),
+            FileOptions::new("my cat.txt").base64(true).encode_name(true),
+            FileOptions::new("cat.txt").encode_name(true).trailer(Some(Checksum::Crc32)),
+        ];
+        for options in all_options {
+            for len in [0, 1, 2, 3, 44, 45, 46, 90, 1000] {
+                let data = alloc::vec![0x5Au8; len];
+                let encoded = uuencode_file(&data, &options).unwrap();
+                assert_eq!(encoded.capacity(), encoded.len(), "allocates exactly {} bytes for {:?}", len, options);
+            }
+        }
+    }
+
+    /// Tests that a cancelled token stops encoding between lines
+    #[test]
+    fn test_encoder_cancel() {
+        let token = crate::CancelToken::new();
+        let mut encoder = Encoder::new(FileOptions::new("cat.txt").cancel_token(token.clone())).unwrap();
+        let mut encoded = String::new();
+        encoder.push(&[0u8; 50], &mut encoded).unwrap();
+        token.cancel();
+        let err = encoder.push(&[0u8; 50], &mut encoded).unwrap_err();
+        assert_eq!(err.kind(), crate::ErrorKind::Cancelled, "stops before the next line");
+        assert_eq!(encoded.lines().count(), 2, "keeps the lines encoded before cancelling");
+        assert!(encoder.finish(&mut encoded).is_err(), "can't finish once cancelled");
+    }
+}
@@ -0,0 +1,333 @@
+use alloc::boxed::Box;
+use alloc::string::String;
+use alloc::vec::Vec;
+use crate::{
+    ascii85_decode, ascii85_encode, base64_decode, base64_encode, btoa_decode, btoa_encode, quoted_printable_decode, quoted_printable_encode, uudecode_with, uuencode_with, z85_decode, z85_encode, DecodeOptions,
+    EncodeOptions, Table, UUEncodeError,
+};
+
+/// A text encoding for binary data, so codecs can be chosen at runtime and used interchangeably.
+/// Implemented by every codec in the crate that encodes to text and decodes back without extra metadata;
+/// yEnc (which produces 8-bit output) and BinHex (which decodes to a `BinHexFile`) have their own functions instead.
+/// Example:
+/// ```rust
+/// fn pipeline() -> Result<(), uuencode_lite::UUEncodeError> {
+///     use uuencode_lite::Encoding;
+///     let codec = uuencode_lite::encoding_for_name("xxencode").unwrap();
+///     let encoded = codec.encode(b"cat")?;
+///     assert_eq!(encoded, "1Mq3o");
+///     assert_eq!(codec.decode(encoded.as_bytes())?, b"cat");
+///     Ok(())
+/// }
+/// ```
+pub trait Encoding {
+    /// The name of the encoding, as accepted by `encoding_for_name`.
+    fn name(&self) -> &'static str;
+
+    /// Encodes the data as text.
+    fn encode(&self, data: &[u8]) -> Result<String, UUEncodeError>;
+
+    /// Decodes text produced by `encode` back into the data.
+    fn decode(&self, data: &[u8]) -> Result<Vec<u8>, UUEncodeError>;
+
+    /// Estimates the length of the encoded text for `len` bytes of data, for preallocating buffers.
+    fn encoded_len_hint(&self, len: usize) -> usize;
+
+    /// Estimates the length of the data decoded from `len` bytes of text, for preallocating buffers.
+    fn decoded_len_hint(&self, len: usize) -> usize;
+}
+
+/// uuencoded lines, without the `begin` and `end` framing, as `uuencode_with` and `uudecode_with`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct UUEncoding {
+    table: Table,
+}
+
+impl UUEncoding {
+    /// Creates the encoding with the given alphabet.
+    pub fn new(table: Table) -> Self {
+        UUEncoding { table }
+    }
+
+    /// Creates the encoding with the xxencode alphabet.
+    pub fn xxencode() -> Self {
+        UUEncoding { table: Table::XX }
+    }
+}
+
+impl Encoding for UUEncoding {
+    fn name(&self) -> &'static str {
+        match self.table {
+            Table::STANDARD => "uuencode",
+            Table::XX => "xxencode",
+            _ => "uuencode-table",
+        }
+    }
+
+    fn encode(&self, data: &[u8]) -> Result<String, UUEncodeError> {
+        uuencode_with(data, &EncodeOptions::new().table(self.table.clone()))
+    }
+
+    fn decode(&self, data: &[u8]) -> Result<Vec<u8>, UUEncodeError> {
+        uudecode_with(data, &DecodeOptions::new().table(self.table.clone()))
+    }
+
+    fn encoded_len_hint(&self, len: usize) -> usize {
+        len.div_ceil(3) * 4 + len.div_ceil(45) * 2
+    }
+
+    fn decoded_len_hint(&self, len: usize) -> usize {
+        len / 4 * 3
+    }
+}
+
+/// MIME Base64, as `base64_encode` and `base64_decode`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Base64Encoding;
+
+impl Encoding for Base64Encoding {
+    fn name(&self) -> &'static str {
+        "base64"
+    }
+
+    fn encode(&self, data: &[u8]) -> Result<String, UUEncodeError> {
+        Ok(base64_encode(data))
+    }
+
+    fn decode(&self, data: &[u8]) -> Result<Vec<u8>, UUEncodeError> {
+        base64_decode(data)
+    }
+
+    fn encoded_len_hint(&self, len: usize) -> usize {
+        len.div_ceil(3) * 4 + len.div_ceil(57) * 2
+    }
+
+    fn decoded_len_hint(&self, len: usize) -> usize {
+        len / 4 * 3
+    }
+}
+
+/// Adobe Ascii85, as `ascii85_encode` and `ascii85_decode`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Ascii85Encoding;
+
+impl Encoding for Ascii85Encoding {
+    fn name(&self) -> &'static str {
+        "ascii85"
+    }
+
+    fn encode(&self, data: &[u8]) -> Result<String, UUEncodeError> {
+        Ok(ascii85_encode(data))
+    }
+
+    fn decode(&self, data: &[u8]) -> Result<Vec<u8>, UUEncodeError> {
+        ascii85_decode(data)
+    }
+
+    fn encoded_len_hint(&self, len: usize) -> usize {
+        len.div_ceil(4) * 5 + 4
+    }
+
+    fn decoded_len_hint(&self, len: usize) -> usize {
+        len / 5 * 4 + 4
+    }
+}
+
+/// ZeroMQ Z85, as `z85_encode` and `z85_decode`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Z85Encoding;
+
+impl Encoding for Z85Encoding {
+    fn name(&self) -> &'static str {
+        "z85"
+    }
+
+    fn encode(&self, data: &[u8]) -> Result<String, UUEncodeError> {
+        z85_encode(data)
+    }
+
+    fn decode(&self, data: &[u8]) -> Result<Vec<u8>, UUEncodeError> {
+        z85_decode(data)
+    }
+
+    fn encoded_len_hint(&self, len: usize) -> usize {
+        len / 4 * 5
+    }
+
+    fn decoded_len_hint(&self, len: usize) -> usize {
+        len / 5 * 4
+    }
+}
+
+/// The Unix `btoa` format, as `btoa_encode` and `btoa_decode`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct BtoaEncoding;
+
+impl Encoding for BtoaEncoding {
+    fn name(&self) -> &'static str {
+        "btoa"
+    }
+
+    fn encode(&self, data: &[u8]) -> Result<String, UUEncodeError> {
+        Ok(btoa_encode(data))
+    }
+
+    fn decode(&self, data: &[u8]) -> Result<Vec<u8>, UUEncodeError> {
+        btoa_decode(data)
+    }
+
+    fn encoded_len_hint(&self, len: usize) -> usize {
+        len.div_ceil(4) * 5 + len / 60 + 80
+    }
+
+    fn decoded_len_hint(&self, len: usize) -> usize {
+        len / 5 * 4
+    }
+}
+
+/// Quoted-printable, as `quoted_printable_encode` and `quoted_printable_decode`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct QuotedPrintableEncoding;
+
+impl Encoding for QuotedPrintableEncoding {
+    fn name(&self) -> &'static str {
+        "quoted-printable"
+    }
+
+    fn encode(&self, data: &[u8]) -> Result<String, UUEncodeError> {
+        Ok(quoted_printable_encode(data))
+    }
+
+    fn decode(&self, data: &[u8]) -> Result<Vec<u8>, UUEncodeError> {
+        quoted_printable_decode(data)
+    }
+
+    fn encoded_len_hint(&self, len: usize) -> usize {
+        // binary data may need every byte escaped
+        len * 3 + len / 25
+    }
+
+    fn decoded_len_hint(&self, len: usize) -> usize {
+        len
+    }
+}
+
+/// RFC 4648 Base32, with the standard or "Extended Hex" alphabet.
+#[cfg(feature = "base32")]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Base32Encoding {
+    hex: bool,
+}
+
+#[cfg(feature = "base32")]
+impl Base32Encoding {
+    /// Creates the encoding with the standard alphabet.
+    pub fn new() -> Self {
+        Base32Encoding { hex: false }
+    }
+
+    /// Creates the encoding with the "Extended Hex" alphabet.
+    pub fn hex() -> Self {
+        Base32Encoding { hex: true }
+    }
+}
+
+#[cfg(feature = "base32")]
+impl Encoding for Base32Encoding {
+    fn name(&self) -> &'static str {
+        if self.hex { "base32hex" } else { "base32" }
+    }
+
+    fn encode(&self, data: &[u8]) -> Result<String, UUEncodeError> {
+        Ok(if self.hex { crate::base32hex_encode(data) } else { crate::base32_encode(data) })
+    }
+
+    fn decode(&self, data: &[u8]) -> Result<Vec<u8>, UUEncodeError> {
+        if self.hex { crate::base32hex_decode(data) } else { crate::base32_decode(data) }
+    }
+
+    fn encoded_len_hint(&self, len: usize) -> usize {
+        len.div_ceil(5) * 8
+    }
+
+    fn decoded_len_hint(&self, len: usize) -> usize {
+        len / 8 * 5 + 4
+    }
+}
+
+/// RFC 4648 Base16, as `base16_encode` and `base16_decode`.
+#[cfg(feature = "base32")]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Base16Encoding;
+
+#[cfg(feature = "base32")]
+impl Encoding for Base16Encoding {
+    fn name(&self) -> &'static str {
+        "base16"
+    }
+
+    fn encode(&self, data: &[u8]) -> Result<String, UUEncodeError> {
+        Ok(crate::base16_encode(data))
+    }
+
+    fn decode(&self, data: &[u8]) -> Result<Vec<u8>, UUEncodeError> {
+        crate::base16_decode(data)
+    }
+
+    fn encoded_len_hint(&self, len: usize) -> usize {
+        len * 2
+    }
+
+    fn decoded_len_hint(&self, len: usize) -> usize {
+        len / 2
+    }
+}
+
+/// Looks up an encoding by its name (case-insensitively), returning `None` for unknown names.
+/// The names are those returned by `Encoding::name`, such as `uuencode`, `xxencode`, `base64` or `ascii85`.
+pub fn encoding_for_name(name: &str) -> Option<Box<dyn Encoding>> {
+    let encoding: Box<dyn Encoding> = match name.to_ascii_lowercase().as_str() {
+        "uuencode" => Box::new(UUEncoding::default()),
+        "xxencode" => Box::new(UUEncoding::xxencode()),
+        "base64" => Box::new(Base64Encoding),
+        "ascii85" => Box::new(Ascii85Encoding),
+        "z85" => Box::new(Z85Encoding),
+        "btoa" => Box::new(BtoaEncoding),
+        "quoted-printable" => Box::new(QuotedPrintableEncoding),
+        #[cfg(feature = "base32")]
+        "base32" => Box::new(Base32Encoding::new()),
+        #[cfg(feature = "base32")]
+        "base32hex" => Box::new(Base32Encoding::hex()),
+        #[cfg(feature = "base32")]
+        "base16" => Box::new(Base16Encoding),
+        _ => return None,
+    };
+    Some(encoding)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::vec;
+
+    /// Tests round-tripping data through every named encoding
+    #[test]
+    fn test_encodings_rt() {
+        let source_data = std::fs::read("test_data/random_data.bin").expect("Can open test data");
+        // z85 requires a multiple of 4 bytes
+        let data = &source_data[..source_data.len() / 4 * 4];
+        let mut names = vec!["uuencode", "xxencode", "base64", "ascii85", "z85", "btoa", "quoted-printable"];
+        if cfg!(feature = "base32") {
+            names.extend(["base32", "base32hex", "base16"]);
+        }
+        for name in names {
+            let codec = encoding_for_name(name).unwrap();
+            assert_eq!(codec.name(), name, "reports its name");
+            let encoded = codec.encode(data).unwrap();
+            assert!(codec.encoded_len_hint(data.len()) * 11 / 10 >= encoded.len(), "estimates the encoded length of {}", name);
+            assert!(codec.decoded_len_hint(encoded.len()) * 11 / 10 >= data.len(), "estimates the decoded length of {}", name);
+            assert_eq!(codec.decode(encoded.as_bytes()).unwrap(), data, "can round-trip {}", name);
+        }
+        assert!(encoding_for_name("rot13").is_none(), "rejects unknown names");
+    }
+}
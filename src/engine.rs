@@ -0,0 +1,136 @@
+use alloc::format;
+use alloc::string::String;
+use alloc::vec::Vec;
+use crate::base64::{encode_into_with, Base64Decoder};
+use crate::file::numbered_lines;
+use crate::{Encoding, Table, UUEncodeError};
+
+/// A Base64-style codec built from any 64-character `Table` and padding rule, packing 3 bytes into 4 characters
+/// without the per-line length prefix of uuencode (for which `EncodeOptions::table` takes a custom table).
+/// Encoded text can be wrapped into lines, and decoding accepts lines of any length, reporting errors by line and character.
+/// Example:
+/// ```rust
+/// fn url_safe() -> Result<(), uuencode_lite::UUEncodeError> {
+///     use uuencode_lite::Encoding;
+///     let engine = uuencode_lite::Engine::new(uuencode_lite::Table::BASE64_URL, None)?;
+///     let encoded = engine.encode(&[0xFB, 0xFF])?;
+///     assert_eq!(encoded, "-_8");
+///     assert_eq!(engine.decode(encoded.as_bytes())?, [0xFB, 0xFF]);
+///     Ok(())
+/// }
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Engine {
+    /// The alphabet used to encode 6-bit values.
+    table: Table,
+    /// The character padding the final group, if any.
+    padding: Option<u8>,
+    /// The number of characters per encoded line, or 0 to write a single line.
+    line_length: usize,
+}
+
+impl Engine {
+    /// Creates an engine with the given alphabet and padding character.
+    /// Without a padding character, the final group is left short, and decoding accepts a short final group.
+    /// The padding character must be printable ASCII other than space, as the alphabet's characters are, and must not be
+    /// part of the alphabet.
+    pub fn new(table: Table, padding: Option<u8>) -> Result<Engine, UUEncodeError> {
+        if let Some(padding) = padding {
+            if let Some(position) = table.alphabet().iter().position(|&ch| ch == padding) {
+                return Err(UUEncodeError::new(0, position, format!("Padding character is part of the alphabet: {}", padding as char)));
+            }
+            if !padding.is_ascii_graphic() {
+                return Err(UUEncodeError::new(0, 0, format!("Padding character is not printable ASCII: {:#04x}", padding)));
+            }
+        }
+        Ok(Engine { table, padding, line_length: 0 })
+    }
+
+    /// Sets the number of characters per encoded line, rounded down to a multiple of 4. Lines end with LF.
+    /// A length of 0 (the default) writes all the encoded text on a single line.
+    pub fn line_length(mut self, line_length: usize) -> Self {
+        self.line_length = line_length / 4 * 4;
+        self
+    }
+}
+
+impl Encoding for Engine {
+    fn name(&self) -> &'static str {
+        "engine"
+    }
+
+    fn encode(&self, data: &[u8]) -> Result<String, UUEncodeError> {
+        let mut encoded = String::with_capacity(self.encoded_len_hint(data.len()));
+        if self.line_length == 0 {
+            encode_into_with(data, &self.table, self.padding, &mut encoded);
+            return Ok(encoded);
+        }
+        for line in data.chunks(self.line_length / 4 * 3) {
+            encode_into_with(line, &self.table, self.padding, &mut encoded);
+            encoded.push('\n');
+        }
+        Ok(encoded)
+    }
+
+    fn decode(&self, data: &[u8]) -> Result<Vec<u8>, UUEncodeError> {
+        let mut decoder = Base64Decoder::new(self.table.clone(), self.padding);
+        let mut decoded = Vec::with_capacity(self.decoded_len_hint(data.len()));
+        let mut last_line = 0;
+        for (cur_line, line) in numbered_lines(data) {
+            decoder.push_line(line, cur_line, b"", &mut decoded)?;
+            last_line = cur_line;
+        }
+        decoder.finish(last_line, &mut decoded)?;
+        Ok(decoded)
+    }
+
+    fn encoded_len_hint(&self, len: usize) -> usize {
+        let lines = if self.line_length == 0 { 0 } else { len.div_ceil(self.line_length / 4 * 3) };
+        len.div_ceil(3) * 4 + lines
+    }
+
+    fn decoded_len_hint(&self, len: usize) -> usize {
+        len / 4 * 3 + 2
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::string::ToString;
+
+    /// Tests round-tripping data through engines with and without padding and line wrapping
+    #[test]
+    fn test_engine_rt() {
+        let source_data = std::fs::read("test_data/random_data.bin").expect("Can open test data");
+        let engines = [
+            Engine::new(Table::BASE64, Some(b'=')).unwrap().line_length(76),
+            Engine::new(Table::BASE64_URL, None).unwrap(),
+            Engine::new(Table::XX, Some(b'~')).unwrap().line_length(60),
+        ];
+        for engine in engines {
+            for len in [0, 1, 2, 3, 100, source_data.len()] {
+                let encoded = engine.encode(&source_data[..len]).unwrap();
+                assert_eq!(engine.decode(encoded.as_bytes()).unwrap(), &source_data[..len], "can round-trip {} bytes", len);
+            }
+        }
+        let padded = Engine::new(Table::BASE64, Some(b'=')).unwrap();
+        assert_eq!(padded.encode(b"fo").unwrap(), "Zm8=", "pads the final group");
+        assert!(padded.decode(b"Zm8").is_err(), "requires padding when it is configured");
+    }
+
+    /// Tests validation of the padding character and decoded characters
+    #[test]
+    fn test_engine_errors() {
+        assert!(Engine::new(Table::BASE64, Some(b'+')).is_err(), "rejects padding from the alphabet");
+        for padding in [b'\n', b'\r', b' ', 0, 0x7F, 0x80, 0xFF] {
+            let err = Engine::new(Table::BASE64, Some(padding)).unwrap_err();
+            assert_eq!(err.message().to_string(), format!("Padding character is not printable ASCII: {:#04x}", padding), "rejects {:#04x} as padding", padding);
+        }
+        assert!(Engine::new(Table::BASE64, Some(b'~')).is_ok(), "accepts other printable padding");
+        let engine = Engine::new(Table::BASE64_URL, None).unwrap();
+        let err = engine.decode(b"Zm9v\nZm+v").unwrap_err();
+        assert_eq!((err.line, err.character), (1, 2), "reports the position of an invalid character");
+        assert!(engine.decode(b"Z").is_err(), "rejects a single trailing character");
+    }
+}
@@ -0,0 +1,427 @@
+use crate::{uudecode_to_slice, uuencode_to_slice, uuencoded_len, Decoder, DecodeOptions, Encoder, EncodeOptions, ErrorKind, FileOptions, UUEncodeError};
+use core::ffi::{c_char, c_int, CStr};
+use core::ptr;
+use std::boxed::Box;
+use std::cell::RefCell;
+use std::ffi::CString;
+use std::string::{String, ToString};
+use std::vec::Vec;
+
+/// The result of a call from C. Null pointers are reported rather than dereferenced.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UUStatus {
+    /// The call succeeded.
+    Ok = 0,
+    /// The input was malformed, or an argument was invalid. `uu_last_error` describes the problem.
+    Invalid = 1,
+    /// The output did not fit in the buffer given for it.
+    Capacity = 2,
+    /// A required pointer was null.
+    NullPointer = 3,
+}
+
+std::thread_local! {
+    /// The message of the last error on this thread, for `uu_last_error`.
+    static LAST_ERROR: RefCell<Option<CString>> = const { RefCell::new(None) };
+}
+
+/// Converts a string for C, cutting it short at any nul.
+fn c_string(text: &str) -> CString {
+    CString::new(text.split('\0').next().unwrap_or_default()).expect("no nul before the first nul")
+}
+
+/// Records the message of a failed call, returning its status.
+fn set_error(status: UUStatus, msg: String) -> UUStatus {
+    LAST_ERROR.with(|last| *last.borrow_mut() = Some(c_string(&msg)));
+    status
+}
+
+/// Records a codec error, returning its status.
+fn codec_error(err: UUEncodeError) -> UUStatus {
+    let status = match err.kind() {
+        ErrorKind::Capacity => UUStatus::Capacity,
+        _ => UUStatus::Invalid,
+    };
+    set_error(status, err.to_string())
+}
+
+/// Records a null pointer argument, returning its status.
+fn null_pointer(name: &str) -> UUStatus {
+    set_error(UUStatus::NullPointer, std::format!("{} is null", name))
+}
+
+/// Borrows `len` bytes from `data`, which may be null if `len` is zero.
+///
+/// # Safety
+/// `data` must be null or point to `len` readable bytes which outlive the returned slice.
+unsafe fn input<'a>(data: *const u8, len: usize) -> Option<&'a [u8]> {
+    match (data.is_null(), len) {
+        (true, 0) => Some(&[]),
+        (true, _) => None,
+        // SAFETY: the caller guarantees `len` readable bytes.
+        (false, _) => Some(unsafe { core::slice::from_raw_parts(data, len) }),
+    }
+}
+
+/// Hands `output` to the caller through `out` and `out_len`.
+///
+/// # Safety
+/// `out` and `out_len` must be null or valid for writes.
+unsafe fn hand_out(output: &[u8], out: *mut *const u8, out_len: *mut usize) -> UUStatus {
+    if out.is_null() || out_len.is_null() {
+        return null_pointer("out");
+    }
+    // SAFETY: both were checked for null, and the caller guarantees they are valid for writes.
+    unsafe {
+        *out = output.as_ptr();
+        *out_len = output.len();
+    }
+    UUStatus::Ok
+}
+
+/// Returns the message of the last failed call on this thread, or null if no call has failed.
+/// The string is valid until the next failed call on this thread.
+#[unsafe(no_mangle)]
+pub extern "C" fn uu_last_error() -> *const c_char {
+    LAST_ERROR.with(|last| last.borrow().as_ref().map_or(ptr::null(), |msg| msg.as_ptr()))
+}
+
+/// Returns a static description of a status, or "Unknown status" for any other value.
+/// The status is taken as an `int`, as C may pass any value of an enum's type.
+#[unsafe(no_mangle)]
+pub extern "C" fn uu_status_string(status: c_int) -> *const c_char {
+    let known = [UUStatus::Ok, UUStatus::Invalid, UUStatus::Capacity, UUStatus::NullPointer].into_iter().find(|&known| known as c_int == status);
+    let msg: &'static CStr = match known {
+        Some(UUStatus::Ok) => c"OK",
+        Some(UUStatus::Invalid) => c"Invalid input",
+        Some(UUStatus::Capacity) => c"Output buffer too small",
+        Some(UUStatus::NullPointer) => c"Null pointer",
+        None => c"Unknown status",
+    };
+    msg.as_ptr()
+}
+
+/// The number of bytes `uu_encode` writes for `data_len` bytes of input.
+#[unsafe(no_mangle)]
+pub extern "C" fn uu_encoded_len(data_len: usize) -> usize {
+    uuencoded_len(data_len, &EncodeOptions::new())
+}
+
+/// Encodes `data_len` bytes of `data` as bare uuencoded lines, as `uuencode`, into the `out_cap` bytes at `out`,
+/// storing the number of bytes written in `out_len`. `uu_encoded_len` gives the space needed.
+/// The output is not nul-terminated.
+///
+/// # Safety
+/// `data` must point to `data_len` readable bytes, `out` to `out_cap` writable bytes, and `out_len` must be valid
+/// for writes. `data` and `out` may be null when their lengths are zero.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn uu_encode(data: *const u8, data_len: usize, out: *mut u8, out_cap: usize, out_len: *mut usize) -> UUStatus {
+    // SAFETY: the caller guarantees the buffers.
+    let (Some(data), Some(output)) = (unsafe { input(data, data_len) }, unsafe { output(out, out_cap) }) else {
+        return null_pointer("data or out");
+    };
+    if out_len.is_null() {
+        return null_pointer("out_len");
+    }
+    match uuencode_to_slice(data, output) {
+        Ok(len) => {
+            // SAFETY: checked for null above, and the caller guarantees it is valid for writes.
+            unsafe { *out_len = len };
+            UUStatus::Ok
+        }
+        Err(err) => codec_error(err),
+    }
+}
+
+/// Decodes `data_len` bytes of bare uuencoded lines from `data`, as `uudecode`, into the `out_cap` bytes at `out`,
+/// storing the number of bytes written in `out_len`. The decoded data is never longer than the text, so an
+/// `out_cap` of `data_len` is always enough.
+///
+/// # Safety
+/// `data` must point to `data_len` readable bytes, `out` to `out_cap` writable bytes, and `out_len` must be valid
+/// for writes. `data` and `out` may be null when their lengths are zero.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn uu_decode(data: *const u8, data_len: usize, out: *mut u8, out_cap: usize, out_len: *mut usize) -> UUStatus {
+    // SAFETY: the caller guarantees the buffers.
+    let (Some(data), Some(output)) = (unsafe { input(data, data_len) }, unsafe { output(out, out_cap) }) else {
+        return null_pointer("data or out");
+    };
+    if out_len.is_null() {
+        return null_pointer("out_len");
+    }
+    match uudecode_to_slice(data, output) {
+        Ok(len) => {
+            // SAFETY: checked for null above, and the caller guarantees it is valid for writes.
+            unsafe { *out_len = len };
+            UUStatus::Ok
+        }
+        Err(err) => codec_error(err),
+    }
+}
+
+/// Borrows `len` writable bytes at `out`, which may be null if `len` is zero.
+///
+/// # Safety
+/// `out` must be null or point to `len` writable bytes which outlive the returned slice.
+unsafe fn output<'a>(out: *mut u8, len: usize) -> Option<&'a mut [u8]> {
+    match (out.is_null(), len) {
+        (true, 0) => Some(&mut []),
+        (true, _) => None,
+        // SAFETY: the caller guarantees `len` writable bytes.
+        (false, _) => Some(unsafe { core::slice::from_raw_parts_mut(out, len) }),
+    }
+}
+
+/// A streaming decoder for a complete file, as `Decoder`, created by `uu_decoder_new`.
+pub struct UUDecoder {
+    decoder: Decoder,
+    /// The bytes decoded by the last call, handed out until the next.
+    output: Vec<u8>,
+    /// The file name from the `begin` line, once it has been seen.
+    name: Option<CString>,
+}
+
+/// Creates a decoder for a complete file, with the default options. Free it with `uu_decoder_free`.
+#[unsafe(no_mangle)]
+pub extern "C" fn uu_decoder_new() -> *mut UUDecoder {
+    Box::into_raw(Box::new(UUDecoder { decoder: Decoder::new(DecodeOptions::new()), output: Vec::new(), name: None }))
+}
+
+/// Decodes the next `data_len` bytes of text, which may end part way through a line. Points `out` at the bytes
+/// decoded from the lines it completed, and stores their number in `out_len`; they are valid until the next call
+/// with this decoder.
+///
+/// # Safety
+/// `decoder` must come from `uu_decoder_new` and not have been freed, `data` must point to `data_len` readable
+/// bytes, and `out` and `out_len` must be valid for writes.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn uu_decoder_feed(decoder: *mut UUDecoder, data: *const u8, data_len: usize, out: *mut *const u8, out_len: *mut usize) -> UUStatus {
+    // SAFETY: the caller guarantees the decoder and buffer.
+    let (Some(decoder), Some(data)) = (unsafe { decoder.as_mut() }, unsafe { input(data, data_len) }) else {
+        return null_pointer("decoder or data");
+    };
+    decoder.output.clear();
+    if let Err(err) = decoder.decoder.push(data, &mut decoder.output) {
+        return codec_error(err);
+    }
+    // SAFETY: the caller guarantees the out pointers.
+    unsafe { hand_out(&decoder.output, out, out_len) }
+}
+
+/// Decodes any last line without a newline, handing out its bytes as `uu_decoder_feed` does, and checks that the
+/// file was complete.
+///
+/// # Safety
+/// As for `uu_decoder_feed`.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn uu_decoder_finish(decoder: *mut UUDecoder, out: *mut *const u8, out_len: *mut usize) -> UUStatus {
+    // SAFETY: the caller guarantees the decoder.
+    let Some(decoder) = (unsafe { decoder.as_mut() }) else {
+        return null_pointer("decoder");
+    };
+    decoder.output.clear();
+    if let Err(err) = decoder.decoder.finish(&mut decoder.output) {
+        return codec_error(err);
+    }
+    // SAFETY: the caller guarantees the out pointers.
+    unsafe { hand_out(&decoder.output, out, out_len) }
+}
+
+/// Returns the file name from the `begin` line, storing its permission bits in `mode` unless it is null,
+/// or returns null if the `begin` line has not been seen yet. The name is valid until the decoder is freed.
+///
+/// # Safety
+/// `decoder` must come from `uu_decoder_new` and not have been freed, and `mode` must be null or valid for writes.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn uu_decoder_header(decoder: *mut UUDecoder, mode: *mut u32) -> *const c_char {
+    // SAFETY: the caller guarantees the decoder.
+    let Some(decoder) = (unsafe { decoder.as_mut() }) else {
+        null_pointer("decoder");
+        return ptr::null();
+    };
+    let Some((file_mode, name)) = decoder.decoder.header() else {
+        return ptr::null();
+    };
+    if !mode.is_null() {
+        // SAFETY: checked for null, and the caller guarantees it is valid for writes.
+        unsafe { *mode = file_mode };
+    }
+    decoder.name.get_or_insert_with(|| c_string(name)).as_ptr()
+}
+
+/// Frees a decoder. Does nothing if `decoder` is null.
+///
+/// # Safety
+/// `decoder` must be null, or come from `uu_decoder_new` and not have been freed.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn uu_decoder_free(decoder: *mut UUDecoder) {
+    if !decoder.is_null() {
+        // SAFETY: the caller guarantees it came from `Box::into_raw` in `uu_decoder_new`.
+        drop(unsafe { Box::from_raw(decoder) });
+    }
+}
+
+/// A streaming encoder for a complete file, as `Encoder`, created by `uu_encoder_new`.
+pub struct UUEncoder {
+    encoder: Encoder,
+    /// The text encoded by the last call, handed out until the next.
+    output: String,
+}
+
+/// Creates an encoder for a complete file with the given nul-terminated UTF-8 name and permission bits.
+/// Returns null if the name is null or invalid, with the reason in `uu_last_error`. Free it with `uu_encoder_free`.
+///
+/// # Safety
+/// `name` must be null or a valid nul-terminated string.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn uu_encoder_new(name: *const c_char, mode: u32) -> *mut UUEncoder {
+    if name.is_null() {
+        null_pointer("name");
+        return ptr::null_mut();
+    }
+    // SAFETY: checked for null, and the caller guarantees it is nul-terminated.
+    let Ok(name) = unsafe { CStr::from_ptr(name) }.to_str() else {
+        set_error(UUStatus::Invalid, "File name is not UTF-8".to_string());
+        return ptr::null_mut();
+    };
+    match Encoder::new(FileOptions::new(name).mode(mode)) {
+        Ok(encoder) => Box::into_raw(Box::new(UUEncoder { encoder, output: String::new() })),
+        Err(err) => {
+            codec_error(err);
+            ptr::null_mut()
+        }
+    }
+}
+
+/// Encodes the next `data_len` bytes. Points `out` at the text of the lines completed, and stores its length in
+/// `out_len`; it is valid until the next call with this encoder, and is not nul-terminated.
+///
+/// # Safety
+/// `encoder` must come from `uu_encoder_new` and not have been freed, `data` must point to `data_len` readable
+/// bytes, and `out` and `out_len` must be valid for writes.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn uu_encoder_feed(encoder: *mut UUEncoder, data: *const u8, data_len: usize, out: *mut *const u8, out_len: *mut usize) -> UUStatus {
+    // SAFETY: the caller guarantees the encoder and buffer.
+    let (Some(encoder), Some(data)) = (unsafe { encoder.as_mut() }, unsafe { input(data, data_len) }) else {
+        return null_pointer("encoder or data");
+    };
+    encoder.output.clear();
+    if let Err(err) = encoder.encoder.push(data, &mut encoder.output) {
+        return codec_error(err);
+    }
+    // SAFETY: the caller guarantees the out pointers.
+    unsafe { hand_out(encoder.output.as_bytes(), out, out_len) }
+}
+
+/// Encodes the last partial line and the end of the file, handing out the text as `uu_encoder_feed` does.
+///
+/// # Safety
+/// As for `uu_encoder_feed`.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn uu_encoder_finish(encoder: *mut UUEncoder, out: *mut *const u8, out_len: *mut usize) -> UUStatus {
+    // SAFETY: the caller guarantees the encoder.
+    let Some(encoder) = (unsafe { encoder.as_mut() }) else {
+        return null_pointer("encoder");
+    };
+    encoder.output.clear();
+    if let Err(err) = encoder.encoder.finish(&mut encoder.output) {
+        return codec_error(err);
+    }
+    // SAFETY: the caller guarantees the out pointers.
+    unsafe { hand_out(encoder.output.as_bytes(), out, out_len) }
+}
+
+/// Frees an encoder. Does nothing if `encoder` is null.
+///
+/// # Safety
+/// `encoder` must be null, or come from `uu_encoder_new` and not have been freed.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn uu_encoder_free(encoder: *mut UUEncoder) {
+    if !encoder.is_null() {
+        // SAFETY: the caller guarantees it came from `Box::into_raw` in `uu_encoder_new`.
+        drop(unsafe { Box::from_raw(encoder) });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Reads the last error message.
+    fn last_error() -> String {
+        unsafe { CStr::from_ptr(uu_last_error()) }.to_str().unwrap().to_string()
+    }
+
+    /// Tests encoding and decoding buffers, and reporting errors
+    #[test]
+    fn test_ffi_buffers() {
+        let source_data = std::fs::read("test_data/random_data.bin").expect("Can open test data");
+        let expected = std::fs::read("test_data/random_data.bin.uu").expect("Can open test data");
+        let expected = expected.strip_suffix(b"\n").unwrap_or(&expected);
+
+        let mut encoded = std::vec![0u8; uu_encoded_len(source_data.len())];
+        let mut len = 0;
+        let status = unsafe { uu_encode(source_data.as_ptr(), source_data.len(), encoded.as_mut_ptr(), encoded.len(), &mut len) };
+        assert_eq!((status, &encoded[..len]), (UUStatus::Ok, expected), "can encode into a buffer");
+
+        let mut decoded = std::vec![0u8; expected.len()];
+        let status = unsafe { uu_decode(expected.as_ptr(), expected.len(), decoded.as_mut_ptr(), decoded.len(), &mut len) };
+        assert_eq!((status, &decoded[..len]), (UUStatus::Ok, &source_data[..]), "can decode into a buffer");
+
+        let status = unsafe { uu_decode(expected.as_ptr(), expected.len(), decoded.as_mut_ptr(), 100, &mut len) };
+        assert_eq!(status, UUStatus::Capacity, "reports a buffer too small");
+        assert!(last_error().starts_with("Output buffer too small"), "describes the error");
+        let status = unsafe { uu_decode(b"#8V\x01T".as_ptr(), 5, decoded.as_mut_ptr(), decoded.len(), &mut len) };
+        assert_eq!(status, UUStatus::Invalid, "reports invalid input");
+        let status = unsafe { uu_decode(ptr::null(), 5, decoded.as_mut_ptr(), decoded.len(), &mut len) };
+        assert_eq!(status, UUStatus::NullPointer, "reports null pointers");
+        assert_eq!(unsafe { CStr::from_ptr(uu_status_string(status as c_int)) }, c"Null pointer", "describes statuses");
+        for unknown in [-1, 4, c_int::MAX] {
+            assert_eq!(unsafe { CStr::from_ptr(uu_status_string(unknown)) }, c"Unknown status", "describes {} as unknown", unknown);
+        }
+    }
+
+    /// Tests encoding and decoding a file in pieces through the streaming contexts
+    #[test]
+    fn test_ffi_streaming() {
+        let source_data = std::fs::read("test_data/the_machine_stops.txt").expect("Can open test data");
+        let (mut out, mut out_len) = (ptr::null(), 0);
+
+        let encoder = unsafe { uu_encoder_new(c"the_machine_stops.txt".as_ptr(), 0o600) };
+        let mut encoded = Vec::new();
+        for piece in source_data.chunks(100) {
+            assert_eq!(unsafe { uu_encoder_feed(encoder, piece.as_ptr(), piece.len(), &mut out, &mut out_len) }, UUStatus::Ok);
+            encoded.extend_from_slice(unsafe { core::slice::from_raw_parts(out, out_len) });
+        }
+        assert_eq!(unsafe { uu_encoder_finish(encoder, &mut out, &mut out_len) }, UUStatus::Ok);
+        encoded.extend_from_slice(unsafe { core::slice::from_raw_parts(out, out_len) });
+        unsafe { uu_encoder_free(encoder) };
+        let file = crate::uudecode_file(&encoded).unwrap();
+        assert_eq!((file.name.as_str(), file.mode, file.data), ("the_machine_stops.txt", 0o600, source_data.clone()), "can encode a file in pieces");
+
+        let decoder = uu_decoder_new();
+        let mut decoded = Vec::new();
+        let mut mode = 0;
+        assert!(unsafe { uu_decoder_header(decoder, &mut mode) }.is_null(), "has no header before the begin line");
+        for piece in encoded.chunks(100) {
+            assert_eq!(unsafe { uu_decoder_feed(decoder, piece.as_ptr(), piece.len(), &mut out, &mut out_len) }, UUStatus::Ok);
+            decoded.extend_from_slice(unsafe { core::slice::from_raw_parts(out, out_len) });
+        }
+        assert_eq!(unsafe { uu_decoder_finish(decoder, &mut out, &mut out_len) }, UUStatus::Ok);
+        decoded.extend_from_slice(unsafe { core::slice::from_raw_parts(out, out_len) });
+        let name = unsafe { CStr::from_ptr(uu_decoder_header(decoder, &mut mode)) };
+        assert_eq!((name, mode), (c"the_machine_stops.txt", 0o600), "reports the begin line");
+        assert_eq!(decoded, source_data, "can decode a file in pieces");
+
+        let truncated = &encoded[..1000];
+        let decoder2 = uu_decoder_new();
+        assert_eq!(unsafe { uu_decoder_feed(decoder2, truncated.as_ptr(), truncated.len(), &mut out, &mut out_len) }, UUStatus::Ok);
+        assert_eq!(unsafe { uu_decoder_finish(decoder2, &mut out, &mut out_len) }, UUStatus::Invalid, "reports a truncated file");
+        assert!(last_error().contains("at line"), "describes the error");
+        unsafe { uu_decoder_free(decoder) };
+        unsafe { uu_decoder_free(decoder2) };
+
+        assert!(unsafe { uu_encoder_new(c"a\nb".as_ptr(), 0o644) }.is_null(), "rejects an invalid name");
+    }
+}
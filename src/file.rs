@@ -0,0 +1,748 @@
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use crate::encoder::Encoder;
+use crate::checksum::Checksum;
+use crate::decoder::Decoder;
+use crate::{CancelToken, DecodeOptions, EncodeOptions, UUEncodeError};
+#[cfg(feature = "std")]
+use std::path::Path;
+
+/// Options controlling how a complete uuencoded file is framed.
+/// Example:
+/// ```rust
+/// let options = uuencode_lite::FileOptions::new("cat.txt").mode(0o600);
+/// assert_eq!(options.name(), "cat.txt");
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FileOptions {
+    /// The file name written to the `begin` line.
+    pub(crate) name: String,
+    /// The Unix permission bits written to the `begin` line, in octal.
+    pub(crate) mode: u32,
+    /// Whether to encode the body as Base64 under a `begin-base64` line.
+    pub(crate) base64: bool,
+    /// Whether to encode the file name on the `begin` line, as the body is encoded.
+    pub(crate) encode_name: bool,
+    /// How to encode a uuencoded body.
+    pub(crate) encoding: EncodeOptions,
+    /// Whether to write a `table` directive declaring the alphabet ahead of the `begin` line.
+    pub(crate) emit_table: bool,
+    /// The checksum to write on a trailer line after the end of the file, if any.
+    pub(crate) trailer: Option<Checksum>,
+    /// A token which stops the encoding when cancelled.
+    pub(crate) cancel: Option<CancelToken>,
+}
+
+impl FileOptions {
+    /// Creates options for a file with the given name, and the default mode of `644`.
+    pub fn new(name: impl Into<String>) -> Self {
+        FileOptions { name: name.into(), mode: 0o644, base64: false, encode_name: false, encoding: EncodeOptions::default(), emit_table: false, trailer: None, cancel: None }
+    }
+
+    /// Sets the permission bits to write to the `begin` line. Only the lower 12 bits are kept.
+    pub fn mode(mut self, mode: u32) -> Self {
+        self.mode = mode & 0o7777;
+        self
+    }
+
+    /// Sets whether to encode the body as Base64, framed by `begin-base64` and `====` lines,
+    /// as `uuencode -m` does.
+    pub fn base64(mut self, base64: bool) -> Self {
+        self.base64 = base64;
+        self
+    }
+
+    /// Sets whether to encode the file name, as `uuencode -e` does, so that names containing spaces, newlines or
+    /// other awkward characters survive transport. The `begin` line becomes `begin-encoded` (or
+    /// `begin-base64-encoded`), followed by the name encoded as the body is: as uuencoded lines of up to 45 bytes
+    /// run together, or as Base64. A name with control characters fails `is_safe_file_name`, so is only decoded with
+    /// `DecodeOptions::raw_names`.
+    pub fn encode_name(mut self, encode_name: bool) -> Self {
+        self.encode_name = encode_name;
+        self
+    }
+
+    /// Sets how a uuencoded body is encoded.
+    pub fn encoding(mut self, encoding: EncodeOptions) -> Self {
+        self.encoding = encoding;
+        self
+    }
+
+    /// Sets whether to write a `table` directive ahead of the `begin` line, declaring the alphabet
+    /// used for the body. This is needed for decoders to read back a body encoded with a custom table.
+    pub fn emit_table(mut self, emit_table: bool) -> Self {
+        self.emit_table = emit_table;
+        self
+    }
+
+    /// Sets the whole-file checksum to write on a trailer line after the end of the file, so that
+    /// receivers can confirm the payload survived transport. Decoders that don't understand the trailer ignore it.
+    pub fn trailer(mut self, trailer: Option<Checksum>) -> Self {
+        self.trailer = trailer;
+        self
+    }
+
+    /// Sets a token which stops the encoding, between lines, when cancelled.
+    pub fn cancel_token(mut self, token: CancelToken) -> Self {
+        self.cancel = Some(token);
+        self
+    }
+
+    #[cfg(feature = "std")]
+    /// Creates options from a file on disk, using its file name and current permission bits.
+    pub fn from_path(path: impl AsRef<Path>) -> std::io::Result<Self> {
+        let path = path.as_ref();
+        let name = path.file_name().ok_or_else(|| std::io::Error::new(std::io::ErrorKind::InvalidInput, "path has no file name"))?;
+        let metadata = std::fs::metadata(path)?;
+        Ok(FileOptions::new(name.to_string_lossy()).mode(mode_from_permissions(&metadata.permissions())))
+    }
+
+    /// The file name that will be written to the `begin` line.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+}
+
+/// Encodes the input data as a complete uuencoded file.
+/// The body is framed by a `begin <mode> <name>` line, a zero-length terminator line, and `end`,
+/// matching the output of `uuencode` from GNU sharutils. The output always ends with a newline.
+/// If Base64 was requested, the body is framed by `begin-base64 <mode> <name>` and `====` instead.
+/// Example:
+/// ```rust
+/// fn encode_file() -> Result<(), uuencode_lite::UUEncodeError> {
+///     let options = uuencode_lite::FileOptions::new("cat.txt");
+///     let encoded = uuencode_lite::uuencode_file(b"cat", &options)?;
+///     assert_eq!(encoded, "begin 644 cat.txt\n#8V%T\n`\nend\n");
+///     Ok(())
+/// }
+/// ```
+pub fn uuencode_file(data: &[u8], options: &FileOptions) -> Result<String, UUEncodeError> {
+    let mut encoder = Encoder::new(options.clone())?;
+    let mut encoded = String::with_capacity(encoder.encoded_len(data.len()));
+    encoder.push(data, &mut encoded)?;
+    encoder.finish(&mut encoded)?;
+    Ok(encoded)
+}
+
+/// Encodes several files into one text document, each as a complete uuencoded file (see `uuencode_file`),
+/// separated by blank lines. The files can be read back with `decode_all`.
+/// Example:
+/// ```rust
+/// fn encode_files() -> Result<(), uuencode_lite::UUEncodeError> {
+///     let files = [(uuencode_lite::FileOptions::new("cat.txt"), &b"cat"[..]), (uuencode_lite::FileOptions::new("dog.txt"), &b"dog"[..])];
+///     let encoded = uuencode_lite::uuencode_files(&files)?;
+///     assert_eq!(encoded, "begin 644 cat.txt\n#8V%T\n`\nend\n\nbegin 644 dog.txt\n#9&]G\n`\nend\n");
+///     Ok(())
+/// }
+/// ```
+pub fn uuencode_files(files: &[(FileOptions, &[u8])]) -> Result<String, UUEncodeError> {
+    #[cfg(feature = "tracing")]
+    let _span = tracing::debug_span!("uuencode_files", files = files.len()).entered();
+    let mut encoded = String::new();
+    for (idx, (options, data)) in files.iter().enumerate() {
+        if idx > 0 {
+            encoded.push('\n');
+        }
+        let file = uuencode_file(data, options);
+        #[cfg(feature = "tracing")]
+        match &file {
+            Ok(text) => tracing::debug!(name = %options.name, len = data.len(), encoded_len = text.len(), "encoded file"),
+            Err(err) => tracing::warn!(name = %options.name, error = %err, "failed to encode file"),
+        }
+        encoded.push_str(&file?);
+    }
+    Ok(encoded)
+}
+
+#[cfg(feature = "std")]
+/// Encodes every regular file under a directory into one text document, as `uuencode_files`.
+/// Files are named by their path relative to the directory, using `/` as the separator, and carry their
+/// current permission bits. Files are sorted by name, and symbolic links are skipped.
+pub fn uuencode_dir(path: impl AsRef<Path>) -> std::io::Result<String> {
+    fn walk(dir: &Path, prefix: &str, files: &mut Vec<(FileOptions, Vec<u8>)>) -> std::io::Result<()> {
+        for entry in std::fs::read_dir(dir)? {
+            let entry = entry?;
+            let file_type = entry.file_type()?;
+            let name = format!("{}{}", prefix, entry.file_name().to_string_lossy());
+            if file_type.is_dir() {
+                walk(&entry.path(), &format!("{}/", name), files)?;
+            } else if file_type.is_file() {
+                let mode = mode_from_permissions(&entry.metadata()?.permissions());
+                files.push((FileOptions::new(name).mode(mode), std::fs::read(entry.path())?));
+            }
+        }
+        Ok(())
+    }
+
+    let mut files = Vec::new();
+    walk(path.as_ref(), "", &mut files)?;
+    files.sort_by(|(a, _), (b, _)| a.name.cmp(&b.name));
+    let files: Vec<(FileOptions, &[u8])> = files.iter().map(|(options, data)| (options.clone(), data.as_slice())).collect();
+    uuencode_files(&files).map_err(|err| std::io::Error::new(std::io::ErrorKind::InvalidData, err))
+}
+
+#[cfg(feature = "std")]
+/// Converts file permissions into the octal mode written on a `begin` line.
+/// On platforms without Unix permissions, read-only files map to `444` and all others to `644`.
+pub fn mode_from_permissions(permissions: &std::fs::Permissions) -> u32 {
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        permissions.mode() & 0o7777
+    }
+    #[cfg(not(unix))]
+    {
+        if permissions.readonly() { 0o444 } else { 0o644 }
+    }
+}
+
+#[cfg(feature = "std")]
+/// Applies the mode from a `begin` line to a file on disk.
+/// The setuid, setgid and sticky bits are never applied, as the mode comes from untrusted input.
+/// On platforms without Unix permissions, the file is made read-only if the mode has no write bits.
+/// WASI has no permissions to set, so there the mode is ignored.
+pub fn apply_mode(path: impl AsRef<Path>, mode: u32) -> std::io::Result<()> {
+    #[cfg(target_os = "wasi")]
+    {
+        let _ = (path, mode);
+        Ok(())
+    }
+    #[cfg(not(target_os = "wasi"))]
+    {
+        #[cfg(unix)]
+        let permissions = {
+            use std::os::unix::fs::PermissionsExt;
+            std::fs::Permissions::from_mode(mode & 0o777)
+        };
+        #[cfg(not(unix))]
+        let permissions = {
+            let mut permissions = std::fs::metadata(path.as_ref())?.permissions();
+            permissions.set_readonly(mode & 0o222 == 0);
+            permissions
+        };
+        std::fs::set_permissions(path, permissions)
+    }
+}
+
+#[cfg(feature = "std")]
+/// Writes a decoded file beneath `dir`, at the relative path named on its `begin` line, and applies its mode.
+/// Missing parent directories are created. The name must pass `is_safe_file_name`, so nothing is ever written
+/// outside `dir`; this is what lets extraction run in a sandbox which only grants access to one directory, such as
+/// a WASI preopen. Returns the path written.
+/// Example:
+/// ```rust,no_run
+/// fn extract(digest: &[u8]) -> std::io::Result<()> {
+///     for file in uuencode_lite::decode_all(digest, &uuencode_lite::DecodeOptions::new()).into_iter().flatten() {
+///         uuencode_lite::extract_file("out", &file)?;
+///     }
+///     Ok(())
+/// }
+/// ```
+pub fn extract_file(dir: impl AsRef<Path>, file: &DecodedFile) -> std::io::Result<std::path::PathBuf> {
+    if !is_safe_file_name(&file.name) {
+        return Err(std::io::Error::new(std::io::ErrorKind::InvalidInput, format!("Unsafe file name: {:?}", file.name)));
+    }
+    let mut path = dir.as_ref().to_path_buf();
+    path.extend(file.name.split(['/', '\\']).filter(|component| !component.is_empty() && *component != "."));
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    std::fs::write(&path, &file.data)?;
+    apply_mode(&path, file.mode)?;
+    Ok(path)
+}
+
+/// Checks whether a file name from a `begin` line is safe to use as a relative path.
+/// Names are rejected if they are empty, absolute (including Windows drive and UNC paths),
+/// contain a `..` component, contain a colon anywhere, or contain an ASCII control character, such as a NUL or a
+/// line break, which could end a line or a shell comment the name is written into.
+/// Example:
+/// ```rust
+/// assert!(uuencode_lite::is_safe_file_name("cat.txt"));
+/// assert!(!uuencode_lite::is_safe_file_name("../../.ssh/authorized_keys"));
+/// assert!(!uuencode_lite::is_safe_file_name("sub/C:/Windows/win.ini"));
+/// ```
+pub fn is_safe_file_name(name: &str) -> bool {
+    !name.is_empty() && !name.contains(|ch: char| ch.is_ascii_control()) && !name.starts_with(['/', '\\']) && name.split(['/', '\\']).all(is_safe_component)
+}
+
+/// Checks one component of a file name, between separators. `extract_file` pushes each component onto the path
+/// separately, and on Windows a component such as `C:` is a drive prefix which replaces the path pushed so far,
+/// so a colon is rejected in any component, as is anything the platform parses as a prefix or root.
+fn is_safe_component(component: &str) -> bool {
+    if component == ".." || component.contains(':') {
+        return false;
+    }
+    #[cfg(feature = "std")]
+    if Path::new(component).components().any(|part| matches!(part, std::path::Component::Prefix(_) | std::path::Component::RootDir)) {
+        return false;
+    }
+    true
+}
+
+/// A file decoded from a complete uuencoded document.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DecodedFile {
+    /// The file name declared on the `begin` line.
+    pub name: String,
+    /// The permission bits declared on the `begin` line.
+    pub mode: u32,
+    /// The decoded contents of the file.
+    pub data: Vec<u8>,
+}
+
+/// Splits the input into numbered lines, stripping any trailing carriage return.
+pub(crate) fn numbered_lines(data: &[u8]) -> impl Iterator<Item = (usize, &[u8])> {
+    data.split(|&ch| ch == b'\n').map(|line| line.strip_suffix(b"\r").unwrap_or(line)).enumerate()
+}
+
+/// The framing of a complete file, as declared by its `begin` line.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Framing {
+    /// `begin`, with a uuencoded body and an `end` trailer.
+    UUEncode,
+    /// `begin-base64`, with a Base64 body and a `====` trailer.
+    Base64,
+}
+
+impl Framing {
+    /// Recognizes a `begin` or `begin-base64` line, with or without an encoded file name. Only a line with an octal
+    /// mode followed by a name counts, as GNU uudecode requires, so prose such as "begin the meeting at 10" is skipped.
+    pub(crate) fn of_begin_line(line: &[u8]) -> Option<Framing> {
+        let (framing, _, prefix_len) = Framing::of_header(line)?;
+        let rest = &line[prefix_len..];
+        let mode_len = rest.iter().take_while(|ch| (b'0'..=b'7').contains(ch)).count();
+        (mode_len > 0 && rest.get(mode_len) == Some(&b' ') && rest.len() > mode_len + 1).then_some(framing)
+    }
+
+    /// Recognizes a `begin` line, returning its framing, whether the file name is encoded, and the length of its prefix.
+    fn of_header(line: &[u8]) -> Option<(Framing, bool, usize)> {
+        [
+            (&b"begin "[..], Framing::UUEncode, false),
+            (b"begin-base64 ", Framing::Base64, false),
+            (b"begin-encoded ", Framing::UUEncode, true),
+            (b"begin-base64-encoded ", Framing::Base64, true),
+        ]
+        .into_iter()
+        .find(|(prefix, _, _)| line.starts_with(prefix))
+        .map(|(prefix, framing, encoded_name)| (framing, encoded_name, prefix.len()))
+    }
+}
+
+/// Writes the `begin` line for a file, encoding its name if requested.
+pub(crate) fn begin_line(options: &FileOptions, encoded: &mut String) {
+    let keyword = match (options.base64, options.encode_name) {
+        (false, false) => "begin",
+        (true, false) => "begin-base64",
+        (false, true) => "begin-encoded",
+        (true, true) => "begin-base64-encoded",
+    };
+    encoded.push_str(&format!("{} {:03o} ", keyword, options.mode));
+    if !options.encode_name {
+        encoded.push_str(&options.name);
+    } else if options.base64 {
+        crate::base64::encode_into(options.name.as_bytes(), encoded);
+    } else {
+        // line checksums would make the name ambiguous, as their presence isn't declared on the begin line
+        let encoding = EncodeOptions::new().table(options.encoding.table.clone());
+        for chunk in options.name.as_bytes().chunks(45) {
+            crate::encode_line(chunk, &encoding, encoded);
+        }
+    }
+    encoded.push('\n');
+}
+
+/// Decodes a file name encoded by `begin_line`. Returns `None` if it is not validly encoded.
+fn decode_name(name: &[u8], framing: Framing, options: &DecodeOptions) -> Option<Vec<u8>> {
+    if framing == Framing::Base64 {
+        return crate::base64_decode(name).ok();
+    }
+    let mut decoded = Vec::with_capacity(name.len() / 4 * 3);
+    let mut rest = name;
+    while let Some(&len_char) = rest.first() {
+        let len = options.table.decode(len_char).filter(|&len| len > 0)? as usize;
+        let (line, tail) = rest.split_at_checked(1 + len.div_ceil(3) * 4)?;
+        crate::decode_line(line, 0, &options.table, false, &mut decoded).ok()?;
+        rest = tail;
+    }
+    Some(decoded)
+}
+
+/// Parses a `begin <mode> <name>` line into its mode and name.
+pub(crate) fn parse_begin(line: &[u8], cur_line: usize, options: &DecodeOptions) -> Result<(u32, String), UUEncodeError> {
+    let Some((framing, encoded_name, prefix_len)) = Framing::of_header(line) else {
+        return Err(UUEncodeError::new(cur_line, 0, "Missing begin line"));
+    };
+    let rest = &line[prefix_len..];
+    let mode_len = rest.iter().take_while(|ch| ch.is_ascii_digit()).count();
+    let mode = core::str::from_utf8(&rest[..mode_len]).ok().and_then(|digits| u32::from_str_radix(digits, 8).ok());
+    let (Some(mode), Some(b' ')) = (mode, rest.get(mode_len)) else {
+        return Err(UUEncodeError::new(cur_line, prefix_len, "Invalid mode in begin line"));
+    };
+    let name = &rest[mode_len + 1..];
+    if name.is_empty() {
+        return Err(UUEncodeError::new(cur_line, line.len(), "Missing file name in begin line"));
+    }
+    let name = if encoded_name {
+        let Some(decoded) = decode_name(name.trim_ascii_end(), framing, options).filter(|name| !name.is_empty()) else {
+            return Err(UUEncodeError::new(cur_line, prefix_len + mode_len + 1, "Invalid encoded file name in begin line"));
+        };
+        String::from_utf8_lossy(&decoded).into_owned()
+    } else {
+        String::from_utf8_lossy(name).into_owned()
+    };
+    if !options.raw_names && !is_safe_file_name(&name) {
+        return Err(UUEncodeError::new(cur_line, prefix_len + mode_len + 1, format!("Unsafe file name in begin line: {:?}", name)));
+    }
+    Ok((mode, name))
+}
+
+/// Scans forward to the next `begin` or `begin-base64` line and decodes the file that follows it.
+/// A `table` directive before the `begin` line sets the alphabet used for the body.
+/// Returns `None` if no `begin` line was found before the input ran out.
+pub(crate) fn decode_next_file<'a>(lines: &mut impl Iterator<Item = (usize, &'a [u8])>, options: &DecodeOptions) -> Option<Result<DecodedFile, UUEncodeError>> {
+    let mut decoder = Decoder::new(options.clone());
+    let mut data = Vec::new();
+    let mut last_line = 0;
+    for (cur_line, line) in lines.by_ref() {
+        last_line = cur_line;
+        if let Err(err) = decoder.push_line(cur_line, line, &mut data) {
+            return Some(Err(err));
+        }
+        if decoder.is_done() {
+            break;
+        }
+    }
+    if !decoder.has_started() {
+        return None;
+    }
+    Some(decoder.end_of_input(last_line).map(|_| {
+        let (mode, name) = decoder.header().expect("a finished file has a begin line");
+        DecodedFile { name: name.to_string(), mode, data }
+    }))
+}
+
+/// Decodes a complete uuencoded file, as produced by `uuencode_file`.
+/// Both `begin` and `begin-base64` framing are accepted. Any text before the `begin` line is skipped.
+/// The body must be followed by an `end` (or, for Base64, `====`) line. If the `begin` line is preceded by a
+/// `table` directive, the 64 characters on the following lines are used as the alphabet for the body.
+/// Lines may end with either LF or CRLF. Unsafe file names are rejected; see `uudecode_file_with` to change this.
+/// Example:
+/// ```rust
+/// fn decode_file() -> Result<(), uuencode_lite::UUEncodeError> {
+///     let file = uuencode_lite::uudecode_file(b"begin 644 cat.txt\n#8V%T\n`\nend\n")?;
+///     assert_eq!(file.name, "cat.txt");
+///     assert_eq!(file.mode, 0o644);
+///     println!("{}", String::from_utf8_lossy(&file.data)); // prints "cat"
+///     Ok(())
+/// }
+/// ```
+pub fn uudecode_file(data: &[u8]) -> Result<DecodedFile, UUEncodeError> {
+    uudecode_file_with(data, &DecodeOptions::default())
+}
+
+/// Decodes a complete uuencoded file, as `uudecode_file`, using the given options.
+pub fn uudecode_file_with(data: &[u8], options: &DecodeOptions) -> Result<DecodedFile, UUEncodeError> {
+    let mut lines = numbered_lines(data);
+    decode_next_file(&mut lines, options).unwrap_or_else(|| Err(UUEncodeError::new(0, 0, "Missing begin line")))
+}
+
+/// Records a file that was decoded, or the error that stopped it.
+#[cfg(feature = "tracing")]
+pub(crate) fn trace_file(file: &Result<DecodedFile, UUEncodeError>) {
+    match file {
+        Ok(file) => tracing::debug!(name = %file.name, mode = %format_args!("{:03o}", file.mode), len = file.data.len(), "decoded file"),
+        Err(err) => tracing::warn!(line = err.line, character = err.character, error = %err, "failed to decode file"),
+    }
+}
+
+/// Scans arbitrary text, such as a mailing-list digest, and decodes every embedded uuencoded file.
+/// Text between files is skipped. Each file is returned in the order it appears, either decoded
+/// or with the error that stopped it from being decoded; scanning resumes after a failed file.
+/// Example:
+/// ```rust
+/// let digest = b"Hi all,\nbegin 644 a.txt\n#8V%T\n`\nend\nand\nbegin 600 b.txt\n#9&]G\n`\nend\n";
+/// let files = uuencode_lite::decode_all(digest, &uuencode_lite::DecodeOptions::new());
+/// assert_eq!(files.len(), 2);
+/// assert_eq!(files[1].as_ref().unwrap().data, b"dog");
+/// ```
+pub fn decode_all(data: &[u8], options: &DecodeOptions) -> Vec<Result<DecodedFile, UUEncodeError>> {
+    #[cfg(feature = "tracing")]
+    let _span = tracing::debug_span!("decode_all", len = data.len()).entered();
+    let mut lines = numbered_lines(data).peekable();
+    let mut files = Vec::new();
+    while let Some(file) = decode_next_file(&mut lines, options) {
+        #[cfg(feature = "tracing")]
+        trace_file(&file);
+        if file.is_err() {
+            // skip the rest of the broken file, so its body isn't mistaken for prose
+            while lines.next_if(|(_, line)| Framing::of_begin_line(line).is_none() && !matches!(line.trim_ascii_end(), b"end" | b"====")).is_some() {}
+        }
+        files.push(file);
+    }
+    files
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Table;
+
+    /// A path for a test to write to. WASI has no temporary directory, so there it is under `target`, inside the
+    /// crate directory the test runner preopens
+    #[cfg(feature = "std")]
+    fn scratch_path(name: &str) -> std::path::PathBuf {
+        if cfg!(target_os = "wasi") {
+            std::path::Path::new("target").join(name)
+        } else {
+            std::env::temp_dir().join(format!("{}_{}", name, std::process::id()))
+        }
+    }
+
+    /// Tests framing of a small file
+    #[test]
+    fn test_encode_file_cat() {
+        let encoded = uuencode_file(b"cat", &FileOptions::new("cat.txt").mode(0o755)).unwrap();
+        assert_eq!(encoded, "begin 755 cat.txt\n#8V%T\n`\nend\n", "can uuencode a small file");
+    }
+
+    /// Tests framing of an empty file, and rejection of names that would break the framing
+    #[test]
+    fn test_encode_file_edge_cases() {
+        let encoded = uuencode_file(b"", &FileOptions::new("empty").mode(0o4)).unwrap();
+        assert_eq!(encoded, "begin 004 empty\n`\nend\n", "can uuencode an empty file");
+        assert!(uuencode_file(b"cat", &FileOptions::new("")).is_err(), "rejects an empty name");
+        assert!(uuencode_file(b"cat", &FileOptions::new("a\nb")).is_err(), "rejects a name with a newline");
+    }
+
+    /// Tests decoding a framed file, including surrounding text and CRLF line endings
+    #[test]
+    fn test_decode_file() {
+        let file = uudecode_file(b"Subject: cat\r\n\r\nbegin 755 my cat.txt\r\n#8V%T\r\n`\r\nend\r\n").unwrap();
+        assert_eq!(file, DecodedFile { name: "my cat.txt".to_string(), mode: 0o755, data: b"cat".to_vec() }, "can uudecode a small file");
+
+        let file = uudecode_file(b"begin the meeting at 10
+begin 9 lives
+begin-base64 here
+begin 644 
+begin 644 cat.txt
+#8V%T
+`
+end
+").unwrap();
+        assert_eq!((file.name.as_str(), file.data.as_slice()), ("cat.txt", &b"cat"[..]), "skips prose starting with begin");
+    }
+
+    /// Tests round-tripping a large file through the framing
+    #[test]
+    fn test_file_rt() {
+        let source_data = std::fs::read("test_data/random_data.bin").expect("Can open test data");
+        let encoded = uuencode_file(&source_data, &FileOptions::new("random_data.bin")).unwrap();
+        let file = uudecode_file(encoded.as_bytes()).unwrap();
+        assert_eq!(file.name, "random_data.bin");
+        assert_eq!(file.data, source_data, "can uuencode and uudecode a file");
+    }
+
+    /// Tests that malformed framing is rejected
+    #[test]
+    fn test_decode_file_errors() {
+        assert!(uudecode_file(b"#8V%T\n`\nend\n").is_err(), "requires a begin line");
+        assert!(uudecode_file(b"begin 644 cat.txt\n#8V%T\n`\n").is_err(), "requires an end line");
+        assert!(uudecode_file(b"begin abc cat.txt\n#8V%T\n`\nend\n").is_err(), "requires an octal mode");
+        assert!(uudecode_file(b"begin 644 cat.txt\n#8V\n`\nend\n").is_err(), "rejects a short line");
+    }
+
+    /// Tests capturing and applying modes on disk
+    #[cfg(all(unix, feature = "std"))]
+    #[test]
+    fn test_modes() {
+        let path = scratch_path("uuencode_lite_mode.bin");
+        std::fs::write(&path, b"cat").expect("Can write temp file");
+        apply_mode(&path, 0o4751).unwrap();
+        let options = FileOptions::from_path(&path).unwrap();
+        std::fs::remove_file(&path).expect("Can remove temp file");
+        assert_eq!(options, FileOptions::new(path.file_name().unwrap().to_string_lossy()).mode(0o751), "strips setuid and captures the mode");
+    }
+
+    /// Tests that unsafe names are rejected unless raw names are requested
+    #[test]
+    fn test_unsafe_names() {
+        for name in ["../../.ssh/authorized_keys", "/etc/passwd", "a/../../b", "C:\\evil.exe", "\\\\server\\share", "nul\0name", "sub/C:/Windows/x", "sub\\D:\\x", "sub/C:x", "cat.txt:stream", "tab\tname", "del\x7fname", "esc\x1b[2Jname"] {
+            assert!(!is_safe_file_name(name), "{:?} is unsafe", name);
+            let encoded = format!("begin 644 {}\n#8V%T\n`\nend\n", name);
+            assert!(uudecode_file(encoded.as_bytes()).is_err(), "rejects {:?} by default", name);
+            let file = uudecode_file_with(encoded.as_bytes(), &DecodeOptions::new().raw_names(true)).unwrap();
+            assert_eq!(file.name, name, "accepts {:?} as a raw name", name);
+        }
+        for name in ["x\necho PWNED", "cr\rname", "trailing\n"] {
+            assert!(!is_safe_file_name(name), "{:?} is unsafe", name);
+        }
+        assert!(is_safe_file_name("dir/..cat.txt"), "accepts dotted names that are not parent components");
+    }
+
+    /// Tests extracting several files from a digest, including a broken one
+    #[test]
+    fn test_decode_all() {
+        let cat = uuencode_file(b"cat", &FileOptions::new("cat.txt")).unwrap();
+        let dog = uuencode_file(b"dog", &FileOptions::new("dog.txt").mode(0o600)).unwrap();
+        let digest = format!("Digest 1\n\n{}\n-- \nsig\nbegin 644 broken\n#8V\nM\nend\nMore prose\n{}", cat, dog);
+        let files = decode_all(digest.as_bytes(), &DecodeOptions::new());
+        assert_eq!(files.len(), 3, "finds every file");
+        assert_eq!(files[0].as_ref().unwrap().data, b"cat", "decodes the first file");
+        assert!(files[1].is_err(), "reports the broken file");
+        assert_eq!(files[2].as_ref().unwrap(), &DecodedFile { name: "dog.txt".to_string(), mode: 0o600, data: b"dog".to_vec() }, "decodes the last file");
+        assert!(decode_all(b"no files here", &DecodeOptions::new()).is_empty(), "finds nothing in plain text");
+
+        let mailbox = format!("begin the meeting at 10.\nbegin here tomorrow\n{}begin again later\n", cat);
+        let files = decode_all(mailbox.as_bytes(), &DecodeOptions::new());
+        assert_eq!(files.len(), 1, "skips prose starting with begin");
+        assert_eq!(files[0].as_ref().unwrap().data, b"cat", "decodes the real file");
+    }
+
+    /// Tests encoding and decoding with begin-base64 framing
+    #[test]
+    fn test_base64_file() {
+        let encoded = uuencode_file(b"cat", &FileOptions::new("cat.txt").base64(true)).unwrap();
+        assert_eq!(encoded, "begin-base64 644 cat.txt\nY2F0\n====\n", "can encode a small file as base64");
+        let file = uudecode_file(encoded.as_bytes()).unwrap();
+        assert_eq!(file.data, b"cat", "can decode a small base64 file");
+
+        let source_data = std::fs::read("test_data/random_data.bin").expect("Can open test data");
+        let encoded = uuencode_file(&source_data, &FileOptions::new("random_data.bin").base64(true)).unwrap();
+        assert!(encoded.lines().all(|line| line.len() <= 60), "wraps base64 lines");
+        let mixed = format!("{}{}", uuencode_file(b"dog", &FileOptions::new("dog.txt")).unwrap(), encoded);
+        let files = decode_all(mixed.as_bytes(), &DecodeOptions::new());
+        assert_eq!(files[0].as_ref().unwrap().data, b"dog", "can decode uuencode alongside base64");
+        assert_eq!(files[1].as_ref().unwrap().data, source_data, "can round-trip a large base64 file");
+        assert!(uudecode_file(b"begin-base64 644 cat.txt\nY2F0\n").is_err(), "requires a ==== line");
+    }
+
+    /// Tests encoding and decoding file names with begin-encoded framing
+    #[test]
+    fn test_encoded_name() {
+        let encoded = uuencode_file(b"cat", &FileOptions::new("my cat.txt").encode_name(true)).unwrap();
+        assert_eq!(encoded, "begin-encoded 644 *;7D@8V%T+G1X=```\n#8V%T\n`\nend\n", "encodes the name as a uuencoded line");
+        assert_eq!(uudecode_file(encoded.as_bytes()).unwrap().name, "my cat.txt", "decodes the name");
+
+        let long_name = format!("{}\nline two.txt", "a".repeat(60));
+        for base64 in [false, true] {
+            let options = FileOptions::new(long_name.clone()).encode_name(true).base64(base64);
+            let encoded = uuencode_file(b"cat", &options).unwrap();
+            assert_eq!(encoded.lines().count(), if base64 { 3 } else { 4 }, "keeps a name with a newline on the begin line");
+            assert!(uudecode_file(encoded.as_bytes()).is_err(), "rejects a name with a newline by default");
+            let file = uudecode_file_with(encoded.as_bytes(), &DecodeOptions::new().raw_names(true)).unwrap();
+            assert_eq!((file.name.as_str(), file.data.as_slice()), (long_name.as_str(), &b"cat"[..]), "round-trips a long name with base64 {}", base64);
+        }
+
+        assert!(uudecode_file(b"begin-encoded 644 *;7D@8V%T\n`\nend\n").is_err(), "rejects a truncated name");
+        assert!(uudecode_file(b"begin-base64-encoded 644 Li4vZXRjL3Bhc3N3ZA==\n====\n").is_err(), "rejects an unsafe encoded name");
+        assert!(uuencode_file(b"cat", &FileOptions::new("a\nb")).is_err(), "rejects a newline in a plain name");
+    }
+
+    proptest::proptest! {
+        /// Tests that any file, plain or Base64, with its name encoded or not, decodes back to its name, mode and data
+        #[test]
+        fn test_proptest_file_round_trip(
+            data in proptest::collection::vec(proptest::prelude::any::<u8>(), 0..512),
+            plain_name in "[A-Za-z0-9_][A-Za-z0-9_.-]{0,31}",
+            encoded_name in "[^\\x00]{1,64}",
+            mode in 0u32..=0o7777,
+            base64: bool,
+            encode_name: bool,
+        ) {
+            let name = if encode_name { encoded_name } else { plain_name };
+            let options = FileOptions::new(name.clone()).mode(mode).base64(base64).encode_name(encode_name);
+            let encoded = uuencode_file(&data, &options).unwrap();
+            let file = uudecode_file_with(encoded.as_bytes(), &DecodeOptions::new().raw_names(true)).unwrap();
+            proptest::prop_assert_eq!(file, DecodedFile { name, mode, data });
+        }
+
+        /// Tests that decoding arbitrary input as files, bare or after a `begin` line, returns rather than panics
+        #[test]
+        fn test_proptest_file_decode_arbitrary(data in proptest::collection::vec(proptest::prelude::any::<u8>(), 0..512), begin in "begin(-base64)?(-encoded)? [0-7]{3} [!-~]{1,8}\n") {
+            let _ = uudecode_file(&data);
+            let _ = decode_all(&data, &DecodeOptions::new());
+            let framed = [begin.as_bytes(), &data].concat();
+            let _ = uudecode_file(&framed);
+            let _ = decode_all(&framed, &DecodeOptions::new().raw_names(true));
+        }
+    }
+
+    /// Tests encoding with a custom table, with and without a table directive
+    #[test]
+    fn test_encode_table() {
+        let table = Table::from_alphabet(b"+-0123456789ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz").unwrap();
+        let options = FileOptions::new("cat.txt").encoding(EncodeOptions::new().table(table.clone()));
+        let encoded = uuencode_file(b"cat", &options.clone().emit_table(true)).unwrap();
+        assert_eq!(encoded, "table\n+-0123456789ABCDEFGHIJKLMNOPQRST\nUVWXYZabcdefghijklmnopqrstuvwxyz\nbegin 644 cat.txt\n1Mq3o\n+\nend\n", "can emit a table");
+        assert_eq!(uudecode_file(encoded.as_bytes()).unwrap().data, b"cat", "can decode an emitted table");
+
+        let encoded = uuencode_file(b"cat", &options).unwrap();
+        assert!(!encoded.starts_with("table"), "only emits a table when asked");
+        let file = uudecode_file_with(encoded.as_bytes(), &DecodeOptions::new().table(table)).unwrap();
+        assert_eq!(file.data, b"cat", "can decode with a table from the options");
+    }
+
+    /// Tests decoding a body encoded with a custom table
+    #[test]
+    fn test_decode_table() {
+        // the standard alphabet, shifted by one: '!' is zero, and '`' is 63
+        let encoded = b"table\n!\"#$%&'()*+,-./0123456789:;<=>?@\nABCDEFGHIJKLMNOPQRSTUVWXYZ[\\]^_`\nbegin 644 cat.txt\n$9W&U\n!\nend\n";
+        let file = uudecode_file(encoded).unwrap();
+        assert_eq!(file.data, b"cat", "can decode a custom table");
+        assert!(uudecode_file(b"table\nabc\nbegin 644 cat.txt\n#8V%T\n`\nend\n").is_err(), "rejects a malformed table");
+    }
+
+    /// Tests emitting and verifying whole-file checksum trailers
+    #[test]
+    fn test_trailer() {
+        let encoded = uuencode_file(b"cat", &FileOptions::new("cat.txt").trailer(Some(Checksum::BsdSum))).unwrap();
+        assert_eq!(encoded, "begin 644 cat.txt\n#8V%T\n`\nend\nsum -r/size 16573/3 entire input file\n", "can emit a bsd sum trailer");
+
+        let options = DecodeOptions::new().verify_trailer(true);
+        let source_data = std::fs::read("test_data/random_data.bin").expect("Can open test data");
+        for base64 in [false, true] {
+            let encoded = uuencode_file(&source_data, &FileOptions::new("random_data.bin").base64(base64).trailer(Some(Checksum::Crc32))).unwrap();
+            assert_eq!(uudecode_file_with(encoded.as_bytes(), &options).unwrap().data, source_data, "can verify a crc32 trailer");
+            assert_eq!(uudecode_file(encoded.as_bytes()).unwrap().data, source_data, "ignores the trailer by default");
+        }
+
+        assert!(uudecode_file_with(b"begin 644 cat.txt\n#8V%T\n`\nend\n", &options).is_err(), "requires a trailer when verifying");
+        assert!(uudecode_file_with(b"begin 644 cat.txt\n#8V%T\n`\nend\nsum -r/size 16574/3\n", &options).is_err(), "rejects a mismatched checksum");
+        assert!(uudecode_file_with(b"begin 644 cat.txt\n#8V%T\n`\nend\nsum -r/size 16573/4\n", &options).is_err(), "rejects a mismatched size");
+    }
+
+    /// Tests extracting files beneath a directory, and refusing to leave it
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_extract_file() {
+        let dir = scratch_path("uuencode_lite_extract");
+        let file = DecodedFile { name: "sub/./cat.txt".to_string(), mode: 0o600, data: b"cat".to_vec() };
+        let path = extract_file(&dir, &file);
+        let written = std::fs::read(dir.join("sub").join("cat.txt"));
+        let escaped = extract_file(&dir, &DecodedFile { name: "../cat.txt".to_string(), ..file });
+        std::fs::remove_dir_all(&dir).expect("Can remove temp dir");
+
+        assert_eq!(path.unwrap(), dir.join("sub").join("cat.txt"), "returns the path written");
+        assert_eq!(written.unwrap(), b"cat", "writes the file beneath the directory");
+        assert_eq!(escaped.unwrap_err().kind(), std::io::ErrorKind::InvalidInput, "refuses to write outside the directory");
+    }
+
+    /// Tests encoding a directory tree into one document
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_encode_dir() {
+        let dir = scratch_path("uuencode_lite_dir");
+        std::fs::create_dir_all(dir.join("sub")).expect("Can create temp dir");
+        std::fs::write(dir.join("b.txt"), b"dog").expect("Can write temp file");
+        std::fs::write(dir.join("sub").join("a.txt"), b"cat").expect("Can write temp file");
+        let encoded = uuencode_dir(&dir);
+        std::fs::remove_dir_all(&dir).expect("Can remove temp dir");
+
+        let files = decode_all(encoded.unwrap().as_bytes(), &DecodeOptions::new());
+        let names: Vec<&str> = files.iter().map(|file| file.as_ref().unwrap().name.as_str()).collect();
+        assert_eq!(names, ["b.txt", "sub/a.txt"], "encodes every file in name order");
+        assert_eq!(files[1].as_ref().unwrap().data, b"cat", "encodes nested files");
+    }
+}
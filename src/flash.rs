@@ -0,0 +1,204 @@
+use crate::{DecodeOptions, SliceDecoder, UUEncodeError};
+use embedded_storage::nor_flash::{NorFlash, NorFlashError};
+
+/// The most bytes one decoded line holds.
+const DECODED_LEN: usize = 63;
+
+/// The byte erased NOR flash reads as, used to pad the last write.
+const ERASED: u8 = 0xFF;
+
+/// An error from a `FlashDecoder`: either from the flash, or from decoding.
+#[derive(Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum FlashDecodeError<E> {
+    /// Erasing or writing the flash failed.
+    Flash(E),
+    /// The input was not valid uuencoded text.
+    Decode(UUEncodeError),
+}
+
+impl<E: NorFlashError> core::fmt::Display for FlashDecodeError<E> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            FlashDecodeError::Flash(err) => write!(f, "Flash error: {}", err.kind()),
+            FlashDecodeError::Decode(err) => write!(f, "Decode error: {}", err),
+        }
+    }
+}
+
+impl<E: NorFlashError> core::error::Error for FlashDecodeError<E> {}
+
+/// Decodes bare uuencoded lines, as `uudecode_with` does, straight into NOR flash, such as a firmware image
+/// received over a serial line. Decoded bytes are gathered into a `PAGE`-byte buffer which is written once full,
+/// so the image is never staged in RAM. Each erase sector is erased just before the first write into it.
+/// `PAGE` must be a multiple of the flash's `WRITE_SIZE`, and the image is written from `offset`, which must be
+/// a multiple of its `ERASE_SIZE`. Call `finish` once all the text is pushed, to write the last, partial, page,
+/// padded with erased bytes up to the next `WRITE_SIZE` boundary.
+/// Example:
+/// ```rust
+/// fn burn<F: embedded_storage::nor_flash::NorFlash>(flash: F, image: &[u8]) -> Result<u32, uuencode_lite::FlashDecodeError<F::Error>> {
+///     let options = uuencode_lite::DecodeOptions::new();
+///     let mut decoder = uuencode_lite::FlashDecoder::<_, 256>::new(flash, 0, &options);
+///     for chunk in image.chunks(64) {
+///         decoder.push(chunk)?;
+///     }
+///     decoder.finish()
+/// }
+/// ```
+#[derive(Debug)]
+pub struct FlashDecoder<'a, F, const PAGE: usize> {
+    flash: F,
+    decoder: SliceDecoder<'a>,
+    /// The decoded bytes of the page being gathered.
+    page: [u8; PAGE],
+    page_len: usize,
+    /// Where the page being gathered will be written.
+    offset: u32,
+    /// The end of the flash erased so far.
+    erased_to: u32,
+    /// The number of decoded bytes gathered so far.
+    decoded_len: u32,
+}
+
+impl<'a, F: NorFlash, const PAGE: usize> FlashDecoder<'a, F, PAGE> {
+    /// Creates a decoder which writes the decoded image to `flash` from `offset`, with the given options.
+    /// Panics if `PAGE` is not a nonzero multiple of `F::WRITE_SIZE`, or `offset` is not a multiple of `F::ERASE_SIZE`.
+    pub fn new(flash: F, offset: u32, options: &'a DecodeOptions) -> Self {
+        assert!(PAGE > 0 && PAGE.is_multiple_of(F::WRITE_SIZE), "Page size must be a multiple of the flash write size");
+        assert!((offset as usize).is_multiple_of(F::ERASE_SIZE), "Offset must be a multiple of the flash erase size");
+        FlashDecoder { flash, decoder: SliceDecoder::new(options), page: [ERASED; PAGE], page_len: 0, offset, erased_to: offset, decoded_len: 0 }
+    }
+
+    /// Decodes the text in `data`, writing each page to the flash once it is full.
+    pub fn push(&mut self, mut data: &[u8]) -> Result<(), FlashDecodeError<F::Error>> {
+        let mut decoded = [0u8; DECODED_LEN];
+        while !data.is_empty() {
+            let (consumed, written) = self.decoder.push(data, &mut decoded).map_err(FlashDecodeError::Decode)?;
+            data = &data[consumed..];
+            self.gather(&decoded[..written])?;
+        }
+        Ok(())
+    }
+
+    /// Decodes a last line without a newline, if there is one, and writes the last, partial, page.
+    /// Returns the number of decoded bytes written, not counting padding.
+    pub fn finish(&mut self) -> Result<u32, FlashDecodeError<F::Error>> {
+        let mut decoded = [0u8; DECODED_LEN];
+        let written = self.decoder.finish(&mut decoded).map_err(FlashDecodeError::Decode)?;
+        self.gather(&decoded[..written])?;
+        if self.page_len > 0 {
+            let padded = self.page_len.div_ceil(F::WRITE_SIZE) * F::WRITE_SIZE;
+            self.page[self.page_len..padded].fill(ERASED);
+            self.write_page(padded)?;
+        }
+        Ok(self.decoded_len)
+    }
+
+    /// Returns the flash. Any page not yet written is lost.
+    pub fn into_inner(self) -> F {
+        self.flash
+    }
+
+    /// Copies decoded bytes into the page, writing it each time it fills.
+    fn gather(&mut self, mut decoded: &[u8]) -> Result<(), FlashDecodeError<F::Error>> {
+        while !decoded.is_empty() {
+            let len = decoded.len().min(PAGE - self.page_len);
+            self.page[self.page_len..self.page_len + len].copy_from_slice(&decoded[..len]);
+            self.page_len += len;
+            self.decoded_len += len as u32;
+            decoded = &decoded[len..];
+            if self.page_len == PAGE {
+                self.write_page(PAGE)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Writes the first `len` bytes of the page at the current offset, erasing ahead of it as needed.
+    fn write_page(&mut self, len: usize) -> Result<(), FlashDecodeError<F::Error>> {
+        let end = self.offset + len as u32;
+        if end > self.erased_to {
+            let erase_to = end.div_ceil(F::ERASE_SIZE as u32) * F::ERASE_SIZE as u32;
+            self.flash.erase(self.erased_to, erase_to).map_err(FlashDecodeError::Flash)?;
+            self.erased_to = erase_to;
+        }
+        self.flash.write(self.offset, &self.page[..len]).map_err(FlashDecodeError::Flash)?;
+        self.offset = end;
+        self.page_len = 0;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use embedded_storage::nor_flash::{ErrorType, NorFlashErrorKind, ReadNorFlash};
+
+    /// NOR flash in memory, which only allows writes to erased bytes.
+    struct MemFlash {
+        data: [u8; 4096],
+        erases: usize,
+    }
+
+    impl ErrorType for MemFlash {
+        type Error = NorFlashErrorKind;
+    }
+
+    impl ReadNorFlash for MemFlash {
+        const READ_SIZE: usize = 1;
+
+        fn read(&mut self, offset: u32, bytes: &mut [u8]) -> Result<(), Self::Error> {
+            bytes.copy_from_slice(&self.data[offset as usize..offset as usize + bytes.len()]);
+            Ok(())
+        }
+
+        fn capacity(&self) -> usize {
+            self.data.len()
+        }
+    }
+
+    impl NorFlash for MemFlash {
+        const WRITE_SIZE: usize = 4;
+        const ERASE_SIZE: usize = 512;
+
+        fn erase(&mut self, from: u32, to: u32) -> Result<(), Self::Error> {
+            embedded_storage::nor_flash::check_erase(self, from, to)?;
+            self.data[from as usize..to as usize].fill(ERASED);
+            self.erases += 1;
+            Ok(())
+        }
+
+        fn write(&mut self, offset: u32, bytes: &[u8]) -> Result<(), Self::Error> {
+            embedded_storage::nor_flash::check_write(self, offset, bytes.len())?;
+            let target = &mut self.data[offset as usize..offset as usize + bytes.len()];
+            assert!(target.iter().all(|&b| b == ERASED), "writes only to erased flash");
+            target.copy_from_slice(bytes);
+            Ok(())
+        }
+    }
+
+    /// Tests decoding into flash, in pieces which do not line up with lines or pages
+    #[test]
+    fn test_flash_decoder() {
+        let source_data = std::fs::read("test_data/random_data.bin").expect("Can open test data");
+        let encoded = std::fs::read("test_data/random_data.bin.uu").expect("Can open test data");
+        let options = DecodeOptions::new();
+
+        let mut flash = MemFlash { data: [0; 4096], erases: 0 };
+        let mut decoder = FlashDecoder::<_, 96>::new(&mut flash, 512, &options);
+        for chunk in encoded[..encoded.len() - 10].chunks(50) {
+            decoder.push(chunk).unwrap();
+        }
+        decoder.push(&encoded[encoded.len() - 10..]).unwrap();
+        assert_eq!(decoder.finish().unwrap(), 2048, "counts the decoded bytes");
+        assert_eq!(&flash.data[512..512 + 2048], source_data, "can decode into flash");
+        assert_eq!(flash.data[511], 0, "leaves the flash before the offset alone");
+        assert_eq!(flash.erases, 4, "erases each sector once, as it is reached");
+
+        let mut flash = MemFlash { data: [0; 4096], erases: 0 };
+        let mut decoder = FlashDecoder::<_, 96>::new(&mut flash, 3584, &options);
+        assert!(matches!(decoder.push(&encoded), Err(FlashDecodeError::Flash(NorFlashErrorKind::OutOfBounds))), "reports running out of flash");
+        let mut decoder = FlashDecoder::<_, 96>::new(&mut flash, 0, &options);
+        assert!(matches!(decoder.push(b"#8V\x01T\n"), Err(FlashDecodeError::Decode(_))), "reports invalid input");
+    }
+}
@@ -0,0 +1,217 @@
+use crate::decoder::Decoder;
+use crate::encoder::Encoder;
+use crate::{DecodeOptions, FileOptions, UUEncodeError};
+use bytes::Bytes;
+use futures_core::Stream;
+use futures_sink::Sink;
+use std::pin::Pin;
+use std::string::String;
+use std::task::{ready, Context, Poll};
+use std::vec::Vec;
+
+/// A stream which decodes a complete uuencoded file, as `uudecode_file_with` does, from a stream of encoded chunks,
+/// such as an HTTP response body. Each item is the decoded bytes of the lines completed by one encoded chunk.
+/// The inner stream is not polled after the end of the file has been decoded. Decoding errors are converted into
+/// the inner stream's error type, and end the stream, as do errors from the inner stream.
+/// The inner stream must be `Unpin`; use `Box::pin` for streams which are not.
+/// Example:
+/// ```rust
+/// fn decode() -> std::io::Result<()> {
+///     use futures::StreamExt;
+///     let chunks = ["begin 644 cat.txt\n#8", "V%T\n`\nend\n"].map(|chunk| std::io::Result::Ok(bytes::Bytes::from(chunk)));
+///     let stream = uuencode_lite::DecodeStream::new(futures::stream::iter(chunks), uuencode_lite::DecodeOptions::new());
+///     let decoded: Vec<_> = futures::executor::block_on(stream.collect());
+///     assert_eq!(decoded.into_iter().collect::<std::io::Result<Vec<_>>>()?, [bytes::Bytes::from("cat")]);
+///     Ok(())
+/// }
+/// ```
+#[derive(Debug)]
+pub struct DecodeStream<S> {
+    inner: S,
+    decoder: Decoder,
+    /// Whether the stream has ended.
+    done: bool,
+}
+
+impl<S> DecodeStream<S> {
+    /// Creates a stream which decodes a file with the given options from the chunks of `inner`.
+    pub fn new(inner: S, options: DecodeOptions) -> Self {
+        DecodeStream { inner, decoder: Decoder::new(options), done: false }
+    }
+
+    /// The file name from the `begin` line, once it has been decoded.
+    pub fn name(&self) -> Option<&str> {
+        self.decoder.header().map(|(_, name)| name)
+    }
+
+    /// The permission bits from the `begin` line, once it has been decoded.
+    pub fn mode(&self) -> Option<u32> {
+        self.decoder.header().map(|(mode, _)| mode)
+    }
+
+    /// Returns the inner stream.
+    pub fn into_inner(self) -> S {
+        self.inner
+    }
+}
+
+impl<S, B, E> Stream for DecodeStream<S>
+where
+    S: Stream<Item = Result<B, E>> + Unpin,
+    B: AsRef<[u8]>,
+    E: From<UUEncodeError>,
+{
+    type Item = Result<Bytes, E>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = self.get_mut();
+        let mut decoded = Vec::new();
+        while !this.done {
+            let result = if this.decoder.is_done() {
+                None
+            } else {
+                ready!(Pin::new(&mut this.inner).poll_next(cx))
+            };
+            let result = match result {
+                Some(Ok(chunk)) => this.decoder.push(chunk.as_ref(), &mut decoded).map_err(E::from),
+                Some(Err(err)) => Err(err),
+                None => {
+                    this.done = true;
+                    this.decoder.finish(&mut decoded).map_err(E::from)
+                }
+            };
+            if let Err(err) = result {
+                this.done = true;
+                return Poll::Ready(Some(Err(err)));
+            }
+            if !decoded.is_empty() {
+                return Poll::Ready(Some(Ok(decoded.into())));
+            }
+        }
+        Poll::Ready(None)
+    }
+}
+
+/// A sink which uuencodes the chunks sent to it as a complete file, as `uuencode_file` does, sending the encoded
+/// text on to the inner sink in chunks of whole lines. Close the sink once all the data is sent, to send the last
+/// line, the terminator and `end`. Encoding errors are converted into the inner sink's error type.
+/// The inner sink must be `Unpin`; use `Box::pin` for sinks which are not.
+/// Example:
+/// ```rust
+/// fn encode() -> std::io::Result<()> {
+///     use futures::SinkExt;
+///     let inner = Vec::<bytes::Bytes>::new().sink_map_err(std::io::Error::other);
+///     let mut sink = uuencode_lite::EncodeSink::new(inner, uuencode_lite::FileOptions::new("cat.txt"))?;
+///     futures::executor::block_on(async {
+///         sink.send(&b"cat"[..]).await?;
+///         SinkExt::<&[u8]>::close(&mut sink).await
+///     })?;
+///     assert_eq!(sink.into_inner().into_inner().concat(), b"begin 644 cat.txt\n#8V%T\n`\nend\n");
+///     Ok(())
+/// }
+/// ```
+#[derive(Debug)]
+pub struct EncodeSink<S> {
+    inner: S,
+    encoder: Encoder,
+    /// Encoded text not yet sent to `inner`.
+    buffer: String,
+    /// Whether the end of the file has been encoded.
+    finished: bool,
+}
+
+impl<S> EncodeSink<S> {
+    /// Creates a sink which encodes a file with the given options into `inner`.
+    /// Fails if the file name is invalid, as `uuencode_file` does.
+    pub fn new(inner: S, options: FileOptions) -> Result<Self, UUEncodeError> {
+        Ok(EncodeSink { inner, encoder: Encoder::new(options)?, buffer: String::new(), finished: false })
+    }
+
+    /// Returns the inner sink. Any encoded text not yet sent to it is lost.
+    pub fn into_inner(self) -> S {
+        self.inner
+    }
+
+    /// Sends any encoded text to the inner sink.
+    fn poll_send_buffer(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), S::Error>>
+    where
+        S: Sink<Bytes> + Unpin,
+    {
+        if !self.buffer.is_empty() {
+            ready!(Pin::new(&mut self.inner).poll_ready(cx))?;
+            Pin::new(&mut self.inner).start_send(std::mem::take(&mut self.buffer).into())?;
+        }
+        Poll::Ready(Ok(()))
+    }
+}
+
+impl<S, B> Sink<B> for EncodeSink<S>
+where
+    S: Sink<Bytes> + Unpin,
+    S::Error: From<UUEncodeError>,
+    B: AsRef<[u8]>,
+{
+    type Error = S::Error;
+
+    fn poll_ready(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.get_mut().poll_send_buffer(cx)
+    }
+
+    fn start_send(self: Pin<&mut Self>, item: B) -> Result<(), Self::Error> {
+        let this = self.get_mut();
+        this.encoder.push(item.as_ref(), &mut this.buffer)?;
+        Ok(())
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        let this = self.get_mut();
+        ready!(this.poll_send_buffer(cx))?;
+        Pin::new(&mut this.inner).poll_flush(cx)
+    }
+
+    fn poll_close(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        let this = self.get_mut();
+        if !this.finished {
+            this.finished = true;
+            this.encoder.finish(&mut this.buffer)?;
+        }
+        ready!(this.poll_send_buffer(cx))?;
+        Pin::new(&mut this.inner).poll_close(cx)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{uuencode_file, Checksum};
+    use futures::{SinkExt, StreamExt};
+    use std::io;
+
+    /// Tests encoding and decoding a file through the sink and stream, in chunks
+    #[test]
+    fn test_stream_sink_rt() {
+        let source_data = std::fs::read("test_data/the_machine_stops.txt").expect("Can open test data");
+        let options = FileOptions::new("the_machine_stops.txt").trailer(Some(Checksum::Crc32));
+        let expected = uuencode_file(&source_data, &options).unwrap();
+
+        let mut sink = EncodeSink::new(Vec::<Bytes>::new().sink_map_err(io::Error::other), options).unwrap();
+        futures::executor::block_on(async {
+            for chunk in source_data.chunks(1000) {
+                sink.send(chunk).await.unwrap();
+            }
+            SinkExt::<&[u8]>::close(&mut sink).await.unwrap();
+        });
+        let encoded = sink.into_inner().into_inner();
+        assert!(encoded.iter().all(|chunk| chunk.ends_with(b"\n")), "sends whole lines");
+        assert_eq!(encoded.concat(), expected.as_bytes(), "can encode through a sink");
+
+        let chunks = expected.as_bytes().chunks(999).map(|chunk| io::Result::Ok(Bytes::copy_from_slice(chunk))).collect::<Vec<_>>();
+        let stream = DecodeStream::new(futures::stream::iter(chunks), DecodeOptions::new().verify_trailer(true));
+        let decoded = futures::executor::block_on(stream.collect::<Vec<_>>()).into_iter().collect::<io::Result<Vec<_>>>().unwrap();
+        assert_eq!(decoded.concat(), source_data, "can decode through a stream");
+
+        let truncated = [io::Result::Ok(Bytes::copy_from_slice(&expected.as_bytes()[..1000]))];
+        let results = futures::executor::block_on(DecodeStream::new(futures::stream::iter(truncated), DecodeOptions::new()).collect::<Vec<_>>());
+        assert!(results.last().unwrap().is_err(), "reports a truncated stream");
+    }
+}
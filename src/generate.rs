@@ -0,0 +1,208 @@
+use crate::{uuencode_file, FileOptions};
+use std::string::String;
+use std::vec::Vec;
+
+/// The characters generated file names are made of. Names always start with a letter or digit, so they are never
+/// `.` or `..`, and always pass `is_safe_file_name`.
+const NAME_CHARS: &[u8] = b"abcdefghijklmnopqrstuvwxyz0123456789_.-";
+
+/// Characters outside the standard alphabet, used to corrupt a line.
+const INVALID_CHARS: &[u8] = b"\x01\x02\x03\x04\x05\x06\x07\x08";
+
+/// A structurally valid uuencoded file, for property-testing parsers which embed this codec, with the data, name
+/// and mode it encodes. `text` is exactly what `uuencode_file` produces, with LF or CRLF line endings, so
+/// `uudecode_file` always decodes it back to `data`. Implements `arbitrary::Arbitrary`, and, with the `proptest`
+/// feature, `proptest::arbitrary::Arbitrary`, so `any::<EncodedDocument>()` works.
+/// Example:
+/// ```rust
+/// use arbitrary::{Arbitrary, Unstructured};
+/// let document = uuencode_lite::EncodedDocument::arbitrary(&mut Unstructured::new(b"some fuzzer input")).unwrap();
+/// assert_eq!(uuencode_lite::uudecode_file(document.text.as_bytes()).unwrap().data, document.data);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EncodedDocument {
+    /// The decoded contents of the file.
+    pub data: Vec<u8>,
+    /// The file name on the `begin` line.
+    pub name: String,
+    /// The permission bits on the `begin` line.
+    pub mode: u32,
+    /// The complete uuencoded file.
+    pub text: String,
+}
+
+impl EncodedDocument {
+    /// Encodes a document from the generated parts. Up to 16 bytes of `name` are mapped onto `NAME_CHARS`.
+    fn build(data: Vec<u8>, name: &[u8], mode: u32, crlf: bool) -> Self {
+        let mut name: String = name.iter().take(16).map(|&ch| NAME_CHARS[ch as usize % NAME_CHARS.len()] as char).collect();
+        if !name.starts_with(|ch: char| ch.is_ascii_alphanumeric()) {
+            name.insert(0, 'a');
+        }
+        let text = uuencode_file(&data, &FileOptions::new(name.clone()).mode(mode)).expect("generated names are valid");
+        let text = if crlf { text.replace('\n', "\r\n") } else { text };
+        EncodedDocument { data, name, mode, text }
+    }
+}
+
+/// How a `CorruptedDocument` was damaged. Lines and characters are counted from zero, as `UUEncodeError` counts them,
+/// with the `begin` line as line zero.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum Corruption {
+    /// One character of a body line was replaced with a control character, which is outside the alphabet.
+    InvalidCharacter { line: usize, character: usize },
+    /// The last four characters of a body line were removed, so it is shorter than its declared length.
+    ShortLine { line: usize },
+    /// The `end` line was removed.
+    MissingEnd,
+}
+
+/// An `EncodedDocument` with one deliberate, small, corruption, which `uudecode_file` always rejects.
+/// For checking that a parser reports damaged input rather than returning wrong data or panicking.
+/// Implements `arbitrary::Arbitrary`, and `proptest::arbitrary::Arbitrary` with the `proptest` feature, as
+/// `EncodedDocument` does.
+/// Example:
+/// ```rust
+/// use arbitrary::{Arbitrary, Unstructured};
+/// let document = uuencode_lite::CorruptedDocument::arbitrary(&mut Unstructured::new(b"some fuzzer input")).unwrap();
+/// assert!(uuencode_lite::uudecode_file(document.text.as_bytes()).is_err());
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CorruptedDocument {
+    /// The document before it was corrupted.
+    pub original: EncodedDocument,
+    /// The corrupted text.
+    pub text: String,
+    /// How the text was corrupted.
+    pub corruption: Corruption,
+}
+
+impl CorruptedDocument {
+    /// Corrupts a document with at least one byte of data, choosing the kind of corruption and where it goes from
+    /// the generated seeds.
+    fn build(original: EncodedDocument, kind: usize, line_seed: usize, char_seed: usize) -> Self {
+        let newline = if original.text.contains("\r\n") { "\r\n" } else { "\n" };
+        let mut lines: Vec<String> = original.text.split(newline).map(String::from).collect();
+        // the body runs from after the begin line to the last line with data, before the "`" terminator
+        let body_lines = original.data.len().div_ceil(45);
+        let line = 1 + line_seed % body_lines;
+        let corruption = match kind % 3 {
+            0 => {
+                let character = 1 + char_seed % (lines[line].len() - 1);
+                let invalid = INVALID_CHARS[char_seed % INVALID_CHARS.len()] as char;
+                lines[line].replace_range(character..character + 1, invalid.encode_utf8(&mut [0; 4]));
+                Corruption::InvalidCharacter { line, character }
+            }
+            1 => {
+                let len = lines[line].len();
+                lines[line].truncate(len - 4);
+                Corruption::ShortLine { line }
+            }
+            _ => {
+                lines.retain(|line| line != "end");
+                Corruption::MissingEnd
+            }
+        };
+        CorruptedDocument { text: lines.join(newline), original, corruption }
+    }
+}
+
+impl<'a> arbitrary::Arbitrary<'a> for EncodedDocument {
+    fn arbitrary(u: &mut arbitrary::Unstructured<'a>) -> arbitrary::Result<Self> {
+        let name: Vec<u8> = u.arbitrary()?;
+        let mode = u.int_in_range(0..=0o777)?;
+        let crlf = u.arbitrary()?;
+        Ok(EncodedDocument::build(u.arbitrary()?, &name, mode, crlf))
+    }
+}
+
+impl<'a> arbitrary::Arbitrary<'a> for CorruptedDocument {
+    fn arbitrary(u: &mut arbitrary::Unstructured<'a>) -> arbitrary::Result<Self> {
+        let mut original = EncodedDocument::arbitrary(u)?;
+        if original.data.is_empty() {
+            original = EncodedDocument::build(std::vec![u.arbitrary()?], original.name.as_bytes(), original.mode, original.text.contains('\r'));
+        }
+        Ok(CorruptedDocument::build(original, u.arbitrary()?, u.arbitrary()?, u.arbitrary()?))
+    }
+}
+
+#[cfg(feature = "proptest")]
+impl proptest::arbitrary::Arbitrary for EncodedDocument {
+    type Parameters = ();
+    type Strategy = proptest::strategy::BoxedStrategy<Self>;
+
+    fn arbitrary_with(_: ()) -> Self::Strategy {
+        document_strategy(0)
+    }
+}
+
+#[cfg(feature = "proptest")]
+impl proptest::arbitrary::Arbitrary for CorruptedDocument {
+    type Parameters = ();
+    type Strategy = proptest::strategy::BoxedStrategy<Self>;
+
+    fn arbitrary_with(_: ()) -> Self::Strategy {
+        use proptest::prelude::*;
+        (document_strategy(1), any::<usize>(), any::<usize>(), any::<usize>())
+            .prop_map(|(original, kind, line_seed, char_seed)| CorruptedDocument::build(original, kind, line_seed, char_seed))
+            .boxed()
+    }
+}
+
+/// Generates documents with at least `min_len` bytes of data.
+#[cfg(feature = "proptest")]
+fn document_strategy(min_len: usize) -> proptest::strategy::BoxedStrategy<EncodedDocument> {
+    use proptest::prelude::*;
+    (proptest::collection::vec(any::<u8>(), min_len..1024), proptest::collection::vec(any::<u8>(), 1..=16), 0..=0o777u32, any::<bool>())
+        .prop_map(|(data, name, mode, crlf)| EncodedDocument::build(data, &name, mode, crlf))
+        .boxed()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::uudecode_file;
+
+    /// Tests that every kind of corruption is rejected where it says it is
+    #[test]
+    fn test_corruptions() {
+        assert_eq!(EncodedDocument::build(Vec::new(), b"$", 0o600, false).name, "a_", "starts names with a letter or digit");
+        let original = EncodedDocument::build((0..=255).collect(), b"name", 0o600, false);
+        assert_eq!(uudecode_file(original.text.as_bytes()).unwrap().data, original.data, "builds valid documents");
+        for kind in 0..3 {
+            for crlf in [false, true] {
+                let original = EncodedDocument { text: if crlf { original.text.replace('\n', "\r\n") } else { original.text.clone() }, ..original.clone() };
+                let corrupted = CorruptedDocument::build(original, kind, 4, 17);
+                let err = uudecode_file(corrupted.text.as_bytes()).unwrap_err();
+                match corrupted.corruption {
+                    Corruption::InvalidCharacter { line, character } => assert_eq!((err.line, err.character), (line, character), "reports the invalid character"),
+                    Corruption::ShortLine { line } => assert_eq!(err.line, line, "reports the short line"),
+                    Corruption::MissingEnd => assert!(!corrupted.text.contains("end"), "removes the end line"),
+                }
+            }
+        }
+    }
+
+    /// Tests that documents from arbitrary input decode, and corrupted ones do not
+    #[test]
+    fn test_arbitrary() {
+        use arbitrary::{Arbitrary, Unstructured};
+        let seed = std::fs::read("test_data/random_data.bin").expect("Can open test data");
+        for chunk in seed.chunks(128) {
+            let document = EncodedDocument::arbitrary(&mut Unstructured::new(chunk)).unwrap();
+            assert_eq!(uudecode_file(document.text.as_bytes()).unwrap().data, document.data, "decodes a generated document");
+            let corrupted = CorruptedDocument::arbitrary(&mut Unstructured::new(chunk)).unwrap();
+            assert!(uudecode_file(corrupted.text.as_bytes()).is_err(), "rejects a corrupted document");
+        }
+    }
+
+    #[cfg(feature = "proptest")]
+    proptest::proptest! {
+        /// Tests that generated documents decode, and corrupted ones do not
+        #[test]
+        fn test_proptest(document in proptest::prelude::any::<EncodedDocument>(), corrupted in proptest::prelude::any::<CorruptedDocument>()) {
+            proptest::prop_assert_eq!(uudecode_file(document.text.as_bytes()).unwrap().data, document.data);
+            proptest::prop_assert!(uudecode_file(corrupted.text.as_bytes()).is_err());
+        }
+    }
+}
@@ -0,0 +1,70 @@
+use crate::{uudecode_to_slice_with, uuencode_to_slice_with, uuencoded_len, DecodeOptions, EncodeOptions, UUEncodeError};
+
+/// Encodes the input data as `uuencode` does, into a `heapless::String` of capacity `N`, without a heap.
+/// Fails with an error of kind `Capacity` if the encoded text does not fit.
+/// Example:
+/// ```rust
+/// fn encode() -> Result<(), uuencode_lite::UUEncodeError> {
+///     let encoded = uuencode_lite::uuencode_heapless::<8>(b"cat")?;
+///     assert_eq!(encoded, "#8V%T");
+///     assert_eq!(uuencode_lite::uuencode_heapless::<4>(b"cat").unwrap_err().kind(), uuencode_lite::ErrorKind::Capacity);
+///     Ok(())
+/// }
+/// ```
+pub fn uuencode_heapless<const N: usize>(data: &[u8]) -> Result<heapless::String<N>, UUEncodeError> {
+    uuencode_heapless_with(data, &EncodeOptions::default())
+}
+
+/// Encodes the input data as `uuencode_heapless`, using the given options.
+pub fn uuencode_heapless_with<const N: usize>(data: &[u8], options: &EncodeOptions) -> Result<heapless::String<N>, UUEncodeError> {
+    let mut encoded = heapless::Vec::<u8, N>::new();
+    encoded.resize_default(uuencoded_len(data.len(), options)).map_err(|_| UUEncodeError::capacity(0))?;
+    uuencode_to_slice_with(data, options, &mut encoded)?;
+    Ok(heapless::String::from_utf8(encoded).expect("encoded text is ASCII"))
+}
+
+/// Decodes uuencoded data as `uudecode_with` does, into a `heapless::Vec` of capacity `N`, without a heap.
+/// Fails with an error of kind `Capacity`, reporting the line which did not fit, if the decoded bytes do not fit.
+/// Example:
+/// ```rust
+/// fn decode() -> Result<(), uuencode_lite::UUEncodeError> {
+///     let decoded = uuencode_lite::uudecode_heapless::<45>(b"#8V%T\n")?;
+///     assert_eq!(decoded, b"cat");
+///     Ok(())
+/// }
+/// ```
+pub fn uudecode_heapless<const N: usize>(data: &[u8]) -> Result<heapless::Vec<u8, N>, UUEncodeError> {
+    uudecode_heapless_with(data, &DecodeOptions::default())
+}
+
+/// Decodes uuencoded data as `uudecode_heapless`, using the given options.
+pub fn uudecode_heapless_with<const N: usize>(data: &[u8], options: &DecodeOptions) -> Result<heapless::Vec<u8, N>, UUEncodeError> {
+    let mut decoded = heapless::Vec::<u8, N>::new();
+    decoded.resize_default(N).expect("fills exactly the capacity");
+    let len = uudecode_to_slice_with(data, options, &mut decoded)?;
+    decoded.truncate(len);
+    Ok(decoded)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ErrorKind;
+
+    /// Tests encoding and decoding into heapless containers, and reporting when they are full
+    #[test]
+    fn test_heapless() {
+        let source_data = std::fs::read("test_data/random_data.bin").expect("Can open test data");
+        let expected = std::fs::read_to_string("test_data/random_data.bin.uu").expect("Can open test data");
+        let expected = expected.trim_end();
+
+        let encoded = uuencode_heapless::<4096>(&source_data).unwrap();
+        assert_eq!(encoded.as_str(), expected, "can encode into a heapless string");
+        assert_eq!(uuencode_heapless::<2048>(&source_data).unwrap_err().kind(), ErrorKind::Capacity, "reports a full string");
+
+        let decoded = uudecode_heapless::<2048>(expected.as_bytes()).unwrap();
+        assert_eq!(decoded.as_slice(), source_data, "can decode into a heapless vector");
+        let err = uudecode_heapless::<2047>(expected.as_bytes()).unwrap_err();
+        assert_eq!((err.kind(), err.line), (ErrorKind::Capacity, 45), "reports the line which does not fit");
+    }
+}
@@ -0,0 +1,55 @@
+use crate::{uuencode_file, FileOptions, UUEncodeError};
+use lettre::message::header::{ContentDisposition, ContentTransferEncoding, ContentType};
+use lettre::message::{Body, SinglePart};
+use std::format;
+
+/// Encodes bytes as a complete uuencoded file, as `uuencode_file` does, and wraps it as a `lettre` message part,
+/// for receivers which cannot decode MIME Base64. Set `base64` on the options for `begin-base64` framing.
+/// The part is `text/plain` in US-ASCII with a `7bit` transfer encoding, so mail software which predates MIME shows
+/// the framed file in the message for `uudecode`. It is marked as an attachment named after the file with `.uu`
+/// added, so MIME clients offer to save it as a file which `uudecode` accepts, rather than as the raw data.
+/// Example:
+/// ```rust
+/// fn attach() -> Result<lettre::Message, Box<dyn std::error::Error>> {
+///     let part = uuencode_lite::uuencoded_attachment(b"cat", &uuencode_lite::FileOptions::new("cat.txt"))?;
+///     let message = lettre::Message::builder()
+///         .from("a@example.com".parse()?)
+///         .to("b@example.com".parse()?)
+///         .subject("cat")
+///         .multipart(lettre::message::MultiPart::mixed().singlepart(lettre::message::SinglePart::plain(String::from("Attached."))).singlepart(part))?;
+///     Ok(message)
+/// }
+/// # attach().unwrap();
+/// ```
+pub fn uuencoded_attachment(data: &[u8], options: &FileOptions) -> Result<SinglePart, UUEncodeError> {
+    let encoded = uuencode_file(data, options)?;
+    let body = Body::new_with_encoding(encoded, ContentTransferEncoding::SevenBit).map_err(|_| UUEncodeError::new(0, 0, "Table is not ASCII"))?;
+    Ok(SinglePart::builder()
+        .header(ContentType::parse("text/plain; charset=us-ascii").expect("the content type is valid"))
+        .header(ContentDisposition::attachment(&format!("{}.uu", options.name())))
+        .body(body))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::uudecode_file;
+    use std::string::String;
+
+    /// Tests the headers and body of a uuencoded part, with both framings
+    #[test]
+    fn test_uuencoded_attachment() {
+        let source_data = std::fs::read("test_data/random_data.bin").expect("Can open test data");
+        for base64 in [false, true] {
+            let part = uuencoded_attachment(&source_data, &FileOptions::new("random_data.bin").mode(0o600).base64(base64)).unwrap();
+            let formatted = String::from_utf8(part.formatted()).unwrap();
+            let (headers, body) = formatted.split_once("\r\n\r\n").unwrap();
+            let headers = format!("{}\r\n", headers);
+            assert!(headers.contains("Content-Type: text/plain; charset=us-ascii\r\n"), "is plain text: {}", headers);
+            assert!(headers.contains("Content-Disposition: attachment; filename=\"random_data.bin.uu\"\r\n"), "is named: {}", headers);
+            assert!(headers.contains("Content-Transfer-Encoding: 7bit\r\n"), "is not re-encoded: {}", headers);
+            let file = uudecode_file(body.as_bytes()).unwrap();
+            assert_eq!((file.name.as_str(), file.mode, file.data), ("random_data.bin", 0o600, source_data.clone()), "frames the file");
+        }
+    }
+}
@@ -1,21 +1,403 @@
+#![no_std]
+#![cfg_attr(not(any(test, feature = "unsafe-fast", feature = "simd", feature = "ffi", feature = "mmap", feature = "napi")), forbid(unsafe_code))]
+
+#[cfg(feature = "alloc")]
+extern crate alloc;
+#[cfg(any(feature = "std", test))]
+extern crate std;
+
+#[cfg(feature = "alloc")]
+mod ascii85;
+#[cfg(any(feature = "tokio", feature = "futures-io"))]
+mod async_io;
+#[cfg(feature = "base32")]
+mod base32;
+#[cfg(feature = "alloc")]
+mod base64;
+#[cfg(feature = "alloc")]
+mod binhex;
+#[cfg(feature = "http-body")]
+mod body;
+#[cfg(feature = "alloc")]
+mod btoa;
+#[cfg(feature = "bytes")]
+mod bytes_buf;
+#[cfg(feature = "alloc")]
+mod cancel;
+#[cfg(feature = "alloc")]
+mod checkpoint;
+#[cfg(feature = "alloc")]
+mod checksum;
+#[cfg(feature = "tokio-util")]
+mod codec;
+#[cfg(feature = "flate2")]
+mod compress;
+#[cfg(feature = "alloc")]
+mod decoder;
+#[cfg(feature = "auto-decompress")]
+mod decompress;
+#[cfg(feature = "alloc")]
+mod detect;
+#[cfg(feature = "digest")]
+mod digest_io;
+#[cfg(feature = "embedded-io")]
+mod embedded;
+#[cfg(feature = "alloc")]
+mod encoder;
+#[cfg(feature = "alloc")]
+mod encoding;
+#[cfg(feature = "alloc")]
+mod engine;
+#[cfg(feature = "alloc")]
+mod file;
+#[cfg(feature = "ffi")]
+mod ffi;
+#[cfg(feature = "embedded-storage")]
+mod flash;
+#[cfg(feature = "futures")]
+mod futures_stream;
+#[cfg(feature = "arbitrary")]
+mod generate;
+#[cfg(feature = "heapless")]
+mod heapless_output;
+#[cfg(feature = "lettre")]
+mod lettre_attachment;
+#[cfg(feature = "alloc")]
+mod line_decoder;
+#[cfg(feature = "mail-parser")]
+mod mail;
+#[cfg(feature = "metrics")]
+mod metrics;
+#[cfg(feature = "mmap")]
+mod mmap;
+#[cfg(feature = "uniffi")]
+mod mobile;
+#[cfg(feature = "alloc")]
+mod multipart;
+#[cfg(feature = "napi")]
+mod node;
+#[cfg(feature = "parallel")]
+mod parallel;
+#[cfg(feature = "python")]
+mod python;
+#[cfg(feature = "alloc")]
+mod quoted_printable;
+#[cfg(feature = "alloc")]
+mod repair;
+#[cfg(feature = "serde")]
+/// Helpers for `#[serde(with = "uuencode_lite::serde")]`, which store `Vec<u8>` and other byte fields as strings of
+/// bare uuencoded lines, for binary data in JSON or TOML configs. Use `uuencode_lite::serde::option` for
+/// `Option<Vec<u8>>` fields.
+/// Example:
+/// ```rust
+/// #[derive(serde::Serialize, serde::Deserialize)]
+/// struct Config {
+///     #[serde(with = "uuencode_lite::serde")]
+///     key: Vec<u8>,
+/// }
+///
+/// let config: Config = serde_json::from_str(r##"{"key":"#8V%T"}"##).unwrap();
+/// assert_eq!(config.key, b"cat");
+/// ```
+pub mod serde;
+#[cfg(feature = "alloc")]
+mod shar;
+#[cfg(feature = "simd")]
+mod simd;
+mod slice;
+#[cfg(feature = "std")]
+mod stream;
+mod table;
+#[cfg(all(feature = "tokio-uring", target_os = "linux"))]
+mod uring;
+#[cfg(feature = "wasm-bindgen")]
+mod wasm;
+#[cfg(feature = "alloc")]
+mod yenc;
+#[cfg(feature = "alloc")]
+mod z85;
+
+#[cfg(feature = "alloc")]
+pub use ascii85::{ascii85_decode, ascii85_encode};
+#[cfg(any(feature = "tokio", feature = "futures-io"))]
+pub use async_io::{AsyncDecodeReader, AsyncDecodeWriter, AsyncEncodeReader, AsyncEncodeWriter};
+#[cfg(feature = "base32")]
+pub use base32::{base16_decode, base16_encode, base32_decode, base32_encode, base32hex_decode, base32hex_encode};
+#[cfg(feature = "alloc")]
+pub use base64::{base64_decode, base64_encode};
+#[cfg(feature = "alloc")]
+pub use binhex::{binhex_decode, BinHexFile};
+#[cfg(feature = "http-body")]
+pub use body::{DecodeBody, DecodeBodyError};
+#[cfg(feature = "alloc")]
+pub use btoa::{btoa_decode, btoa_encode};
+#[cfg(feature = "bytes")]
+pub use bytes_buf::{uudecode_to_bytes, uudecode_to_bytes_with, uuencode_to_bytes, uuencode_to_bytes_with};
+#[cfg(feature = "alloc")]
+pub use cancel::CancelToken;
+#[cfg(feature = "alloc")]
+pub use checksum::Checksum;
+#[cfg(feature = "tokio-util")]
+pub use codec::{UUDecodeCodec, UUEncodeCodec};
+#[cfg(feature = "flate2")]
+pub use compress::{CompressEncodeReader, CompressEncodeWriter, CompressionFormat, DecompressDecodeReader, DecompressDecodeWriter};
+#[cfg(feature = "alloc")]
+pub use decoder::{DecodeEvent, Decoder};
+#[cfg(feature = "auto-decompress")]
+pub use decompress::{decompress_payload, detect_compression, DecompressedFile, PayloadCompression};
+#[cfg(feature = "alloc")]
+pub use detect::{decode_auto, detect_format, Format};
+#[cfg(feature = "digest")]
+pub use digest_io::{DigestReader, DigestWriter};
+#[cfg(feature = "embedded-io")]
+pub use embedded::{EmbeddedDecodeReader, EmbeddedDecodeWriter, EmbeddedEncodeReader, EmbeddedEncodeWriter, EmbeddedIoError};
+#[cfg(feature = "alloc")]
+pub use encoder::Encoder;
+#[cfg(feature = "base32")]
+pub use encoding::{Base16Encoding, Base32Encoding};
+#[cfg(feature = "alloc")]
+pub use encoding::{encoding_for_name, Ascii85Encoding, Base64Encoding, BtoaEncoding, Encoding, QuotedPrintableEncoding, UUEncoding, Z85Encoding};
+#[cfg(feature = "alloc")]
+pub use engine::Engine;
+#[cfg(feature = "futures")]
+pub use futures_stream::{DecodeStream, EncodeSink};
+#[cfg(feature = "heapless")]
+pub use heapless_output::{uudecode_heapless, uudecode_heapless_with, uuencode_heapless, uuencode_heapless_with};
+#[cfg(feature = "alloc")]
+pub use file::{decode_all, is_safe_file_name, uudecode_file, uudecode_file_with, uuencode_file, uuencode_files, DecodedFile, FileOptions};
+#[cfg(feature = "std")]
+pub use file::{apply_mode, extract_file, mode_from_permissions, uuencode_dir};
+#[cfg(feature = "ffi")]
+pub use ffi::{UUDecoder, UUEncoder, UUStatus};
+#[cfg(feature = "embedded-storage")]
+pub use flash::{FlashDecodeError, FlashDecoder};
+#[cfg(feature = "arbitrary")]
+pub use generate::{CorruptedDocument, Corruption, EncodedDocument};
+#[cfg(feature = "lettre")]
+pub use lettre_attachment::uuencoded_attachment;
+#[cfg(feature = "alloc")]
+pub use line_decoder::LineDecoder;
+#[cfg(feature = "mail-parser")]
+pub use mail::extract_mail_attachments;
+#[cfg(feature = "metrics")]
+pub use metrics::{set_metrics, Counters, Metrics, MetricsSnapshot};
+#[cfg(feature = "mmap")]
+pub use mmap::{uuencode_path, uuencode_path_with, MappedFile};
+#[cfg(feature = "uniffi")]
+pub use mobile::{mobile_extract_files, mobile_uudecode, mobile_uudecode_file, mobile_uuencode, mobile_uuencode_file, CodecError, MobileDecodedFile, MobileDecoder, MobileExtractedFile};
+#[cfg(feature = "alloc")]
+pub use multipart::{reassemble_parts, uuencode_parts, SplitOptions};
+#[cfg(feature = "napi")]
+pub use node::{node_decode_path, node_encode_path, node_uudecode, node_uudecode_file, node_uuencode, node_uuencode_file, DecodePathTask, EncodePathTask, NodeDecodedFile, NodeDecoder, NodeFileHeader};
+#[cfg(feature = "parallel")]
+pub use parallel::{uudecode_parallel, uudecode_parallel_with, uuencode_parallel, uuencode_parallel_with};
+#[cfg(feature = "python")]
+pub use python::{python_module, PyDecoder, PyEncoder};
+#[cfg(feature = "alloc")]
+pub use quoted_printable::{quoted_printable_decode, quoted_printable_encode};
+#[cfg(feature = "alloc")]
+pub use repair::{repair, Repair, RepairKind};
+#[cfg(feature = "serde_with")]
+pub use serde::UuEncodedBytes;
+#[cfg(feature = "alloc")]
+pub use shar::{extract_shar, shar_archive};
+pub use slice::{uudecode_to_slice, uudecode_to_slice_with, uuencode_to_slice, uuencode_to_slice_with, uuencoded_len, SliceDecoder, SliceEncoder};
+#[cfg(feature = "std")]
+pub use stream::{uudecode_bufread, uudecode_bufread_with, uudecode_to_writer, uuencode_to_writer, DecodeChunks, DecodeReader, DecodeWriter, EncodeReader, EncodeWriter, Progress};
+pub use table::Table;
+#[cfg(all(feature = "tokio-uring", target_os = "linux"))]
+pub use uring::uudecode_file_uring;
+#[cfg(feature = "wasm-bindgen")]
+pub use wasm::{wasm_uudecode, wasm_uudecode_file, wasm_uuencode, wasm_uuencode_file, WasmDecodedFile, WasmDecoder};
+#[cfg(feature = "alloc")]
+pub use yenc::{yenc_decode, yenc_decode_part, yenc_encode, yenc_encode_parts, yenc_join, YEncFile, YEncOptions, YEncPart};
+#[cfg(feature = "alloc")]
+pub use z85::{z85_decode, z85_encode};
+
+#[cfg(feature = "uniffi")]
+uniffi::setup_scaffolding!();
+
+#[cfg(feature = "alloc")]
+use alloc::string::String;
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
+use core::fmt;
+
 /// An error representing malformed input data.
 /// This can occur due to invalid line lengths or invalid characters.
+/// With the `serde` feature, it is serialized as its line, character, kind and message text, so it can be sent
+/// between processes and deserialized with the same `Display` output.
 #[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(::serde::Serialize, ::serde::Deserialize))]
 pub struct UUEncodeError {
     /// The input line that the encoding error is on.
     line: usize,
     /// The input character that the encoding error is on.
     character: usize,
     /// A descriptive (hopefully) message about the error.
-    msg: String,
+    #[cfg_attr(feature = "serde", serde(rename = "message"))]
+    msg: Message,
+    /// What kind of failure this is.
+    kind: ErrorKind,
+}
+impl UUEncodeError {
+    pub(crate) fn new(line: usize, character: usize, msg: impl Into<Message>) -> Self {
+        UUEncodeError { line, character, msg: msg.into(), kind: ErrorKind::Invalid }
+    }
+
+    /// An error for an operation stopped by a `CancelToken` before the given line.
+    #[cfg(feature = "alloc")]
+    pub(crate) fn cancelled(line: usize) -> Self {
+        UUEncodeError { line, character: 0, msg: Message::Static("Cancelled"), kind: ErrorKind::Cancelled }
+    }
+
+    /// An error for output which does not fit in the space given for it, at the given line.
+    pub(crate) fn capacity(line: usize) -> Self {
+        UUEncodeError { line, character: 0, msg: Message::Static("Output buffer too small"), kind: ErrorKind::Capacity }
+    }
+
+    /// What kind of failure this is.
+    pub fn kind(&self) -> ErrorKind {
+        self.kind
+    }
+
+    /// The input line the error is on, counting from 0.
+    pub fn line(&self) -> usize {
+        self.line
+    }
+
+    /// The character within the line the error is on, counting from 0.
+    pub fn character(&self) -> usize {
+        self.character
+    }
+
+    /// The character which could not be decoded, if that is what the error is.
+    /// Example:
+    /// ```rust
+    /// let err = uuencode_lite::uudecode(b"#8V\x01T").unwrap_err();
+    /// assert_eq!(err.invalid_character(), Some(0x01));
+    /// ```
+    pub fn invalid_character(&self) -> Option<u8> {
+        match self.msg {
+            Message::InvalidCharacter(ch) => Some(ch),
+            _ => None,
+        }
+    }
+
+    /// The message describing the error, without its position.
+    /// Example:
+    /// ```rust
+    /// let err = uuencode_lite::uudecode_file(b"begin 644 cat.txt\n#8V\x01T\n`\nend\n").unwrap_err();
+    /// assert_eq!((err.line(), err.character()), (1, 3));
+    /// assert_eq!(err.message().to_string(), "Invalid character in input: \x01");
+    /// ```
+    pub fn message(&self) -> impl fmt::Display + '_ {
+        &self.msg
+    }
 }
-impl std::error::Error for UUEncodeError {}
-impl std::fmt::Display for UUEncodeError {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+
+/// The kind of failure a `UUEncodeError` represents.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(::serde::Serialize, ::serde::Deserialize))]
+#[non_exhaustive]
+pub enum ErrorKind {
+    /// The input was malformed, or an option was invalid.
+    Invalid,
+    /// The operation was stopped by a `CancelToken`.
+    Cancelled,
+    /// The output did not fit in a fixed-size buffer.
+    Capacity,
+}
+
+/// The message of a `UUEncodeError`. The messages the line codec reports are built without allocating,
+/// so errors can be reported without a heap.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum Message {
+    Static(&'static str),
+    InvalidCharacter(u8),
+    LineTooShort(usize),
+    #[cfg(feature = "alloc")]
+    Owned(String),
+}
+impl From<&'static str> for Message {
+    fn from(msg: &'static str) -> Self {
+        Message::Static(msg)
+    }
+}
+#[cfg(feature = "alloc")]
+impl From<String> for Message {
+    fn from(msg: String) -> Self {
+        Message::Owned(msg)
+    }
+}
+impl fmt::Display for Message {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Message::Static(msg) => f.write_str(msg),
+            Message::InvalidCharacter(ch) => write!(f, "Invalid character in input: {}", *ch as char),
+            Message::LineTooShort(len) => write!(f, "Line too short for declared length {}", len),
+            #[cfg(feature = "alloc")]
+            Message::Owned(msg) => f.write_str(msg),
+        }
+    }
+}
+
+/// Formats messages with interned format strings, sending only the line, character, and any byte or length over the wire.
+#[cfg(feature = "defmt")]
+impl defmt::Format for Message {
+    fn format(&self, f: defmt::Formatter<'_>) {
+        match self {
+            Message::Static(msg) => defmt::write!(f, "{=str}", msg),
+            Message::InvalidCharacter(ch) => defmt::write!(f, "Invalid character in input: {=u8:#x}", ch),
+            Message::LineTooShort(len) => defmt::write!(f, "Line too short for declared length {=usize}", len),
+            #[cfg(feature = "alloc")]
+            Message::Owned(msg) => defmt::write!(f, "{=str}", msg.as_str()),
+        }
+    }
+}
+
+/// Serializes the message as its text.
+#[cfg(feature = "serde")]
+impl ::serde::Serialize for Message {
+    fn serialize<S: ::serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+/// Deserializes a message from its text.
+#[cfg(feature = "serde")]
+impl<'de> ::serde::Deserialize<'de> for Message {
+    fn deserialize<D: ::serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        String::deserialize(deserializer).map(Message::Owned)
+    }
+}
+
+impl core::error::Error for UUEncodeError {}
+#[cfg(feature = "defmt")]
+impl defmt::Format for UUEncodeError {
+    fn format(&self, f: defmt::Formatter<'_>) {
+        defmt::write!(f, "{} at line {=usize} character {=usize}", self.msg, self.line, self.character)
+    }
+}
+impl fmt::Display for UUEncodeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         write!(f, "{} at line {} character {}", self.msg, self.line, self.character)
     }
 }
 
+#[cfg(feature = "std")]
+/// Converts to an `io::Error` of kind `InvalidData` (or `Other`, if cancelled or out of capacity), with the original error as its source.
+impl From<UUEncodeError> for std::io::Error {
+    fn from(err: UUEncodeError) -> Self {
+        let kind = match err.kind {
+            ErrorKind::Invalid => std::io::ErrorKind::InvalidData,
+            ErrorKind::Cancelled | ErrorKind::Capacity => std::io::ErrorKind::Other,
+        };
+        std::io::Error::new(kind, err)
+    }
+}
+
 macro_rules! ok_or_decode_error {
     ($f:ident, $input:expr, $cur_line:expr, $cur_char:expr) => {
         match $f($input) {
@@ -24,13 +406,117 @@ macro_rules! ok_or_decode_error {
                 return Err(UUEncodeError {
                     line: $cur_line,
                     character: $cur_char,
-                    msg: format!("Invalid character in input: {}", $input as char),
+                    msg: Message::InvalidCharacter($input),
+                    kind: ErrorKind::Invalid,
                 });
             }
         }
     }
 }
 
+/// Options controlling how data is uuencoded.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct EncodeOptions {
+    /// The alphabet used to encode the data.
+    table: Table,
+    /// Whether to append a checksum character to each line.
+    line_checksums: bool,
+}
+
+impl EncodeOptions {
+    /// Creates the default options, which match `uuencode`.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the alphabet used to encode the data.
+    pub fn table(mut self, table: Table) -> Self {
+        self.table = table;
+        self
+    }
+
+    /// Sets whether to append a checksum character to each line: the sum of the line's decoded bytes, modulo 64.
+    /// Decoders that don't understand checksums ignore the extra character.
+    pub fn line_checksums(mut self, line_checksums: bool) -> Self {
+        self.line_checksums = line_checksums;
+        self
+    }
+}
+
+/// Options controlling how complete uuencoded files are decoded.
+/// Example:
+/// ```rust
+/// # #[cfg(feature = "alloc")]
+/// fn decode_raw() -> Result<(), uuencode_lite::UUEncodeError> {
+///     let options = uuencode_lite::DecodeOptions::new().raw_names(true);
+///     let file = uuencode_lite::uudecode_file_with(b"begin 644 /tmp/cat.txt\n#8V%T\n`\nend\n", &options)?;
+///     assert_eq!(file.name, "/tmp/cat.txt");
+///     Ok(())
+/// }
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct DecodeOptions {
+    /// Whether to accept file names that fail `is_safe_file_name`.
+    pub(crate) raw_names: bool,
+    /// The alphabet used when no `table` directive is present.
+    pub(crate) table: Table,
+    /// Whether each line must end with a checksum character, which is verified.
+    pub(crate) verify_checksums: bool,
+    /// Whether the file must be followed by a whole-file checksum trailer, which is verified.
+    pub(crate) verify_trailer: bool,
+    /// A token which stops the decoding when cancelled.
+    #[cfg(feature = "alloc")]
+    pub(crate) cancel: Option<CancelToken>,
+}
+
+impl DecodeOptions {
+    /// Creates the default options, which reject unsafe file names.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the alphabet used to decode bodies which are not preceded by a `table` directive.
+    pub fn table(mut self, table: Table) -> Self {
+        self.table = table;
+        self
+    }
+
+    /// Sets whether to verify the checksum character at the end of each uuencoded line,
+    /// as emitted with `EncodeOptions::line_checksums`. Lines without a checksum are rejected.
+    pub fn verify_checksums(mut self, verify_checksums: bool) -> Self {
+        self.verify_checksums = verify_checksums;
+        self
+    }
+
+    /// Sets whether to verify the whole-file checksum trailer, as emitted with `FileOptions::trailer`.
+    /// The trailer must be the first non-blank line after the end of the file.
+    pub fn verify_trailer(mut self, verify_trailer: bool) -> Self {
+        self.verify_trailer = verify_trailer;
+        self
+    }
+
+    /// Sets a token which stops the decoding, between lines, when cancelled.
+    #[cfg(feature = "alloc")]
+    pub fn cancel_token(mut self, token: CancelToken) -> Self {
+        self.cancel = Some(token);
+        self
+    }
+
+    /// Sets whether file names from `begin` lines are returned as-is, without sanitization.
+    /// Only enable this if the name will never be used as a path.
+    pub fn raw_names(mut self, raw_names: bool) -> Self {
+        self.raw_names = raw_names;
+        self
+    }
+}
+
+/// Computes the checksum of a line's decoded bytes, as a 6-bit value.
+#[inline]
+fn line_checksum(data: &[u8]) -> u8 {
+    (data.iter().map(|&byte| byte as u32).sum::<u32>() % 64) as u8
+}
+
+#[cfg(feature = "alloc")]
 /// Encodes the input data into UUEncoded format.
 /// This function encodes the data in chunks of 45 bytes, each prefixed with the length of the line.
 /// The output will be separated into 61-character lines, with the first character being the *decoded*
@@ -45,45 +531,165 @@ macro_rules! ok_or_decode_error {
 /// }
 /// ```
 pub fn uuencode(data: &[u8]) -> Result<String, UUEncodeError> {
-    let mut encoded = String::new();
-    let mut buffer = [0u8; 3];
-    let mut cur_line = 0;
-
-    let mut line_chunks = data.chunks(45).into_iter().peekable();
-    while let Some(line_chunk) = line_chunks.next() {
-        let mut cur_char = 0;
-        // Add the length of the line to the beginning of the line
-        encoded.push(ok_or_decode_error!(encode_char, line_chunk.len() as u8, cur_line, cur_char).into());
+    uuencode_with(data, &EncodeOptions::default())
+}
 
-        // encode the line
-        for chunk in line_chunk.chunks(3) {
-            let len = chunk.len();
-            buffer.fill(0u8);
-            buffer[..len].copy_from_slice(chunk);
+#[cfg(feature = "alloc")]
+/// Encodes the input data into UUEncoded format, as `uuencode`, using the given options.
+pub fn uuencode_with(data: &[u8], options: &EncodeOptions) -> Result<String, UUEncodeError> {
+    #[cfg(feature = "metrics")]
+    if let Some(metrics) = metrics::installed() {
+        metrics.encoded(data.len() as u64, data.len().div_ceil(45) as u64);
+    }
+    // every character is ASCII, so the text is built as bytes and converted once at the end
+    let mut encoded = alloc::vec![0u8; uuencoded_len(data.len(), options)];
+    let len = uuencode_to_slice_with(data, options, &mut encoded)?;
+    debug_assert_eq!(len, encoded.len(), "uuencoded_len is exact");
+    Ok(ascii_string(encoded))
+}
 
-            // Encode 3 bytes into 4 characters
-            encoded.push(ok_or_decode_error!(encode_char, (buffer[0] >> 2) & 0x3F, cur_line, cur_char).into());
-            encoded.push(ok_or_decode_error!(encode_char, ((buffer[0] << 4) | (buffer[1] >> 4)) & 0x3F, cur_line, cur_char+1).into());
-            encoded.push(ok_or_decode_error!(encode_char, ((buffer[1] << 2) | (buffer[2] >> 6)) & 0x3F, cur_line, cur_char+1).into());
-            encoded.push(ok_or_decode_error!(encode_char, buffer[2] & 0x3F, cur_line, cur_char+2).into());
+#[cfg(feature = "alloc")]
+/// Encodes the input data as `uuencode` does, yielding each line, without its newline, as it is encoded.
+/// This lets encoded lines be interleaved with other content without building and re-splitting one large string.
+/// Example:
+/// ```rust
+/// let lines: Vec<String> = uuencode_lite::uuencode_lines(b"cat").collect();
+/// assert_eq!(lines, ["#8V%T"]);
+/// ```
+pub fn uuencode_lines(data: &[u8]) -> impl Iterator<Item = String> + '_ {
+    uuencode_lines_with(data, &EncodeOptions::default())
+}
 
-            cur_char += len;
-        }
-        // add newline to the end, if there will be a next line
-        if line_chunks.peek().is_some() {
-            cur_line += 1;
-            encoded.push('\n');
+#[cfg(feature = "alloc")]
+/// Encodes the input data as `uuencode_lines`, using the given options.
+pub fn uuencode_lines_with<'a>(data: &'a [u8], options: &EncodeOptions) -> impl Iterator<Item = String> + use<'a> {
+    let options = options.clone();
+    data.chunks(45).map(move |line_chunk| {
+        #[cfg(feature = "metrics")]
+        if let Some(metrics) = metrics::installed() {
+            metrics.encoded(line_chunk.len() as u64, 1);
         }
+        let mut line = String::with_capacity(63);
+        encode_line(line_chunk, &options, &mut line);
+        line
+    })
+}
+
+#[cfg(feature = "alloc")]
+/// Encodes the input data as `uuencode` does, calling `f` with each line, without its newline, as it is encoded.
+/// A single buffer is reused for every line, so nothing is allocated per line.
+/// Example:
+/// ```rust
+/// let mut framed = Vec::new();
+/// uuencode_lite::uuencode_for_each(b"cat", |line| {
+///     framed.push(line.len() as u8);
+///     framed.extend_from_slice(line);
+/// });
+/// assert_eq!(framed, b"\x05#8V%T");
+/// ```
+pub fn uuencode_for_each(data: &[u8], f: impl FnMut(&[u8])) {
+    uuencode_for_each_with(data, &EncodeOptions::default(), f)
+}
+
+#[cfg(feature = "alloc")]
+/// Encodes the input data as `uuencode_for_each`, using the given options.
+pub fn uuencode_for_each_with(data: &[u8], options: &EncodeOptions, mut f: impl FnMut(&[u8])) {
+    let mut line = String::with_capacity(63);
+    #[cfg(feature = "metrics")]
+    if let Some(metrics) = metrics::installed() {
+        metrics.encoded(data.len() as u64, data.len().div_ceil(45) as u64);
+    }
+    for line_chunk in data.chunks(45) {
+        line.clear();
+        encode_line(line_chunk, options, &mut line);
+        f(line.as_bytes());
     }
+}
 
-    Ok(encoded)
+#[cfg(feature = "alloc")]
+/// Encodes a single line of at most 45 bytes, without a trailing newline, appending it to `encoded`.
+pub(crate) fn encode_line(line_chunk: &[u8], options: &EncodeOptions, encoded: &mut String) {
+    // the line is built on the stack and appended in one go: the length, up to 15 groups and a checksum
+    let mut line = [0u8; 62];
+    let len = slice::encode_line_to_slice(line_chunk, options, &mut line);
+    push_encoded(encoded, &line[..len]);
 }
 
+#[cfg(feature = "alloc")]
+/// Converts encoded text, which is only ever built from a table's ASCII characters and newlines, into a string. With
+/// `unsafe-fast` that is trusted, except in debug builds; otherwise it is checked.
+fn ascii_string(bytes: Vec<u8>) -> String {
+    #[cfg(feature = "unsafe-fast")]
+    {
+        debug_assert!(bytes.is_ascii(), "encoded text is ASCII");
+        // SAFETY: ASCII is valid UTF-8, and the text comes from `uuencode_to_slice_with`, through `uuencode_with` and
+        // `uuencode_parallel_with`, which writes only newlines and characters from `Table::encode`. Every table is
+        // ASCII: the built-in ones are, and `Table::from_alphabet`, which also loads `table` directives and
+        // checkpoints, rejects anything but printable ASCII.
+        unsafe { String::from_utf8_unchecked(bytes) }
+    }
+    #[cfg(not(feature = "unsafe-fast"))]
+    String::from_utf8(bytes).expect("encoded text is ASCII")
+}
+
+#[cfg(feature = "alloc")]
+/// Appends encoded characters, which are from a table and so ASCII, to `encoded` in one go. As with `ascii_string`,
+/// `unsafe-fast` trusts that, except in debug builds.
+#[inline]
+pub(crate) fn push_encoded(encoded: &mut String, chars: &[u8]) {
+    #[cfg(feature = "unsafe-fast")]
+    {
+        debug_assert!(chars.is_ascii(), "encoded text is ASCII");
+        // SAFETY: ASCII is valid UTF-8, and every caller passes ASCII: `encode_line` passes characters from an ASCII
+        // table (see `ascii_string`), `Encoder::header` passes a table's alphabet, and the Base64 encoders pass
+        // characters from a table and their padding, which is '=' or a character `Engine::new` has checked is
+        // printable ASCII.
+        encoded.push_str(unsafe { core::str::from_utf8_unchecked(chars) });
+    }
+    #[cfg(not(feature = "unsafe-fast"))]
+    encoded.push_str(core::str::from_utf8(chars).expect("encoded text is ASCII"));
+}
+
+/// Encodes a group of up to 3 bytes into 4 characters, padding a short group with zeros.
+#[inline]
+pub(crate) fn encode_group(table: &Table, chunk: &[u8]) -> [u8; 4] {
+    let mut buffer = [0u8; 3];
+    buffer[..chunk.len()].copy_from_slice(chunk);
+    [
+        table.encode(buffer[0] >> 2),
+        table.encode((buffer[0] << 4) | (buffer[1] >> 4)),
+        table.encode((buffer[1] << 2) | (buffer[2] >> 6)),
+        table.encode(buffer[2]),
+    ]
+}
+
+/// Encodes 6 bytes into 8 characters of the standard alphabet a word at a time, as `encode_group` does for each half.
+/// The 48 bits are spread into one 6-bit value per byte with shifts and masks, then the offset is added to every
+/// byte at once, with zero values moved up to '`'.
+#[inline]
+pub(crate) fn encode_standard_pair(input: &[u8; 6]) -> [u8; 8] {
+    let mut word = [0u8; 8];
+    word[2..].copy_from_slice(input);
+    let bits = u64::from_be_bytes(word);
+    // one group of 3 bytes in each half of the word, the first in the low half as it is written first
+    let halves = (bits >> 24) | ((bits & 0xFF_FFFF) << 32);
+    // within each half, move the four 6-bit values to their own bytes, first value lowest
+    let values = ((halves >> 18) & 0x0000_003F_0000_003F)
+        | ((halves >> 4) & 0x0000_3F00_0000_3F00)
+        | ((halves << 10) & 0x003F_0000_003F_0000)
+        | ((halves << 24) & 0x3F00_0000_3F00_0000);
+    // adding 63 to a value sets its 0x40 bit unless it was zero; no byte can carry into the next
+    let zeros = !(values + 0x3F3F_3F3F_3F3F_3F3F) & 0x4040_4040_4040_4040;
+    (values + 0x2020_2020_2020_2020 + zeros).to_le_bytes()
+}
+
+#[cfg(feature = "alloc")]
 #[inline]
 fn encoded_to_raw_len(encoded_len: usize) -> usize {
-    ((encoded_len + 3) / 4) * 3
+    encoded_len.div_ceil(4) * 3
 }
 
+#[cfg(feature = "alloc")]
 /// Decodes a string from uuencoded format back into a byte array.
 /// Mirrors uuencode. Will accept ' ' or '`' as 36. Will strip padding.
 /// Example:
@@ -96,43 +702,71 @@ fn encoded_to_raw_len(encoded_len: usize) -> usize {
 /// }
 /// ```
 pub fn uudecode(data: &[u8]) -> Result<Vec<u8>, UUEncodeError> {
+    #[cfg(feature = "metrics")]
+    if let Some(metrics) = metrics::installed() {
+        let result = decode_bare(data);
+        match &result {
+            Ok(decoded) => metrics.decoded(decoded.len() as u64, data.split(|&ch| ch == b'\n').filter(|line| !line.is_empty()).count() as u64),
+            Err(err) => metrics.error(err),
+        }
+        return result;
+    }
+    decode_bare(data)
+}
+
+#[cfg(feature = "alloc")]
+/// Decodes bare lines, as `uudecode`.
+fn decode_bare(data: &[u8]) -> Result<Vec<u8>, UUEncodeError> {
     // allocate a vec internally, then handle utf-8 conversion at the end. This avoids Unicode errors.
     let mut decoded = Vec::with_capacity(encoded_to_raw_len(data.len()));
     let mut buffer = [0u8; 4];
     let mut cur_line = 0;
 
-    let mut input_iter = data.into_iter();
+    let mut input = data;
     loop {
         let mut cur_input_char = 0;
         let mut cur_output_char = 0;
 
         // Decode the length of the line
-        let next_token = input_iter.next();
-        let output_char_count: usize = match next_token {
-            None => return Ok(decoded),
-            Some(ch) => {
-                ok_or_decode_error!(decode_char, *ch, cur_line, cur_input_char) as usize
-            },
+        let Some((&len_char, rest)) = input.split_first() else {
+            return Ok(decoded);
         };
-        // Decode the rest of the line
+        input = rest;
+        let output_char_count = ok_or_decode_error!(decode_char, len_char, cur_line, cur_input_char) as usize;
+        #[cfg(feature = "simd")]
+        if output_char_count == crate::simd::LINE
+            && let Some((chars, rest)) = input.split_first_chunk::<{ crate::simd::LINE_CHARS }>()
+        {
+            let start = decoded.len();
+            decoded.resize(start + crate::simd::LINE, 0);
+            let bytes = (&mut decoded[start..]).try_into().expect("resized to a full line");
+            if crate::simd::decode_standard_line(chars, bytes) {
+                input = rest.get(1..).unwrap_or_default(); // discard newline
+                cur_line += 1;
+                continue;
+            }
+            decoded.truncate(start);
+        }
+        // Decode the rest of the line, a group of 4 characters at a time
         loop {
-            let mut chunk = [0u8;4];
-            let input_chunk = input_iter.by_ref().take(4).copied().collect::<Vec<_>>();
-            chunk[..].copy_from_slice(&input_chunk);
+            let Some((&chunk, rest)) = input.split_first_chunk::<4>() else {
+                return Err(UUEncodeError::new(cur_line, 1 + cur_input_char + input.len(), Message::LineTooShort(output_char_count)));
+            };
+            input = rest;
 
             buffer[0] = ok_or_decode_error!(decode_char, chunk[0], cur_line, cur_input_char);
             buffer[1] = ok_or_decode_error!(decode_char, chunk[1], cur_line, cur_input_char+1);
             buffer[2] = ok_or_decode_error!(decode_char, chunk[2], cur_line, cur_input_char+2);
             buffer[3] = ok_or_decode_error!(decode_char, chunk[3], cur_line, cur_input_char+3);
             // assumes high bits are zero
-            decoded.push(((buffer[0] << 2) | (buffer[1] >> 4)).into());
+            decoded.push((buffer[0] << 2) | (buffer[1] >> 4));
             let byte2 = (buffer[1] << 4) | (buffer[2] >> 2);
             if cur_output_char+1 < output_char_count {
-                decoded.push(byte2.into());
+                decoded.push(byte2);
             }
             let byte3 = (buffer[2] << 6) | buffer[3];
             if cur_output_char+2 < output_char_count {
-                decoded.push(byte3.into());
+                decoded.push(byte3);
             }
 
             cur_output_char += 3;
@@ -141,35 +775,169 @@ pub fn uudecode(data: &[u8]) -> Result<Vec<u8>, UUEncodeError> {
                 break;
             }
         }
-        input_iter.next(); // discard newline
+        input = input.get(1..).unwrap_or_default(); // discard newline
         cur_line += 1;
     }
 }
 
+#[cfg(feature = "alloc")]
+/// Decodes uuencoded data line by line, using the given options.
+/// Unlike `uudecode`, lines may end with either LF or CRLF, and blank lines are skipped.
+/// If checksum verification is enabled, the error reports the first line whose checksum does not match.
+pub fn uudecode_with(data: &[u8], options: &DecodeOptions) -> Result<Vec<u8>, UUEncodeError> {
+    let mut decoded = Vec::with_capacity(encoded_to_raw_len(data.len()));
+    for (cur_line, line) in file::numbered_lines(data) {
+        let result = decode_line(line, cur_line, &options.table, options.verify_checksums, &mut decoded);
+        #[cfg(feature = "metrics")]
+        let result = metrics::check(result);
+        result?;
+    }
+    #[cfg(feature = "metrics")]
+    if let Some(metrics) = metrics::installed() {
+        metrics.decoded(decoded.len() as u64, file::numbered_lines(data).count() as u64);
+    }
+    Ok(decoded)
+}
+
+/// Decodes a single uuencoded line, without its trailing newline, appending the bytes to `decoded`, as `decode_line_to_slice`.
+#[cfg(feature = "alloc")]
+pub(crate) fn decode_line(line: &[u8], cur_line: usize, table: &Table, verify_checksum: bool, decoded: &mut Vec<u8>) -> Result<(), UUEncodeError> {
+    let start = decoded.len();
+    decoded.resize(start + MAX_LINE_DATA, 0);
+    let result = decode_line_to_slice(line, cur_line, table, verify_checksum, &mut decoded[start..]);
+    decoded.truncate(start + result.as_ref().map_or(0, |&len| len));
+    result.map(|_| ())
+}
+
+/// The most bytes one line can declare, as its length character holds a 6-bit value.
+#[cfg(feature = "alloc")]
+const MAX_LINE_DATA: usize = 63;
+
+/// Decodes a single uuencoded line, without its trailing newline, into the start of `decoded`, returning the number of bytes written.
+/// Characters beyond those needed for the declared length are ignored, unless `verify_checksum` is set,
+/// in which case the first of them must be the line's checksum. An empty line decodes to nothing.
+/// Fails if `decoded` is shorter than the declared length.
+pub(crate) fn decode_line_to_slice(line: &[u8], cur_line: usize, table: &Table, verify_checksum: bool, decoded: &mut [u8]) -> Result<usize, UUEncodeError> {
+    let decode_char = |ch| table.decode(ch);
+    let Some(&len_char) = line.first() else {
+        return Ok(0);
+    };
+    let output_char_count = ok_or_decode_error!(decode_char, len_char, cur_line, 0) as usize;
+    let group_count = output_char_count.div_ceil(3);
+    if line.len() - 1 < group_count * 4 {
+        return Err(UUEncodeError::new(cur_line, line.len(), Message::LineTooShort(output_char_count)));
+    }
+    let Some(decoded) = decoded.get_mut(..output_char_count) else {
+        return Err(UUEncodeError::capacity(cur_line));
+    };
+
+    // full lines go to the vector kernels, and anything they reject to the scalar code, to find the error
+    #[cfg(feature = "simd")]
+    let vectorised = match (<&[u8; crate::simd::LINE_CHARS]>::try_from(&line[1..1 + group_count * 4]), <&mut [u8; crate::simd::LINE]>::try_from(&mut *decoded)) {
+        (Ok(chars), Ok(bytes)) => table.decodes_standard() && crate::simd::decode_standard_line(chars, bytes),
+        _ => false,
+    };
+    #[cfg(not(feature = "simd"))]
+    let vectorised = false;
+
+    // the groups which fill all 3 bytes, then any short last group
+    let groups = if vectorised { 0 } else { group_count };
+    let full = groups.min(decoded.len() / 3);
+    let (full_chars, last_chars) = line[1..1 + groups * 4].split_at(full * 4);
+    let (full_bytes, last_bytes) = decoded.split_at_mut(full * 3);
+    for (group_idx, (chunk, out)) in full_chars.chunks_exact(4).zip(full_bytes.chunks_exact_mut(3)).enumerate() {
+        out.copy_from_slice(&pack_group(group_values(chunk, table, cur_line, 1 + group_idx * 4)?));
+    }
+    if !last_chars.is_empty() {
+        let len = last_bytes.len();
+        last_bytes.copy_from_slice(&pack_group(group_values(last_chars, table, cur_line, 1 + full * 4)?)[..len]);
+    }
+
+    if verify_checksum && output_char_count > 0 {
+        let checksum_char = 1 + group_count * 4;
+        let Some(&ch) = line.get(checksum_char) else {
+            return Err(UUEncodeError::new(cur_line, checksum_char, "Missing line checksum"));
+        };
+        if ok_or_decode_error!(decode_char, ch, cur_line, checksum_char) != line_checksum(decoded) {
+            return Err(UUEncodeError::new(cur_line, checksum_char, "Line checksum mismatch"));
+        }
+    }
+    Ok(output_char_count)
+}
+
+/// Decodes the 4 characters of a group, starting at `cur_char`, into their 6-bit values.
+#[inline]
+fn group_values(chunk: &[u8], table: &Table, cur_line: usize, cur_char: usize) -> Result<[u8; 4], UUEncodeError> {
+    let decode_char = |ch| table.decode(ch);
+    let mut values = [0u8; 4];
+    for (idx, (value, &ch)) in values.iter_mut().zip(chunk).enumerate() {
+        *value = ok_or_decode_error!(decode_char, ch, cur_line, cur_char + idx);
+    }
+    Ok(values)
+}
+
+/// Packs the 6-bit values of a group of 4 characters into the 3 bytes they encode.
+#[inline]
+fn pack_group(values: [u8; 4]) -> [u8; 3] {
+    [(values[0] << 2) | (values[1] >> 4), (values[1] << 4) | (values[2] >> 2), (values[2] << 6) | values[3]]
+}
+
 /// Encodes a 6-bit value into a UUEncoded character.
 /// Returns None if input is outside of target range.
 #[inline]
 pub fn encode_char(value: u8) -> Option<u8> {
-    if value == 0 {
-        Some(b'`')
-    } else {
-        value.checked_add(32)
-    }
+    Table::STANDARD.encode_checked(value)
 }
 
 /// Decodes a UUEncoded character into a 6-bit value.
+/// Returns None if the character is not part of the standard alphabet.
 #[inline]
-pub fn decode_char(value: u8) -> Option<u8> {
-    if value == b'`' || value == 0 {
-        Some(0)
-    } else {
-        value.checked_sub(32)
-    }
+pub const fn decode_char(value: u8) -> Option<u8> {
+    Table::STANDARD.decode(value)
 }
 
-#[cfg(test)]
+#[cfg(all(test, feature = "alloc"))]
 mod tests {
     use super::*;
+    use alloc::format;
+    use alloc::string::ToString;
+    use core::alloc::{GlobalAlloc, Layout};
+    use core::cell::Cell;
+
+    /// Counts the allocations made on each thread, so that tests running in parallel don't see each other's.
+    struct CountingAllocator;
+
+    std::thread_local! {
+        static ALLOCATIONS: Cell<usize> = const { Cell::new(0) };
+    }
+
+    // SAFETY: every call is passed straight on to the system allocator
+    unsafe impl GlobalAlloc for CountingAllocator {
+        unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+            let _ = ALLOCATIONS.try_with(|count| count.set(count.get() + 1));
+            unsafe { std::alloc::System.alloc(layout) }
+        }
+
+        unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+            unsafe { std::alloc::System.dealloc(ptr, layout) }
+        }
+
+        unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+            let _ = ALLOCATIONS.try_with(|count| count.set(count.get() + 1));
+            unsafe { std::alloc::System.realloc(ptr, layout, new_size) }
+        }
+    }
+
+    #[global_allocator]
+    static ALLOCATOR: CountingAllocator = CountingAllocator;
+
+    /// Runs `f`, returning its result and the number of allocations and reallocations it made on this thread.
+    #[cfg(feature = "std")]
+    pub(crate) fn count_allocations<T>(f: impl FnOnce() -> T) -> (T, usize) {
+        let before = ALLOCATIONS.with(Cell::get);
+        let result = f();
+        (result, ALLOCATIONS.with(Cell::get) - before)
+    }
 
     // Test cases generated with `echo -n ITEM | uuencode -r -`
 
@@ -186,7 +954,7 @@ mod tests {
     fn test_the_machine_stops() {
         // Do *not* include these in the binary, it'll grow our binary by nearly *a megabyte*
         let source_data = std::fs::read("test_data/the_machine_stops.txt").expect("Can open test data");
-        let expected_data = std::fs::read_to_string(&"test_data/the_machine_stops.txt.uu").expect("Can open test data").trim_end().to_string();
+        let expected_data = std::fs::read_to_string("test_data/the_machine_stops.txt.uu").expect("Can open test data").trim_end().to_string();
         let actual = uuencode(&source_data).unwrap();
         assert_eq!(actual, expected_data, "can uuencode a large text");
     }
@@ -195,9 +963,28 @@ mod tests {
     #[test]
     fn test_random_data() {
         let source_data = std::fs::read("test_data/random_data.bin").expect("Can open test data");
-        let expected_data = std::fs::read_to_string(&"test_data/random_data.bin.uu").expect("Can open test data").trim_end().to_string();
+        let expected_data = std::fs::read_to_string("test_data/random_data.bin.uu").expect("Can open test data").trim_end().to_string();
         let actual = uuencode(&source_data).unwrap();
         assert_eq!(actual, expected_data, "can uuencode random data");
+        assert_eq!(actual.capacity(), actual.len(), "allocates exactly the space needed");
+    }
+
+    /// Tests that encoded lines match the lines of the encoded string
+    #[test]
+    fn test_uuencode_lines() {
+        let source_data = std::fs::read("test_data/random_data.bin").expect("Can open test data");
+        let options = EncodeOptions::new().line_checksums(true);
+        let encoded = uuencode_with(&source_data, &options).unwrap();
+        let lines: Vec<String> = uuencode_lines_with(&source_data, &options).collect();
+        assert_eq!(lines, encoded.lines().collect::<Vec<_>>(), "yields each encoded line");
+        assert_eq!(uuencode_lines(b"").count(), 0, "yields nothing for empty data");
+
+        let mut joined = Vec::new();
+        uuencode_for_each_with(&source_data, &options, |line| {
+            joined.extend_from_slice(line);
+            joined.push(b'\n');
+        });
+        assert_eq!(joined, format!("{}\n", encoded).as_bytes(), "calls back with each encoded line");
     }
 
     /// Tests decoding cat
@@ -226,6 +1013,102 @@ mod tests {
         assert_eq!(String::from_utf8_lossy(&encoded), "ork,\n1968).\n25", "can uudecode a small text");
     }
 
+    /// Tests that the word-at-a-time encoder agrees with encoding each group in turn
+    #[test]
+    fn test_encode_standard_pair() {
+        let mut state = 0x2545_F491_4F6C_DD1Du64;
+        for round in 0..10_000 {
+            // every value from 0 to 63 in every position, then pseudo-random input
+            let input: [u8; 6] = if round < 64 {
+                let value = round as u8;
+                let group = [(value << 2) | (value >> 4), (value << 4) | (value >> 2), (value << 6) | value];
+                [group, group].concat().try_into().unwrap()
+            } else {
+                state ^= state << 13;
+                state ^= state >> 7;
+                state ^= state << 17;
+                state.to_le_bytes()[..6].try_into().unwrap()
+            };
+            let expected = [encode_group(&Table::STANDARD, &input[..3]), encode_group(&Table::STANDARD, &input[3..])].concat();
+            assert_eq!(encode_standard_pair(&input)[..], expected[..], "encodes {:?}", input);
+        }
+    }
+
+    /// Tests that input ending part way through a group is an error rather than a panic
+    #[test]
+    fn test_decode_truncated() {
+        let err = uudecode(b"#8V").unwrap_err();
+        assert_eq!((err.line(), err.character(), err.message().to_string()), (0, 3, "Line too short for declared length 3".to_string()));
+        assert_eq!(uudecode(b"#8V%T\nM86").unwrap_err().line(), 1, "reports the line that was cut short");
+    }
+
+    /// The alphabets the property tests encode with
+    fn any_table() -> impl proptest::strategy::Strategy<Value = Table> {
+        proptest::sample::select(alloc::vec![Table::STANDARD, Table::XX, Table::BASE64, Table::BASE64_URL])
+    }
+
+    proptest::proptest! {
+        /// Tests that any data decodes back to itself, with any table and with or without checksums, through owned
+        /// buffers and through slices
+        #[test]
+        fn test_proptest_round_trip(data in proptest::collection::vec(proptest::prelude::any::<u8>(), 0..1024), table in any_table(), line_checksums: bool) {
+            let options = EncodeOptions::new().table(table.clone()).line_checksums(line_checksums);
+            let decode_options = DecodeOptions::new().table(table).verify_checksums(line_checksums);
+            let encoded = uuencode_with(&data, &options).unwrap();
+            proptest::prop_assert_eq!(uudecode_with(encoded.as_bytes(), &decode_options).unwrap(), data.clone());
+
+            let mut encoded = alloc::vec![0u8; uuencoded_len(data.len(), &options)];
+            let written = uuencode_to_slice_with(&data, &options, &mut encoded).unwrap();
+            proptest::prop_assert_eq!(written, encoded.len());
+            let mut decoded = alloc::vec![0u8; data.len()];
+            let read = uudecode_to_slice_with(&encoded, &decode_options, &mut decoded).unwrap();
+            proptest::prop_assert_eq!(&decoded[..read], &data[..]);
+        }
+
+        /// Tests that decoding arbitrary input, into an owned buffer or a slice of any size, returns rather than panics
+        #[test]
+        fn test_proptest_decode_arbitrary(data in proptest::collection::vec(proptest::prelude::any::<u8>(), 0..512), table in any_table(), verify_checksums: bool, output_len in 0usize..512) {
+            let options = DecodeOptions::new().table(table).verify_checksums(verify_checksums);
+            let _ = uudecode(&data);
+            let _ = uudecode_with(&data, &options);
+            let mut output = alloc::vec![0u8; output_len];
+            let _ = uudecode_to_slice_with(&data, &options, &mut output);
+        }
+    }
+
+    /// Tests that an invalid character is reported where it is, in a full group or a short last one
+    #[test]
+    fn test_decode_invalid_position() {
+        let encoded = uuencode(b"catdog!").unwrap();
+        for position in 1..encoded.len() {
+            let mut corrupt = encoded.clone().into_bytes();
+            corrupt[position] = 0x01;
+            let err = uudecode_with(&corrupt, &DecodeOptions::new()).unwrap_err();
+            assert_eq!((err.line(), err.character()), (0, position), "reports the character at {}", position);
+        }
+    }
+
+    /// Tests emitting and verifying per-line checksums
+    #[test]
+    fn test_line_checksums() {
+        let encoded = uuencode_with(b"cat", &EncodeOptions::new().line_checksums(true)).unwrap();
+        // 'c' + 'a' + 't' = 0x138, which is 56 modulo 64
+        assert_eq!(encoded, "#8V%TX", "can append a line checksum");
+        let options = DecodeOptions::new().verify_checksums(true);
+        assert_eq!(uudecode_with(encoded.as_bytes(), &options).unwrap(), b"cat", "can verify a line checksum");
+        assert_eq!(uudecode_with(encoded.as_bytes(), &DecodeOptions::new()).unwrap(), b"cat", "ignores checksums by default");
+
+        let source_data = std::fs::read("test_data/random_data.bin").expect("Can open test data");
+        let mut encoded = uuencode_with(&source_data, &EncodeOptions::new().line_checksums(true)).unwrap();
+        assert_eq!(uudecode_with(encoded.as_bytes(), &options).unwrap(), source_data, "can round-trip with line checksums");
+        // corrupt the third line, in a way that still decodes
+        let third_line = encoded.match_indices('\n').nth(1).unwrap().0 + 1;
+        encoded.replace_range(third_line + 5..third_line + 6, if &encoded[third_line + 5..third_line + 6] == "!" { "\"" } else { "!" });
+        let err = uudecode_with(encoded.as_bytes(), &options).unwrap_err();
+        assert_eq!((err.line, err.msg.to_string().as_str()), (2, "Line checksum mismatch"), "reports the first mismatching line");
+        assert!(uudecode_with(b"#8V%T", &options).is_err(), "requires a checksum when verifying");
+    }
+
     /// Tests round-trip execution
     #[test]
     fn test_rt() {
@@ -235,4 +1118,70 @@ mod tests {
         let decoded = uudecode(encoded.as_bytes()).unwrap();
         assert_eq!(String::from_utf8_lossy(&decoded), source_as_string, "can uuencode and uudecode");
     }
+
+    /// Tests that errors can be logged through defmt
+    #[cfg(feature = "defmt")]
+    #[test]
+    fn test_defmt_format() {
+        fn assert_format<T: defmt::Format>() {}
+        assert_format::<UUEncodeError>();
+        assert_format::<ErrorKind>();
+    }
+
+    /// Tests sending errors between processes as JSON
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_error_serde() {
+        let err = uudecode(b"#8V\x01T").unwrap_err();
+        let json = serde_json::to_string(&err).unwrap();
+        assert_eq!(json, r#"{"line":0,"character":2,"message":"Invalid character in input: \u0001","kind":"Invalid"}"#, "serializes the fields");
+        let received: UUEncodeError = serde_json::from_str(&json).unwrap();
+        assert_eq!((received.line, received.character, received.kind()), (0, 2, ErrorKind::Invalid), "deserializes the fields");
+        assert_eq!(received.to_string(), err.to_string(), "keeps the message");
+    }
+}
+
+#[cfg(kani)]
+mod proofs {
+    use super::*;
+
+    /// Proves that every 6-bit value encodes to a character from '!' to '`', and that no other value encodes
+    #[kani::proof]
+    fn proof_encode_char_range() {
+        let value: u8 = kani::any();
+        match encode_char(value) {
+            Some(ch) => assert!(value < 64 && (b'!'..=b'`').contains(&ch)),
+            None => assert!(value >= 64),
+        }
+    }
+
+    /// Proves that decoding inverts encoding for every 6-bit value, with the character functions and every
+    /// predefined table
+    #[kani::proof]
+    fn proof_decode_encode_identity() {
+        let value: u8 = kani::any_where(|value| *value < 64);
+        assert_eq!(encode_char(value).and_then(decode_char), Some(value));
+        for table in [Table::STANDARD, Table::XX, Table::BASE64, Table::BASE64_URL] {
+            assert_eq!(table.decode(table.encode(value)), Some(value));
+        }
+    }
+
+    /// Proves that a group of any 3 bytes decodes back to itself, with every predefined table
+    #[kani::proof]
+    fn proof_group_round_trip() {
+        let bytes: [u8; 3] = kani::any();
+        for table in [Table::STANDARD, Table::XX, Table::BASE64, Table::BASE64_URL] {
+            let values = group_values(&encode_group(&table, &bytes), &table, 0, 0);
+            assert!(values.is_ok_and(|values| pack_group(values) == bytes));
+        }
+    }
+
+    /// Proves that the word-at-a-time encoder agrees with `encode_group` for every 6 bytes
+    #[kani::proof]
+    fn proof_encode_standard_pair() {
+        let input: [u8; 6] = kani::any();
+        let encoded = encode_standard_pair(&input);
+        assert_eq!(encoded[..4], encode_group(&Table::STANDARD, &input[..3]));
+        assert_eq!(encoded[4..], encode_group(&Table::STANDARD, &input[3..]));
+    }
 }
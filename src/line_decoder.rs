@@ -0,0 +1,85 @@
+use alloc::vec::Vec;
+use crate::{decode_line, DecodeOptions, UUEncodeError};
+
+/// Decodes bare uuencoded lines, as `uudecode_with`, one at a time into a reusable buffer which is lent out
+/// for each line, so the buffer is the only allocation however much input is decoded.
+/// Lines may end with either LF or CRLF, and blank lines are skipped. Nothing more is decoded after an error.
+/// Example:
+/// ```rust
+/// fn decode() -> Result<(), uuencode_lite::UUEncodeError> {
+///     let mut decoder = uuencode_lite::LineDecoder::new(b"#8V%T\r\n\n#9&]G\n", &uuencode_lite::DecodeOptions::new());
+///     assert_eq!(decoder.next_line().transpose()?, Some(&b"cat"[..]));
+///     assert_eq!(decoder.next_line().transpose()?, Some(&b"dog"[..]));
+///     assert_eq!(decoder.next_line().transpose()?, None);
+///     Ok(())
+/// }
+/// ```
+#[derive(Debug, Clone)]
+pub struct LineDecoder<'a> {
+    /// The input not yet decoded.
+    input: &'a [u8],
+    options: DecodeOptions,
+    /// The number of the next input line.
+    cur_line: usize,
+    /// The decoded bytes of the current line.
+    scratch: Vec<u8>,
+}
+
+impl<'a> LineDecoder<'a> {
+    /// Creates a decoder for the input with the given options.
+    pub fn new(input: &'a [u8], options: &DecodeOptions) -> Self {
+        LineDecoder { input, options: options.clone(), cur_line: 0, scratch: Vec::with_capacity(64) }
+    }
+
+    /// Decodes the next non-blank line, lending its decoded bytes until the next call.
+    /// Returns `None` once the input is exhausted, or after an error.
+    pub fn next_line(&mut self) -> Option<Result<&[u8], UUEncodeError>> {
+        loop {
+            if self.input.is_empty() {
+                return None;
+            }
+            let (line, rest) = match self.input.iter().position(|&ch| ch == b'\n') {
+                Some(len) => (&self.input[..len], &self.input[len + 1..]),
+                None => (self.input, &self.input[self.input.len()..]),
+            };
+            self.input = rest;
+            let cur_line = self.cur_line;
+            self.cur_line += 1;
+            let line = line.strip_suffix(b"\r").unwrap_or(line);
+            if line.is_empty() {
+                continue;
+            }
+            self.scratch.clear();
+            if let Err(err) = decode_line(line, cur_line, &self.options.table, self.options.verify_checksums, &mut self.scratch) {
+                self.input = &[];
+                return Some(Err(err));
+            }
+            return Some(Ok(&self.scratch));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::string::String;
+
+    /// Tests that the lent lines join up to the decoded data
+    #[test]
+    fn test_line_decoder() {
+        let source_data = std::fs::read("test_data/the_machine_stops.txt").expect("Can open test data");
+        let encoded = std::fs::read("test_data/the_machine_stops.txt.uu").expect("Can open test data");
+        let mut decoder = LineDecoder::new(&encoded, &DecodeOptions::new());
+        let mut decoded = Vec::new();
+        while let Some(line) = decoder.next_line() {
+            decoded.extend_from_slice(line.unwrap());
+        }
+        assert_eq!(String::from_utf8_lossy(&decoded), String::from_utf8_lossy(&source_data).trim_end(), "can decode line by line");
+
+        let mut decoder = LineDecoder::new(b"#8V%T\n#8V\x01T\n#8V%T\n", &DecodeOptions::new());
+        assert!(decoder.next_line().unwrap().is_ok(), "decodes the lines before an error");
+        let err = decoder.next_line().unwrap().unwrap_err();
+        assert_eq!((err.line, err.character), (1, 3), "reports the position of an invalid character");
+        assert!(decoder.next_line().is_none(), "stops after an error");
+    }
+}
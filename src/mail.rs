@@ -0,0 +1,93 @@
+use crate::{decode_all, is_safe_file_name, uudecode_with, DecodeOptions, DecodedFile, UUEncodeError};
+use mail_parser::{Message, MessageParser, MimeHeaders, PartType};
+use std::format;
+use std::string::ToString;
+use std::vec::Vec;
+
+/// The `Content-Transfer-Encoding` values mailers use for uuencoded parts.
+const UUENCODE_ENCODINGS: [&str; 4] = ["x-uuencode", "x-uue", "uuencode", "x-uu"];
+
+/// The mode given to files from `x-uuencode` parts without a `begin` line.
+const DEFAULT_MODE: u32 = 0o644;
+
+/// Extracts every uuencoded attachment from a raw RFC 822 or MIME message. Text parts are scanned for embedded
+/// files, as `decode_all` does, after any quoted-printable or Base64 transfer encoding is undone. Parts with an
+/// `x-uuencode` transfer encoding are decoded whole; if they have no `begin` line, the file is named from the part's
+/// `Content-Disposition` or `Content-Type` name, and given mode `644`. Attached `message/rfc822` parts are searched
+/// too. Each file is returned in the order it appears, either decoded or with the error which stopped it.
+/// Example:
+/// ```rust
+/// let raw = b"From: a@example.com\r\nSubject: cat\r\n\r\nHere it is:\r\nbegin 644 cat.txt\r\n#8V%T\r\n`\r\nend\r\n";
+/// let files = uuencode_lite::extract_mail_attachments(raw, &uuencode_lite::DecodeOptions::new());
+/// assert_eq!(files[0].as_ref().unwrap().name, "cat.txt");
+/// ```
+pub fn extract_mail_attachments(raw: &[u8], options: &DecodeOptions) -> Vec<Result<DecodedFile, UUEncodeError>> {
+    let mut files = Vec::new();
+    if let Some(message) = MessageParser::default().parse(raw) {
+        extract_from_message(&message, options, &mut files);
+    }
+    files
+}
+
+fn extract_from_message(message: &Message<'_>, options: &DecodeOptions, files: &mut Vec<Result<DecodedFile, UUEncodeError>>) {
+    for part in &message.parts {
+        let is_uuencoded = part.content_transfer_encoding().is_some_and(|encoding| UUENCODE_ENCODINGS.iter().any(|uu| encoding.eq_ignore_ascii_case(uu)));
+        match &part.body {
+            PartType::Message(nested) => extract_from_message(nested, options, files),
+            _ if is_uuencoded => {
+                let found = decode_all(part.contents(), options);
+                if found.is_empty() {
+                    files.push(decode_bare_part(part.contents(), part.attachment_name(), options));
+                } else {
+                    files.extend(found);
+                }
+            }
+            PartType::Text(text) => files.extend(decode_all(text.as_bytes(), options)),
+            _ => {}
+        }
+    }
+}
+
+/// Decodes an `x-uuencode` part which holds bare lines, naming it from the part's headers.
+fn decode_bare_part(body: &[u8], name: Option<&str>, options: &DecodeOptions) -> Result<DecodedFile, UUEncodeError> {
+    let Some(name) = name else {
+        return Err(UUEncodeError::new(0, 0, "Missing file name for x-uuencode part"));
+    };
+    if !options.raw_names && !is_safe_file_name(name) {
+        return Err(UUEncodeError::new(0, 0, format!("Unsafe file name in part headers: {:?}", name)));
+    }
+    let data = uudecode_with(body.trim_ascii_end(), options)?;
+    Ok(DecodedFile { name: name.to_string(), mode: DEFAULT_MODE, data })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{uuencode, uuencode_file, FileOptions};
+
+    /// Tests finding files in text parts, x-uuencode parts with and without begin lines, and attached messages
+    #[test]
+    fn test_mail_attachments() {
+        let source_data = std::fs::read("test_data/random_data.bin").expect("Can open test data");
+        let framed = uuencode_file(&source_data, &FileOptions::new("random_data.bin")).unwrap();
+        let bare = uuencode(b"dog").unwrap();
+        let raw = format!(
+            "From: a@example.com\r\nSubject: files\r\nMIME-Version: 1.0\r\nContent-Type: multipart/mixed; boundary=\"b1\"\r\n\r\n\
+             --b1\r\nContent-Type: text/plain\r\nContent-Transfer-Encoding: quoted-printable\r\n\r\nSee below=3D\r\nbegin 644 cat.txt\r\n#8V%T\r\n`\r\nend\r\n\r\n\
+             --b1\r\nContent-Type: application/octet-stream\r\nContent-Transfer-Encoding: x-uuencode\r\n\r\n{}\r\n\
+             --b1\r\nContent-Type: application/octet-stream; name=\"dog.txt\"\r\nContent-Transfer-Encoding: X-UUE\r\n\r\n{}\r\n\
+             --b1\r\nContent-Type: application/octet-stream\r\nContent-Transfer-Encoding: x-uuencode\r\n\r\n{}\r\n\
+             --b1\r\nContent-Type: message/rfc822\r\n\r\nSubject: forwarded\r\n\r\nbegin 600 fwd.txt\r\n#9&]G\r\n`\r\nend\r\n\r\n\
+             --b1--\r\n",
+            framed, bare, bare
+        );
+        let files = extract_mail_attachments(raw.as_bytes(), &DecodeOptions::new());
+        assert_eq!(files.len(), 5, "finds every attachment");
+        assert_eq!(files[0].as_ref().unwrap(), &DecodedFile { name: "cat.txt".to_string(), mode: 0o644, data: b"cat".to_vec() }, "decodes a file in a text part");
+        assert_eq!(files[1].as_ref().unwrap().data, source_data, "decodes an x-uuencode part");
+        assert_eq!(files[2].as_ref().unwrap(), &DecodedFile { name: "dog.txt".to_string(), mode: 0o644, data: b"dog".to_vec() }, "names a bare part from its headers");
+        assert!(files[3].is_err(), "reports a bare part without a name");
+        assert_eq!(files[4].as_ref().unwrap().name, "fwd.txt", "searches attached messages");
+        assert!(extract_mail_attachments(b"Subject: nothing\r\n\r\nNo files here.\r\n", &DecodeOptions::new()).is_empty(), "finds nothing in plain text");
+    }
+}
@@ -0,0 +1,201 @@
+use crate::{Repair, UUEncodeError};
+use std::sync::OnceLock;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+/// Receives counts from the codec, for charting its health in long-running services. Install one with
+/// `set_metrics`. Every method does nothing by default, so only the counts of interest need implementing.
+/// Counts are reported as they happen, from whichever thread is encoding or decoding, so implementations should
+/// be cheap and must not block; atomic counters, as in `Counters`, or the `metrics` crate's macros are suitable.
+/// Uuencoded files (with `begin` or `begin-base64` framing), bare uuencoded lines, and all of the streaming
+/// adapters over them are counted; the other encodings are not.
+pub trait Metrics: Send + Sync {
+    /// Data was encoded into the given number of lines of text, including framing lines.
+    fn encoded(&self, bytes: u64, lines: u64) {
+        let _ = (bytes, lines);
+    }
+
+    /// Lines of text were decoded into the given number of bytes, including framing lines which produce none.
+    fn decoded(&self, bytes: u64, lines: u64) {
+        let _ = (bytes, lines);
+    }
+
+    /// Encoding or decoding failed, or was cancelled.
+    fn error(&self, error: &UUEncodeError) {
+        let _ = error;
+    }
+
+    /// `repair` fixed a line.
+    fn repaired(&self, repair: &Repair) {
+        let _ = repair;
+    }
+}
+
+static METRICS: OnceLock<&'static dyn Metrics> = OnceLock::new();
+
+/// Installs the receiver for the codec's counts, for the rest of the process. Only one can be installed;
+/// returns `false`, leaving the first in place, if one already was.
+/// Example:
+/// ```rust
+/// static COUNTERS: uuencode_lite::Counters = uuencode_lite::Counters::new();
+/// uuencode_lite::set_metrics(&COUNTERS);
+/// uuencode_lite::uudecode_file(b"begin 644 cat.txt\n#8V%T\n`\nend\n").unwrap();
+/// let counts = COUNTERS.snapshot();
+/// assert_eq!((counts.bytes_decoded, counts.lines_decoded), (3, 4));
+/// ```
+pub fn set_metrics(metrics: &'static dyn Metrics) -> bool {
+    METRICS.set(metrics).is_ok()
+}
+
+/// The installed receiver, if any.
+#[inline]
+pub(crate) fn installed() -> Option<&'static dyn Metrics> {
+    METRICS.get().copied()
+}
+
+/// Counts the error, if the result is one, and passes the result on.
+#[inline]
+pub(crate) fn check<T>(result: Result<T, UUEncodeError>) -> Result<T, UUEncodeError> {
+    if let (Err(err), Some(metrics)) = (&result, installed()) {
+        metrics.error(err);
+    }
+    result
+}
+
+/// The totals kept by `Counters`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct MetricsSnapshot {
+    /// The number of bytes encoded.
+    pub bytes_encoded: u64,
+    /// The number of lines of text encoded.
+    pub lines_encoded: u64,
+    /// The number of bytes decoded.
+    pub bytes_decoded: u64,
+    /// The number of lines of text decoded.
+    pub lines_decoded: u64,
+    /// The number of failed or cancelled operations.
+    pub errors: u64,
+    /// The number of lines fixed by `repair`.
+    pub repairs: u64,
+}
+
+/// A `Metrics` implementation which keeps running totals in atomic counters, for services which poll them.
+#[derive(Debug, Default)]
+pub struct Counters {
+    bytes_encoded: AtomicU64,
+    lines_encoded: AtomicU64,
+    bytes_decoded: AtomicU64,
+    lines_decoded: AtomicU64,
+    errors: AtomicU64,
+    repairs: AtomicU64,
+}
+
+impl Counters {
+    /// Creates counters starting at zero. This is `const`, so counters can be kept in a `static`.
+    pub const fn new() -> Self {
+        Counters {
+            bytes_encoded: AtomicU64::new(0),
+            lines_encoded: AtomicU64::new(0),
+            bytes_decoded: AtomicU64::new(0),
+            lines_decoded: AtomicU64::new(0),
+            errors: AtomicU64::new(0),
+            repairs: AtomicU64::new(0),
+        }
+    }
+
+    /// The current totals. Each is read separately, so totals changing during the call may be from either side of it.
+    pub fn snapshot(&self) -> MetricsSnapshot {
+        MetricsSnapshot {
+            bytes_encoded: self.bytes_encoded.load(Ordering::Relaxed),
+            lines_encoded: self.lines_encoded.load(Ordering::Relaxed),
+            bytes_decoded: self.bytes_decoded.load(Ordering::Relaxed),
+            lines_decoded: self.lines_decoded.load(Ordering::Relaxed),
+            errors: self.errors.load(Ordering::Relaxed),
+            repairs: self.repairs.load(Ordering::Relaxed),
+        }
+    }
+}
+
+impl Metrics for Counters {
+    fn encoded(&self, bytes: u64, lines: u64) {
+        self.bytes_encoded.fetch_add(bytes, Ordering::Relaxed);
+        self.lines_encoded.fetch_add(lines, Ordering::Relaxed);
+    }
+
+    fn decoded(&self, bytes: u64, lines: u64) {
+        self.bytes_decoded.fetch_add(bytes, Ordering::Relaxed);
+        self.lines_decoded.fetch_add(lines, Ordering::Relaxed);
+    }
+
+    fn error(&self, _: &UUEncodeError) {
+        self.errors.fetch_add(1, Ordering::Relaxed);
+    }
+
+    fn repaired(&self, _: &Repair) {
+        self.repairs.fetch_add(1, Ordering::Relaxed);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{repair, uudecode, uudecode_file, uuencode, uuencode_file, DecodeOptions, DecodeReader, EncodeWriter, FileOptions};
+    use std::io::{Read, Write};
+    use std::vec::Vec;
+
+    std::thread_local! {
+        /// The counts from this thread, so tests running the codec on other threads do not disturb them.
+        static COUNTS: Counters = const { Counters::new() };
+    }
+
+    /// Forwards counts to the counters of the thread they come from.
+    struct PerThread;
+
+    impl Metrics for PerThread {
+        fn encoded(&self, bytes: u64, lines: u64) {
+            COUNTS.with(|counts| counts.encoded(bytes, lines));
+        }
+
+        fn decoded(&self, bytes: u64, lines: u64) {
+            COUNTS.with(|counts| counts.decoded(bytes, lines));
+        }
+
+        fn error(&self, error: &UUEncodeError) {
+            COUNTS.with(|counts| counts.error(error));
+        }
+
+        fn repaired(&self, repair: &Repair) {
+            COUNTS.with(|counts| counts.repaired(repair));
+        }
+    }
+
+    /// Tests the counts from one-shot and streaming encoding and decoding, errors and repairs
+    #[test]
+    fn test_metrics() {
+        static PER_THREAD: PerThread = PerThread;
+        static UNUSED: Counters = Counters::new();
+        assert!(set_metrics(&PER_THREAD), "installs the first receiver");
+        assert!(!set_metrics(&UNUSED), "keeps the first receiver");
+        let counts = || COUNTS.with(Counters::snapshot);
+
+        let source_data = std::fs::read("test_data/random_data.bin").expect("Can open test data");
+        let encoded = uuencode_file(&source_data, &FileOptions::new("random_data.bin")).unwrap();
+        let lines = encoded.lines().count() as u64;
+        assert_eq!(counts(), MetricsSnapshot { bytes_encoded: 2048, lines_encoded: lines, ..Default::default() }, "counts a file");
+
+        let mut writer = EncodeWriter::new(Vec::new(), FileOptions::new("random_data.bin")).unwrap();
+        for chunk in source_data.chunks(100) {
+            writer.write_all(chunk).unwrap();
+        }
+        writer.finish().unwrap();
+        let mut decoded = Vec::new();
+        DecodeReader::new(encoded.as_bytes(), DecodeOptions::new()).read_to_end(&mut decoded).unwrap();
+        uudecode_file(encoded.as_bytes()).unwrap();
+        assert_eq!(counts(), MetricsSnapshot { bytes_encoded: 4096, lines_encoded: 2 * lines, bytes_decoded: 4096, lines_decoded: 2 * lines, ..Default::default() }, "counts streams");
+
+        uudecode(uuencode(b"cat").unwrap().as_bytes()).unwrap();
+        assert!(uudecode_file(b"begin 644 cat.txt\n#8V%T\n").is_err());
+        assert!(uudecode(b"#8\x01%T").is_err());
+        let (_, report) = repair("> #8V%T\n> `");
+        assert_eq!(counts(), MetricsSnapshot { bytes_encoded: 4099, lines_encoded: 2 * lines + 1, bytes_decoded: 4102, lines_decoded: 2 * lines + 4, errors: 2, repairs: report.len() as u64 }, "counts bare lines, errors and repairs");
+    }
+}
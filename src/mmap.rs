@@ -0,0 +1,135 @@
+use crate::{EncodeReader, FileOptions};
+use std::fs::File;
+use std::io::{self, BufReader, Read};
+use std::path::Path;
+
+/// A file opened for encoding: memory-mapped where the platform and the file allow, so that its pages are read
+/// straight from the page cache, and read through a buffer otherwise, as for pipes and other special files.
+#[derive(Debug)]
+pub struct MappedFile {
+    source: Source,
+}
+
+#[derive(Debug)]
+enum Source {
+    Mapped { map: memmap2::Mmap, position: usize },
+    Buffered(BufReader<File>),
+}
+
+impl MappedFile {
+    /// Opens the file at `path`, mapping it if possible.
+    /// # Safety
+    /// The file must not be truncated or modified, by this process or any other, until the `MappedFile` is dropped.
+    /// Reading a mapped page which another process has truncated away raises `SIGBUS`, and a mapping changed under
+    /// a reader breaks the guarantee that a shared slice does not change, so this can't be checked here.
+    pub unsafe fn open(path: impl AsRef<Path>) -> io::Result<Self> {
+        let file = File::open(path)?;
+        // SAFETY: the caller guarantees that the file is left alone while it is mapped.
+        match unsafe { memmap2::Mmap::map(&file) } {
+            Ok(map) => {
+                #[cfg(unix)]
+                let _ = map.advise(memmap2::Advice::Sequential);
+                Ok(MappedFile { source: Source::Mapped { map, position: 0 } })
+            }
+            Err(_) => Ok(MappedFile::buffered(file)),
+        }
+    }
+
+    /// Opens the file at `path` to be read through a buffer, without mapping it, which is safe whatever happens to
+    /// the file while it is read.
+    pub fn open_buffered(path: impl AsRef<Path>) -> io::Result<Self> {
+        Ok(MappedFile::buffered(File::open(path)?))
+    }
+
+    /// Reads the file through a buffer, without mapping it.
+    fn buffered(file: File) -> Self {
+        MappedFile { source: Source::Buffered(BufReader::with_capacity(crate::stream::READ_CHUNK, file)) }
+    }
+
+    /// Whether the file is memory-mapped, rather than read through a buffer.
+    pub fn is_mapped(&self) -> bool {
+        matches!(self.source, Source::Mapped { .. })
+    }
+}
+
+impl Read for MappedFile {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        match &mut self.source {
+            Source::Mapped { map, position } => {
+                let pending = &map[*position..];
+                let len = pending.len().min(buf.len());
+                buf[..len].copy_from_slice(&pending[..len]);
+                *position += len;
+                Ok(len)
+            }
+            Source::Buffered(reader) => reader.read(buf),
+        }
+    }
+}
+
+/// Encodes the file at `path` as a complete uuencoded file, as `uuencode_file`, named after the file and with its
+/// permission bits (see `FileOptions::from_path`). The file is memory-mapped where possible and the encoded text is
+/// produced as it is read, so neither the input nor the output is ever held in memory whole.
+/// # Safety
+/// As for `MappedFile::open`, the file must not be truncated or modified until the reader is dropped. To encode a
+/// file which may change, wrap `MappedFile::open_buffered` in an `EncodeReader` instead.
+/// Example:
+/// ```rust
+/// fn encode() -> std::io::Result<()> {
+///     // SAFETY: nothing else writes to the test data.
+///     let mut reader = unsafe { uuencode_lite::uuencode_path("test_data/random_data.bin")? };
+///     let mut encoded = Vec::new();
+///     std::io::copy(&mut reader, &mut encoded)?;
+///     assert!(encoded.starts_with(b"begin ") && encoded.ends_with(b"\nend\n"));
+///     Ok(())
+/// }
+/// ```
+pub unsafe fn uuencode_path(path: impl AsRef<Path>) -> io::Result<EncodeReader<MappedFile>> {
+    let options = FileOptions::from_path(path.as_ref())?;
+    // SAFETY: the caller guarantees that the file is left alone.
+    unsafe { uuencode_path_with(path, options) }
+}
+
+/// Encodes the file at `path` as `uuencode_path`, using the given options rather than ones read from the file.
+/// # Safety
+/// As for `uuencode_path`, the file must not be truncated or modified until the reader is dropped.
+pub unsafe fn uuencode_path_with(path: impl AsRef<Path>, options: FileOptions) -> io::Result<EncodeReader<MappedFile>> {
+    // SAFETY: the caller guarantees that the file is left alone.
+    Ok(EncodeReader::new(unsafe { MappedFile::open(path)? }, options)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::format;
+    use std::string::String;
+
+    /// Tests that encoding a path, mapped or buffered, matches encoding its contents
+    #[test]
+    fn test_uuencode_path() {
+        let path = "test_data/random_data.bin";
+        let source_data = std::fs::read(path).expect("Can open test data");
+        let expected = crate::uuencode_file(&source_data, &FileOptions::from_path(path).unwrap()).unwrap();
+        // SAFETY: nothing writes to the test data, or to the temporary files below, while they are encoded.
+        let mut reader = unsafe { uuencode_path(path) }.unwrap();
+        let mut encoded = String::new();
+        reader.read_to_string(&mut encoded).unwrap();
+        assert_eq!(encoded, expected);
+        assert!(reader.into_inner().is_mapped(), "maps a regular file");
+
+        let options = FileOptions::new("data.bin");
+        let mut reader = EncodeReader::new(MappedFile::open_buffered(path).unwrap(), options.clone()).unwrap();
+        let mut encoded = String::new();
+        reader.read_to_string(&mut encoded).unwrap();
+        assert_eq!(encoded, crate::uuencode_file(&source_data, &options).unwrap(), "falls back to buffered reads");
+
+        let empty = std::env::temp_dir().join(format!("uuencode_lite_mmap_empty_{}", std::process::id()));
+        std::fs::write(&empty, b"").expect("Can write temp file");
+        let mut encoded = String::new();
+        unsafe { uuencode_path_with(&empty, options.clone()) }.unwrap().read_to_string(&mut encoded).unwrap();
+        std::fs::remove_file(&empty).expect("Can remove temp file");
+        assert_eq!(encoded, crate::uuencode_file(b"", &options).unwrap(), "encodes an empty file");
+        assert!(unsafe { uuencode_path("test_data/missing") }.is_err(), "reports a missing file");
+        assert!(MappedFile::open_buffered("test_data/missing").is_err(), "reports a missing file when buffered");
+    }
+}
@@ -0,0 +1,177 @@
+use crate::{decode_all, uudecode, uudecode_file, uuencode, uuencode_file, DecodeOptions, DecodedFile, Decoder, ErrorKind, FileOptions, UUEncodeError};
+use std::string::{String, ToString};
+use std::sync::{Arc, Mutex};
+use std::vec::Vec;
+
+/// An error from the UniFFI bindings, raised as an exception in Kotlin and Swift.
+#[derive(Debug, uniffi::Error)]
+pub enum CodecError {
+    /// The input was malformed, or an argument was invalid.
+    Invalid { line: u64, character: u64, message: String },
+    /// The operation was stopped before it finished.
+    Cancelled { line: u64 },
+}
+
+impl From<UUEncodeError> for CodecError {
+    fn from(err: UUEncodeError) -> Self {
+        match err.kind() {
+            ErrorKind::Cancelled => CodecError::Cancelled { line: err.line as u64 },
+            _ => CodecError::Invalid { line: err.line as u64, character: err.character as u64, message: err.msg.to_string() },
+        }
+    }
+}
+
+impl core::fmt::Display for CodecError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            CodecError::Invalid { line, character, message } => write!(f, "{} at line {} character {}", message, line, character),
+            CodecError::Cancelled { line } => write!(f, "Cancelled at line {}", line),
+        }
+    }
+}
+
+impl core::error::Error for CodecError {}
+
+/// A decoded file. Exposed to Kotlin and Swift as `DecodedFile`.
+#[derive(Debug, Clone, PartialEq, Eq, uniffi::Record)]
+#[uniffi(name = "DecodedFile")]
+pub struct MobileDecodedFile {
+    /// The file name declared on the `begin` line.
+    pub name: String,
+    /// The permission bits declared on the `begin` line.
+    pub mode: u32,
+    /// The decoded contents of the file.
+    pub data: Vec<u8>,
+}
+
+impl From<DecodedFile> for MobileDecodedFile {
+    fn from(file: DecodedFile) -> Self {
+        MobileDecodedFile { name: file.name, mode: file.mode, data: file.data }
+    }
+}
+
+/// One file found by `extractFiles`: either decoded, or the message of the error which stopped it.
+/// Exposed to Kotlin and Swift as `ExtractedFile`.
+#[derive(Debug, Clone, PartialEq, Eq, uniffi::Record)]
+#[uniffi(name = "ExtractedFile")]
+pub struct MobileExtractedFile {
+    pub file: Option<MobileDecodedFile>,
+    pub error: Option<String>,
+}
+
+/// Encodes bytes as bare uuencoded lines, as `uuencode`. Exposed to Kotlin and Swift as `uuencode`.
+#[uniffi::export(name = "uuencode")]
+pub fn mobile_uuencode(data: Vec<u8>) -> Result<String, CodecError> {
+    Ok(uuencode(&data)?)
+}
+
+/// Decodes bare uuencoded lines, as `uudecode`. Exposed to Kotlin and Swift as `uudecode`.
+#[uniffi::export(name = "uudecode")]
+pub fn mobile_uudecode(text: String) -> Result<Vec<u8>, CodecError> {
+    Ok(uudecode(text.as_bytes())?)
+}
+
+/// Encodes bytes as a complete file with the given name and mode, as `uuencode_file`.
+/// Exposed to Kotlin and Swift as `uuencodeFile`.
+#[uniffi::export(name = "uuencode_file")]
+pub fn mobile_uuencode_file(data: Vec<u8>, name: String, mode: u32) -> Result<String, CodecError> {
+    Ok(uuencode_file(&data, &FileOptions::new(name).mode(mode))?)
+}
+
+/// Decodes the first complete file in the text, as `uudecode_file`. Exposed to Kotlin and Swift as `uudecodeFile`.
+#[uniffi::export(name = "uudecode_file")]
+pub fn mobile_uudecode_file(text: Vec<u8>) -> Result<MobileDecodedFile, CodecError> {
+    Ok(uudecode_file(&text)?.into())
+}
+
+/// Decodes every file embedded in a message, as `decode_all`, such as the attachments of a mail body.
+/// Exposed to Kotlin and Swift as `extractFiles`.
+#[uniffi::export(name = "extract_files")]
+pub fn mobile_extract_files(text: Vec<u8>) -> Vec<MobileExtractedFile> {
+    decode_all(&text, &DecodeOptions::new())
+        .into_iter()
+        .map(|result| match result {
+            Ok(file) => MobileExtractedFile { file: Some(file.into()), error: None },
+            Err(err) => MobileExtractedFile { file: None, error: Some(err.to_string()) },
+        })
+        .collect()
+}
+
+/// Incrementally decodes a complete file from text that arrives in pieces, as `Decoder`, such as a message
+/// streamed from an IMAP server. Exposed to Kotlin and Swift as `Decoder`.
+#[derive(Debug, uniffi::Object)]
+#[uniffi(name = "Decoder")]
+pub struct MobileDecoder {
+    decoder: Mutex<Decoder>,
+}
+
+#[uniffi::export]
+impl MobileDecoder {
+    /// Creates a decoder with the default options.
+    #[uniffi::constructor]
+    pub fn new() -> Arc<Self> {
+        Arc::new(MobileDecoder { decoder: Mutex::new(Decoder::new(DecodeOptions::new())) })
+    }
+
+    /// Decodes the next piece of text, returning the bytes decoded from the lines it completes.
+    pub fn push(&self, data: Vec<u8>) -> Result<Vec<u8>, CodecError> {
+        let mut decoded = Vec::new();
+        self.decoder.lock().expect("decoder lock poisoned").push(&data, &mut decoded)?;
+        Ok(decoded)
+    }
+
+    /// Decodes any last line without a newline, returning its bytes, and checks that the file was complete.
+    pub fn finish(&self) -> Result<Vec<u8>, CodecError> {
+        let mut decoded = Vec::new();
+        self.decoder.lock().expect("decoder lock poisoned").finish(&mut decoded)?;
+        Ok(decoded)
+    }
+
+    /// The file name from the `begin` line, if it has been seen.
+    pub fn name(&self) -> Option<String> {
+        self.decoder.lock().expect("decoder lock poisoned").header().map(|(_, name)| name.to_string())
+    }
+
+    /// The permission bits from the `begin` line, if it has been seen.
+    pub fn mode(&self) -> Option<u32> {
+        self.decoder.lock().expect("decoder lock poisoned").header().map(|(mode, _)| mode)
+    }
+
+    /// Whether the end of the file has been reached.
+    pub fn is_done(&self) -> bool {
+        self.decoder.lock().expect("decoder lock poisoned").is_done()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Tests the bindings as plain Rust functions
+    #[test]
+    fn test_mobile_bindings() {
+        let source_data = std::fs::read("test_data/the_machine_stops.txt").expect("Can open test data");
+        let encoded = mobile_uuencode(source_data.clone()).unwrap();
+        assert_eq!(mobile_uudecode(encoded).unwrap(), uudecode(uuencode(&source_data).unwrap().as_bytes()).unwrap(), "can encode and decode");
+        assert!(matches!(mobile_uudecode("#8V\x01T".to_string()), Err(CodecError::Invalid { line: 0, character: 2, .. })), "reports invalid input");
+
+        let encoded = mobile_uuencode_file(source_data.clone(), "the_machine_stops.txt".to_string(), 0o600).unwrap();
+        let file = mobile_uudecode_file(encoded.clone().into_bytes()).unwrap();
+        assert_eq!(file, MobileDecodedFile { name: "the_machine_stops.txt".to_string(), mode: 0o600, data: source_data.clone() }, "can encode and decode a file");
+
+        let digest = std::format!("Hi all,\n{}and\nbegin 644 b.txt\n#9&]G\n", encoded);
+        let files = mobile_extract_files(digest.into_bytes());
+        assert_eq!(files.len(), 2, "finds every file");
+        assert_eq!(files[0].file.as_ref(), Some(&file), "decodes each file");
+        assert!(files[1].error.as_deref().unwrap().starts_with("Missing end line"), "reports a broken file");
+
+        let decoder = MobileDecoder::new();
+        let mut decoded = Vec::new();
+        for piece in encoded.as_bytes().chunks(100) {
+            decoded.extend(decoder.push(piece.to_vec()).unwrap());
+        }
+        decoded.extend(decoder.finish().unwrap());
+        assert_eq!((decoder.name().as_deref(), decoder.mode(), decoder.is_done()), (Some("the_machine_stops.txt"), Some(0o600), true), "reports the begin line");
+        assert_eq!(decoded, source_data, "can decode in pieces");
+    }
+}
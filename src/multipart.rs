@@ -0,0 +1,278 @@
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use crate::checksum::Checksum;
+use crate::file::{numbered_lines, Framing};
+use crate::repair::encoded_line_len;
+use crate::{decode_char, uudecode_file_with, uuencode_file, DecodeOptions, DecodedFile, FileOptions, UUEncodeError};
+
+/// Options controlling how an encoded file is split into parts for posting.
+/// Example:
+/// ```rust
+/// let options = uuencode_lite::SplitOptions::new(500).header("[{part}/{total}] {name}");
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SplitOptions {
+    /// The maximum number of encoded lines in each part, not counting the part header.
+    lines_per_part: usize,
+    /// The template for the header line at the start of each part.
+    header: String,
+}
+
+impl SplitOptions {
+    /// The default header template, in the style of classic Usenet posting tools.
+    pub const DEFAULT_HEADER: &'static str = "section {part} of {total} of file {name}";
+
+    /// Creates options for parts of at most `lines_per_part` encoded lines, using the default header.
+    pub fn new(lines_per_part: usize) -> Self {
+        SplitOptions { lines_per_part, header: Self::DEFAULT_HEADER.to_string() }
+    }
+
+    /// Sets the template for the header line at the start of each part.
+    /// `{part}`, `{total}` and `{name}` are replaced with the part number (starting at 1),
+    /// the number of parts, and the file name.
+    pub fn header(mut self, header: impl Into<String>) -> Self {
+        self.header = header.into();
+        self
+    }
+}
+
+/// Encodes the input data as a complete uuencoded file, as `uuencode_file`, split into parts.
+/// Each part starts with a header line and holds at most the configured number of encoded lines.
+/// The `begin` line is in the first part and the `end` line in the last, so that concatenating the
+/// parts (without their headers) gives the complete file.
+/// Example:
+/// ```rust
+/// fn split() -> Result<(), uuencode_lite::UUEncodeError> {
+///     let parts = uuencode_lite::uuencode_parts(b"cat", &uuencode_lite::FileOptions::new("cat.txt"), &uuencode_lite::SplitOptions::new(2))?;
+///     assert_eq!(parts, vec![
+///         "section 1 of 2 of file cat.txt\nbegin 644 cat.txt\n#8V%T\n",
+///         "section 2 of 2 of file cat.txt\n`\nend\n",
+///     ]);
+///     Ok(())
+/// }
+/// ```
+pub fn uuencode_parts(data: &[u8], file: &FileOptions, options: &SplitOptions) -> Result<Vec<String>, UUEncodeError> {
+    if options.lines_per_part == 0 {
+        return Err(UUEncodeError::new(0, 0, "Parts must hold at least one line"));
+    }
+    if options.header.contains(['\n', '\r']) {
+        return Err(UUEncodeError::new(0, 0, "Part header must be a single line"));
+    }
+    if options.header.contains("{name}") && file.name().contains(['\n', '\r']) {
+        return Err(UUEncodeError::new(0, 0, "File name in part header must be a single line"));
+    }
+
+    let encoded = uuencode_file(data, file)?;
+    let lines: Vec<&str> = encoded.lines().collect();
+    let total = lines.len().div_ceil(options.lines_per_part);
+    let parts = lines
+        .chunks(options.lines_per_part)
+        .enumerate()
+        .map(|(idx, part_lines)| {
+            let header = options.header.replace("{part}", &(idx + 1).to_string()).replace("{total}", &total.to_string()).replace("{name}", file.name());
+            let mut part = String::with_capacity(header.len() + part_lines.len() * 62);
+            part.push_str(&header);
+            part.push('\n');
+            for line in part_lines {
+                part.push_str(line);
+                part.push('\n');
+            }
+            part
+        })
+        .collect();
+    Ok(parts)
+}
+
+/// Finds a part number and total in a banner line, such as `section 2 of 5`, `part 2/5` or `[2/5]`.
+fn parse_banner(line: &[u8]) -> Option<(usize, usize)> {
+    let line = String::from_utf8_lossy(line).to_ascii_lowercase();
+    let parse_numbers = |rest: &str| -> Option<(usize, usize)> {
+        let rest = rest.trim_start_matches([' ', ':', '#']);
+        let digits = rest.find(|ch: char| !ch.is_ascii_digit()).unwrap_or(rest.len());
+        let part = rest[..digits].parse().ok()?;
+        let rest = rest[digits..].trim_start();
+        let rest = rest.strip_prefix('/').or_else(|| rest.strip_prefix("of"))?.trim_start();
+        let digits = rest.find(|ch: char| !ch.is_ascii_digit()).unwrap_or(rest.len());
+        let total = rest[..digits].parse().ok()?;
+        (part >= 1 && part <= total).then_some((part, total))
+    };
+    ["section", "part", "[", "("]
+        .iter()
+        .flat_map(|keyword| line.match_indices(keyword).map(move |(idx, _)| idx + keyword.len()))
+        .find_map(|idx| parse_numbers(&line[idx..]))
+}
+
+/// Checks whether a line could be part of a uuencoded body.
+fn is_body_line(line: &[u8]) -> bool {
+    let Some(len) = line.first().and_then(|&ch| decode_char(ch)).filter(|&len| len <= 45) else {
+        return false;
+    };
+    // allow for one extra character, as some encoders append a checksum character
+    let expected = encoded_line_len(len as usize);
+    line.iter().all(|&ch| (b' '..=b'`').contains(&ch)) && (expected..=expected + 1).contains(&line.len())
+}
+
+/// Checks whether a line could be part of a Base64 body.
+fn is_base64_line(line: &[u8]) -> bool {
+    !line.is_empty() && line.iter().all(|&ch| ch.is_ascii_alphanumeric() || matches!(ch, b'+' | b'/' | b'='))
+}
+
+/// Reassembles a file that was posted in several parts, and decodes it.
+/// Parts may be given in any order: each part's position is taken from a banner line such as
+/// `section 2 of 5`, `part 2/5` or `[2/5]`, falling back to the order given. Repeated parts are ignored.
+/// Everything that is not part of the encoded file is stripped, including banners, repeated headers,
+/// and signatures (everything after a `-- ` line, up to the end of the part).
+/// Example:
+/// ```rust
+/// fn reassemble() -> Result<(), uuencode_lite::UUEncodeError> {
+///     let options = uuencode_lite::FileOptions::new("cat.txt");
+///     let parts = uuencode_lite::uuencode_parts(b"cat", &options, &uuencode_lite::SplitOptions::new(2))?;
+///     let file = uuencode_lite::reassemble_parts(&[parts[1].as_bytes(), parts[0].as_bytes()], &uuencode_lite::DecodeOptions::new())?;
+///     assert_eq!(file.data, b"cat");
+///     Ok(())
+/// }
+/// ```
+pub fn reassemble_parts(parts: &[&[u8]], options: &DecodeOptions) -> Result<DecodedFile, UUEncodeError> {
+    #[cfg(feature = "tracing")]
+    let _span = tracing::debug_span!("reassemble_parts", parts = parts.len()).entered();
+    let mut numbered: Vec<(usize, &[u8])> = Vec::with_capacity(parts.len());
+    let mut total = None;
+    for (idx, part) in parts.iter().enumerate() {
+        let banner = numbered_lines(part).take_while(|(_, line)| Framing::of_begin_line(line).is_none() && !is_body_line(line)).find_map(|(_, line)| parse_banner(line));
+        let position = match banner {
+            Some((position, part_total)) => {
+                total = total.max(Some(part_total));
+                position
+            }
+            None => idx + 1,
+        };
+        if !numbered.iter().any(|(existing, _)| *existing == position) {
+            numbered.push((position, part));
+        } else {
+            #[cfg(feature = "tracing")]
+            tracing::debug!(part = idx, position, "skipped repeated part");
+        }
+    }
+    numbered.sort_by_key(|(position, _)| *position);
+    if let Some(total) = total
+        && let Some(missing) = (1..=total).find(|position| !numbered.iter().any(|(existing, _)| existing == position))
+    {
+        #[cfg(feature = "tracing")]
+        tracing::warn!(missing, total, "missing part");
+        return Err(UUEncodeError::new(0, 0, format!("Missing part {} of {}", missing, total)));
+    }
+
+    let mut payload = Vec::with_capacity(parts.iter().map(|part| part.len()).sum());
+    let mut framing = None;
+    let mut table_lines = 0;
+    let mut finished = false;
+    for (_, part) in numbered {
+        for (_, line) in numbered_lines(part) {
+            if finished && Checksum::parse_trailer(line).is_some() {
+                payload.extend_from_slice(line);
+                payload.push(b'\n');
+            }
+            if line == b"-- " || finished {
+                break;
+            }
+            let keep = if table_lines > 0 {
+                table_lines -= 1;
+                true
+            } else {
+                match framing {
+                    None if line.trim_ascii_end() == b"table" => {
+                        table_lines = 2;
+                        true
+                    }
+                    None => {
+                        framing = Framing::of_begin_line(line);
+                        framing.is_some()
+                    }
+                    // a repeated header
+                    Some(_) if Framing::of_begin_line(line).is_some() => false,
+                    Some(Framing::UUEncode) => {
+                        finished = line.trim_ascii_end() == b"end";
+                        finished || is_body_line(line)
+                    }
+                    Some(Framing::Base64) => {
+                        finished = line.trim_ascii_end() == b"====";
+                        finished || is_base64_line(line)
+                    }
+                }
+            };
+            if keep {
+                payload.extend_from_slice(line);
+                payload.push(b'\n');
+            }
+        }
+    }
+    let file = uudecode_file_with(&payload, options);
+    #[cfg(feature = "tracing")]
+    crate::file::trace_file(&file);
+    file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::uudecode_file;
+
+    /// Tests splitting a large file, and decoding the concatenated parts
+    #[test]
+    fn test_split() {
+        let source_data = std::fs::read("test_data/random_data.bin").expect("Can open test data");
+        let file = FileOptions::new("random_data.bin");
+        let parts = uuencode_parts(&source_data, &file, &SplitOptions::new(10).header("Part {part}/{total}: {name}")).unwrap();
+        let whole = uuencode_file(&source_data, &file).unwrap();
+        assert_eq!(parts.len(), whole.lines().count().div_ceil(10), "splits into the expected number of parts");
+        assert_eq!(parts[0].lines().next().unwrap(), format!("Part 1/{}: random_data.bin", parts.len()), "fills in the header template");
+        assert!(parts.iter().all(|part| part.lines().count() <= 11), "limits the lines in each part");
+
+        let joined: String = parts.iter().flat_map(|part| part.split_inclusive('\n').skip(1)).collect();
+        assert_eq!(joined, whole, "can rejoin the parts");
+        assert_eq!(uudecode_file(joined.as_bytes()).unwrap().data, source_data, "can decode the rejoined parts");
+    }
+
+    /// Tests rejection of unusable options
+    #[test]
+    fn test_split_errors() {
+        assert!(uuencode_parts(b"cat", &FileOptions::new("cat.txt"), &SplitOptions::new(0)).is_err(), "rejects empty parts");
+        assert!(uuencode_parts(b"cat", &FileOptions::new("cat.txt"), &SplitOptions::new(5).header("a\nb")).is_err(), "rejects multi-line headers");
+        let file = FileOptions::new("x\nbegin 644 evil").encode_name(true);
+        assert!(uuencode_parts(b"cat", &file, &SplitOptions::new(5)).is_err(), "rejects a name which would split the header");
+        let parts = uuencode_parts(b"cat", &file, &SplitOptions::new(5).header("part {part} of {total}")).unwrap();
+        assert_eq!(parts[0].lines().next(), Some("part 1 of 1"), "accepts the name when the header leaves it out");
+    }
+
+    /// Tests recognizing part banners
+    #[test]
+    fn test_parse_banner() {
+        assert_eq!(parse_banner(b"section 2 of 5 of file foo.zip"), Some((2, 5)));
+        assert_eq!(parse_banner(b"Subject: foo.zip - Part 03/12"), Some((3, 12)));
+        assert_eq!(parse_banner(b"foo.zip [1/2]"), Some((1, 2)));
+        assert_eq!(parse_banner(b"part of the fun"), None);
+        assert_eq!(parse_banner(b"part 3/2"), None, "rejects a part beyond the total");
+    }
+
+    /// Tests reassembling shuffled, decorated parts
+    #[test]
+    fn test_reassemble() {
+        let source_data = std::fs::read("test_data/random_data.bin").expect("Can open test data");
+        for base64 in [false, true] {
+            let file = FileOptions::new("random_data.bin").base64(base64);
+            let parts = uuencode_parts(&source_data, &file, &SplitOptions::new(7).header("Subject: random_data.bin ({part}/{total})")).unwrap();
+            let mut decorated: Vec<String> = parts
+                .iter()
+                .map(|part| format!("From: poster@example.com\n{}\nbegin 644 random_data.bin\n-- \nMy signature\nM has a lovely sig\n", part))
+                .collect();
+            decorated.reverse();
+            decorated.push(decorated[1].clone());
+            let decorated: Vec<&[u8]> = decorated.iter().map(|part| part.as_bytes()).collect();
+            let decoded = reassemble_parts(&decorated, &DecodeOptions::new()).unwrap();
+            assert_eq!(decoded.data, source_data, "can reassemble shuffled parts");
+
+            assert!(reassemble_parts(&decorated[1..], &DecodeOptions::new()).is_err(), "reports a missing part");
+        }
+    }
+}
@@ -0,0 +1,219 @@
+use crate::{uudecode, uudecode_file, uuencode, uuencode_file, Decoder, DecodeOptions, FileOptions, UUEncodeError};
+use napi::bindgen_prelude::{AsyncTask, Buffer, Either};
+use napi::{Env, Task};
+use napi_derive::napi;
+use std::boxed::Box;
+use std::format;
+use std::path::PathBuf;
+use std::string::{String, ToString};
+use std::vec;
+use std::vec::Vec;
+
+/// Converts an error to a JavaScript `Error` carrying its message.
+fn js_error(err: UUEncodeError) -> napi::Error {
+    napi::Error::from_reason(err.to_string())
+}
+
+/// Uuencoded text, given as either a `string` or a `Buffer`.
+type Text = Either<String, Buffer>;
+
+/// The bytes of uuencoded text.
+fn text_bytes(text: &Text) -> &[u8] {
+    match text {
+        Either::A(text) => text.as_bytes(),
+        Either::B(buffer) => buffer,
+    }
+}
+
+/// Encodes a `Buffer` as bare uuencoded lines, as `uuencode`. Exported to Node as `uuencode`.
+#[napi(js_name = "uuencode")]
+pub fn node_uuencode(data: Buffer) -> napi::Result<String> {
+    uuencode(&data).map_err(js_error)
+}
+
+/// Decodes bare uuencoded lines, as `uudecode`. Exported to Node as `uudecode`.
+#[napi(js_name = "uudecode")]
+pub fn node_uudecode(text: Text) -> napi::Result<Buffer> {
+    uudecode(text_bytes(&text)).map(Buffer::from).map_err(js_error)
+}
+
+/// Encodes a `Buffer` as a complete file with the given name and mode, as `uuencode_file`.
+/// Exported to Node as `uuencodeFile`.
+#[napi(js_name = "uuencodeFile")]
+pub fn node_uuencode_file(data: Buffer, name: String, mode: Option<u32>) -> napi::Result<String> {
+    uuencode_file(&data, &FileOptions::new(name).mode(mode.unwrap_or(0o644))).map_err(js_error)
+}
+
+/// The file name and permission bits from a `begin` line.
+#[napi(object, js_name = "FileHeader")]
+pub struct NodeFileHeader {
+    pub name: String,
+    pub mode: u32,
+}
+
+/// A decoded file. Exported to Node as `DecodedFile`.
+#[napi(object, js_name = "DecodedFile")]
+pub struct NodeDecodedFile {
+    pub name: String,
+    pub mode: u32,
+    pub data: Buffer,
+}
+
+/// Decodes the first complete file in the text, as `uudecode_file`. Exported to Node as `uudecodeFile`.
+#[napi(js_name = "uudecodeFile")]
+pub fn node_uudecode_file(text: Text) -> napi::Result<NodeDecodedFile> {
+    let file = uudecode_file(text_bytes(&text)).map_err(js_error)?;
+    Ok(NodeDecodedFile { name: file.name, mode: file.mode, data: file.data.into() })
+}
+
+/// Incrementally decodes a complete file from text that arrives in pieces, as `Decoder`, such as an article
+/// read from a news server socket. Exported to Node as `Decoder`.
+/// Example, in JavaScript:
+/// ```js
+/// const decoder = new Decoder();
+/// const parts = [];
+/// socket.on('data', (chunk) => parts.push(decoder.push(chunk)));
+/// socket.on('end', () => {
+///     parts.push(decoder.finish());
+///     fs.writeFileSync(decoder.header.name, Buffer.concat(parts));
+/// });
+/// ```
+#[napi(js_name = "Decoder")]
+pub struct NodeDecoder {
+    decoder: Decoder,
+}
+
+impl Default for NodeDecoder {
+    fn default() -> Self {
+        NodeDecoder::new()
+    }
+}
+
+#[napi]
+impl NodeDecoder {
+    /// Creates a decoder with the default options.
+    #[napi(constructor)]
+    pub fn new() -> Self {
+        NodeDecoder { decoder: Decoder::new(DecodeOptions::new()) }
+    }
+
+    /// Decodes the next piece of text, returning the bytes decoded from the lines it completes.
+    #[napi]
+    pub fn push(&mut self, data: Text) -> napi::Result<Buffer> {
+        let mut decoded = Vec::new();
+        self.decoder.push(text_bytes(&data), &mut decoded).map_err(js_error)?;
+        Ok(decoded.into())
+    }
+
+    /// Decodes any last line without a newline, returning its bytes, and checks that the file was complete.
+    #[napi]
+    pub fn finish(&mut self) -> napi::Result<Buffer> {
+        let mut decoded = Vec::new();
+        self.decoder.finish(&mut decoded).map_err(js_error)?;
+        Ok(decoded.into())
+    }
+
+    /// The name and mode from the `begin` line, or `null` if it has not been seen yet.
+    #[napi(getter)]
+    pub fn header(&self) -> Option<NodeFileHeader> {
+        self.decoder.header().map(|(mode, name)| NodeFileHeader { name: name.to_string(), mode })
+    }
+
+    /// Whether the end of the file has been reached.
+    #[napi(getter)]
+    pub fn is_done(&self) -> bool {
+        self.decoder.is_done()
+    }
+}
+
+/// Encodes the file at `input` into `output` on the libuv thread pool, for `encodePath`.
+pub struct EncodePathTask {
+    input: PathBuf,
+    output: PathBuf,
+}
+
+impl Task for EncodePathTask {
+    type Output = ();
+    type JsValue = ();
+
+    fn compute(&mut self) -> napi::Result<()> {
+        let options = FileOptions::from_path(&self.input)?;
+        let encoded = uuencode_file(&std::fs::read(&self.input)?, &options).map_err(js_error)?;
+        Ok(std::fs::write(&self.output, encoded)?)
+    }
+
+    fn resolve(&mut self, _env: Env, output: ()) -> napi::Result<()> {
+        Ok(output)
+    }
+}
+
+/// Encodes the file at `input` as a complete uuencoded file, named and with the permission bits of `input`, and
+/// writes it to `output`, off the main thread. Exported to Node as `encodePath`, which returns a `Promise`.
+#[napi(js_name = "encodePath")]
+pub fn node_encode_path(input: String, output: String) -> AsyncTask<EncodePathTask> {
+    AsyncTask::new(EncodePathTask { input: input.into(), output: output.into() })
+}
+
+/// Decodes the file at `input` into `output` on the libuv thread pool, for `decodePath`.
+pub struct DecodePathTask {
+    input: PathBuf,
+    output: PathBuf,
+}
+
+impl Task for DecodePathTask {
+    type Output = (String, u32);
+    type JsValue = NodeFileHeader;
+
+    fn compute(&mut self) -> napi::Result<(String, u32)> {
+        let file = uudecode_file(&std::fs::read(&self.input)?).map_err(js_error)?;
+        std::fs::write(&self.output, &file.data)?;
+        Ok((file.name, file.mode))
+    }
+
+    fn resolve(&mut self, _env: Env, (name, mode): (String, u32)) -> napi::Result<NodeFileHeader> {
+        Ok(NodeFileHeader { name, mode })
+    }
+}
+
+/// Decodes the first complete file in the uuencoded file at `input`, writing its data to `output`, off the main
+/// thread. Exported to Node as `decodePath`, which returns a `Promise` of the name and mode from its `begin` line;
+/// the mode is not applied to `output`.
+#[napi(js_name = "decodePath")]
+pub fn node_decode_path(input: String, output: String) -> AsyncTask<DecodePathTask> {
+    AsyncTask::new(DecodePathTask { input: input.into(), output: output.into() })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Tests the bindings as plain Rust functions, and the file tasks' work outside of Node
+    #[test]
+    fn test_node_bindings() {
+        let source_data = std::fs::read("test_data/the_machine_stops.txt").expect("Can open test data");
+        let encoded = node_uuencode(source_data.clone().into()).unwrap();
+        assert_eq!(node_uudecode(Either::A(encoded.clone())).unwrap().to_vec(), uudecode(encoded.as_bytes()).unwrap(), "can encode and decode");
+
+        let encoded = node_uuencode_file(source_data.clone().into(), "the_machine_stops.txt".to_string(), Some(0o600)).unwrap();
+        let file = node_uudecode_file(Either::B(encoded.as_bytes().to_vec().into())).unwrap();
+        assert_eq!((file.name.as_str(), file.mode, file.data.to_vec()), ("the_machine_stops.txt", 0o600, source_data.clone()), "can encode and decode a file");
+
+        let mut decoder = NodeDecoder::new();
+        let mut decoded = vec![];
+        for piece in encoded.as_bytes().chunks(100) {
+            decoded.extend_from_slice(&decoder.push(Either::B(piece.to_vec().into())).unwrap());
+        }
+        decoded.extend_from_slice(&decoder.finish().unwrap());
+        let header = decoder.header().unwrap();
+        assert_eq!((header.name.as_str(), header.mode, decoder.is_done()), ("the_machine_stops.txt", 0o600, true), "reports the begin line");
+        assert_eq!(decoded, source_data, "can decode in pieces");
+
+        let dir = std::env::temp_dir().join(format!("uuencode_lite_node_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        EncodePathTask { input: "test_data/the_machine_stops.txt".into(), output: dir.join("encoded.uu") }.compute().unwrap();
+        let header = DecodePathTask { input: dir.join("encoded.uu"), output: dir.join("decoded") }.compute().unwrap();
+        assert_eq!(header.0, "the_machine_stops.txt", "returns the begin line");
+        assert_eq!(std::fs::read(dir.join("decoded")).unwrap(), source_data, "can encode and decode paths");
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
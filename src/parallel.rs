@@ -0,0 +1,175 @@
+use crate::{ascii_string, decode_line, decode_line_to_slice, uudecode_with, uuencode_to_slice_with, uuencode_with, uuencoded_len, DecodeOptions, EncodeOptions, UUEncodeError};
+use alloc::string::String;
+use alloc::vec::Vec;
+use rayon::prelude::*;
+
+/// The lines each task encodes or decodes. Inputs of about one chunk or less are handled on the calling thread.
+const CHUNK_LINES: usize = 4096;
+
+/// The encoded text of one chunk of full lines, with their newlines.
+const CHUNK_TEXT: usize = CHUNK_LINES * 62;
+
+/// Encodes the input data as `uuencode` does, splitting it across the current rayon pool. Each line depends only on
+/// its own 45 bytes of input, so chunks of whole lines are encoded straight into their place in the output.
+/// Example:
+/// ```rust
+/// fn encode() -> Result<(), uuencode_lite::UUEncodeError> {
+///     let data = vec![0x55; 1 << 20];
+///     assert_eq!(uuencode_lite::uuencode_parallel(&data)?, uuencode_lite::uuencode(&data)?);
+///     Ok(())
+/// }
+/// ```
+pub fn uuencode_parallel(data: &[u8]) -> Result<String, UUEncodeError> {
+    uuencode_parallel_with(data, &EncodeOptions::default())
+}
+
+/// Encodes the input data as `uuencode_parallel`, using the given options.
+pub fn uuencode_parallel_with(data: &[u8], options: &EncodeOptions) -> Result<String, UUEncodeError> {
+    let chunk_len = CHUNK_LINES * 45;
+    if data.len() <= chunk_len {
+        return uuencode_with(data, options);
+    }
+    #[cfg(feature = "metrics")]
+    if let Some(metrics) = crate::metrics::installed() {
+        metrics.encoded(data.len() as u64, data.len().div_ceil(45) as u64);
+    }
+    let mut encoded = alloc::vec![0u8; uuencoded_len(data.len(), options)];
+    // every chunk but the last is followed by the newline ending its last line
+    let encoded_chunk_len = uuencoded_len(chunk_len, options) + 1;
+    encoded.par_chunks_mut(encoded_chunk_len).zip(data.par_chunks(chunk_len)).try_for_each(|(encoded, chunk)| {
+        let len = uuencode_to_slice_with(chunk, options, encoded)?;
+        if let Some(newline) = encoded.get_mut(len) {
+            *newline = b'\n';
+        }
+        Ok(())
+    })?;
+    Ok(ascii_string(encoded))
+}
+
+/// Decodes uuencoded data as `uudecode_with` does, splitting the lines across the current rayon pool. A first pass
+/// finds every line, and the length its first character declares places its bytes in the output, so that chunks of
+/// lines are then decoded straight into their place. An error reports the first failing line, as `uudecode_with`.
+/// Example:
+/// ```rust
+/// fn decode() -> Result<(), uuencode_lite::UUEncodeError> {
+///     let data = vec![0x55; 1 << 20];
+///     let encoded = uuencode_lite::uuencode(&data)?;
+///     assert_eq!(uuencode_lite::uudecode_parallel(encoded.as_bytes())?, data);
+///     Ok(())
+/// }
+/// ```
+pub fn uudecode_parallel(data: &[u8]) -> Result<Vec<u8>, UUEncodeError> {
+    uudecode_parallel_with(data, &DecodeOptions::default())
+}
+
+/// Decodes uuencoded data as `uudecode_parallel`, using the given options.
+pub fn uudecode_parallel_with(data: &[u8], options: &DecodeOptions) -> Result<Vec<u8>, UUEncodeError> {
+    if data.len() <= CHUNK_TEXT {
+        return uudecode_with(data, options);
+    }
+    // the end of every line, the last ending with the input
+    let ends: Vec<usize> = memchr::memchr_iter(b'\n', data).chain([data.len()]).collect();
+    let line = |idx: usize| {
+        let start = if idx == 0 { 0 } else { ends[idx - 1] + 1 };
+        let line = &data[start..ends[idx]];
+        line.strip_suffix(b"\r").unwrap_or(line)
+    };
+    // where each line's bytes start in the output, up to the first line whose length can't be read
+    let mut offsets = Vec::with_capacity(ends.len() + 1);
+    offsets.push(0);
+    let mut len = 0;
+    for idx in 0..ends.len() {
+        let Some(line_len) = line(idx).first().map_or(Some(0), |&ch| options.table.decode(ch)) else {
+            break;
+        };
+        len += line_len as usize;
+        offsets.push(len);
+    }
+    let placed = offsets.len() - 1;
+
+    let mut decoded = alloc::vec![0u8; len];
+    let mut chunks = Vec::with_capacity(placed.div_ceil(CHUNK_LINES));
+    let mut rest = &mut decoded[..];
+    for first in (0..placed).step_by(CHUNK_LINES) {
+        let last = (first + CHUNK_LINES).min(placed);
+        let (chunk, after) = rest.split_at_mut(offsets[last] - offsets[first]);
+        chunks.push((first..last, chunk));
+        rest = after;
+    }
+    let results: Vec<Result<(), UUEncodeError>> = chunks
+        .into_par_iter()
+        .map(|(lines, mut output)| {
+            for idx in lines {
+                let len = decode_line_to_slice(line(idx), idx, &options.table, options.verify_checksums, output)?;
+                output = &mut core::mem::take(&mut output)[len..];
+            }
+            Ok(())
+        })
+        .collect();
+    // the chunks are in order, and each stops at its first failure, so the first error is the earliest
+    let result = match results.into_iter().find_map(Result::err) {
+        Some(err) => Err(err),
+        // decoding the line whose length couldn't be read reports why
+        None if placed < ends.len() => decode_line(line(placed), placed, &options.table, options.verify_checksums, &mut Vec::new()),
+        None => Ok(()),
+    };
+    #[cfg(feature = "metrics")]
+    let result = crate::metrics::check(result);
+    #[cfg(feature = "metrics")]
+    if let (Ok(()), Some(metrics)) = (&result, crate::metrics::installed()) {
+        metrics.decoded(decoded.len() as u64, ends.len() as u64);
+    }
+    result.map(|()| decoded)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Table;
+    use alloc::string::ToString;
+    use alloc::vec::Vec;
+
+    /// Tests that parallel encoding matches sequential encoding at and around chunk boundaries
+    #[test]
+    fn test_uuencode_parallel() {
+        let chunk_len = CHUNK_LINES * 45;
+        let data: Vec<u8> = (0..3 * chunk_len as u64 + 100).map(|i| (i.wrapping_mul(0x9E37_79B9) >> 11) as u8).collect();
+        for len in [0, 1, chunk_len, chunk_len + 1, 2 * chunk_len, 2 * chunk_len + 44, data.len()] {
+            assert_eq!(uuencode_parallel(&data[..len]).unwrap(), crate::uuencode(&data[..len]).unwrap(), "encodes {} bytes", len);
+        }
+        for options in [EncodeOptions::new().line_checksums(true), EncodeOptions::new().table(Table::XX)] {
+            assert_eq!(uuencode_parallel_with(&data, &options).unwrap(), uuencode_with(&data, &options).unwrap(), "encodes with {:?}", options);
+        }
+    }
+
+    /// Tests that parallel decoding matches sequential decoding, including blank lines and CRLF, and reports the
+    /// same first error
+    #[test]
+    fn test_uudecode_parallel() {
+        let data: Vec<u8> = (0..3 * CHUNK_LINES as u64 * 45 + 100).map(|i| (i.wrapping_mul(0x9E37_79B9) >> 11) as u8).collect();
+        let encoded = crate::uuencode(&data).unwrap().into_bytes();
+        assert_eq!(uudecode_parallel(&encoded).unwrap(), data);
+        let mut lines: Vec<&[u8]> = encoded.split(|&ch| ch == b'\n').collect();
+        lines.insert(CHUNK_LINES, b"");
+        let crlf = lines.join(&b"\r\n"[..]);
+        assert_eq!(uudecode_parallel(&crlf).unwrap(), crate::uudecode_with(&crlf, &DecodeOptions::new()).unwrap(), "decodes blank lines and CRLF");
+        let options = DecodeOptions::new().verify_checksums(true);
+        let checksummed = crate::uuencode_with(&data, &EncodeOptions::new().line_checksums(true)).unwrap().into_bytes();
+        assert_eq!(uudecode_parallel_with(&checksummed, &options).unwrap(), data, "verifies checksums");
+
+        // a bad character in the third chunk, with a bad length or a bad character in an earlier one, then a bad
+        // length ahead of the bad character, where the lines after it can't be placed
+        let errors = |data: &[u8]| (uudecode_parallel(data).unwrap_err().to_string(), uudecode_with(data, &DecodeOptions::new()).unwrap_err().to_string());
+        let mut corrupt = encoded.clone();
+        corrupt[2 * CHUNK_TEXT + 5] = b'a';
+        for (position, ch) in [(CHUNK_TEXT + 62 * 100, b'a'), (62 * 3000 + 3, b'~')] {
+            let mut corrupt = corrupt.clone();
+            corrupt[position] = ch;
+            let (parallel, sequential) = errors(&corrupt);
+            assert_eq!(parallel, sequential);
+        }
+        corrupt[62 * 5000] = b'~';
+        let (parallel, sequential) = errors(&corrupt);
+        assert_eq!(parallel, sequential);
+    }
+}
@@ -0,0 +1,277 @@
+use crate::{apply_mode, is_safe_file_name, uudecode, uudecode_file, uuencode, uuencode_file, Decoder, DecodeOptions, EncodeOptions, Encoder, FileOptions, Table, UUEncodeError};
+use pyo3::exceptions::PyException;
+use pyo3::prelude::*;
+use pyo3::types::{PyBytes, PyString};
+use std::borrow::Cow;
+use std::path::PathBuf;
+use std::string::{String, ToString};
+use std::vec::Vec;
+
+pyo3::create_exception!(uuencode_lite, Error, PyException, "Raised for malformed input, as `uu.Error` was.");
+
+/// Converts an error to the module's `Error` exception.
+fn py_error(err: UUEncodeError) -> PyErr {
+    Error::new_err(err.to_string())
+}
+
+/// Reads uuencoded text given as either `bytes` or `str`.
+fn text<'a>(obj: &'a Bound<'_, PyAny>) -> PyResult<Cow<'a, [u8]>> {
+    match obj.cast::<PyString>() {
+        Ok(text) => Ok(Cow::Owned(text.to_str()?.as_bytes().to_vec())),
+        Err(_) => Ok(Cow::Borrowed(obj.extract::<&[u8]>()?)),
+    }
+}
+
+/// Encodes bytes as bare uuencoded lines, as `uuencode`.
+#[pyfunction(name = "uuencode")]
+fn py_uuencode(data: &[u8]) -> PyResult<String> {
+    uuencode(data).map_err(py_error)
+}
+
+/// Decodes bare uuencoded lines, given as `bytes` or `str`, as `uudecode`.
+#[pyfunction(name = "uudecode")]
+fn py_uudecode<'py>(py: Python<'py>, text_obj: &Bound<'py, PyAny>) -> PyResult<Bound<'py, PyBytes>> {
+    let decoded = uudecode(&text(text_obj)?).map_err(py_error)?;
+    Ok(PyBytes::new(py, &decoded))
+}
+
+/// Encodes bytes as a complete file with a `begin` line, as `uuencode_file`.
+#[pyfunction(name = "uuencode_file", signature = (data, name, mode = 0o644))]
+fn py_uuencode_file(data: &[u8], name: &str, mode: u32) -> PyResult<String> {
+    uuencode_file(data, &FileOptions::new(name).mode(mode)).map_err(py_error)
+}
+
+/// Decodes the first complete file in the text, as `uudecode_file`, returning `(name, mode, data)`.
+#[pyfunction(name = "uudecode_file")]
+fn py_uudecode_file<'py>(py: Python<'py>, text_obj: &Bound<'py, PyAny>) -> PyResult<(String, u32, Bound<'py, PyBytes>)> {
+    let file = uudecode_file(&text(text_obj)?).map_err(py_error)?;
+    Ok((file.name, file.mode, PyBytes::new(py, &file.data)))
+}
+
+/// Where `encode` and `decode` read from or write to: a path, `-` for the standard streams, or a binary file object.
+enum Target<'py> {
+    Path(PathBuf),
+    Standard,
+    File(Bound<'py, PyAny>),
+}
+
+impl<'py> Target<'py> {
+    fn new(obj: &Bound<'py, PyAny>) -> Self {
+        match obj.extract::<PathBuf>() {
+            Ok(path) if path.as_os_str() == "-" => Target::Standard,
+            Ok(path) => Target::Path(path),
+            Err(_) => Target::File(obj.clone()),
+        }
+    }
+
+    /// Reads everything, from `sys.stdin.buffer` for `-`.
+    fn read(&self, py: Python<'py>) -> PyResult<Vec<u8>> {
+        match self {
+            Target::Path(path) => Ok(std::fs::read(path)?),
+            Target::Standard => py.import("sys")?.getattr("stdin")?.getattr("buffer")?.call_method0("read")?.extract(),
+            Target::File(file) => file.call_method0("read")?.extract(),
+        }
+    }
+
+    /// Writes everything, to `sys.stdout.buffer` for `-`.
+    fn write(&self, py: Python<'py>, data: &[u8]) -> PyResult<()> {
+        match self {
+            Target::Path(path) => Ok(std::fs::write(path, data)?),
+            Target::Standard => py.import("sys")?.getattr("stdout")?.getattr("buffer")?.call_method1("write", (PyBytes::new(py, data),)).map(drop),
+            Target::File(file) => file.call_method1("write", (PyBytes::new(py, data),)).map(drop),
+        }
+    }
+}
+
+/// Encodes `in_file` into `out_file` as a complete file, with the signature of the removed stdlib `uu.encode`.
+/// Either may be a path, `-` for the standard streams, or a binary file object. The name and mode default to
+/// those of an input path, or `-` and `0o666`. Without `backtick`, zeros are written as spaces, as `uu` did.
+#[pyfunction(signature = (in_file, out_file, name = None, mode = None, *, backtick = false))]
+fn encode(py: Python<'_>, in_file: &Bound<'_, PyAny>, out_file: &Bound<'_, PyAny>, name: Option<String>, mode: Option<u32>, backtick: bool) -> PyResult<()> {
+    let input = Target::new(in_file);
+    let (default_name, default_mode) = match &input {
+        Target::Path(path) => {
+            let file_options = FileOptions::from_path(path)?;
+            (file_options.name, file_options.mode)
+        }
+        _ => ("-".to_string(), 0o666),
+    };
+    let data = input.read(py)?;
+    let mut encoding = EncodeOptions::new();
+    if !backtick {
+        encoding = encoding.table(Table::SPACE_ZERO);
+    }
+    let options = FileOptions::new(name.unwrap_or(default_name)).mode(mode.unwrap_or(default_mode) & 0o777).encoding(encoding);
+    let encoded = uuencode_file(&data, &options).map_err(py_error)?;
+    Target::new(out_file).write(py, encoded.as_bytes())
+}
+
+/// Decodes the first file in `in_file` into `out_file`, with the signature of the removed stdlib `uu.decode`.
+/// Either may be a path, `-` for the standard streams, or a binary file object. Without `out_file`, the file is
+/// written to the name from its `begin` line, which must be a plain relative path to a file which doesn't exist.
+/// The mode defaults to the one from the `begin` line, and is applied to output paths. Malformed input always
+/// raises `Error`, so `quiet` is accepted only for compatibility.
+#[pyfunction(signature = (in_file, out_file = None, mode = None, quiet = false))]
+fn decode(py: Python<'_>, in_file: &Bound<'_, PyAny>, out_file: Option<&Bound<'_, PyAny>>, mode: Option<u32>, quiet: bool) -> PyResult<()> {
+    let _ = quiet;
+    let file = uudecode_file(&Target::new(in_file).read(py)?).map_err(py_error)?;
+    let output = match out_file {
+        Some(out_file) => Target::new(out_file),
+        None if !is_safe_file_name(&file.name) => return Err(Error::new_err(std::format!("Refusing to write to unsafe file name: {}", file.name))),
+        None if std::path::Path::new(&file.name).exists() => return Err(Error::new_err(std::format!("Cannot overwrite existing file: {}", file.name))),
+        None => Target::Path(PathBuf::from(&file.name)),
+    };
+    output.write(py, &file.data)?;
+    if let Target::Path(path) = &output {
+        apply_mode(path, mode.unwrap_or(file.mode))?;
+    }
+    Ok(())
+}
+
+/// Incrementally encodes a complete file from data that arrives in pieces, as `Encoder`.
+#[pyclass(name = "Encoder")]
+pub struct PyEncoder {
+    encoder: Encoder,
+}
+
+#[pymethods]
+impl PyEncoder {
+    #[new]
+    #[pyo3(signature = (name, mode = 0o644))]
+    fn new(name: &str, mode: u32) -> PyResult<Self> {
+        Ok(PyEncoder { encoder: Encoder::new(FileOptions::new(name).mode(mode)).map_err(py_error)? })
+    }
+
+    /// Encodes the data, returning the text of the lines it completed.
+    fn push(&mut self, data: &[u8]) -> PyResult<String> {
+        let mut encoded = String::new();
+        self.encoder.push(data, &mut encoded).map_err(py_error)?;
+        Ok(encoded)
+    }
+
+    /// Returns the text of the last partial line and the end of the file.
+    fn finish(&mut self) -> PyResult<String> {
+        let mut encoded = String::new();
+        self.encoder.finish(&mut encoded).map_err(py_error)?;
+        Ok(encoded)
+    }
+}
+
+/// Incrementally decodes a complete file from text that arrives in pieces, as `Decoder`.
+#[pyclass(name = "Decoder")]
+pub struct PyDecoder {
+    decoder: Decoder,
+}
+
+#[pymethods]
+impl PyDecoder {
+    #[new]
+    fn new() -> Self {
+        PyDecoder { decoder: Decoder::new(DecodeOptions::new()) }
+    }
+
+    /// Decodes the next piece of text, given as `bytes` or `str`, returning the bytes decoded from the lines it completed.
+    fn push<'py>(&mut self, py: Python<'py>, text_obj: &Bound<'py, PyAny>) -> PyResult<Bound<'py, PyBytes>> {
+        let mut decoded = Vec::new();
+        self.decoder.push(&text(text_obj)?, &mut decoded).map_err(py_error)?;
+        Ok(PyBytes::new(py, &decoded))
+    }
+
+    /// Decodes any last line without a newline, returning its bytes, and checks that the file was complete.
+    fn finish<'py>(&mut self, py: Python<'py>) -> PyResult<Bound<'py, PyBytes>> {
+        let mut decoded = Vec::new();
+        self.decoder.finish(&mut decoded).map_err(py_error)?;
+        Ok(PyBytes::new(py, &decoded))
+    }
+
+    /// The `(name, mode)` from the `begin` line, or `None` if it has not been seen yet.
+    #[getter]
+    fn header(&self) -> Option<(String, u32)> {
+        self.decoder.header().map(|(mode, name)| (name.to_string(), mode))
+    }
+
+    /// Whether the end of the file has been reached.
+    #[getter]
+    fn is_done(&self) -> bool {
+        self.decoder.is_done()
+    }
+}
+
+/// The `uuencode_lite` Python module. Build it with maturin, which enables the `python` feature.
+#[pymodule]
+#[pyo3(name = "uuencode_lite")]
+pub fn python_module(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add("Error", m.py().get_type::<Error>())?;
+    m.add_function(wrap_pyfunction!(py_uuencode, m)?)?;
+    m.add_function(wrap_pyfunction!(py_uudecode, m)?)?;
+    m.add_function(wrap_pyfunction!(py_uuencode_file, m)?)?;
+    m.add_function(wrap_pyfunction!(py_uudecode_file, m)?)?;
+    m.add_function(wrap_pyfunction!(encode, m)?)?;
+    m.add_function(wrap_pyfunction!(decode, m)?)?;
+    m.add_class::<PyEncoder>()?;
+    m.add_class::<PyDecoder>()?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pyo3::types::PyDict;
+
+    /// Runs Python code with the module imported as `uu`, and the test data as `source_data`.
+    fn run_python(code: &core::ffi::CStr) {
+        Python::initialize();
+        Python::attach(|py| {
+            let module = PyModule::new(py, "uuencode_lite")?;
+            python_module(&module)?;
+            let globals = PyDict::new(py);
+            globals.set_item("uu", module)?;
+            globals.set_item("source_data", PyBytes::new(py, &std::fs::read("test_data/the_machine_stops.txt")?))?;
+            py.run(code, Some(&globals), None)
+        })
+        .unwrap_or_else(|err| panic!("{}", err));
+    }
+
+    /// Tests the functions, as a stdlib `uu` replacement would use them
+    #[test]
+    fn test_python_functions() {
+        run_python(c"
+import io
+assert uu.uudecode(uu.uuencode(b'cat')) == b'cat', 'can encode and decode'
+assert uu.uudecode('#8V%T') == b'cat', 'can decode a str'
+assert uu.uudecode_file(uu.uuencode_file(b'cat', 'cat.txt')) == ('cat.txt', 0o644, b'cat'), 'can encode and decode a file'
+try:
+    uu.uudecode(b'#8V\\x01T')
+    assert False, 'rejects invalid input'
+except uu.Error:
+    pass
+
+encoded = io.BytesIO()
+uu.encode(io.BytesIO(source_data), encoded, name='the_machine_stops.txt', mode=0o600)
+assert encoded.getvalue().startswith(b'begin 600 the_machine_stops.txt\\n'), 'writes the begin line'
+assert encoded.getvalue().endswith(b'\\n \\nend\\n'), 'writes zeros as spaces'
+decoded = io.BytesIO()
+uu.decode(io.BytesIO(encoded.getvalue()), decoded)
+assert decoded.getvalue() == source_data, 'can encode and decode file objects'
+
+backtick = io.BytesIO()
+uu.encode(io.BytesIO(source_data), backtick, backtick=True)
+assert backtick.getvalue().endswith(b'\\n`\\nend\\n'), 'writes zeros as backticks'
+");
+    }
+
+    /// Tests the streaming classes
+    #[test]
+    fn test_python_classes() {
+        run_python(c"
+encoder = uu.Encoder('the_machine_stops.txt', 0o600)
+encoded = ''.join(encoder.push(source_data[i:i + 100]) for i in range(0, len(source_data), 100)) + encoder.finish()
+decoder = uu.Decoder()
+assert decoder.header is None, 'has no header before the begin line'
+decoded = b''.join(decoder.push(encoded[i:i + 100]) for i in range(0, len(encoded), 100)) + decoder.finish()
+assert decoded == source_data, 'can encode and decode in pieces'
+assert decoder.header == ('the_machine_stops.txt', 0o600), 'reports the begin line'
+assert decoder.is_done, 'reports the end of the file'
+");
+    }
+}
@@ -0,0 +1,133 @@
+use alloc::string::String;
+use alloc::vec::Vec;
+use crate::UUEncodeError;
+
+/// The longest encoded line allowed by RFC 2045, including a trailing `=` for a soft line break.
+const LINE_LEN: usize = 76;
+
+const HEX_DIGITS: &[u8; 16] = b"0123456789ABCDEF";
+
+/// Encodes the input data as quoted-printable (RFC 2045).
+/// Line breaks in the input (LF or CRLF) are kept as they are; longer lines are wrapped with soft line breaks (`=\n`)
+/// so no encoded line exceeds 76 characters. Whitespace at the end of a line is encoded so it survives transport.
+/// Example:
+/// ```rust
+/// assert_eq!(uuencode_lite::quoted_printable_encode(b"x = 1 \nend"), "x =3D 1=20\nend");
+/// ```
+pub fn quoted_printable_encode(data: &[u8]) -> String {
+    let mut encoded = String::with_capacity(data.len() + data.len() / 8);
+    let mut lines = data.split(|&ch| ch == b'\n').peekable();
+    while let Some(line) = lines.next() {
+        let hard_break = lines.peek().is_some();
+        let (line, crlf) = match line.strip_suffix(b"\r") {
+            Some(line) if hard_break => (line, true),
+            _ => (line, false),
+        };
+        let mut line_len = 0;
+        for (idx, &ch) in line.iter().enumerate() {
+            let at_end = idx + 1 == line.len();
+            let literal = matches!(ch, b'!'..=b'<' | b'>'..=b'~') || (matches!(ch, b' ' | b'\t') && !at_end);
+            let width = if literal { 1 } else { 3 };
+            // leave room for the soft break, unless this is the last character of the line
+            if line_len + width > LINE_LEN - 1 && !(at_end && line_len + width <= LINE_LEN) {
+                encoded.push_str("=\n");
+                line_len = 0;
+            }
+            if literal {
+                encoded.push(ch as char);
+            } else {
+                encoded.push('=');
+                encoded.push(HEX_DIGITS[(ch >> 4) as usize] as char);
+                encoded.push(HEX_DIGITS[(ch & 0xF) as usize] as char);
+            }
+            line_len += width;
+        }
+        if hard_break {
+            encoded.push_str(if crlf { "\r\n" } else { "\n" });
+        }
+    }
+    encoded
+}
+
+/// Decodes quoted-printable (RFC 2045) text.
+/// Hard line breaks are kept as they are (LF or CRLF), soft line breaks are removed, and whitespace added to the
+/// end of lines in transport is ignored. Lowercase hex digits are accepted.
+/// Errors report the line and character of the offending input.
+/// Example:
+/// ```rust
+/// fn decode() -> Result<(), uuencode_lite::UUEncodeError> {
+///     let decoded = uuencode_lite::quoted_printable_decode(b"begin 644 cat.txt\n#8V%T\n=60\nend=\n\n")?;
+///     assert_eq!(decoded, b"begin 644 cat.txt\n#8V%T\n`\nend\n");
+///     Ok(())
+/// }
+/// ```
+pub fn quoted_printable_decode(data: &[u8]) -> Result<Vec<u8>, UUEncodeError> {
+    let mut decoded = Vec::with_capacity(data.len());
+    let mut lines = data.split(|&ch| ch == b'\n').enumerate().peekable();
+    while let Some((cur_line, line)) = lines.next() {
+        let hard_break = lines.peek().is_some();
+        let (line, crlf) = match line.strip_suffix(b"\r") {
+            Some(line) => (line, true),
+            None => (line, false),
+        };
+        let line = line.trim_ascii_end();
+        let (line, soft_break) = match line.strip_suffix(b"=") {
+            Some(line) => (line, true),
+            None => (line, false),
+        };
+        let mut idx = 0;
+        while idx < line.len() {
+            let ch = line[idx];
+            if ch != b'=' {
+                decoded.push(ch);
+                idx += 1;
+                continue;
+            }
+            let hex = line.get(idx + 1..idx + 3).and_then(|hex| core::str::from_utf8(hex).ok()).and_then(|hex| u8::from_str_radix(hex, 16).ok());
+            let Some(value) = hex else {
+                return Err(UUEncodeError::new(cur_line, idx, "Invalid escape sequence in input"));
+            };
+            decoded.push(value);
+            idx += 3;
+        }
+        if hard_break && !soft_break {
+            decoded.extend_from_slice(if crlf { b"\r\n" } else { b"\n" });
+        }
+    }
+    Ok(decoded)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Tests round-tripping text and binary data
+    #[test]
+    fn test_quoted_printable_rt() {
+        let text = std::fs::read("test_data/the_machine_stops.txt").expect("Can open test data");
+        let binary = std::fs::read("test_data/random_data.bin").expect("Can open test data");
+        for data in [&text[..], &binary[..], b"trailing space \r\nand tab\t", b"=\r\r\n"] {
+            let encoded = quoted_printable_encode(data);
+            assert!(encoded.lines().all(|line| line.len() <= LINE_LEN), "wraps lines at 76 characters");
+            assert_eq!(quoted_printable_decode(encoded.as_bytes()).unwrap(), data, "can round-trip data");
+        }
+    }
+
+    /// Tests unwrapping a quoted-printable layer around a uuencoded file
+    #[test]
+    fn test_quoted_printable_uuencoded() {
+        let source = std::fs::read("test_data/the_machine_stops.txt.uu").expect("Can open test data");
+        let mangled = quoted_printable_encode(&source);
+        assert!(mangled.contains("=3D"), "escapes = in uuencoded lines");
+        assert_eq!(quoted_printable_decode(mangled.as_bytes()).unwrap(), source, "can unwrap the uuencoded file");
+    }
+
+    /// Tests rejection of malformed escapes
+    #[test]
+    fn test_quoted_printable_errors() {
+        assert_eq!(quoted_printable_decode(b"a=3d \n").unwrap(), b"a=\n", "accepts lowercase hex and trailing whitespace");
+        let err = quoted_printable_decode(b"ok\nbad =G1\n").unwrap_err();
+        assert_eq!((err.line, err.character), (1, 4), "reports the position of an invalid escape");
+        assert!(quoted_printable_decode(b"=4").is_err(), "rejects a truncated escape");
+    }
+}
@@ -0,0 +1,216 @@
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use crate::decode_char;
+
+/// The kind of fix that `repair` applied to a line.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RepairKind {
+    /// A mail quote prefix (`>` or `> `) was removed from the start of the line.
+    QuotePrefixRemoved,
+    /// Tab characters were expanded back into spaces, using 8-column tab stops.
+    TabsExpanded,
+    /// The line was shorter than its length character declares, and was padded back out.
+    Padded,
+    /// The line had been wrapped by a transport, and was joined with the following line.
+    Rejoined,
+}
+
+/// A single fix applied by `repair`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Repair {
+    /// The (zero-based) line of the damaged input that was changed.
+    pub line: usize,
+    /// What was done to the line.
+    pub kind: RepairKind,
+}
+
+/// Returns the number of characters a body line declaring `len` decoded bytes should have,
+/// including the length character itself.
+#[inline]
+pub(crate) fn encoded_line_len(len: usize) -> usize {
+    1 + len.div_ceil(3) * 4
+}
+
+/// Returns the declared decoded length of a line, if the line looks like a body line.
+/// Framing lines (`begin`, `end`) and blank lines are not body lines.
+fn declared_len(line: &str) -> Option<usize> {
+    let first = *line.as_bytes().first()?;
+    match decode_char(first) {
+        Some(len @ 1..=45) => Some(len as usize),
+        _ => None,
+    }
+}
+
+/// Expands tabs into spaces, using 8-column tab stops.
+fn expand_tabs(line: &str) -> String {
+    let mut expanded = String::with_capacity(line.len() + 8);
+    for ch in line.chars() {
+        if ch == '\t' {
+            let pad = 8 - (expanded.len() % 8);
+            expanded.extend(core::iter::repeat_n(' ', pad));
+        } else {
+            expanded.push(ch);
+        }
+    }
+    expanded
+}
+
+/// Attempts to fix common transport damage to uuencoded text.
+/// The following fixes are applied, in order:
+/// - mail quote prefixes (`> `) shared by every non-blank line are removed,
+/// - tabs are expanded back into spaces,
+/// - lines that were wrapped by a transport are rejoined with the following line,
+/// - lines that had their trailing spaces stripped are padded back out with '`'.
+///
+/// Lines which are not body lines (such as `begin` and `end`) are only subject to the first two fixes.
+/// Returns the repaired text along with a report of every change made. An empty report means the input
+/// was not changed.
+/// Example:
+/// ```rust
+/// fn repair() -> Result<(), uuencode_lite::UUEncodeError> {
+///     let (repaired, report) = uuencode_lite::repair("> #8V%T");
+///     assert_eq!(report.len(), 1);
+///     let decoded = uuencode_lite::uudecode(repaired.as_bytes())?;
+///     println!("{}", String::from_utf8_lossy(&decoded)); // prints "cat"
+///     Ok(())
+/// }
+/// ```
+pub fn repair(data: &str) -> (String, Vec<Repair>) {
+    let mut report = Vec::new();
+    let mut lines: Vec<String> = data.lines().map(str::to_string).collect();
+
+    // Strip quote prefixes, but only if every non-blank line carries one; '>' is a legal length character.
+    for prefix in ["> ", ">"] {
+        while lines.iter().any(|line| !line.is_empty()) && lines.iter().all(|line| line.is_empty() || line.starts_with(prefix)) {
+            for (idx, line) in lines.iter_mut().enumerate() {
+                if !line.is_empty() {
+                    line.drain(..prefix.len());
+                    report.push(Repair { line: idx, kind: RepairKind::QuotePrefixRemoved });
+                }
+            }
+        }
+    }
+
+    for (idx, line) in lines.iter_mut().enumerate() {
+        if line.contains('\t') {
+            *line = expand_tabs(line);
+            report.push(Repair { line: idx, kind: RepairKind::TabsExpanded });
+        }
+    }
+
+    let mut repaired = Vec::with_capacity(lines.len());
+    let mut idx = 0;
+    while idx < lines.len() {
+        let mut line = core::mem::take(&mut lines[idx]);
+        let cur_line = idx;
+        idx += 1;
+        let Some(expected) = declared_len(&line).map(encoded_line_len) else {
+            repaired.push(line);
+            continue;
+        };
+        if line.len() < expected {
+            // A wrapped line and its continuation add up to (about) the expected length.
+            // Allow for one extra character, as some encoders append a checksum character.
+            let next_len = lines.get(idx).map_or(0, |next| next.len());
+            if next_len > 0 && (expected..=expected + 1).contains(&(line.len() + next_len)) {
+                line.push_str(&lines[idx]);
+                idx += 1;
+                report.push(Repair { line: cur_line, kind: RepairKind::Rejoined });
+            } else {
+                line.extend(core::iter::repeat_n('`', expected - line.len()));
+                report.push(Repair { line: cur_line, kind: RepairKind::Padded });
+            }
+        }
+        repaired.push(line);
+    }
+
+    let mut output = repaired.join("\n");
+    if data.ends_with('\n') {
+        output.push('\n');
+    }
+    #[cfg(feature = "metrics")]
+    if let Some(metrics) = crate::metrics::installed() {
+        report.iter().for_each(|repair| metrics.repaired(repair));
+    }
+    (output, report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::format;
+    use alloc::vec;
+    use crate::{uudecode, uuencode};
+
+    /// Tests that undamaged input passes through untouched
+    #[test]
+    fn test_repair_clean() {
+        let encoded = uuencode(b"The quick brown fox jumps over the lazy dog, twice over!").unwrap();
+        let (repaired, report) = repair(&encoded);
+        assert_eq!(repaired, encoded, "does not alter clean input");
+        assert!(report.is_empty(), "reports no changes for clean input");
+    }
+
+    /// Tests that stripped trailing spaces are padded back out
+    #[test]
+    fn test_repair_padding() {
+        // "a\0\0\0" encodes with trailing spaces when the encoder uses ' ' for zero
+        let (repaired, report) = repair("$80");
+        assert_eq!(repaired, "$80``````", "pads a stripped line");
+        assert_eq!(report, vec![Repair { line: 0, kind: RepairKind::Padded }]);
+        assert_eq!(uudecode(repaired.as_bytes()).unwrap(), b"a\0\0\0", "can decode the padded line");
+    }
+
+    /// Tests removal of quote prefixes, tab expansion and rejoining of wrapped lines
+    #[test]
+    fn test_repair_mangled() {
+        let data: Vec<u8> = (0u8..90).collect();
+        let encoded = uuencode(&data).unwrap();
+        let first = encoded.lines().next().unwrap();
+        let second = encoded.lines().nth(1).unwrap();
+        let mangled = format!("> {}\n> {}\n> {}\n", &first[..30], &first[30..], second);
+        let (repaired, report) = repair(&mangled);
+        assert_eq!(repaired, format!("{}\n", encoded), "rejoins wrapped lines after removing quotes");
+        assert!(report.contains(&Repair { line: 0, kind: RepairKind::Rejoined }), "reports the rejoined line");
+        assert_eq!(report.iter().filter(|r| r.kind == RepairKind::QuotePrefixRemoved).count(), 3, "reports every unquoted line");
+
+        let (repaired, report) = repair("#8V\t");
+        assert_eq!(repaired, "#8V     ", "expands tabs to the next tab stop");
+        assert_eq!(report, vec![Repair { line: 0, kind: RepairKind::TabsExpanded }]);
+    }
+
+    proptest::proptest! {
+        /// Tests that repairing is idempotent: repaired text, damaged or not, is left as it is by a second repair.
+        /// The text is framed by `begin` and `end`, as a whole file is: without them, a line rejoined with its
+        /// continuation can be the only line, and one whose length character is '>' then looks like a quote
+        #[test]
+        fn test_proptest_idempotent(body in "([ -~\t]{0,70}\n){0,8}", quote: bool) {
+            let data: String = format!("begin 644 data.bin\n{}end\n", body)
+                .lines()
+                .map(|line| if quote { format!("> {}\n", line) } else { format!("{}\n", line) })
+                .collect();
+            let (repaired, _) = repair(&data);
+            let (again, report) = repair(&repaired);
+            proptest::prop_assert_eq!(&again, &repaired);
+            proptest::prop_assert!(report.is_empty(), "reports changes to repaired text: {:?}", report);
+        }
+
+        /// Tests that wrapped or stripped files, quoted or not, are repaired back to something which decodes to the
+        /// original data
+        #[test]
+        fn test_proptest_damaged(data in proptest::collection::vec(proptest::prelude::any::<u8>(), 0..256), wrap in proptest::option::of(2usize..60), quote: bool) {
+            let encoded = crate::uuencode_file(&data, &crate::FileOptions::new("data.bin")).unwrap();
+            let damaged: Vec<String> = encoded
+                .lines()
+                .flat_map(|line| match wrap {
+                    Some(wrap) if wrap < line.len() && declared_len(line).is_some() => vec![&line[..wrap], &line[wrap..]],
+                    Some(_) => vec![line],
+                    None => vec![line.trim_end_matches('`')],
+                })
+                .map(|part| if quote { format!("> {}\n", part) } else { format!("{}\n", part) })
+                .collect();
+            let (repaired, _) = repair(&damaged.concat());
+            proptest::prop_assert_eq!(crate::uudecode_file(repaired.as_bytes()).unwrap().data, data);
+        }
+    }
+}
@@ -0,0 +1,169 @@
+use alloc::vec::Vec;
+use crate::{uudecode, uuencode};
+use ::serde::de::{self, Deserializer, Visitor};
+use ::serde::ser::{self, Serialize, Serializer};
+
+/// Serializes bytes as a string of bare uuencoded lines, as `uuencode`.
+pub fn serialize<T: AsRef<[u8]> + ?Sized, S: Serializer>(bytes: &T, serializer: S) -> Result<S::Ok, S::Error> {
+    let encoded = uuencode(bytes.as_ref()).map_err(ser::Error::custom)?;
+    serializer.serialize_str(&encoded)
+}
+
+/// Deserializes bytes from a string of bare uuencoded lines, as `uudecode`.
+pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<u8>, D::Error> {
+    deserializer.deserialize_str(UUDecodeVisitor)
+}
+
+/// Decodes a string, whether it is borrowed from the input or not.
+struct UUDecodeVisitor;
+
+impl Visitor<'_> for UUDecodeVisitor {
+    type Value = Vec<u8>;
+
+    fn expecting(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.write_str("a uuencoded string")
+    }
+
+    fn visit_str<E: de::Error>(self, v: &str) -> Result<Vec<u8>, E> {
+        uudecode(v.as_bytes()).map_err(E::custom)
+    }
+}
+
+/// The same, for `Option<Vec<u8>>` fields: `None` is serialized as a missing value rather than an empty string.
+/// Example:
+/// ```rust
+/// #[derive(serde::Serialize, serde::Deserialize)]
+/// struct Config {
+///     #[serde(with = "uuencode_lite::serde::option", default)]
+///     key: Option<Vec<u8>>,
+/// }
+/// ```
+pub mod option {
+    use super::*;
+
+    /// Serializes the bytes, if there are any, as a string of bare uuencoded lines.
+    pub fn serialize<T: AsRef<[u8]>, S: Serializer>(bytes: &Option<T>, serializer: S) -> Result<S::Ok, S::Error> {
+        match bytes {
+            Some(bytes) => serializer.serialize_some(&Encoded(bytes.as_ref())),
+            None => serializer.serialize_none(),
+        }
+    }
+
+    /// Deserializes the bytes, if there are any, from a string of bare uuencoded lines.
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<Vec<u8>>, D::Error> {
+        deserializer.deserialize_option(OptionVisitor)
+    }
+
+    struct Encoded<'a>(&'a [u8]);
+
+    impl Serialize for Encoded<'_> {
+        fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+            super::serialize(self.0, serializer)
+        }
+    }
+
+    struct OptionVisitor;
+
+    impl<'de> Visitor<'de> for OptionVisitor {
+        type Value = Option<Vec<u8>>;
+
+        fn expecting(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+            f.write_str("an optional uuencoded string")
+        }
+
+        fn visit_none<E: de::Error>(self) -> Result<Self::Value, E> {
+            Ok(None)
+        }
+
+        fn visit_unit<E: de::Error>(self) -> Result<Self::Value, E> {
+            Ok(None)
+        }
+
+        fn visit_some<D: Deserializer<'de>>(self, deserializer: D) -> Result<Self::Value, D::Error> {
+            super::deserialize(deserializer).map(Some)
+        }
+    }
+}
+
+/// A `serde_with` adapter which stores byte fields as strings of bare uuencoded lines, as the functions in this
+/// module do. It also works inside containers, such as `Option<UuEncodedBytes>` or `Vec<UuEncodedBytes>`.
+/// Example:
+/// ```rust
+/// #[serde_with::serde_as]
+/// #[derive(serde::Serialize, serde::Deserialize)]
+/// struct Config {
+///     #[serde_as(as = "uuencode_lite::UuEncodedBytes")]
+///     key: Vec<u8>,
+///     #[serde_as(as = "Option<uuencode_lite::UuEncodedBytes>")]
+///     salt: Option<Vec<u8>>,
+/// }
+/// ```
+#[cfg(feature = "serde_with")]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct UuEncodedBytes;
+
+#[cfg(feature = "serde_with")]
+impl<T: AsRef<[u8]>> serde_with::SerializeAs<T> for UuEncodedBytes {
+    fn serialize_as<S: Serializer>(source: &T, serializer: S) -> Result<S::Ok, S::Error> {
+        serialize(source, serializer)
+    }
+}
+
+#[cfg(feature = "serde_with")]
+impl<'de, T: From<Vec<u8>>> serde_with::DeserializeAs<'de, T> for UuEncodedBytes {
+    fn deserialize_as<D: Deserializer<'de>>(deserializer: D) -> Result<T, D::Error> {
+        deserialize(deserializer).map(T::from)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::string::String;
+    use alloc::vec::Vec;
+
+    #[derive(Debug, PartialEq, ::serde::Serialize, ::serde::Deserialize)]
+    struct Config {
+        name: String,
+        #[serde(with = "crate::serde")]
+        key: Vec<u8>,
+        #[serde(with = "crate::serde::option", default)]
+        salt: Option<Vec<u8>>,
+    }
+
+    /// Tests round-tripping byte fields through JSON, with and without the optional field
+    #[test]
+    fn test_serde_fields() {
+        let config = Config { name: "cat".into(), key: b"cat".to_vec(), salt: Some(b"dog".to_vec()) };
+        let json = serde_json::to_string(&config).unwrap();
+        assert_eq!(json, r##"{"name":"cat","key":"#8V%T","salt":"#9&]G"}"##, "serializes bytes as uuencoded strings");
+        assert_eq!(serde_json::from_str::<Config>(&json).unwrap(), config, "can round-trip");
+
+        let config = Config { salt: None, ..config };
+        assert_eq!(serde_json::from_str::<Config>(&serde_json::to_string(&config).unwrap()).unwrap(), config, "can round-trip a null field");
+        assert_eq!(serde_json::from_str::<Config>(r##"{"name":"cat","key":"#8V%T"}"##).unwrap(), config, "can omit the optional field");
+        assert!(serde_json::from_str::<Config>(r##"{"name":"cat","key":"#8V\u0001T"}"##).is_err(), "rejects invalid uuencoded text");
+
+        assert_eq!(serde_json::from_reader::<_, Config>(json.as_bytes()).unwrap(), Config { salt: Some(b"dog".to_vec()), ..config }, "can decode strings which are not borrowed");
+    }
+
+    /// Tests the serde_with adapter, including inside an Option
+    #[cfg(feature = "serde_with")]
+    #[test]
+    fn test_serde_as() {
+        #[serde_with::serde_as]
+        #[derive(Debug, PartialEq, ::serde::Serialize, ::serde::Deserialize)]
+        struct Config {
+            #[serde_as(as = "super::UuEncodedBytes")]
+            key: Vec<u8>,
+            #[serde_as(as = "Option<super::UuEncodedBytes>")]
+            salt: Option<Vec<u8>>,
+        }
+
+        let config = Config { key: b"cat".to_vec(), salt: Some(b"dog".to_vec()) };
+        let json = serde_json::to_string(&config).unwrap();
+        assert_eq!(json, r##"{"key":"#8V%T","salt":"#9&]G"}"##, "serializes bytes as uuencoded strings");
+        assert_eq!(serde_json::from_str::<Config>(&json).unwrap(), config, "can round-trip");
+        let config = Config { salt: None, ..config };
+        assert_eq!(serde_json::from_str::<Config>(&serde_json::to_string(&config).unwrap()).unwrap(), config, "can round-trip a missing value");
+    }
+}
@@ -0,0 +1,245 @@
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use crate::file::numbered_lines;
+use crate::{is_safe_file_name, uudecode_file_with, uuencode_file, DecodeOptions, DecodedFile, FileOptions, UUEncodeError};
+
+/// The delimiter used for the here-documents in generated archives.
+/// It can never be mistaken for a line of a uuencoded or Base64 body.
+const DELIMITER: &str = "SHAR_EOF";
+
+/// Quotes a word for a POSIX shell, using single quotes.
+fn shell_quote(word: &str) -> String {
+    format!("'{}'", word.replace('\'', "'\\''"))
+}
+
+/// Builds a self-extracting POSIX shell archive holding each file as a uuencoded member.
+/// Running the archive with `sh` recreates each file, with its mode, using the system `uudecode`.
+/// File names must be safe relative paths (see `is_safe_file_name`); missing directories are created.
+/// Names are listed unquoted in the archive's comments, which is only safe because they hold no line breaks.
+/// Example:
+/// ```rust
+/// fn archive() -> Result<(), uuencode_lite::UUEncodeError> {
+///     let files = [(uuencode_lite::FileOptions::new("cat.txt"), &b"cat"[..])];
+///     let archive = uuencode_lite::shar_archive(&files)?;
+///     assert!(archive.starts_with("#!/bin/sh\n"));
+///     assert!(archive.contains("uudecode << 'SHAR_EOF'\nbegin 644 cat.txt\n#8V%T\n`\nend\nSHAR_EOF\n"));
+///     Ok(())
+/// }
+/// ```
+pub fn shar_archive(files: &[(FileOptions, &[u8])]) -> Result<String, UUEncodeError> {
+    let mut archive = String::from("#!/bin/sh\n# This is a shell archive.\n# To extract the files, save it to some FILE, remove everything before\n# the '#!/bin/sh' line above, then type 'sh FILE'.\n#\n# Contents:\n");
+    for (file, _) in files {
+        if !is_safe_file_name(file.name()) {
+            return Err(UUEncodeError::new(0, 0, format!("Unsafe file name for archive: {:?}", file.name())));
+        }
+        archive.push_str(&format!("#   {}\n", file.name()));
+    }
+    archive.push_str("#\n");
+
+    for (file, data) in files {
+        let name = shell_quote(file.name());
+        archive.push_str(&format!("echo x - {}\n", name));
+        if let Some((dir, _)) = file.name().rsplit_once('/') {
+            archive.push_str(&format!("mkdir -p {}\n", shell_quote(dir)));
+        }
+        archive.push_str(&format!("uudecode << '{}'\n", DELIMITER));
+        archive.push_str(&uuencode_file(data, file)?);
+        archive.push_str(DELIMITER);
+        archive.push('\n');
+    }
+    archive.push_str("exit 0\n");
+    Ok(archive)
+}
+
+/// Parses one shell word from the start of `text`, returning it and the remaining text.
+/// Handles single quotes, double quotes and backslash escapes, but not expansions.
+fn parse_word(text: &str) -> Option<(String, &str)> {
+    let text = text.trim_start();
+    let mut word = String::new();
+    let mut chars = text.char_indices().peekable();
+    while let Some(&(idx, ch)) = chars.peek() {
+        match ch {
+            ' ' | '\t' | '<' | '>' | '|' | ';' | '&' => return (!word.is_empty()).then(|| (word, &text[idx..])),
+            '\'' => {
+                chars.next();
+                word.extend(chars.by_ref().map(|(_, ch)| ch).take_while(|&ch| ch != '\''));
+            }
+            '"' => {
+                chars.next();
+                while let Some((_, ch)) = chars.next() {
+                    match ch {
+                        '"' => break,
+                        '\\' if matches!(chars.peek(), Some((_, '"' | '\\' | '$' | '`'))) => word.push(chars.next()?.1),
+                        _ => word.push(ch),
+                    }
+                }
+            }
+            '\\' => {
+                chars.next();
+                word.push(chars.next()?.1);
+            }
+            _ => {
+                chars.next();
+                word.push(ch);
+            }
+        }
+    }
+    (!word.is_empty()).then_some((word, ""))
+}
+
+/// A here-document found in a shell archive, with the command that consumes it.
+struct HereDoc {
+    /// The here-document delimiter.
+    delimiter: String,
+    /// Whether leading tabs are stripped from the body (`<<-`).
+    strip_tabs: bool,
+    /// The prefix `sed 's/^X//'` strips from each line, if any.
+    strip_prefix: Option<String>,
+    /// Whether the body is piped into `uudecode`.
+    uudecode: bool,
+    /// The file the body is redirected into, if any.
+    output: Option<String>,
+}
+
+/// Recognizes a command line that feeds a here-document into `uudecode`, `sed` or `cat`.
+fn parse_heredoc(line: &str) -> Option<HereDoc> {
+    let (command, rest) = line.split_once("<<")?;
+    let (strip_tabs, rest) = match rest.strip_prefix('-') {
+        Some(rest) => (true, rest),
+        None => (false, rest),
+    };
+    let (delimiter, after) = parse_word(rest)?;
+    let command = format!("{} {}", command, after);
+
+    let uudecode = command.split_whitespace().any(|word| word == "uudecode");
+    let strip_prefix = command.find("sed ").and_then(|idx| parse_word(&command[idx + 4..])).and_then(|(script, _)| {
+        let pattern = script.strip_prefix("s/^")?.strip_suffix("//")?;
+        (!pattern.is_empty() && !pattern.contains(['/', '\\', '.', '*', '['])).then(|| pattern.to_string())
+    });
+    let output = command.find('>').and_then(|idx| parse_word(&command[idx + 1..])).map(|(name, _)| name);
+    if !uudecode && output.is_none() {
+        return None;
+    }
+    Some(HereDoc { delimiter, strip_tabs, strip_prefix, uudecode, output })
+}
+
+/// Extracts the files from a shell archive without executing any of it.
+/// Members are recognized by their here-documents: those fed to `uudecode` are decoded as uuencoded
+/// files, while those redirected into a file (as `sed 's/^X//' > name << 'EOF'` or `cat > name << 'EOF'`)
+/// are extracted as plain text. A later `chmod <octal mode> name` line sets the mode of a plain member.
+/// File names are checked as they are for `uudecode_file_with`.
+/// Example:
+/// ```rust
+/// let archive = b"#!/bin/sh\nsed 's/^X//' > notes.txt << 'EOF'\nXhello\nEOF\nchmod 600 notes.txt\n";
+/// let files = uuencode_lite::extract_shar(archive, &uuencode_lite::DecodeOptions::new());
+/// let notes = files[0].as_ref().unwrap();
+/// assert_eq!((notes.name.as_str(), notes.mode, notes.data.as_slice()), ("notes.txt", 0o600, &b"hello\n"[..]));
+/// ```
+pub fn extract_shar(data: &[u8], options: &DecodeOptions) -> Vec<Result<DecodedFile, UUEncodeError>> {
+    let mut files: Vec<Result<DecodedFile, UUEncodeError>> = Vec::new();
+    let mut lines = numbered_lines(data);
+    while let Some((cur_line, line)) = lines.next() {
+        let line = String::from_utf8_lossy(line);
+        if let Some(rest) = line.trim_start().strip_prefix("chmod ") {
+            // apply modes to plain members; uuencoded members carry their own
+            let mut words = rest.split_whitespace();
+            if let (Some(Ok(mode)), Some((name, _))) = (words.next().map(|mode| u32::from_str_radix(mode, 8)), words.next().and_then(parse_word))
+                && let Some(Ok(file)) = files.iter_mut().rev().find(|file| file.as_ref().is_ok_and(|file| file.name == name))
+            {
+                file.mode = mode & 0o7777;
+            }
+            continue;
+        }
+        let Some(heredoc) = parse_heredoc(&line) else {
+            continue;
+        };
+
+        let mut body = Vec::new();
+        let mut terminated = false;
+        for (_, line) in lines.by_ref() {
+            let line = if heredoc.strip_tabs { line.trim_ascii_start() } else { line };
+            if line == heredoc.delimiter.as_bytes() {
+                terminated = true;
+                break;
+            }
+            let line = match &heredoc.strip_prefix {
+                Some(prefix) => line.strip_prefix(prefix.as_bytes()).unwrap_or(line),
+                None => line,
+            };
+            body.extend_from_slice(line);
+            body.push(b'\n');
+        }
+        if !terminated {
+            files.push(Err(UUEncodeError::new(cur_line, 0, format!("Missing here-document delimiter {}", heredoc.delimiter))));
+            break;
+        }
+
+        files.push(match (heredoc.uudecode, heredoc.output) {
+            (true, _) => uudecode_file_with(&body, options).map_err(|err| UUEncodeError::new(cur_line + 1 + err.line, err.character, err.msg)),
+            (false, Some(name)) if !options.raw_names && !is_safe_file_name(&name) => Err(UUEncodeError::new(cur_line, 0, format!("Unsafe file name in archive: {:?}", name))),
+            (false, Some(name)) => Ok(DecodedFile { name, mode: 0o644, data: body }),
+            (false, None) => unreachable!("here-documents are only recognized with uudecode or an output file"),
+        });
+    }
+    files
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Tests round-tripping files through an archive
+    #[test]
+    fn test_shar_rt() {
+        let source_data = std::fs::read("test_data/random_data.bin").expect("Can open test data");
+        let files = [(FileOptions::new("data/random_data.bin").mode(0o600), &source_data[..]), (FileOptions::new("it's.txt"), &b"cat"[..])];
+        let archive = shar_archive(&files).unwrap();
+        assert!(archive.contains("mkdir -p 'data'\n"), "creates directories");
+        assert!(archive.contains("echo x - 'it'\\''s.txt'\n"), "quotes names");
+        let extracted = extract_shar(archive.as_bytes(), &DecodeOptions::new());
+        assert_eq!(extracted.len(), 2, "extracts every member");
+        assert_eq!(extracted[0].as_ref().unwrap(), &DecodedFile { name: "data/random_data.bin".to_string(), mode: 0o600, data: source_data }, "extracts a binary member");
+        assert_eq!(extracted[1].as_ref().unwrap().name, "it's.txt", "extracts a quoted name");
+        assert!(shar_archive(&[(FileOptions::new("../evil"), &b"cat"[..])]).is_err(), "refuses unsafe names");
+    }
+
+    /// Tests that names which would run as commands are refused or quoted
+    #[test]
+    fn test_shar_hostile_names() {
+        for name in ["x\necho PWNED >&2\n#", "x\recho PWNED", "x\x0becho PWNED"] {
+            let files = [(FileOptions::new(name).encode_name(true), &b"cat"[..])];
+            assert!(shar_archive(&files).is_err(), "refuses {:?}", name);
+        }
+
+        let name = "$(echo PWNED) `id`;.txt";
+        let archive = shar_archive(&[(FileOptions::new(name).encode_name(true), &b"cat"[..])]).unwrap();
+        assert!(archive.contains(&format!("\n#   {}\n#\necho x - '{}'\n", name, name)), "lists the name as a comment and quotes it");
+        assert_eq!(extract_shar(archive.as_bytes(), &DecodeOptions::new())[0].as_ref().unwrap().name, name, "extracts the name");
+    }
+
+    /// Tests extracting the styles of member produced by other shar tools
+    #[test]
+    fn test_extract_shar_styles() {
+        let archive = b"#!/bin/sh\n\
+            echo shar: extracting notes.txt\n\
+            cat > \"notes.txt\" <<\\END_OF_FILE\n\
+            hello\n\
+            END_OF_FILE\n\
+            sed 's/^X//' << 'SHAR_EOF' | uudecode &&\n\
+            Xbegin 755 cat.bin\n\
+            X#8V%T\n\
+            X`\n\
+            Xend\n\
+            SHAR_EOF\n\
+            sed 's/^X//' >'../../evil' <<- EOF\n\
+            \tXrm -rf /\n\
+            \tEOF\n";
+        let extracted = extract_shar(archive, &DecodeOptions::new());
+        assert_eq!(extracted.len(), 3);
+        assert_eq!(extracted[0].as_ref().unwrap().data, b"hello\n", "extracts a cat member");
+        assert_eq!(extracted[1].as_ref().unwrap(), &DecodedFile { name: "cat.bin".to_string(), mode: 0o755, data: b"cat".to_vec() }, "extracts a sed-wrapped uuencoded member");
+        assert!(extracted[2].is_err(), "refuses unsafe names");
+        assert!(extract_shar(b"cat > a << EOF\nhello\n", &DecodeOptions::new())[0].is_err(), "reports an unterminated member");
+    }
+}
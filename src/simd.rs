@@ -0,0 +1,471 @@
+//! Vector kernels for the standard alphabet, encoding or decoding a whole line per call. On x86 the widest of AVX2
+//! and SSSE3 the CPU has is chosen at run time, or at compile time without `std`; on aarch64 NEON is always present.
+//! WebAssembly has no run-time detection, so simd128 is used when the build enables it, with
+//! `RUSTFLAGS="-C target-feature=+simd128"`, which every current browser supports.
+//!
+//! The kernels work on the caller's line in place, never reading or writing past it: the last block of each line
+//! is loaded overlapping the one before, so that one group is converted twice, identically. Partial lines, and
+//! lines the decoders reject, are left to the scalar code.
+
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+use core::sync::atomic::{AtomicU8, Ordering};
+
+/// Full lines, which are all the kernels take.
+pub(crate) use crate::slice::{LINE, LINE_CHARS};
+
+/// Where each block of four groups starts in the line's bytes and in its characters, for the kernels working on
+/// 16-byte registers, the last block ending with the line.
+#[cfg(any(target_arch = "x86", target_arch = "x86_64", all(target_arch = "wasm32", target_feature = "simd128")))]
+const BLOCK_BYTES: [usize; 4] = [0, 12, 24, LINE - 12];
+#[cfg(any(target_arch = "x86", target_arch = "x86_64", all(target_arch = "wasm32", target_feature = "simd128")))]
+const BLOCK_CHARS: [usize; 4] = [0, 16, 32, LINE_CHARS - 16];
+
+/// Where encoding loads each block's 16 bytes. The last load ends with the line, so the block starts 4 bytes
+/// into its register.
+#[cfg(any(target_arch = "x86", target_arch = "x86_64", all(target_arch = "wasm32", target_feature = "simd128")))]
+const ENCODE_LOADS: [usize; 4] = [0, 12, 24, LINE - 16];
+
+/// Moves each group of 3 bytes to the 32-bit word `[b1, b0, b2, b1]`, for a register starting at its first group.
+#[cfg(any(target_arch = "x86", target_arch = "x86_64", all(target_arch = "wasm32", target_feature = "simd128")))]
+const SHUFFLE: [i8; 16] = [1, 0, 2, 1, 4, 3, 5, 4, 7, 6, 8, 7, 10, 9, 11, 10];
+/// As `SHUFFLE`, for the last load, which starts 4 bytes before its first group.
+#[cfg(any(target_arch = "x86", target_arch = "x86_64", all(target_arch = "wasm32", target_feature = "simd128")))]
+const SHUFFLE_LAST: [i8; 16] = [5, 4, 6, 5, 8, 7, 9, 8, 11, 10, 12, 11, 14, 13, 15, 14];
+
+/// Encodes a full line into characters of the standard alphabet, returning false without touching `output` if no
+/// vector unit is available.
+#[inline]
+pub(crate) fn encode_standard_line(input: &[u8; LINE], output: &mut [u8; LINE_CHARS]) -> bool {
+    #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+    {
+        match x86::level() {
+            // SAFETY: the level is only reported when the CPU has the kernel's target features
+            x86::AVX2 => unsafe { x86::encode_avx2(input, output) },
+            x86::SSSE3 => unsafe { x86::encode_ssse3(input, output) },
+            _ => return false,
+        }
+        true
+    }
+    #[cfg(target_arch = "aarch64")]
+    {
+        aarch64::encode_neon(input, output);
+        true
+    }
+    #[cfg(all(target_arch = "wasm32", target_feature = "simd128"))]
+    {
+        wasm32::encode_simd128(input, output);
+        true
+    }
+    #[cfg(not(any(target_arch = "x86", target_arch = "x86_64", target_arch = "aarch64", all(target_arch = "wasm32", target_feature = "simd128"))))]
+    {
+        let _ = (input, output);
+        false
+    }
+}
+
+/// Decodes the characters of a full line, after its length character, returning false if no vector unit is
+/// available or any character is outside the standard alphabet, in which case `output` holds nothing useful and the
+/// line should be decoded by the scalar code, to find the error. NUL, which the standard table accepts as zero, is
+/// rejected here, as only broken encoders pad with it.
+#[inline]
+pub(crate) fn decode_standard_line(input: &[u8; LINE_CHARS], output: &mut [u8; LINE]) -> bool {
+    #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+    {
+        match x86::level() {
+            // SAFETY: the level is only reported when the CPU has the kernel's target features
+            x86::AVX2 => unsafe { x86::decode_avx2(input, output) },
+            x86::SSSE3 => unsafe { x86::decode_ssse3(input, output) },
+            _ => false,
+        }
+    }
+    #[cfg(target_arch = "aarch64")]
+    {
+        aarch64::decode_neon(input, output)
+    }
+    #[cfg(all(target_arch = "wasm32", target_feature = "simd128"))]
+    {
+        wasm32::decode_simd128(input, output)
+    }
+    #[cfg(not(any(target_arch = "x86", target_arch = "x86_64", target_arch = "aarch64", all(target_arch = "wasm32", target_feature = "simd128"))))]
+    {
+        let _ = (input, output);
+        false
+    }
+}
+
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+mod x86 {
+    use super::{AtomicU8, BLOCK_BYTES, BLOCK_CHARS, ENCODE_LOADS, LINE, LINE_CHARS, Ordering, SHUFFLE, SHUFFLE_LAST};
+    #[cfg(target_arch = "x86")]
+    use core::arch::x86::*;
+    #[cfg(target_arch = "x86_64")]
+    use core::arch::x86_64::*;
+
+    pub(super) const NONE: u8 = 1;
+    pub(super) const SSSE3: u8 = 2;
+    pub(super) const AVX2: u8 = 3;
+
+    /// The detected level, or 0 before the first call.
+    static LEVEL: AtomicU8 = AtomicU8::new(0);
+
+    /// The widest instruction set available, detected once.
+    #[inline]
+    pub(super) fn level() -> u8 {
+        match LEVEL.load(Ordering::Relaxed) {
+            0 => {
+                let level = detect();
+                LEVEL.store(level, Ordering::Relaxed);
+                level
+            }
+            level => level,
+        }
+    }
+
+    #[cfg(feature = "std")]
+    fn detect() -> u8 {
+        if std::is_x86_feature_detected!("avx2") {
+            AVX2
+        } else if std::is_x86_feature_detected!("ssse3") {
+            SSSE3
+        } else {
+            NONE
+        }
+    }
+
+    /// Without `std` there is no run-time detection, so only what the build targets is used.
+    #[cfg(not(feature = "std"))]
+    fn detect() -> u8 {
+        if cfg!(target_feature = "avx2") {
+            AVX2
+        } else if cfg!(target_feature = "ssse3") {
+            SSSE3
+        } else {
+            NONE
+        }
+    }
+
+    /// Spreads each shuffled group into four 6-bit values, one per byte, first value lowest. Every step works on
+    /// 16-bit halves, so this serves both widths.
+    macro_rules! unpack {
+        ($and:ident, $mulhi:ident, $mullo:ident, $or:ident, $set1_32:ident, $groups:expr) => {{
+            let groups = $groups;
+            // values 0 and 2 are shifted right into place by taking the high half of a multiplication
+            let high = $mulhi($and(groups, $set1_32(0x0FC0_FC00)), $set1_32(0x0400_0040));
+            // values 1 and 3 are shifted left
+            let low = $mullo($and(groups, $set1_32(0x003F_03F0)), $set1_32(0x0100_0010));
+            $or(high, low)
+        }};
+    }
+
+    /// Maps 6-bit values to characters: adds the offset of the alphabet, then moves zeros up to '`'.
+    macro_rules! to_standard {
+        ($add:ident, $and:ident, $cmpeq:ident, $set1_8:ident, $zero:ident, $values:expr) => {{
+            let values = $values;
+            let zeros = $cmpeq(values, $zero());
+            $add($add(values, $set1_8(0x20)), $and(zeros, $set1_8(0x40)))
+        }};
+    }
+
+    /// Encodes the line 12 bytes at a time, one 16-byte register for each.
+    #[target_feature(enable = "ssse3")]
+    pub(super) fn encode_ssse3(input: &[u8; LINE], output: &mut [u8; LINE_CHARS]) {
+        for block in 0..4 {
+            // SAFETY: every load and store lies within the line, as `ENCODE_LOADS` and `BLOCK_CHARS` are laid out; both are
+            // unaligned, and the shuffles are arrays of 16 bytes
+            let (bytes, shuffle) = unsafe {
+                let shuffle = if block == 3 { &SHUFFLE_LAST } else { &SHUFFLE };
+                (_mm_loadu_si128(input.as_ptr().add(ENCODE_LOADS[block]).cast()), _mm_loadu_si128(shuffle.as_ptr().cast()))
+            };
+            let values = unpack!(_mm_and_si128, _mm_mulhi_epu16, _mm_mullo_epi16, _mm_or_si128, _mm_set1_epi32, _mm_shuffle_epi8(bytes, shuffle));
+            let chars = to_standard!(_mm_add_epi8, _mm_and_si128, _mm_cmpeq_epi8, _mm_set1_epi8, _mm_setzero_si128, values);
+            unsafe { _mm_storeu_si128(output.as_mut_ptr().add(BLOCK_CHARS[block]).cast(), chars) };
+        }
+    }
+
+    /// Encodes the line 24 bytes at a time, 12 in each 128-bit lane, as `pshufb` can't cross lanes.
+    #[target_feature(enable = "avx2")]
+    pub(super) fn encode_avx2(input: &[u8; LINE], output: &mut [u8; LINE_CHARS]) {
+        for pair in 0..2 {
+            let (low, high) = (pair * 2, pair * 2 + 1);
+            // SAFETY: as for `encode_ssse3`
+            let (bytes, shuffle) = unsafe {
+                let shuffle = if high == 3 { &SHUFFLE_LAST } else { &SHUFFLE };
+                let bytes = (_mm_loadu_si128(input.as_ptr().add(ENCODE_LOADS[low]).cast()), _mm_loadu_si128(input.as_ptr().add(ENCODE_LOADS[high]).cast()));
+                (bytes, (_mm_loadu_si128(SHUFFLE.as_ptr().cast()), _mm_loadu_si128(shuffle.as_ptr().cast())))
+            };
+            let bytes = _mm256_inserti128_si256::<1>(_mm256_castsi128_si256(bytes.0), bytes.1);
+            let shuffle = _mm256_inserti128_si256::<1>(_mm256_castsi128_si256(shuffle.0), shuffle.1);
+            let values = unpack!(_mm256_and_si256, _mm256_mulhi_epu16, _mm256_mullo_epi16, _mm256_or_si256, _mm256_set1_epi32, _mm256_shuffle_epi8(bytes, shuffle));
+            let chars = to_standard!(_mm256_add_epi8, _mm256_and_si256, _mm256_cmpeq_epi8, _mm256_set1_epi8, _mm256_setzero_si256, values);
+            // the last two blocks' characters overlap, so the lanes are stored separately
+            unsafe {
+                _mm_storeu_si128(output.as_mut_ptr().add(BLOCK_CHARS[low]).cast(), _mm256_castsi256_si128(chars));
+                _mm_storeu_si128(output.as_mut_ptr().add(BLOCK_CHARS[high]).cast(), _mm256_extracti128_si256::<1>(chars));
+            }
+        }
+    }
+
+    /// Maps characters to 6-bit values, along with a mask of those in the alphabet: ' ' to '`', as offsets of
+    /// 0 to 64 from ' '.
+    macro_rules! from_standard {
+        ($sub:ident, $min:ident, $cmpeq:ident, $and:ident, $set1_8:ident, $chars:expr) => {{
+            let offsets = $sub($chars, $set1_8(0x20));
+            ($and(offsets, $set1_8(0x3F)), $cmpeq($min(offsets, $set1_8(0x40)), offsets))
+        }};
+    }
+
+    /// Packs the four 6-bit values of each group into its 3 bytes: pairs of values into 12 bits, then pairs of those
+    /// into 24, whose bytes `order` then gathers into the low 12 bytes of each lane.
+    macro_rules! pack {
+        ($maddubs:ident, $madd:ident, $shuffle:ident, $set1_32:ident, $values:expr, $order:expr) => {{
+            let pairs = $maddubs($values, $set1_32(0x0140_0140));
+            $shuffle($madd(pairs, $set1_32(0x0001_1000)), $order)
+        }};
+    }
+
+    /// Stores the low 12 bytes of a register.
+    ///
+    /// # Safety
+    /// `output` must be valid for 12 bytes of writes.
+    #[inline(always)]
+    unsafe fn store_12(output: *mut u8, bytes: __m128i) {
+        unsafe {
+            _mm_storel_epi64(output.cast(), bytes);
+            output.add(8).cast::<i32>().write_unaligned(_mm_cvtsi128_si32(_mm_srli_si128::<8>(bytes)));
+        }
+    }
+
+    /// Decodes the line 16 characters at a time, one register for each.
+    #[target_feature(enable = "ssse3")]
+    pub(super) fn decode_ssse3(input: &[u8; LINE_CHARS], output: &mut [u8; LINE]) -> bool {
+        // each group's bytes are in the low 24 bits of its word, highest first
+        let order = _mm_setr_epi8(2, 1, 0, 6, 5, 4, 10, 9, 8, 14, 13, 12, -1, -1, -1, -1);
+        let mut valid = _mm_set1_epi8(-1);
+        for block in 0..4 {
+            // SAFETY: every load and store lies within the line, as `BLOCK_CHARS` and `BLOCK_BYTES` are laid out
+            let chars = unsafe { _mm_loadu_si128(input.as_ptr().add(BLOCK_CHARS[block]).cast()) };
+            let (values, in_alphabet) = from_standard!(_mm_sub_epi8, _mm_min_epu8, _mm_cmpeq_epi8, _mm_and_si128, _mm_set1_epi8, chars);
+            valid = _mm_and_si128(valid, in_alphabet);
+            let bytes = pack!(_mm_maddubs_epi16, _mm_madd_epi16, _mm_shuffle_epi8, _mm_set1_epi32, values, order);
+            unsafe { store_12(output.as_mut_ptr().add(BLOCK_BYTES[block]), bytes) };
+        }
+        _mm_movemask_epi8(valid) == 0xFFFF
+    }
+
+    /// Decodes the line 32 characters at a time, 16 in each 128-bit lane.
+    #[target_feature(enable = "avx2")]
+    pub(super) fn decode_avx2(input: &[u8; LINE_CHARS], output: &mut [u8; LINE]) -> bool {
+        let order = _mm256_setr_epi8(
+            2, 1, 0, 6, 5, 4, 10, 9, 8, 14, 13, 12, -1, -1, -1, -1, 2, 1, 0, 6, 5, 4, 10, 9, 8, 14, 13, 12, -1, -1, -1, -1,
+        );
+        let mut valid = _mm256_set1_epi8(-1);
+        for pair in 0..2 {
+            let (low, high) = (pair * 2, pair * 2 + 1);
+            // SAFETY: as for `decode_ssse3`
+            let chars = unsafe {
+                (_mm_loadu_si128(input.as_ptr().add(BLOCK_CHARS[low]).cast()), _mm_loadu_si128(input.as_ptr().add(BLOCK_CHARS[high]).cast()))
+            };
+            let chars = _mm256_inserti128_si256::<1>(_mm256_castsi128_si256(chars.0), chars.1);
+            let (values, in_alphabet) = from_standard!(_mm256_sub_epi8, _mm256_min_epu8, _mm256_cmpeq_epi8, _mm256_and_si256, _mm256_set1_epi8, chars);
+            valid = _mm256_and_si256(valid, in_alphabet);
+            let bytes = pack!(_mm256_maddubs_epi16, _mm256_madd_epi16, _mm256_shuffle_epi8, _mm256_set1_epi32, values, order);
+            unsafe {
+                store_12(output.as_mut_ptr().add(BLOCK_BYTES[low]), _mm256_castsi256_si128(bytes));
+                store_12(output.as_mut_ptr().add(BLOCK_BYTES[high]), _mm256_extracti128_si256::<1>(bytes));
+            }
+        }
+        _mm256_movemask_epi8(valid) == -1
+    }
+}
+
+#[cfg(target_arch = "aarch64")]
+mod aarch64 {
+    use super::{LINE, LINE_CHARS};
+    use core::arch::aarch64::*;
+
+    /// Encodes the line 8 groups at a time: `ld3` splits 24 bytes into the first, second and third byte of each
+    /// group, and `st4` interleaves the four values of each group back together. The second half starts at
+    /// group 7, so that it ends with the line.
+    pub(super) fn encode_neon(input: &[u8; LINE], output: &mut [u8; LINE_CHARS]) {
+        for (load, store) in [(0, 0), (LINE - 24, LINE_CHARS - 32)] {
+            // SAFETY: NEON is part of every aarch64 target; each load reads 24 bytes within the line and each
+            // store writes 32 characters within the output
+            unsafe {
+                let bytes = vld3_u8(input.as_ptr().add(load));
+                let mask = vdup_n_u8(0x3F);
+                let values = [
+                    vshr_n_u8::<2>(bytes.0),
+                    vand_u8(vorr_u8(vshl_n_u8::<4>(bytes.0), vshr_n_u8::<4>(bytes.1)), mask),
+                    vand_u8(vorr_u8(vshl_n_u8::<2>(bytes.1), vshr_n_u8::<6>(bytes.2)), mask),
+                    vand_u8(bytes.2, mask),
+                ];
+                let chars = values.map(|values| vadd_u8(vadd_u8(values, vdup_n_u8(0x20)), vand_u8(vceqz_u8(values), vdup_n_u8(0x40))));
+                vst4_u8(output.as_mut_ptr().add(store), uint8x8x4_t(chars[0], chars[1], chars[2], chars[3]));
+            }
+        }
+    }
+    /// Decodes the line 8 groups at a time: `ld4` splits 32 characters into the four of each group, and `st3`
+    /// interleaves the three bytes of each group back together. The second half starts at group 7, as in
+    /// `encode_neon`.
+    pub(super) fn decode_neon(input: &[u8; LINE_CHARS], output: &mut [u8; LINE]) -> bool {
+        // SAFETY: NEON is part of every aarch64 target; each load reads 32 characters within the line and each
+        // store writes 24 bytes within the output
+        unsafe {
+            let mut valid = vdup_n_u8(0xFF);
+            for (load, store) in [(0, 0), (LINE_CHARS - 32, LINE - 24)] {
+                let chars = vld4_u8(input.as_ptr().add(load));
+                // the alphabet is ' ' to '`', offsets of 0 to 64 from ' '
+                let offsets = [chars.0, chars.1, chars.2, chars.3].map(|chars| vsub_u8(chars, vdup_n_u8(0x20)));
+                for offsets in offsets {
+                    valid = vand_u8(valid, vcle_u8(offsets, vdup_n_u8(0x40)));
+                }
+                let [a, b, c, d] = offsets.map(|offsets| vand_u8(offsets, vdup_n_u8(0x3F)));
+                let bytes = uint8x8x3_t(
+                    vorr_u8(vshl_n_u8::<2>(a), vshr_n_u8::<4>(b)),
+                    vorr_u8(vshl_n_u8::<4>(b), vshr_n_u8::<2>(c)),
+                    vorr_u8(vshl_n_u8::<6>(c), d),
+                );
+                vst3_u8(output.as_mut_ptr().add(store), bytes);
+            }
+            vminv_u8(valid) == 0xFF
+        }
+    }
+}
+
+#[cfg(all(target_arch = "wasm32", target_feature = "simd128"))]
+mod wasm32 {
+    use super::{BLOCK_BYTES, BLOCK_CHARS, ENCODE_LOADS, LINE, LINE_CHARS, SHUFFLE, SHUFFLE_LAST};
+    use core::arch::wasm32::*;
+
+    /// Encodes the line 12 bytes at a time, as `encode_ssse3` does. There is no multiplication giving the high half,
+    /// so each of the four values is masked and shifted into place on its own.
+    pub(super) fn encode_simd128(input: &[u8; LINE], output: &mut [u8; LINE_CHARS]) {
+        for block in 0..4 {
+            let shuffle = if block == 3 { &SHUFFLE_LAST } else { &SHUFFLE };
+            // SAFETY: every load and store lies within the line, as `ENCODE_LOADS` and `BLOCK_CHARS` are laid out;
+            // wasm loads and stores need no alignment
+            let (bytes, shuffle) = unsafe { (v128_load(input.as_ptr().add(ENCODE_LOADS[block]).cast()), v128_load(shuffle.as_ptr().cast())) };
+            let groups = i8x16_swizzle(bytes, shuffle);
+            let values = v128_or(
+                v128_or(u32x4_shr(v128_and(groups, u32x4_splat(0x0000_FC00)), 10), u32x4_shl(v128_and(groups, u32x4_splat(0x0000_03F0)), 4)),
+                v128_or(u32x4_shr(v128_and(groups, u32x4_splat(0x0FC0_0000)), 6), u32x4_shl(v128_and(groups, u32x4_splat(0x003F_0000)), 8)),
+            );
+            let zeros = u8x16_eq(values, u8x16_splat(0));
+            let chars = u8x16_add(u8x16_add(values, u8x16_splat(0x20)), v128_and(zeros, u8x16_splat(0x40)));
+            unsafe { v128_store(output.as_mut_ptr().add(BLOCK_CHARS[block]).cast(), chars) };
+        }
+    }
+
+    /// Decodes the line 16 characters at a time, as `decode_ssse3` does, with shifts standing in for the first
+    /// multiplication.
+    pub(super) fn decode_simd128(input: &[u8; LINE_CHARS], output: &mut [u8; LINE]) -> bool {
+        // each group's bytes are in the low 24 bits of its word, highest first
+        let order = u8x16(2, 1, 0, 6, 5, 4, 10, 9, 8, 14, 13, 12, 0xFF, 0xFF, 0xFF, 0xFF);
+        let mut valid = u8x16_splat(0xFF);
+        for block in 0..4 {
+            // SAFETY: as for `encode_simd128`, with `BLOCK_CHARS` and `BLOCK_BYTES`
+            let chars = unsafe { v128_load(input.as_ptr().add(BLOCK_CHARS[block]).cast()) };
+            // the alphabet is ' ' to '`', offsets of 0 to 64 from ' '
+            let offsets = u8x16_sub(chars, u8x16_splat(0x20));
+            valid = v128_and(valid, u8x16_le(offsets, u8x16_splat(0x40)));
+            let values = v128_and(offsets, u8x16_splat(0x3F));
+            // pairs of values into 12 bits, then pairs of those into 24
+            let pairs = v128_or(u16x8_shl(v128_and(values, u16x8_splat(0x00FF)), 6), u16x8_shr(values, 8));
+            let bytes = i8x16_swizzle(i32x4_dot_i16x8(pairs, u32x4_splat(0x0001_1000)), order);
+            unsafe {
+                let start = output.as_mut_ptr().add(BLOCK_BYTES[block]);
+                v128_store64_lane::<0>(bytes, start.cast());
+                v128_store32_lane::<2>(bytes, start.add(8).cast());
+            }
+        }
+        u8x16_all_true(valid)
+    }
+}
+
+#[cfg(all(test, feature = "alloc"))]
+mod tests {
+    use super::*;
+
+    /// Encodes a line with the scalar code, for comparison.
+    fn scalar(input: &[u8; LINE]) -> [u8; LINE_CHARS] {
+        let mut output = [0u8; LINE_CHARS];
+        for (group, chars) in input.chunks(3).zip(output.chunks_mut(4)) {
+            chars.copy_from_slice(&crate::encode_group(&crate::Table::STANDARD, group));
+        }
+        output
+    }
+
+    /// Lines of every byte value in every position, and of zeros, which must become '`'.
+    fn lines() -> impl Iterator<Item = [u8; LINE]> {
+        (0..=255u8).step_by(3).map(|seed| core::array::from_fn(|i| seed.wrapping_mul(i as u8 | 1).wrapping_add(i as u8))).chain([[0u8; LINE]])
+    }
+
+    /// Tests that every kernel the CPU supports agrees with the scalar code
+    #[test]
+    fn test_encode_standard_line() {
+        for input in lines() {
+            let expected = scalar(&input);
+            let mut output = [0u8; LINE_CHARS];
+            if encode_standard_line(&input, &mut output) {
+                assert_eq!(output, expected, "dispatched kernel on {:?}", input);
+            }
+            #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+            {
+                if x86::level() >= x86::SSSE3 {
+                    let mut output = [0u8; LINE_CHARS];
+                    unsafe { x86::encode_ssse3(&input, &mut output) };
+                    assert_eq!(output, expected, "SSSE3 kernel on {:?}", input);
+                }
+                if x86::level() >= x86::AVX2 {
+                    let mut output = [0u8; LINE_CHARS];
+                    unsafe { x86::encode_avx2(&input, &mut output) };
+                    assert_eq!(output, expected, "AVX2 kernel on {:?}", input);
+                }
+            }
+        }
+    }
+
+    /// Decodes a line with the scalar code, for comparison, or `None` if it holds NUL or a character outside the
+    /// alphabet.
+    fn scalar_decode(input: &[u8; LINE_CHARS]) -> Option<[u8; LINE]> {
+        let mut output = [0u8; LINE];
+        for (chars, bytes) in input.chunks(4).zip(output.chunks_mut(3)) {
+            let mut values = [0u8; 4];
+            for (value, &ch) in values.iter_mut().zip(chars) {
+                *value = crate::Table::STANDARD.decode(ch).filter(|_| ch != 0)?;
+            }
+            bytes.copy_from_slice(&[(values[0] << 2) | (values[1] >> 4), (values[1] << 4) | (values[2] >> 2), (values[2] << 6) | values[3]]);
+        }
+        Some(output)
+    }
+
+    /// Tests that every decoding kernel the CPU supports agrees with the scalar code, including ' ' as zero, and
+    /// rejects a bad character in any position
+    #[test]
+    fn test_decode_standard_line() {
+        let mut inputs: alloc::vec::Vec<[u8; LINE_CHARS]> = lines().map(|line| scalar(&line)).collect();
+        inputs.push([b' '; LINE_CHARS]);
+        for (position, bad) in (0..LINE_CHARS).zip([0, b'a', 0x1F, 0xFF, b'\n'].into_iter().cycle()) {
+            let mut input = inputs[position];
+            input[position] = bad;
+            inputs.push(input);
+        }
+        for input in inputs {
+            let expected = scalar_decode(&input);
+            let mut output = [0u8; LINE];
+            let mut decoded = |decode: &dyn Fn(&mut [u8; LINE]) -> bool| decode(&mut output).then_some(output);
+            if encode_standard_line(&[0; LINE], &mut [0; LINE_CHARS]) {
+                assert_eq!(decoded(&|output| decode_standard_line(&input, output)), expected, "dispatched kernel on {:?}", input);
+            }
+            #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+            {
+                if x86::level() >= x86::SSSE3 {
+                    assert_eq!(decoded(&|output| unsafe { x86::decode_ssse3(&input, output) }), expected, "SSSE3 kernel on {:?}", input);
+                }
+                if x86::level() >= x86::AVX2 {
+                    assert_eq!(decoded(&|output| unsafe { x86::decode_avx2(&input, output) }), expected, "AVX2 kernel on {:?}", input);
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,449 @@
+use crate::{decode_line_to_slice, encode_group, encode_standard_pair, line_checksum, DecodeOptions, EncodeOptions, Table, UUEncodeError};
+
+/// The bytes in a full line.
+pub(crate) const LINE: usize = 45;
+
+/// The characters a full line encodes to, without its length character.
+pub(crate) const LINE_CHARS: usize = 60;
+
+/// The longest line a `SliceDecoder` buffers, including any checksum character and carriage return.
+const MAX_LINE: usize = 128;
+
+/// The length of the text `uuencode_with` produces for `data_len` bytes of input with the given options.
+/// Example:
+/// ```rust
+/// assert_eq!(uuencode_lite::uuencoded_len(3, &uuencode_lite::EncodeOptions::new()), "#8V%T".len());
+/// ```
+pub fn uuencoded_len(data_len: usize, options: &EncodeOptions) -> usize {
+    let checksum_len = options.line_checksums as usize;
+    let full_lines = data_len / 45;
+    let mut len = full_lines * (1 + 60 + checksum_len);
+    if !data_len.is_multiple_of(45) {
+        len += 1 + (data_len % 45).div_ceil(3) * 4 + checksum_len;
+    }
+    len + data_len.div_ceil(45).saturating_sub(1)
+}
+
+/// Encodes the input data as `uuencode` does, into `output` instead of a new string, returning the length written.
+/// Nothing is allocated, so this works without a heap. Fails if `output` is shorter than `uuencoded_len`.
+/// Example:
+/// ```rust
+/// fn encode() -> Result<(), uuencode_lite::UUEncodeError> {
+///     let mut output = [0u8; 8];
+///     let len = uuencode_lite::uuencode_to_slice(b"cat", &mut output)?;
+///     assert_eq!(&output[..len], b"#8V%T");
+///     Ok(())
+/// }
+/// ```
+pub fn uuencode_to_slice(data: &[u8], output: &mut [u8]) -> Result<usize, UUEncodeError> {
+    uuencode_to_slice_with(data, &EncodeOptions::default(), output)
+}
+
+/// Encodes the input data as `uuencode_to_slice`, using the given options.
+pub fn uuencode_to_slice_with(data: &[u8], options: &EncodeOptions, output: &mut [u8]) -> Result<usize, UUEncodeError> {
+    if output.len() < uuencoded_len(data.len(), options) {
+        return Err(UUEncodeError::capacity(0));
+    }
+    let mut written = 0;
+    let mut lines = data.chunks_exact(LINE);
+    for (idx, line) in lines.by_ref().enumerate() {
+        if idx > 0 {
+            output[written] = b'\n';
+            written += 1;
+        }
+        written += encode_full_line(line.try_into().expect("chunks are a full line"), options, &mut output[written..]);
+    }
+    let last = lines.remainder();
+    if !last.is_empty() {
+        if written > 0 {
+            output[written] = b'\n';
+            written += 1;
+        }
+        written += encode_line_to_slice(last, options, &mut output[written..]);
+    }
+    Ok(written)
+}
+
+/// Decodes uuencoded data as `uudecode_with` does, into `output` instead of a new vector, returning the length written.
+/// Nothing is allocated, so this works without a heap. Cancellation tokens are not checked.
+/// Fails if a line does not fit in what remains of `output`; the error reports that line.
+/// Example:
+/// ```rust
+/// fn decode() -> Result<(), uuencode_lite::UUEncodeError> {
+///     let mut output = [0u8; 45];
+///     let len = uuencode_lite::uudecode_to_slice(b"#8V%T\r\n", &mut output)?;
+///     assert_eq!(&output[..len], b"cat");
+///     Ok(())
+/// }
+/// ```
+pub fn uudecode_to_slice(data: &[u8], output: &mut [u8]) -> Result<usize, UUEncodeError> {
+    uudecode_to_slice_with(data, &DecodeOptions::default(), output)
+}
+
+/// Decodes uuencoded data as `uudecode_to_slice`, using the given options.
+pub fn uudecode_to_slice_with(data: &[u8], options: &DecodeOptions, output: &mut [u8]) -> Result<usize, UUEncodeError> {
+    let mut written = 0;
+    for (cur_line, line) in data.split(|&ch| ch == b'\n').enumerate() {
+        let line = line.strip_suffix(b"\r").unwrap_or(line);
+        written += decode_line_to_slice(line, cur_line, &options.table, options.verify_checksums, &mut output[written..])?;
+    }
+    Ok(written)
+}
+
+/// Encodes one line of at most 45 bytes, without a trailing newline, into the start of `output`, returning its length.
+/// `output` must have room for the whole line.
+pub(crate) fn encode_line_to_slice(line_chunk: &[u8], options: &EncodeOptions, output: &mut [u8]) -> usize {
+    if let Ok(line) = <&[u8; LINE]>::try_from(line_chunk) {
+        return encode_full_line(line, options, output);
+    }
+    let table = &options.table;
+    output[0] = table.encode(line_chunk.len() as u8);
+    let mut written = 1;
+    let mut rest = line_chunk;
+    if table.encodes_standard() {
+        let mut pairs = rest.chunks_exact(6);
+        for pair in pairs.by_ref() {
+            output[written..written + 8].copy_from_slice(&encode_standard_pair(pair.try_into().expect("chunks are 6 bytes")));
+            written += 8;
+        }
+        rest = pairs.remainder();
+    }
+    for chunk in rest.chunks(3) {
+        output[written..written + 4].copy_from_slice(&encode_group(table, chunk));
+        written += 4;
+    }
+    if options.line_checksums {
+        output[written] = table.encode(line_checksum(line_chunk));
+        written += 1;
+    }
+    written
+}
+
+/// Encodes a full line as `encode_line_to_slice` does. Its length is fixed, so the groups are unrolled, with no
+/// lengths to work out or short groups to pad.
+#[inline]
+fn encode_full_line(line: &[u8; LINE], options: &EncodeOptions, output: &mut [u8]) -> usize {
+    let table = &options.table;
+    let (length, chars) = output[..1 + LINE_CHARS].split_first_mut().expect("output has room for the line");
+    *length = table.encode(LINE as u8);
+    encode_full_line_chars(line, table, chars.try_into().expect("the line is 60 characters"));
+    if options.line_checksums {
+        output[1 + LINE_CHARS] = table.encode(line_checksum(line));
+        return 2 + LINE_CHARS;
+    }
+    1 + LINE_CHARS
+}
+
+/// Encodes the groups of a full line: with the vector kernels where they can take it, otherwise a word at a time
+/// for the standard table, and a group at a time for any other.
+#[inline]
+fn encode_full_line_chars(line: &[u8; LINE], table: &Table, chars: &mut [u8; LINE_CHARS]) {
+    #[cfg(feature = "simd")]
+    if table.encodes_standard() && crate::simd::encode_standard_line(line, chars) {
+        return;
+    }
+    if table.encodes_standard() {
+        // seven pairs of groups, then the fifteenth group alone
+        for (pair, out) in line.chunks_exact(6).zip(chars.chunks_exact_mut(8)) {
+            out.copy_from_slice(&encode_standard_pair(pair.try_into().expect("chunks are 6 bytes")));
+        }
+        chars[LINE_CHARS - 4..].copy_from_slice(&encode_group(table, &line[LINE - 3..]));
+    } else {
+        for (group, out) in line.chunks_exact(3).zip(chars.chunks_exact_mut(4)) {
+            out.copy_from_slice(&encode_group(table, group));
+        }
+    }
+}
+
+/// Encodes data incrementally into caller-provided buffers, without allocating, for use without a heap.
+/// Input is taken in pieces of any size, and each line is written, followed by a newline, once 45 bytes are
+/// available for it. The encoder itself holds less than one line of input.
+/// Example:
+/// ```rust
+/// fn encode() -> Result<(), uuencode_lite::UUEncodeError> {
+///     let options = uuencode_lite::EncodeOptions::new();
+///     let mut encoder = uuencode_lite::SliceEncoder::new(&options);
+///     let mut output = [0u8; 8];
+///     let (consumed, written) = encoder.push(b"cat", &mut output);
+///     assert_eq!((consumed, written), (3, 0));
+///     let written = encoder.finish(&mut output)?;
+///     assert_eq!(&output[..written], b"#8V%T\n");
+///     Ok(())
+/// }
+/// ```
+#[derive(Debug, Clone)]
+pub struct SliceEncoder<'a> {
+    options: &'a EncodeOptions,
+    /// Input for the next line.
+    pending: [u8; 45],
+    /// The number of bytes in `pending`.
+    pending_len: usize,
+}
+
+impl<'a> SliceEncoder<'a> {
+    /// Creates an encoder with the given options.
+    pub fn new(options: &'a EncodeOptions) -> Self {
+        SliceEncoder { options, pending: [0; 45], pending_len: 0 }
+    }
+
+    /// Takes as much of `data` as possible, writing every completed line to `output`.
+    /// Returns how many bytes of `data` were consumed and how many bytes of `output` were written.
+    /// Input is only left unconsumed when `output` is full; call again with more room to continue.
+    pub fn push(&mut self, data: &[u8], output: &mut [u8]) -> (usize, usize) {
+        let line_len = uuencoded_len(45, self.options) + 1;
+        let mut consumed = 0;
+        let mut written = 0;
+        loop {
+            if self.pending_len == 45 {
+                if output.len() - written < line_len {
+                    break;
+                }
+                written += encode_line_to_slice(&self.pending, self.options, &mut output[written..]);
+                output[written] = b'\n';
+                written += 1;
+                self.pending_len = 0;
+            }
+            if consumed == data.len() {
+                break;
+            }
+            let take = (45 - self.pending_len).min(data.len() - consumed);
+            self.pending[self.pending_len..self.pending_len + take].copy_from_slice(&data[consumed..consumed + take]);
+            self.pending_len += take;
+            consumed += take;
+        }
+        (consumed, written)
+    }
+
+    /// Writes the last, partial, line to `output`, returning how many bytes were written.
+    /// Fails, without changing the encoder, if `output` is too short for the line.
+    pub fn finish(&mut self, output: &mut [u8]) -> Result<usize, UUEncodeError> {
+        if self.pending_len == 0 {
+            return Ok(0);
+        }
+        if output.len() < uuencoded_len(self.pending_len, self.options) + 1 {
+            return Err(UUEncodeError::capacity(0));
+        }
+        let written = encode_line_to_slice(&self.pending[..self.pending_len], self.options, output);
+        output[written] = b'\n';
+        self.pending_len = 0;
+        Ok(written + 1)
+    }
+}
+
+/// Decodes uuencoded lines incrementally, as `uudecode_with` does, into caller-provided buffers, without allocating,
+/// for use without a heap. Input is taken in pieces of any size, and each line is decoded once its newline arrives.
+/// The decoder itself holds less than one line of input. Cancellation tokens are not checked.
+/// Example:
+/// ```rust
+/// fn decode() -> Result<(), uuencode_lite::UUEncodeError> {
+///     let options = uuencode_lite::DecodeOptions::new();
+///     let mut decoder = uuencode_lite::SliceDecoder::new(&options);
+///     let mut output = [0u8; 45];
+///     let (_, first) = decoder.push(b"#8V", &mut output)?;
+///     let (_, second) = decoder.push(b"%T\n", &mut output[first..])?;
+///     assert_eq!(&output[..first + second], b"cat");
+///     Ok(())
+/// }
+/// ```
+#[derive(Debug, Clone)]
+pub struct SliceDecoder<'a> {
+    options: &'a DecodeOptions,
+    /// The current line, so far.
+    line: [u8; MAX_LINE],
+    /// The number of bytes in `line`.
+    line_len: usize,
+    /// The number of the current line.
+    cur_line: usize,
+}
+
+impl<'a> SliceDecoder<'a> {
+    /// Creates a decoder with the given options.
+    pub fn new(options: &'a DecodeOptions) -> Self {
+        SliceDecoder { options, line: [0; MAX_LINE], line_len: 0, cur_line: 0 }
+    }
+
+    /// Takes as much of `data` as possible, writing the bytes of every completed line to `output`.
+    /// Returns how many bytes of `data` were consumed and how many bytes of `output` were written.
+    /// Input is only left unconsumed when the next line would not fit in `output`; call again with more room to continue.
+    pub fn push(&mut self, data: &[u8], output: &mut [u8]) -> Result<(usize, usize), UUEncodeError> {
+        let mut written = 0;
+        for (consumed, &ch) in data.iter().enumerate() {
+            if ch == b'\n' {
+                let Some(len) = self.decode_line(&mut output[written..])? else {
+                    return Ok((consumed, written));
+                };
+                written += len;
+                self.line_len = 0;
+                self.cur_line += 1;
+            } else if self.line_len == MAX_LINE {
+                return Err(UUEncodeError::new(self.cur_line, MAX_LINE, "Line too long"));
+            } else {
+                self.line[self.line_len] = ch;
+                self.line_len += 1;
+            }
+        }
+        Ok((data.len(), written))
+    }
+
+    /// Decodes the last line, if the input did not end with a newline, returning how many bytes were written.
+    /// Fails, without changing the decoder, if `output` is too short for the line.
+    pub fn finish(&mut self, output: &mut [u8]) -> Result<usize, UUEncodeError> {
+        let Some(len) = self.decode_line(output)? else {
+            return Err(UUEncodeError::capacity(self.cur_line));
+        };
+        self.line_len = 0;
+        Ok(len)
+    }
+
+    /// Decodes the current line into `output`, or returns `None` if its declared length does not fit.
+    fn decode_line(&self, output: &mut [u8]) -> Result<Option<usize>, UUEncodeError> {
+        let line = &self.line[..self.line_len];
+        let line = line.strip_suffix(b"\r").unwrap_or(line);
+        let declared = line.first().and_then(|&ch| self.options.table.decode(ch)).unwrap_or(0) as usize;
+        if output.len() < declared {
+            return Ok(None);
+        }
+        decode_line_to_slice(line, self.cur_line, &self.options.table, self.options.verify_checksums, output).map(Some)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ErrorKind;
+    use std::vec::Vec;
+
+    /// Tests encoding and decoding between slices, in one call and incrementally with small buffers
+    #[test]
+    fn test_slices() {
+        let source_data = std::fs::read("test_data/random_data.bin").expect("Can open test data");
+        let expected = std::fs::read("test_data/random_data.bin.uu").expect("Can open test data");
+        let expected = expected.strip_suffix(b"\n").unwrap_or(&expected);
+        let options = EncodeOptions::new();
+        assert_eq!(uuencoded_len(source_data.len(), &options), expected.len(), "computes the encoded length");
+
+        let mut encoded = [0u8; 4096];
+        let len = uuencode_to_slice(&source_data, &mut encoded).unwrap();
+        assert_eq!(&encoded[..len], expected, "can encode into a slice");
+        assert_eq!(uuencode_to_slice(&source_data, &mut encoded[..len - 1]).unwrap_err().kind(), ErrorKind::Capacity, "rejects a short output");
+
+        let mut decoded = [0u8; 2048];
+        let len = uudecode_to_slice(expected, &mut decoded).unwrap();
+        assert_eq!(&decoded[..len], source_data, "can decode into a slice");
+        assert_eq!(uudecode_to_slice(expected, &mut decoded[..2000]).unwrap_err().line, 44, "reports the line that does not fit");
+
+        let mut encoder = SliceEncoder::new(&options);
+        let mut encoded = Vec::new();
+        let mut buffer = [0u8; 70];
+        for mut chunk in source_data.chunks(100) {
+            while !chunk.is_empty() {
+                let (consumed, written) = encoder.push(chunk, &mut buffer);
+                encoded.extend_from_slice(&buffer[..written]);
+                chunk = &chunk[consumed..];
+            }
+        }
+        let written = encoder.finish(&mut buffer).unwrap();
+        encoded.extend_from_slice(&buffer[..written]);
+        assert_eq!(encoded.strip_suffix(b"\n").unwrap(), expected, "can encode incrementally");
+
+        let decode_options = DecodeOptions::new();
+        let mut decoder = SliceDecoder::new(&decode_options);
+        let mut decoded = Vec::new();
+        let mut buffer = [0u8; 50];
+        for mut chunk in encoded.chunks(99) {
+            while !chunk.is_empty() {
+                let (consumed, written) = decoder.push(chunk, &mut buffer).unwrap();
+                decoded.extend_from_slice(&buffer[..written]);
+                chunk = &chunk[consumed..];
+            }
+        }
+        assert_eq!(decoder.finish(&mut buffer).unwrap(), 0, "has nothing left after the last newline");
+        assert_eq!(decoded, source_data, "can decode incrementally");
+
+        let mut decoder = SliceDecoder::new(&decode_options);
+        assert!(decoder.push(&[b'M'; MAX_LINE + 1], &mut buffer).is_err(), "rejects an overlong line");
+    }
+
+    /// Tests that the unrolled full lines and the generic last line match encoding one group at a time, for every
+    /// table, with and without checksums
+    #[test]
+    fn test_full_lines() {
+        let source_data = std::fs::read("test_data/random_data.bin").expect("Can open test data");
+        let reference = |data: &[u8], options: &EncodeOptions| {
+            let lines: Vec<Vec<u8>> = data
+                .chunks(LINE)
+                .map(|line| {
+                    let mut encoded = std::vec![options.table.encode(line.len() as u8)];
+                    line.chunks(3).for_each(|group| encoded.extend_from_slice(&encode_group(&options.table, group)));
+                    if options.line_checksums {
+                        encoded.push(options.table.encode(line_checksum(line)));
+                    }
+                    encoded
+                })
+                .collect();
+            lines.join(&b'\n')
+        };
+        let mut encoded = [0u8; 512];
+        for table in [Table::STANDARD, Table::XX, Table::BASE64, Table::BASE64_URL] {
+            for line_checksums in [false, true] {
+                let options = EncodeOptions::new().table(table.clone()).line_checksums(line_checksums);
+                for len in [0, 1, 44, 45, 46, 89, 90, 135, 136] {
+                    let written = uuencode_to_slice_with(&source_data[..len], &options, &mut encoded).unwrap();
+                    assert_eq!(&encoded[..written], reference(&source_data[..len], &options), "encodes {} bytes with {:?}", len, options);
+                }
+            }
+        }
+    }
+}
+
+#[cfg(kani)]
+mod proofs {
+    use super::*;
+
+    /// The most input the proofs encode: a pair of groups, then a short group
+    const MAX_DATA: usize = 7;
+    /// The most text the proofs decode: a length character, two groups, a checksum and a line break
+    const MAX_TEXT: usize = 12;
+
+    /// Proves that encoding any short input into a slice of any size fails cleanly rather than indexing out of
+    /// bounds, and writes exactly `uuencoded_len` when it succeeds
+    #[kani::proof]
+    #[kani::unwind(14)]
+    fn proof_encode_in_bounds() {
+        let data: [u8; MAX_DATA] = kani::any();
+        let data = &data[..kani::any_where(|len: &usize| *len <= MAX_DATA)];
+        let mut output = [0u8; MAX_TEXT];
+        let output = &mut output[..kani::any_where(|len: &usize| *len <= MAX_TEXT)];
+        let table = if kani::any() { Table::STANDARD } else { Table::XX };
+        let options = EncodeOptions::new().table(table).line_checksums(kani::any());
+        if let Ok(written) = uuencode_to_slice_with(data, &options, output) {
+            assert_eq!(written, uuencoded_len(data.len(), &options));
+        }
+    }
+
+    /// Proves that a full line of any bytes is encoded within the room `uuencoded_len` says it needs
+    #[kani::proof]
+    #[kani::unwind(47)]
+    fn proof_encode_full_line_in_bounds() {
+        let line: [u8; LINE] = kani::any();
+        let table = if kani::any() { Table::STANDARD } else { Table::XX };
+        let options = EncodeOptions::new().table(table).line_checksums(kani::any());
+        let mut output = [0u8; 2 + LINE_CHARS];
+        let len = uuencoded_len(LINE, &options);
+        assert_eq!(encode_full_line(&line, &options, &mut output[..len]), len);
+    }
+
+    /// Proves that decoding any short text into a slice of any size fails cleanly rather than indexing out of bounds
+    #[kani::proof]
+    #[kani::unwind(14)]
+    fn proof_decode_in_bounds() {
+        let text: [u8; MAX_TEXT] = kani::any();
+        let text = &text[..kani::any_where(|len: &usize| *len <= MAX_TEXT)];
+        let mut output = [0u8; MAX_DATA];
+        let output = &mut output[..kani::any_where(|len: &usize| *len <= MAX_DATA)];
+        let options = DecodeOptions::new().verify_checksums(kani::any());
+        if let Ok(written) = uudecode_to_slice_with(text, &options, output) {
+            assert!(written <= output.len());
+        }
+    }
+}
@@ -0,0 +1,973 @@
+use crate::decoder::Decoder;
+use crate::encoder::Encoder;
+use crate::{decode_line, DecodeOptions, FileOptions, UUEncodeError};
+use std::boxed::Box;
+use std::io::{self, BufRead, Read, Write};
+use std::string::{String, ToString};
+use std::vec;
+use std::vec::Vec;
+
+/// How far a streaming encode or decode has got, as passed to a progress callback.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Progress {
+    /// The number of input bytes consumed so far.
+    pub consumed: u64,
+    /// The number of output bytes produced so far.
+    pub produced: u64,
+}
+
+/// A callback invoked each time another `interval` input bytes have been consumed, and once more at the end.
+/// With the `tracing` feature, it also records the adapter's progress, completion and errors as events in a span.
+struct ProgressHook {
+    progress: Progress,
+    interval: u64,
+    /// The number of consumed bytes at which the callback is next due.
+    next: u64,
+    callback: Option<Box<dyn FnMut(Progress) + Send>>,
+    #[cfg(feature = "tracing")]
+    span: tracing::Span,
+}
+
+impl ProgressHook {
+    /// Creates a hook with no callback, for the named adapter and, when encoding, the name of the file.
+    #[cfg_attr(not(feature = "tracing"), allow(unused_variables))]
+    fn new(adapter: &'static str, file: Option<&str>) -> Self {
+        ProgressHook {
+            progress: Progress::default(),
+            interval: 1,
+            next: 1,
+            callback: None,
+            #[cfg(feature = "tracing")]
+            span: tracing::debug_span!("stream", adapter, file, mode = tracing::field::Empty),
+        }
+    }
+
+    /// Sets the callback, to be called each time another `interval` input bytes have been consumed.
+    fn set_callback(&mut self, interval: u64, callback: impl FnMut(Progress) + Send + 'static) {
+        self.interval = interval.max(1);
+        self.next = self.progress.consumed / self.interval * self.interval + self.interval;
+        self.callback = Some(Box::new(callback));
+    }
+
+    /// Records more input consumed and output produced, calling the callback if it is due.
+    fn update(&mut self, consumed: usize, produced: usize) {
+        self.progress.consumed += consumed as u64;
+        self.progress.produced += produced as u64;
+        #[cfg(feature = "tracing")]
+        tracing::trace!(parent: &self.span, consumed = self.progress.consumed, produced = self.progress.produced, "progress");
+        if let Some(callback) = &mut self.callback
+            && self.progress.consumed >= self.next
+        {
+            self.next = (self.progress.consumed / self.interval + 1) * self.interval;
+            callback(self.progress);
+        }
+    }
+
+    /// Calls the callback with the final totals.
+    fn finish(&mut self) {
+        #[cfg(feature = "tracing")]
+        tracing::debug!(parent: &self.span, consumed = self.progress.consumed, produced = self.progress.produced, "finished");
+        if let Some(callback) = &mut self.callback {
+            callback(self.progress);
+        }
+    }
+
+    /// Records the file a decoder found and the number of lines it read, then calls the callback with the final totals.
+    fn finish_decoding(&mut self, decoder: &Decoder) {
+        #[cfg(feature = "tracing")]
+        if let Some((mode, name)) = decoder.header() {
+            self.span.record("file", name);
+            self.span.record("mode", tracing::field::display(format_args!("{:03o}", mode)));
+        }
+        #[cfg(feature = "tracing")]
+        tracing::debug!(parent: &self.span, lines = decoder.lines(), "decoded");
+        #[cfg(not(feature = "tracing"))]
+        let _ = decoder;
+        self.finish();
+    }
+
+    /// Records the error, if encoding or decoding failed, and passes the result on.
+    fn check<T>(&self, result: Result<T, UUEncodeError>) -> Result<T, UUEncodeError> {
+        #[cfg(feature = "tracing")]
+        if let Err(err) = &result {
+            tracing::warn!(parent: &self.span, line = err.line, character = err.character, error = %err, "failed");
+        }
+        result
+    }
+}
+
+impl std::fmt::Debug for ProgressHook {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ProgressHook").field("progress", &self.progress).field("interval", &self.interval).finish_non_exhaustive()
+    }
+}
+
+/// Writes the pending bytes to the inner writer from `position`, advancing it past each byte written, so a write that
+/// fails part way can be resumed later without repeating or losing anything.
+fn drain<W: Write>(inner: &mut W, pending: &[u8], position: &mut usize) -> io::Result<()> {
+    while *position < pending.len() {
+        match inner.write(&pending[*position..]) {
+            Ok(0) => return Err(io::ErrorKind::WriteZero.into()),
+            Ok(len) => *position += len,
+            Err(err) if err.kind() == io::ErrorKind::Interrupted => {}
+            Err(err) => return Err(err),
+        }
+    }
+    Ok(())
+}
+
+/// A writer that uuencodes everything written to it as a complete file, as `uuencode_file`, writing
+/// each encoded line to the inner writer as soon as it is complete. Each write takes at most a few kilobytes, encoded
+/// into a buffer kept from one write to the next, so once the `begin` line is written streaming allocates nothing.
+/// Call `finish` once all the data is written, to write the last line, the terminator and `end`.
+/// If the inner writer fails, the text not yet written is kept, and written first by the next call.
+/// Example:
+/// ```rust
+/// fn encode() -> std::io::Result<()> {
+///     use std::io::Write;
+///     let mut writer = uuencode_lite::EncodeWriter::new(Vec::new(), uuencode_lite::FileOptions::new("cat.txt"))?;
+///     writer.write_all(b"c")?;
+///     writer.write_all(b"at")?;
+///     let encoded = writer.finish()?;
+///     assert_eq!(encoded, b"begin 644 cat.txt\n#8V%T\n`\nend\n");
+///     Ok(())
+/// }
+/// ```
+#[derive(Debug)]
+pub struct EncodeWriter<W: Write> {
+    inner: W,
+    encoder: Encoder,
+    /// Encoded text waiting to be written to `inner`.
+    buffer: String,
+    /// How much of `buffer` has been written.
+    position: usize,
+    progress: ProgressHook,
+}
+
+impl<W: Write> EncodeWriter<W> {
+    /// Creates a writer that encodes a file with the given options into `inner`.
+    /// Fails if the file name is invalid, as `uuencode_file` does.
+    pub fn new(inner: W, options: FileOptions) -> Result<Self, UUEncodeError> {
+        let progress = ProgressHook::new("EncodeWriter", Some(&options.name));
+        Ok(EncodeWriter { inner, encoder: progress.check(Encoder::new(options))?, buffer: String::new(), position: 0, progress })
+    }
+
+    /// Sets a callback to report progress, invoked each time another `interval` bytes of data have been consumed,
+    /// and once more with the final totals when the end of the file is reached.
+    pub fn on_progress(mut self, interval: u64, callback: impl FnMut(Progress) + Send + 'static) -> Self {
+        self.progress.set_callback(interval, callback);
+        self
+    }
+
+    /// Writes any buffered text to the inner writer.
+    fn write_buffer(&mut self) -> io::Result<()> {
+        drain(&mut self.inner, self.buffer.as_bytes(), &mut self.position)?;
+        self.buffer.clear();
+        self.position = 0;
+        Ok(())
+    }
+
+    /// Writes the final partial line and the end of the file, flushes the inner writer, and returns it.
+    pub fn finish(mut self) -> io::Result<W> {
+        self.write_buffer()?;
+        self.progress.check(self.encoder.finish(&mut self.buffer))?;
+        self.progress.update(0, self.buffer.len());
+        self.write_buffer()?;
+        self.progress.finish();
+        self.inner.flush()?;
+        Ok(self.inner)
+    }
+
+    /// The inner writer.
+    pub fn get_ref(&self) -> &W {
+        &self.inner
+    }
+}
+
+impl<W: Write> Write for EncodeWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.write_buffer()?;
+        let buf = &buf[..buf.len().min(READ_CHUNK)];
+        let result = self.progress.check(self.encoder.push(buf, &mut self.buffer));
+        self.progress.update(buf.len(), self.buffer.len());
+        // The input has been taken, so a failure to pass its text on is left for the next call to report.
+        let _ = self.write_buffer();
+        result?;
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.write_buffer()?;
+        self.inner.flush()
+    }
+}
+
+/// A writer that decodes a complete uuencoded file written to it, as `uudecode_file_with`, writing the
+/// decoded bytes to the inner writer as each line completes. Lines may be split across writes at any point,
+/// and text after the end of the file is ignored. The decoded bytes and any incomplete line are kept in buffers
+/// reused across writes, so a long body decodes without allocating once the first lines have been seen.
+/// Call `finish` once all the text is written, to check that the file was complete.
+/// Decoding errors are returned from `write` as `io::Error`s of kind `InvalidData`, with the `UUEncodeError` as their source.
+/// If the inner writer fails, the bytes not yet written are kept, and written first by the next call.
+/// Example:
+/// ```rust
+/// fn decode() -> std::io::Result<()> {
+///     let mut writer = uuencode_lite::DecodeWriter::new(Vec::new(), uuencode_lite::DecodeOptions::new());
+///     std::io::copy(&mut &b"begin 644 cat.txt\n#8V%T\n`\nend\n"[..], &mut writer)?;
+///     assert_eq!(writer.name(), Some("cat.txt"));
+///     assert_eq!(writer.finish()?, b"cat");
+///     Ok(())
+/// }
+/// ```
+#[derive(Debug)]
+pub struct DecodeWriter<W: Write> {
+    inner: W,
+    decoder: Decoder,
+    /// Decoded bytes waiting to be written to `inner`.
+    buffer: Vec<u8>,
+    /// How much of `buffer` has been written.
+    position: usize,
+    progress: ProgressHook,
+}
+
+impl<W: Write> DecodeWriter<W> {
+    /// Creates a writer that decodes a file with the given options into `inner`.
+    pub fn new(inner: W, options: DecodeOptions) -> Self {
+        DecodeWriter { inner, decoder: Decoder::new(options), buffer: Vec::new(), position: 0, progress: ProgressHook::new("DecodeWriter", None) }
+    }
+
+    /// Sets a callback to report progress, invoked each time another `interval` bytes of encoded text have been consumed,
+    /// and once more with the final totals when the end of the file is reached.
+    pub fn on_progress(mut self, interval: u64, callback: impl FnMut(Progress) + Send + 'static) -> Self {
+        self.progress.set_callback(interval, callback);
+        self
+    }
+
+    /// Writes any buffered bytes to the inner writer.
+    fn write_buffer(&mut self) -> io::Result<()> {
+        drain(&mut self.inner, &self.buffer, &mut self.position)?;
+        self.buffer.clear();
+        self.position = 0;
+        Ok(())
+    }
+
+    /// The file name from the `begin` line, once it has been written.
+    pub fn name(&self) -> Option<&str> {
+        self.decoder.header().map(|(_, name)| name)
+    }
+
+    /// The permission bits from the `begin` line, once it has been written.
+    pub fn mode(&self) -> Option<u32> {
+        self.decoder.header().map(|(mode, _)| mode)
+    }
+
+    /// Decodes any final line without a newline, checks that the file was complete,
+    /// flushes the inner writer, and returns it.
+    pub fn finish(mut self) -> io::Result<W> {
+        self.write_buffer()?;
+        let result = self.progress.check(self.decoder.finish(&mut self.buffer));
+        self.progress.update(0, self.buffer.len());
+        self.write_buffer()?;
+        result?;
+        self.progress.finish_decoding(&self.decoder);
+        self.inner.flush()?;
+        Ok(self.inner)
+    }
+
+    /// The inner writer.
+    pub fn get_ref(&self) -> &W {
+        &self.inner
+    }
+}
+
+impl<W: Write> Write for DecodeWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.write_buffer()?;
+        let buf = &buf[..buf.len().min(READ_CHUNK)];
+        let result = self.progress.check(self.decoder.push(buf, &mut self.buffer));
+        self.progress.update(buf.len(), self.buffer.len());
+        // The input has been taken, so a failure to pass its bytes on is left for the next call to report.
+        let _ = self.write_buffer();
+        result?;
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.write_buffer()?;
+        self.inner.flush()
+    }
+}
+
+/// The number of bytes read from the inner reader at a time, and the most taken from one write: a whole number of
+/// 45-byte lines. Bounding each step keeps the adapters' buffers at a fixed size, reused from one call to the next.
+pub(crate) const READ_CHUNK: usize = 45 * 64;
+
+/// A reader that uuencodes the data read from the inner reader as a complete file, as `uuencode_file`,
+/// producing the encoded text as it is read rather than all at once.
+/// Example:
+/// ```rust
+/// fn encode() -> std::io::Result<()> {
+///     use std::io::Read;
+///     let mut reader = uuencode_lite::EncodeReader::new(&b"cat"[..], uuencode_lite::FileOptions::new("cat.txt"))?;
+///     let mut encoded = String::new();
+///     reader.read_to_string(&mut encoded)?;
+///     assert_eq!(encoded, "begin 644 cat.txt\n#8V%T\n`\nend\n");
+///     Ok(())
+/// }
+/// ```
+#[derive(Debug)]
+pub struct EncodeReader<R: Read> {
+    inner: R,
+    encoder: Encoder,
+    /// Encoded text not yet read.
+    buffer: String,
+    /// How much of `buffer` has been read.
+    position: usize,
+    /// Whether the inner reader has reached its end.
+    finished: bool,
+    progress: ProgressHook,
+}
+
+impl<R: Read> EncodeReader<R> {
+    /// Creates a reader that encodes the data from `inner` as a file with the given options.
+    /// Fails if the file name is invalid, as `uuencode_file` does.
+    pub fn new(inner: R, options: FileOptions) -> Result<Self, UUEncodeError> {
+        let progress = ProgressHook::new("EncodeReader", Some(&options.name));
+        Ok(EncodeReader { inner, encoder: progress.check(Encoder::new(options))?, buffer: String::new(), position: 0, finished: false, progress })
+    }
+
+    /// Sets a callback to report progress, invoked each time another `interval` bytes of data have been consumed,
+    /// and once more with the final totals when the end of the file is reached.
+    pub fn on_progress(mut self, interval: u64, callback: impl FnMut(Progress) + Send + 'static) -> Self {
+        self.progress.set_callback(interval, callback);
+        self
+    }
+
+    /// Returns the inner reader.
+    pub fn into_inner(self) -> R {
+        self.inner
+    }
+}
+
+impl<R: Read> Read for EncodeReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let mut chunk = [0u8; READ_CHUNK];
+        while self.position == self.buffer.len() && !self.finished {
+            self.buffer.clear();
+            self.position = 0;
+            let len = match self.inner.read(&mut chunk) {
+                Ok(len) => len,
+                Err(err) if err.kind() == io::ErrorKind::Interrupted => continue,
+                Err(err) => return Err(err),
+            };
+            if len == 0 {
+                self.progress.check(self.encoder.finish(&mut self.buffer))?;
+                self.finished = true;
+                self.progress.update(0, self.buffer.len());
+                self.progress.finish();
+            } else {
+                self.progress.check(self.encoder.push(&chunk[..len], &mut self.buffer))?;
+                self.progress.update(len, self.buffer.len());
+            }
+        }
+        let pending = &self.buffer.as_bytes()[self.position..];
+        let len = pending.len().min(buf.len());
+        buf[..len].copy_from_slice(&pending[..len]);
+        self.position += len;
+        Ok(len)
+    }
+}
+
+/// A reader that decodes a complete uuencoded file from the inner reader, as `uudecode_file_with`,
+/// producing the decoded bytes as each line completes. Reading from the inner reader stops once the end of
+/// the file has been decoded, so a stream that stays open afterwards does not block.
+/// Decoding errors are returned from `read` as `io::Error`s of kind `InvalidData`, with the `UUEncodeError` as their source.
+/// Example:
+/// ```rust
+/// fn decode() -> std::io::Result<()> {
+///     use std::io::Read;
+///     let mut reader = uuencode_lite::DecodeReader::new(&b"begin 644 cat.txt\n#8V%T\n`\nend\n"[..], uuencode_lite::DecodeOptions::new());
+///     let mut decoded = Vec::new();
+///     reader.read_to_end(&mut decoded)?;
+///     assert_eq!(decoded, b"cat");
+///     assert_eq!(reader.name(), Some("cat.txt"));
+///     Ok(())
+/// }
+/// ```
+#[derive(Debug)]
+pub struct DecodeReader<R: Read> {
+    inner: R,
+    decoder: Decoder,
+    /// Decoded bytes not yet read.
+    buffer: Vec<u8>,
+    /// How much of `buffer` has been read.
+    position: usize,
+    /// Whether the end of the file has been decoded and checked.
+    finished: bool,
+    progress: ProgressHook,
+}
+
+impl<R: Read> DecodeReader<R> {
+    /// Creates a reader that decodes a file with the given options from `inner`.
+    pub fn new(inner: R, options: DecodeOptions) -> Self {
+        DecodeReader { inner, decoder: Decoder::new(options), buffer: Vec::new(), position: 0, finished: false, progress: ProgressHook::new("DecodeReader", None) }
+    }
+
+    /// Sets a callback to report progress, invoked each time another `interval` bytes of encoded text have been consumed,
+    /// and once more with the final totals when the end of the file is reached.
+    pub fn on_progress(mut self, interval: u64, callback: impl FnMut(Progress) + Send + 'static) -> Self {
+        self.progress.set_callback(interval, callback);
+        self
+    }
+
+    /// The file name from the `begin` line, once it has been read.
+    pub fn name(&self) -> Option<&str> {
+        self.decoder.header().map(|(_, name)| name)
+    }
+
+    /// The permission bits from the `begin` line, once it has been read.
+    pub fn mode(&self) -> Option<u32> {
+        self.decoder.header().map(|(mode, _)| mode)
+    }
+
+    /// Returns the inner reader.
+    pub fn into_inner(self) -> R {
+        self.inner
+    }
+
+    /// Turns this reader into an iterator over decoded chunks of at most `max_len` bytes (at least 1),
+    /// reading from the inner reader only as each chunk is needed.
+    pub fn chunks(self, max_len: usize) -> DecodeChunks<R> {
+        DecodeChunks { reader: self, max_len: max_len.max(1), error: None, done: false }
+    }
+}
+
+impl<R: Read> Read for DecodeReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let mut chunk = [0u8; READ_CHUNK];
+        while self.position == self.buffer.len() && !self.finished {
+            self.buffer.clear();
+            self.position = 0;
+            let len = if self.decoder.is_done() {
+                0
+            } else {
+                match self.inner.read(&mut chunk) {
+                    Ok(len) => len,
+                    Err(err) if err.kind() == io::ErrorKind::Interrupted => continue,
+                    Err(err) => return Err(err),
+                }
+            };
+            if len == 0 {
+                self.finished = true;
+                self.progress.check(self.decoder.finish(&mut self.buffer))?;
+                self.progress.update(0, self.buffer.len());
+                self.progress.finish_decoding(&self.decoder);
+            } else {
+                self.progress.check(self.decoder.push(&chunk[..len], &mut self.buffer))?;
+                self.progress.update(len, self.buffer.len());
+            }
+        }
+        let pending = &self.buffer[self.position..];
+        let len = pending.len().min(buf.len());
+        buf[..len].copy_from_slice(&pending[..len]);
+        self.position += len;
+        Ok(len)
+    }
+}
+
+/// An iterator over the decoded bytes of a complete uuencoded file, in chunks of bounded size, created by
+/// `DecodeReader::chunks`. Every chunk but the last is full, so memory use stays constant however large the file is.
+/// Each chunk is a new `Vec`; to decode without allocating, read from the `DecodeReader` into a buffer of your own.
+/// After an error, the bytes decoded before it are yielded, then the error, then nothing more.
+/// Example:
+/// ```rust
+/// fn decode() -> std::io::Result<()> {
+///     let reader = uuencode_lite::DecodeReader::new(&b"begin 644 cat.txt\n#8V%T\n`\nend\n"[..], uuencode_lite::DecodeOptions::new());
+///     let chunks = reader.chunks(2).collect::<std::io::Result<Vec<_>>>()?;
+///     assert_eq!(chunks, [b"ca".to_vec(), b"t".to_vec()]);
+///     Ok(())
+/// }
+/// ```
+#[derive(Debug)]
+pub struct DecodeChunks<R: Read> {
+    reader: DecodeReader<R>,
+    max_len: usize,
+    /// An error to yield after the chunk decoded before it.
+    error: Option<io::Error>,
+    /// Whether the end of the file or an error has been reached.
+    done: bool,
+}
+
+impl<R: Read> DecodeChunks<R> {
+    /// The file name from the `begin` line, once it has been read.
+    pub fn name(&self) -> Option<&str> {
+        self.reader.name()
+    }
+
+    /// The permission bits from the `begin` line, once it has been read.
+    pub fn mode(&self) -> Option<u32> {
+        self.reader.mode()
+    }
+}
+
+impl<R: Read> Iterator for DecodeChunks<R> {
+    type Item = io::Result<Vec<u8>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if let Some(err) = self.error.take() {
+            return Some(Err(err));
+        }
+        if self.done {
+            return None;
+        }
+        let mut chunk = vec![0u8; self.max_len];
+        let mut filled = 0;
+        while filled < chunk.len() {
+            match self.reader.read(&mut chunk[filled..]) {
+                Ok(0) => {
+                    self.done = true;
+                    break;
+                }
+                Ok(len) => filled += len,
+                Err(err) if err.kind() == io::ErrorKind::Interrupted => {}
+                Err(err) => {
+                    self.done = true;
+                    if filled == 0 {
+                        return Some(Err(err));
+                    }
+                    self.error = Some(err);
+                    break;
+                }
+            }
+        }
+        if filled == 0 {
+            return None;
+        }
+        chunk.truncate(filled);
+        Some(Ok(chunk))
+    }
+}
+
+impl<R: Read> std::iter::FusedIterator for DecodeChunks<R> {}
+
+/// Uuencodes everything read from `input` as a complete file, as `uuencode_file`, writing the encoded text to `output`.
+/// Returns the number of encoded bytes written. Byte slices implement `Read`, so in-memory data can be passed directly.
+/// Example:
+/// ```rust
+/// fn encode() -> std::io::Result<()> {
+///     let mut encoded = Vec::new();
+///     uuencode_lite::uuencode_to_writer(&b"cat"[..], &mut encoded, uuencode_lite::FileOptions::new("cat.txt"))?;
+///     assert_eq!(encoded, b"begin 644 cat.txt\n#8V%T\n`\nend\n");
+///     Ok(())
+/// }
+/// ```
+pub fn uuencode_to_writer(input: impl Read, mut output: impl Write, options: FileOptions) -> io::Result<u64> {
+    let mut reader = EncodeReader::new(input, options)?;
+    io::copy(&mut reader, &mut output)
+}
+
+/// Decodes a complete uuencoded file read from `input`, as `uudecode_file_with`, writing the decoded bytes to `output`.
+/// Returns the permission bits and file name from the `begin` line. Reading stops at the end of the file.
+/// Example:
+/// ```rust
+/// fn decode() -> std::io::Result<()> {
+///     let mut decoded = Vec::new();
+///     let (mode, name) = uuencode_lite::uudecode_to_writer(&b"begin 644 cat.txt\n#8V%T\n`\nend\n"[..], &mut decoded, uuencode_lite::DecodeOptions::new())?;
+///     assert_eq!((mode, name.as_str(), decoded.as_slice()), (0o644, "cat.txt", &b"cat"[..]));
+///     Ok(())
+/// }
+/// ```
+pub fn uudecode_to_writer(input: impl Read, mut output: impl Write, options: DecodeOptions) -> io::Result<(u32, String)> {
+    let mut reader = DecodeReader::new(input, options);
+    io::copy(&mut reader, &mut output)?;
+    let (mode, name) = reader.decoder.header().expect("a finished file has a begin line");
+    Ok((mode, name.to_string()))
+}
+
+/// Decodes bare uuencoded lines, as `uudecode_with`, reading them one at a time from a buffered reader so the
+/// encoded text is never held in memory all at once. Lines may end with either LF or CRLF, and blank lines are skipped.
+/// Decoding errors are returned as `io::Error`s of kind `InvalidData`, carrying the `UUEncodeError`.
+/// Example:
+/// ```rust
+/// fn decode() -> std::io::Result<()> {
+///     let decoded = uuencode_lite::uudecode_bufread(&b"#8V%T\r\n\n#8V%T\r\n"[..])?;
+///     assert_eq!(decoded, b"catcat");
+///     Ok(())
+/// }
+/// ```
+pub fn uudecode_bufread(reader: impl BufRead) -> io::Result<Vec<u8>> {
+    uudecode_bufread_with(reader, &DecodeOptions::default())
+}
+
+/// Decodes bare uuencoded lines from a buffered reader, as `uudecode_bufread`, using the given options.
+pub fn uudecode_bufread_with(mut reader: impl BufRead, options: &DecodeOptions) -> io::Result<Vec<u8>> {
+    let mut decoded = Vec::new();
+    let mut line = Vec::with_capacity(64);
+    for cur_line in 0.. {
+        line.clear();
+        if reader.read_until(b'\n', &mut line)? == 0 {
+            break;
+        }
+        let trimmed = line.strip_suffix(b"\n").unwrap_or(&line);
+        let trimmed = trimmed.strip_suffix(b"\r").unwrap_or(trimmed);
+        decode_line(trimmed, cur_line, &options.table, options.verify_checksums, &mut decoded)?;
+    }
+    Ok(decoded)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{uudecode_file_with, uuencode_file, Checksum};
+
+    /// Tests that chunks are bounded and reassemble into the decoded file
+    #[test]
+    fn test_decode_chunks() {
+        let source_data = std::fs::read("test_data/random_data.bin").expect("Can open test data");
+        let encoded = uuencode_file(&source_data, &FileOptions::new("random_data.bin")).unwrap();
+        for max_len in [0, 1, 100, 4096, source_data.len() + 1] {
+            let chunks = DecodeReader::new(encoded.as_bytes(), DecodeOptions::new()).chunks(max_len).collect::<io::Result<Vec<_>>>().unwrap();
+            let (last, full) = chunks.split_last().unwrap();
+            assert!(full.iter().all(|chunk| chunk.len() == max_len.max(1)) && last.len() <= max_len.max(1), "bounds chunks to {} bytes", max_len);
+            assert_eq!(chunks.concat(), source_data, "can decode in chunks of {} bytes", max_len);
+        }
+
+        let mut chunks = DecodeReader::new(&encoded.as_bytes()[..200], DecodeOptions::new()).chunks(1 << 20);
+        assert!(chunks.next().unwrap().is_ok_and(|chunk| !chunk.is_empty()), "yields the data decoded before an error");
+        assert!(chunks.next().unwrap().is_err(), "then yields the error");
+        assert!(chunks.next().is_none(), "then stops");
+    }
+
+    /// Tests that progress is reported at each interval and at the end, for each adapter
+    #[test]
+    fn test_progress() {
+        use std::sync::{Arc, Mutex};
+        let source_data = std::fs::read("test_data/the_machine_stops.txt").expect("Can open test data");
+        let options = FileOptions::new("the_machine_stops.txt");
+        let encoded = uuencode_file(&source_data, &options).unwrap();
+        let recorder = || {
+            let reports = Arc::new(Mutex::new(Vec::new()));
+            let sink = reports.clone();
+            (reports, move |progress: Progress| sink.lock().unwrap().push(progress))
+        };
+        let check = |reports: &[Progress], consumed: usize, produced: usize, what: &str| {
+            assert_eq!(reports.last(), Some(&Progress { consumed: consumed as u64, produced: produced as u64 }), "reports the totals when {}", what);
+            assert!(reports.len() > consumed / 5000, "reports every interval when {}", what);
+            assert!(reports.windows(2).all(|pair| pair[0].consumed <= pair[1].consumed), "reports increasing progress when {}", what);
+        };
+
+        let (reports, callback) = recorder();
+        let mut writer = EncodeWriter::new(Vec::new(), options.clone()).unwrap().on_progress(5000, callback);
+        source_data.chunks(100).for_each(|chunk| writer.write_all(chunk).unwrap());
+        writer.finish().unwrap();
+        check(&reports.lock().unwrap(), source_data.len(), encoded.len(), "encoding to a writer");
+
+        let (reports, callback) = recorder();
+        let mut writer = DecodeWriter::new(Vec::new(), DecodeOptions::new()).on_progress(5000, callback);
+        encoded.as_bytes().chunks(100).for_each(|chunk| writer.write_all(chunk).unwrap());
+        writer.finish().unwrap();
+        check(&reports.lock().unwrap(), encoded.len(), source_data.len(), "decoding to a writer");
+
+        let (reports, callback) = recorder();
+        let mut reader = EncodeReader::new(io::BufReader::with_capacity(100, &source_data[..]), options.clone()).unwrap().on_progress(5000, callback);
+        io::copy(&mut reader, &mut io::sink()).unwrap();
+        check(&reports.lock().unwrap(), source_data.len(), encoded.len(), "encoding from a reader");
+
+        let (reports, callback) = recorder();
+        let mut reader = DecodeReader::new(io::BufReader::with_capacity(100, encoded.as_bytes()), DecodeOptions::new()).on_progress(5000, callback);
+        io::copy(&mut reader, &mut io::sink()).unwrap();
+        check(&reports.lock().unwrap(), encoded.len(), source_data.len(), "decoding from a reader");
+    }
+
+    /// Tests the one-shot writer helpers against the in-memory functions
+    #[test]
+    fn test_to_writer() {
+        let source_data = std::fs::read("test_data/random_data.bin").expect("Can open test data");
+        let options = FileOptions::new("random_data.bin").mode(0o600);
+        let mut encoded = Vec::new();
+        let written = uuencode_to_writer(&source_data[..], &mut encoded, options.clone()).unwrap();
+        assert_eq!(encoded, uuencode_file(&source_data, &options).unwrap().as_bytes(), "can encode to a writer");
+        assert_eq!(written, encoded.len() as u64, "returns the number of bytes written");
+
+        let mut decoded = Vec::new();
+        let header = uudecode_to_writer(&encoded[..], &mut decoded, DecodeOptions::new()).unwrap();
+        assert_eq!(header, (0o600, "random_data.bin".to_string()), "returns the header");
+        assert_eq!(decoded, source_data, "can decode to a writer");
+        assert!(uudecode_to_writer(&encoded[..100], io::sink(), DecodeOptions::new()).is_err(), "rejects truncated input");
+        assert!(uudecode_to_writer(&b"no file here"[..], io::sink(), DecodeOptions::new()).is_err(), "rejects input without a file");
+    }
+
+    /// Tests decoding bare lines from a buffered reader
+    #[test]
+    fn test_uudecode_bufread() {
+        let source_data = std::fs::read("test_data/random_data.bin").expect("Can open test data");
+        let encoded = std::fs::read_to_string("test_data/random_data.bin.uu").expect("Can open test data").replace('\n', "\r\n");
+        let reader = io::BufReader::with_capacity(7, encoded.as_bytes());
+        assert_eq!(uudecode_bufread(reader).unwrap(), source_data, "can decode CRLF lines through a small buffer");
+
+        let err = uudecode_bufread(&b"#8V%T\n#8V\x01T\n"[..]).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData, "reports invalid input");
+        let source = err.into_inner().unwrap().downcast::<UUEncodeError>().unwrap();
+        assert_eq!((source.line, source.character), (1, 3), "reports the position of an invalid character");
+    }
+
+    /// A writer that takes at most five bytes at a time, and would block on every third call until `until` bytes have
+    /// been written.
+    struct Stalling {
+        data: Vec<u8>,
+        calls: usize,
+        until: usize,
+    }
+
+    impl Write for Stalling {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            self.calls += 1;
+            if self.calls.is_multiple_of(3) && self.data.len() < self.until {
+                return Err(io::ErrorKind::WouldBlock.into());
+            }
+            let len = buf.len().min(5);
+            self.data.extend_from_slice(&buf[..len]);
+            Ok(len)
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    /// Writes all of `data`, retrying whenever the writer would block.
+    fn write_retrying(writer: &mut impl Write, mut data: &[u8]) {
+        while !data.is_empty() {
+            match writer.write(data) {
+                Ok(len) => data = &data[len..],
+                Err(err) => assert_eq!(err.kind(), io::ErrorKind::WouldBlock, "only fails when the inner writer would block"),
+            }
+        }
+    }
+
+    /// Tests that streamed output matches encoding all at once, for any write sizes
+    #[test]
+    fn test_encode_writer() {
+        let source_data = std::fs::read("test_data/random_data.bin").expect("Can open test data");
+        let all_options = [FileOptions::new("random_data.bin"), FileOptions::new("random_data.bin").base64(true).trailer(Some(Checksum::Crc32))];
+        for options in all_options {
+            let expected = uuencode_file(&source_data, &options).unwrap();
+            for write_size in [1, 44, 45, 46, 1000] {
+                let mut writer = EncodeWriter::new(Vec::new(), options.clone()).unwrap();
+                for chunk in source_data.chunks(write_size) {
+                    writer.write_all(chunk).unwrap();
+                }
+                assert_eq!(String::from_utf8(writer.finish().unwrap()).unwrap(), expected, "streams writes of {} bytes", write_size);
+            }
+        }
+        assert!(EncodeWriter::new(Vec::new(), FileOptions::new("")).is_err(), "rejects an invalid name");
+
+        let expected = uuencode_file(&source_data, &FileOptions::new("random_data.bin")).unwrap();
+        let inner = Stalling { data: Vec::new(), calls: 0, until: expected.len() / 2 };
+        let mut writer = EncodeWriter::new(inner, FileOptions::new("random_data.bin")).unwrap();
+        for chunk in source_data.chunks(100) {
+            write_retrying(&mut writer, chunk);
+        }
+        assert_eq!(writer.finish().unwrap().data, expected.as_bytes(), "neither repeats nor loses text when the inner writer blocks");
+    }
+
+    /// Tests decoding text split across writes at every possible point
+    #[test]
+    fn test_decode_writer() {
+        let source_data = std::fs::read("test_data/random_data.bin").expect("Can open test data");
+        let options = DecodeOptions::new().verify_trailer(true);
+        let encoded = uuencode_file(&source_data, &FileOptions::new("random_data.bin").mode(0o600).trailer(Some(Checksum::BsdSum))).unwrap().replace('\n', "\r\n");
+        for write_size in [1, 7, 61, 62, 63, 4096] {
+            let mut writer = DecodeWriter::new(Vec::new(), options.clone());
+            for chunk in encoded.as_bytes().chunks(write_size) {
+                writer.write_all(chunk).unwrap();
+            }
+            assert_eq!((writer.name(), writer.mode()), (Some("random_data.bin"), Some(0o600)), "reads the begin line");
+            assert_eq!(writer.finish().unwrap(), source_data, "decodes writes of {} bytes", write_size);
+        }
+
+        let inner = Stalling { data: Vec::new(), calls: 0, until: source_data.len() / 2 };
+        let mut writer = DecodeWriter::new(inner, options.clone());
+        for chunk in encoded.as_bytes().chunks(100) {
+            write_retrying(&mut writer, chunk);
+        }
+        assert_eq!(writer.finish().unwrap().data, source_data, "neither repeats nor loses bytes when the inner writer blocks");
+
+        let truncated = &encoded[..encoded.len() / 2];
+        let mut writer = DecodeWriter::new(Vec::new(), options.clone());
+        writer.write_all(truncated.as_bytes()).unwrap();
+        let err = writer.finish().unwrap_err();
+        let source = err.get_ref().and_then(|source| source.downcast_ref::<UUEncodeError>()).expect("carries the decoding error");
+        assert_eq!(source.to_string(), uudecode_file_with(truncated.as_bytes(), &options).unwrap_err().to_string(), "reports the same error as decoding all at once");
+
+        let mut writer = DecodeWriter::new(Vec::new(), options);
+        assert_eq!(writer.write_all(b"begin 644 ../evil\n").unwrap_err().kind(), io::ErrorKind::InvalidData, "reports decoding errors from write");
+    }
+
+    /// Tests that reading encoded output matches encoding all at once, for any read sizes
+    #[test]
+    fn test_encode_reader() {
+        let source_data = std::fs::read("test_data/random_data.bin").expect("Can open test data");
+        let options = FileOptions::new("random_data.bin").trailer(Some(Checksum::Crc32));
+        let expected = uuencode_file(&source_data, &options).unwrap();
+        for read_size in [1, 60, 62, 4096] {
+            let mut reader = EncodeReader::new(&source_data[..], options.clone()).unwrap();
+            let mut encoded = Vec::new();
+            let mut buf = vec![0u8; read_size];
+            loop {
+                let len = reader.read(&mut buf).unwrap();
+                if len == 0 {
+                    break;
+                }
+                encoded.extend_from_slice(&buf[..len]);
+            }
+            assert_eq!(String::from_utf8(encoded).unwrap(), expected, "streams reads of {} bytes", read_size);
+        }
+    }
+
+    /// Tests reading decoded data, and the errors surfaced from malformed input
+    #[test]
+    fn test_decode_reader() {
+        let source_data = std::fs::read("test_data/random_data.bin").expect("Can open test data");
+        let encoded = uuencode_file(&source_data, &FileOptions::new("random_data.bin")).unwrap().into_bytes();
+        for read_size in [1, 45, 4096] {
+            let mut reader = DecodeReader::new(&encoded[..], DecodeOptions::new());
+            let mut decoded = Vec::new();
+            let mut buf = vec![0u8; read_size];
+            loop {
+                let len = reader.read(&mut buf).unwrap();
+                if len == 0 {
+                    break;
+                }
+                decoded.extend_from_slice(&buf[..len]);
+            }
+            assert_eq!(decoded, source_data, "streams reads of {} bytes", read_size);
+        }
+
+        let mut reader = DecodeReader::new(&encoded[..encoded.len() / 2], DecodeOptions::new());
+        let err = reader.read_to_end(&mut Vec::new()).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData, "reports truncated input");
+        assert!(err.get_ref().is_some_and(|source| source.is::<UUEncodeError>()), "carries the decoding error");
+
+        struct Unreadable;
+        impl Read for Unreadable {
+            fn read(&mut self, _: &mut [u8]) -> io::Result<usize> {
+                Err(io::Error::other("read past the end of the file"))
+            }
+        }
+        let mut reader = DecodeReader::new((&b"begin 644 cat.txt\n#8V%T\n`\nend\n"[..]).chain(Unreadable), DecodeOptions::new());
+        let mut decoded = Vec::new();
+        reader.read_to_end(&mut decoded).unwrap();
+        assert_eq!(decoded, b"cat", "stops reading at the end of the file");
+    }
+
+    /// A subscriber which writes down every span, recorded field and event, as `level name: field=value ...`
+    #[cfg(feature = "tracing")]
+    #[derive(Clone, Default)]
+    struct Recorder(std::sync::Arc<std::sync::Mutex<Vec<String>>>);
+
+    #[cfg(feature = "tracing")]
+    impl Recorder {
+        fn write(&self, level: &tracing::Level, name: &str, record: impl FnOnce(&mut dyn tracing::field::Visit)) {
+            use std::fmt::Write;
+            let mut text = std::format!("{} {}:", level, name);
+            record(&mut |field: &tracing::field::Field, value: &dyn std::fmt::Debug| write!(text, " {}={:?}", field.name(), value).unwrap());
+            self.0.lock().unwrap().push(text);
+        }
+    }
+
+    #[cfg(feature = "tracing")]
+    impl tracing::Subscriber for Recorder {
+        fn enabled(&self, _: &tracing::Metadata<'_>) -> bool {
+            true
+        }
+
+        fn new_span(&self, span: &tracing::span::Attributes<'_>) -> tracing::span::Id {
+            self.write(span.metadata().level(), span.metadata().name(), |visitor| span.record(visitor));
+            tracing::span::Id::from_u64(1)
+        }
+
+        fn record(&self, _: &tracing::span::Id, values: &tracing::span::Record<'_>) {
+            self.write(&tracing::Level::DEBUG, "record", |visitor| values.record(visitor));
+        }
+
+        fn record_follows_from(&self, _: &tracing::span::Id, _: &tracing::span::Id) {}
+
+        fn event(&self, event: &tracing::Event<'_>) {
+            self.write(event.metadata().level(), "event", |visitor| event.record(visitor));
+        }
+
+        fn enter(&self, _: &tracing::span::Id) {}
+
+        fn exit(&self, _: &tracing::span::Id) {}
+    }
+
+    /// Tests the spans and events recorded by the adapters and the multi-file functions
+    #[cfg(feature = "tracing")]
+    #[test]
+    fn test_tracing() {
+        let recorder = Recorder::default();
+        tracing::subscriber::with_default(recorder.clone(), || {
+            let mut writer = DecodeWriter::new(Vec::new(), DecodeOptions::new());
+            writer.write_all(b"begin 600 cat.txt\n#8V%T\n`\nend\n").unwrap();
+            writer.finish().unwrap();
+            let mut reader = EncodeReader::new(&b"cat"[..], FileOptions::new("cat.txt")).unwrap();
+            reader.read_to_end(&mut Vec::new()).unwrap();
+            crate::decode_all(b"begin 644 dog.txt\n#9&]G\n`\nend\nbegin 644 bad.txt\n#\x01\n", &DecodeOptions::new());
+        });
+        let recorded = recorder.0.lock().unwrap().clone();
+        let expected = [
+            "DEBUG stream: adapter=\"DecodeWriter\"",
+            "TRACE event: message=progress consumed=30 produced=3",
+            "TRACE event: message=progress consumed=30 produced=3",
+            "DEBUG record: file=\"cat.txt\"",
+            "DEBUG record: mode=600",
+            "DEBUG event: message=decoded lines=4",
+            "DEBUG event: message=finished consumed=30 produced=3",
+            "DEBUG stream: adapter=\"EncodeReader\" file=\"cat.txt\"",
+            "TRACE event: message=progress consumed=3 produced=18",
+            "TRACE event: message=progress consumed=3 produced=30",
+            "DEBUG event: message=finished consumed=3 produced=30",
+            "DEBUG decode_all: len=51",
+            "DEBUG event: message=decoded file name=dog.txt mode=644 len=3",
+            "WARN event: message=failed to decode file line=5 character=2 error=Line too short for declared length 3 at line 5 character 2",
+        ];
+        assert_eq!(recorded, expected);
+    }
+
+    /// Tests that the adapters keep their buffers between calls, so that once warmed up they stream any amount of
+    /// data, in writes of any size, without allocating
+    #[test]
+    fn test_steady_state_allocations() {
+        use crate::tests::count_allocations;
+        let source_data = std::fs::read("test_data/the_machine_stops.txt").expect("Can open test data");
+        // full lines only, so that repeating the body continues the same file
+        let source_data = &source_data[..source_data.len() / 45 * 45];
+        let large = source_data.repeat(16);
+        let body = crate::uuencode(source_data).unwrap() + "\n";
+        let large_body = body.repeat(16);
+        let header = "begin 644 the_machine_stops.txt\n";
+
+        let mut writer = EncodeWriter::new(io::sink(), FileOptions::new("the_machine_stops.txt")).unwrap();
+        writer.write_all(source_data).unwrap();
+        let ((), allocations) = count_allocations(|| writer.write_all(&large).unwrap());
+        assert_eq!(allocations, 0, "encodes to a writer without allocating");
+
+        let mut writer = DecodeWriter::new(io::sink(), DecodeOptions::new());
+        writer.write_all(header.as_bytes()).unwrap();
+        writer.write_all(body.as_bytes()).unwrap();
+        let ((), allocations) = count_allocations(|| large_body.as_bytes().chunks(1000).for_each(|chunk| writer.write_all(chunk).unwrap()));
+        assert_eq!(allocations, 0, "decodes to a writer without allocating");
+
+        let mut buf = [0u8; 4096];
+        let mut reader = EncodeReader::new(&large[..], FileOptions::new("the_machine_stops.txt")).unwrap();
+        reader.read_exact(&mut buf).unwrap();
+        let ((), allocations) = count_allocations(|| (0..100).for_each(|_| reader.read_exact(&mut buf).unwrap()));
+        assert_eq!(allocations, 0, "encodes from a reader without allocating");
+
+        let mut reader = DecodeReader::new(header.as_bytes().chain(large_body.as_bytes()), DecodeOptions::new());
+        reader.read_exact(&mut buf).unwrap();
+        let ((), allocations) = count_allocations(|| (0..100).for_each(|_| reader.read_exact(&mut buf).unwrap()));
+        assert_eq!(allocations, 0, "decodes from a reader without allocating");
+    }
+}
@@ -0,0 +1,190 @@
+#[cfg(feature = "alloc")]
+use alloc::format;
+#[cfg(feature = "alloc")]
+use crate::UUEncodeError;
+
+/// Marks a byte which is not part of the alphabet in a decode table.
+pub(crate) const INVALID: u8 = 0xFF;
+
+/// A 64-character alphabet used to encode 6-bit values, along with its reverse mapping.
+/// Custom tables can be used to avoid characters that a transport mangles; `FileOptions::emit_table`
+/// writes the table ahead of the `begin` line so that decoders can read it back.
+/// Example:
+/// ```rust
+/// # #[cfg(feature = "alloc")]
+/// fn encode_table() -> Result<(), uuencode_lite::UUEncodeError> {
+///     let table = uuencode_lite::Table::from_alphabet(b"+-0123456789ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz")?;
+///     let options = uuencode_lite::EncodeOptions::new().table(table.clone());
+///     let encoded = uuencode_lite::uuencode_with(b"cat", &options)?;
+///     assert_eq!(encoded, "1Mq3o");
+///     let decoded = uuencode_lite::uudecode_with(encoded.as_bytes(), &uuencode_lite::DecodeOptions::new().table(table))?;
+///     assert_eq!(decoded, b"cat");
+///     Ok(())
+/// }
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Table {
+    /// Maps each 6-bit value to its character.
+    encode: [u8; 64],
+    /// Maps each character to its 6-bit value, or `INVALID`.
+    decode: [u8; 256],
+}
+
+impl Table {
+    /// The standard uuencode alphabet, which accepts both ' ' and '`' as zero, as well as the NUL some encoders
+    /// pad lines with. Every other character outside the alphabet is invalid.
+    pub const STANDARD: Table = {
+        let mut table = Table::from_alphabet_const(b"`!\"#$%&'()*+,-./0123456789:;<=>?@ABCDEFGHIJKLMNOPQRSTUVWXYZ[\\]^_");
+        table.decode[b' ' as usize] = 0;
+        table.decode[0] = 0;
+        table
+    };
+
+    /// The xxencode alphabet, which avoids punctuation that some gateways mangle.
+    pub const XX: Table = Table::from_alphabet_const(b"+-0123456789ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz");
+
+    /// The standard Base64 alphabet from RFC 4648.
+    pub const BASE64: Table = Table::from_alphabet_const(b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/");
+
+    /// The URL and filename safe Base64 alphabet from RFC 4648.
+    pub const BASE64_URL: Table = Table::from_alphabet_const(b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789-_");
+
+    /// The original alphabet, which writes zero as a space rather than '`', as Python's `uu` module did. Custom
+    /// tables can't hold a space, as trailing spaces are easily stripped in transport, so this is only built in.
+    #[cfg(feature = "python")]
+    pub(crate) const SPACE_ZERO: Table = Table::from_alphabet_const(b" !\"#$%&'()*+,-./0123456789:;<=>?@ABCDEFGHIJKLMNOPQRSTUVWXYZ[\\]^_");
+
+    /// Builds a table from an alphabet known to be valid.
+    const fn from_alphabet_const(alphabet: &[u8; 64]) -> Table {
+        let mut encode = [0u8; 64];
+        let mut decode = [INVALID; 256];
+        let mut idx = 0;
+        while idx < 64 {
+            encode[idx] = alphabet[idx];
+            decode[alphabet[idx] as usize] = idx as u8;
+            idx += 1;
+        }
+        Table { encode, decode }
+    }
+
+    /// Creates a table from a 64-character alphabet, where the character at index `n` encodes `n`.
+    /// Every character must be distinct, and printable ASCII other than space, so that the encoded text can be
+    /// written out and read back as the alphabet itself.
+    #[cfg(feature = "alloc")]
+    pub fn from_alphabet(alphabet: &[u8]) -> Result<Table, UUEncodeError> {
+        if alphabet.len() != 64 {
+            return Err(UUEncodeError::new(0, alphabet.len(), format!("Table must have 64 characters, not {}", alphabet.len())));
+        }
+        let mut encode = [0u8; 64];
+        let mut decode = [INVALID; 256];
+        for (value, &ch) in alphabet.iter().enumerate() {
+            if !ch.is_ascii_graphic() {
+                return Err(UUEncodeError::new(0, value, format!("Table character is not printable ASCII: {:#04x}", ch)));
+            }
+            if decode[ch as usize] != INVALID {
+                return Err(UUEncodeError::new(0, value, format!("Duplicate character in table: {}", ch as char)));
+            }
+            encode[value] = ch;
+            decode[ch as usize] = value as u8;
+        }
+        Ok(Table { encode, decode })
+    }
+
+    /// The 64 characters of the alphabet, in order.
+    pub fn alphabet(&self) -> &[u8; 64] {
+        &self.encode
+    }
+
+    /// Whether the table encodes as the standard one does, so that the word-at-a-time and vector encoders can be used.
+    #[inline]
+    pub(crate) fn encodes_standard(&self) -> bool {
+        self.encode == Table::STANDARD.encode
+    }
+
+    /// Whether the table decodes as the standard one does, so that the vector decoders can be used. Decode tables
+    /// are only ever built from their alphabet, so checking the aliases the standard one adds is enough.
+    #[cfg(feature = "simd")]
+    #[inline]
+    pub(crate) fn decodes_standard(&self) -> bool {
+        self.encodes_standard() && self.decode[b' ' as usize] == 0 && self.decode[0] == 0
+    }
+
+    /// Encodes a 6-bit value into its character.
+    #[inline]
+    pub(crate) const fn encode(&self, value: u8) -> u8 {
+        self.encode[(value & 0x3F) as usize]
+    }
+
+    /// Encodes a value into its character, or `None` if it doesn't fit in 6 bits.
+    #[inline]
+    pub(crate) const fn encode_checked(&self, value: u8) -> Option<u8> {
+        if value < 64 { Some(self.encode[value as usize]) } else { None }
+    }
+
+    /// Decodes a character into its 6-bit value, or `None` if it isn't in the alphabet.
+    #[inline]
+    pub(crate) const fn decode(&self, ch: u8) -> Option<u8> {
+        match self.decode[ch as usize] {
+            INVALID => None,
+            value => Some(value),
+        }
+    }
+}
+
+impl Default for Table {
+    fn default() -> Self {
+        Table::STANDARD
+    }
+}
+
+#[cfg(all(test, feature = "alloc"))]
+mod tests {
+    use super::*;
+
+    /// Tests that the standard table agrees with the character functions
+    #[test]
+    fn test_standard_table() {
+        for value in 0..64u8 {
+            assert_eq!(Table::STANDARD.decode(Table::STANDARD.encode[value as usize]), Some(value), "round-trips {}", value);
+        }
+        assert_eq!(Table::STANDARD.decode(b' '), Some(0), "accepts space as zero");
+        assert_eq!(Table::STANDARD.decode(0), Some(0), "accepts NUL padding as zero");
+        for ch in (1..b' ').chain(b'a'..=0xFF) {
+            assert_eq!(Table::STANDARD.decode(ch), None, "rejects {:#04x}", ch);
+        }
+        for table in [Table::XX, Table::BASE64, Table::BASE64_URL] {
+            assert_eq!(Table::from_alphabet(table.alphabet()).unwrap(), table, "builds the predefined tables");
+        }
+        #[cfg(feature = "simd")]
+        {
+            assert!(Table::STANDARD.decodes_standard());
+            assert!(!Table::from_alphabet(Table::STANDARD.alphabet()).unwrap().decodes_standard(), "needs the aliases too");
+        }
+    }
+
+    /// Tests parsing tables, and rejecting malformed ones
+    #[test]
+    fn test_parse_table() {
+        let lines = ["abcdefghijklmnopqrstuvwxyz012345", "ABCDEFGHIJKLMNOPQRSTUVWXYZ6789+-"];
+        let decode = |table_lines: &[&str], body: &str| crate::uudecode_file(format!("table\n{}\nbegin 644 a\n{}\n\nend\n", table_lines.join("\n"), body).as_bytes());
+        assert_eq!(decode(&lines, "baaaa").unwrap().data, b"\0", "decodes with the table");
+        assert_eq!(decode(&lines, "b-aaa").unwrap().data, b"\xFC", "decodes the last character of the table");
+        assert!(decode(&lines, "b`aaa").is_err(), "rejects characters outside the table");
+
+        assert!(decode(&lines[..1], "baaaa").is_err(), "rejects a short table");
+        assert!(decode(&[lines[0], lines[0]], "baaaa").is_err(), "rejects duplicate characters");
+    }
+
+    /// Tests that custom tables are limited to printable ASCII, which can be written out and read back
+    #[test]
+    fn test_non_ascii_table() {
+        for ch in [0xE9, 0x80, b' ', b'\n', b'\r', b'\t', 0, 0x7F] {
+            let mut alphabet = *Table::XX.alphabet();
+            alphabet[5] = ch;
+            let err = Table::from_alphabet(&alphabet).unwrap_err();
+            assert_eq!(err.character, 5, "rejects {:#04x}", ch);
+        }
+        let alphabet: alloc::vec::Vec<u8> = (0x80..0xC0).collect();
+        assert!(Table::from_alphabet(&alphabet).is_err(), "rejects an alphabet beyond ASCII");
+    }
+}
@@ -0,0 +1,190 @@
+use crate::decoder::Decoder;
+use crate::stream::READ_CHUNK;
+use crate::DecodeOptions;
+use std::collections::VecDeque;
+use std::ffi::OsString;
+use std::future::Future;
+use std::io;
+use std::path::Path;
+use std::rc::Rc;
+use std::string::{String, ToString};
+use std::vec::Vec;
+use tokio_uring::buf::{BoundedBuf, Slice};
+use tokio_uring::fs::{self, File};
+use tokio_uring::BufResult;
+
+/// The number of reads of the input file kept in flight at once.
+const READS_IN_FLIGHT: usize = 4;
+
+/// A source of reads at given offsets, as a file opened for io_uring is, so that tests can make reads come up short
+/// or fail.
+trait ReadAt: 'static {
+    fn read_at(&self, buffer: Slice<Vec<u8>>, pos: u64) -> impl Future<Output = BufResult<usize, Slice<Vec<u8>>>>;
+}
+
+impl ReadAt for File {
+    fn read_at(&self, buffer: Slice<Vec<u8>>, pos: u64) -> impl Future<Output = BufResult<usize, Slice<Vec<u8>>>> {
+        File::read_at(self, buffer, pos)
+    }
+}
+
+/// Decodes the complete uuencoded file at `input`, as `uudecode_file_with`, writing the decoded bytes to a new file
+/// at `output`, through io_uring. Several reads of the input are kept in flight ahead of the decoder, each into a
+/// buffer owned by the kernel until it completes, and the decoded bytes are handed to the kernel to write without
+/// further copying. Reading stops at the end of the file. Returns the permission bits and file name from the
+/// `begin` line; they are not applied to `output`.
+/// The decoded bytes are written to `output` with `.part` appended, which is renamed to `output` once the file has
+/// decoded, or removed if it fails, so an existing file at `output` is only replaced by a complete one.
+/// Must be called from within a `tokio_uring` runtime.
+/// Example:
+/// ```rust
+/// fn decode() -> std::io::Result<()> {
+///     let (mode, name) = tokio_uring::start(uuencode_lite::uudecode_file_uring("cat.txt.uu", "cat.txt", uuencode_lite::DecodeOptions::new()))?;
+///     println!("decoded {} with mode {:o}", name, mode);
+///     Ok(())
+/// }
+/// ```
+pub async fn uudecode_file_uring(input: impl AsRef<Path>, output: impl AsRef<Path>, options: DecodeOptions) -> io::Result<(u32, String)> {
+    decode_to_file(File::open(input).await?, output.as_ref(), options).await
+}
+
+/// Decodes the file read from `input` into a partial file beside `output`, then renames it to `output`.
+async fn decode_to_file(input: impl ReadAt, output: &Path, options: DecodeOptions) -> io::Result<(u32, String)> {
+    let mut partial = OsString::from(output.as_os_str());
+    partial.push(".part");
+    let result = match decode_reads(input, Path::new(&partial), options).await {
+        Ok(header) => fs::rename(&partial, output).await.map(|()| header),
+        Err(err) => Err(err),
+    };
+    if result.is_err() {
+        let _ = fs::remove_file(&partial).await;
+    }
+    result
+}
+
+/// Decodes the file read from `input`, writing the decoded bytes to a new file at `output`.
+async fn decode_reads(input: impl ReadAt, output: &Path, options: DecodeOptions) -> io::Result<(u32, String)> {
+    let input = Rc::new(input);
+    let output = File::create(output).await?;
+    let mut decoder = Decoder::new(options);
+    // Each read in flight, with the offset and length it asked for.
+    let mut reads = VecDeque::with_capacity(READS_IN_FLIGHT);
+    let read = |pos: u64, len: usize, buffer: Vec<u8>| {
+        let input = Rc::clone(&input);
+        (pos, len, tokio_uring::spawn(async move { input.read_at(buffer.slice(..len), pos).await }))
+    };
+    let mut next_pos = 0u64;
+    for _ in 0..READS_IN_FLIGHT {
+        reads.push_back(read(next_pos, READ_CHUNK, Vec::with_capacity(READ_CHUNK)));
+        next_pos += READ_CHUNK as u64;
+    }
+
+    let mut decoded = Vec::with_capacity(READ_CHUNK);
+    let mut write_pos = 0u64;
+    while let Some((pos, len, handle)) = reads.pop_front() {
+        let (result, buffer) = handle.await.map_err(io::Error::other)?;
+        let mut buffer = buffer.into_inner();
+        let read_len = result?;
+        if read_len == 0 {
+            decoder.finish(&mut decoded)?;
+        } else {
+            decoder.push(&buffer, &mut decoded)?;
+        }
+        buffer.clear();
+        if read_len == 0 || decoder.is_done() {
+            reads.clear();
+        } else if read_len < len {
+            // A short read: the rest of its range is read next, ahead of the reads already in flight.
+            let pos = pos + read_len as u64;
+            reads.push_front(read(pos, len - read_len, buffer));
+        } else {
+            reads.push_back(read(next_pos, READ_CHUNK, buffer));
+            next_pos += READ_CHUNK as u64;
+        }
+        if !decoded.is_empty() {
+            let (result, buffer) = output.write_all_at(decoded, write_pos).await;
+            result?;
+            write_pos += buffer.len() as u64;
+            decoded = buffer;
+            decoded.clear();
+        }
+    }
+    output.sync_all().await?;
+    output.close().await?;
+    let (mode, name) = decoder.header().ok_or_else(|| io::Error::new(io::ErrorKind::InvalidData, "Input ended before a begin line"))?;
+    Ok((mode, name.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{uuencode_file, FileOptions};
+    use std::format;
+    use tokio_uring::buf::BoundedBufMut;
+
+    /// Tests decoding a file to a file through io_uring, and reporting a truncated file
+    #[test]
+    fn test_uudecode_file_uring() {
+        let source_data = std::fs::read("test_data/the_machine_stops.txt").expect("Can open test data");
+        let encoded = uuencode_file(&source_data, &FileOptions::new("the_machine_stops.txt")).unwrap();
+        let dir = std::env::temp_dir().join(format!("uuencode_lite_uring_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("encoded.uu"), &encoded).unwrap();
+        std::fs::write(dir.join("truncated.uu"), &encoded[..1000]).unwrap();
+
+        let (mode, name) = tokio_uring::start(uudecode_file_uring(dir.join("encoded.uu"), dir.join("decoded"), DecodeOptions::new())).unwrap();
+        assert_eq!((mode, name.as_str()), (0o644, "the_machine_stops.txt"), "returns the begin line");
+        assert_eq!(std::fs::read(dir.join("decoded")).unwrap(), source_data, "can decode file to file");
+
+        let err = tokio_uring::start(uudecode_file_uring(dir.join("truncated.uu"), dir.join("decoded"), DecodeOptions::new())).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData, "rejects a truncated file");
+        assert_eq!(std::fs::read(dir.join("decoded")).unwrap(), source_data, "leaves an existing file in place on failure");
+        assert!(!dir.join("decoded.part").exists(), "removes the partial file on failure");
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    /// A file in memory which reads at most `limit` bytes at a time, and fails any read from `fail_at` on.
+    struct Flaky {
+        data: Vec<u8>,
+        limit: usize,
+        fail_at: u64,
+    }
+
+    impl ReadAt for Flaky {
+        fn read_at(&self, mut buffer: Slice<Vec<u8>>, pos: u64) -> impl Future<Output = BufResult<usize, Slice<Vec<u8>>>> {
+            let result = if pos >= self.fail_at {
+                Err(io::Error::other("read failed"))
+            } else {
+                let start = (pos as usize).min(self.data.len());
+                let len = buffer.bytes_total().min(self.limit).min(self.data.len() - start);
+                buffer.put_slice(&self.data[start..start + len]);
+                Ok(len)
+            };
+            std::future::ready((result, buffer))
+        }
+    }
+
+    /// Tests that short reads are completed before decoding on, and that a failed read leaves no output
+    #[test]
+    fn test_uring_short_and_failed_reads() {
+        let source_data = std::fs::read("test_data/the_machine_stops.txt").expect("Can open test data");
+        let encoded = uuencode_file(&source_data, &FileOptions::new("the_machine_stops.txt")).unwrap().into_bytes();
+        let dir = std::env::temp_dir().join(format!("uuencode_lite_uring_flaky_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let output = dir.join("decoded");
+
+        for limit in [1, 100, READ_CHUNK - 1] {
+            let input = Flaky { data: encoded.clone(), limit, fail_at: u64::MAX };
+            let (_, name) = tokio_uring::start(decode_to_file(input, &output, DecodeOptions::new())).unwrap();
+            assert_eq!(name, "the_machine_stops.txt", "returns the begin line after reads of {} bytes", limit);
+            assert_eq!(std::fs::read(&output).unwrap(), source_data, "decodes reads of at most {} bytes", limit);
+            std::fs::remove_file(&output).unwrap();
+        }
+
+        let input = Flaky { data: encoded, limit: 1000, fail_at: 5000 };
+        let err = tokio_uring::start(decode_to_file(input, &output, DecodeOptions::new())).unwrap_err();
+        assert_eq!(err.to_string(), "read failed", "reports a failed read");
+        assert!(!output.exists() && !dir.join("decoded.part").exists(), "leaves no output after a failed read");
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,151 @@
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use crate::{uudecode_file, uuencode_file, uudecode, uuencode, Decoder, DecodeOptions, DecodedFile, FileOptions, UUEncodeError};
+use wasm_bindgen::prelude::*;
+
+/// Converts an error to a JavaScript `Error` carrying its message.
+fn js_error(err: UUEncodeError) -> JsError {
+    JsError::new(&err.to_string())
+}
+
+/// Encodes bytes as bare uuencoded lines, as `uuencode`. Exported to JavaScript as `uuencode`.
+#[wasm_bindgen(js_name = uuencode)]
+pub fn wasm_uuencode(data: &[u8]) -> Result<String, JsError> {
+    uuencode(data).map_err(js_error)
+}
+
+/// Decodes bare uuencoded lines, as `uudecode`. Exported to JavaScript as `uudecode`.
+#[wasm_bindgen(js_name = uudecode)]
+pub fn wasm_uudecode(text: &str) -> Result<Vec<u8>, JsError> {
+    uudecode(text.as_bytes()).map_err(js_error)
+}
+
+/// Encodes bytes as a complete file with the given name and mode, as `uuencode_file`.
+/// Exported to JavaScript as `uuencodeFile`.
+#[wasm_bindgen(js_name = uuencodeFile)]
+pub fn wasm_uuencode_file(data: &[u8], name: &str, mode: u32) -> Result<String, JsError> {
+    uuencode_file(data, &FileOptions::new(name).mode(mode)).map_err(js_error)
+}
+
+/// Decodes the first complete file in the text, as `uudecode_file`. Exported to JavaScript as `uudecodeFile`.
+#[wasm_bindgen(js_name = uudecodeFile)]
+pub fn wasm_uudecode_file(text: &str) -> Result<WasmDecodedFile, JsError> {
+    uudecode_file(text.as_bytes()).map(|file| WasmDecodedFile { file }).map_err(js_error)
+}
+
+/// A decoded file, as `DecodedFile`. Exported to JavaScript as `DecodedFile`.
+#[wasm_bindgen(js_name = DecodedFile)]
+pub struct WasmDecodedFile {
+    file: DecodedFile,
+}
+
+#[wasm_bindgen(js_class = DecodedFile)]
+impl WasmDecodedFile {
+    /// The file name declared on the `begin` line.
+    #[wasm_bindgen(getter)]
+    pub fn name(&self) -> String {
+        self.file.name.clone()
+    }
+
+    /// The permission bits declared on the `begin` line.
+    #[wasm_bindgen(getter)]
+    pub fn mode(&self) -> u32 {
+        self.file.mode
+    }
+
+    /// The decoded contents of the file, as a `Uint8Array`.
+    #[wasm_bindgen(getter)]
+    pub fn data(&self) -> Vec<u8> {
+        self.file.data.clone()
+    }
+}
+
+/// Incrementally decodes a complete file from text that arrives in pieces, as `Decoder`, such as a message
+/// fetched with a streaming `fetch`. Exported to JavaScript as `Decoder`.
+/// Example, in JavaScript:
+/// ```js
+/// const decoder = new Decoder();
+/// const parts = [];
+/// for await (const chunk of response.body) {
+///     parts.push(decoder.push(chunk));
+/// }
+/// parts.push(decoder.finish());
+/// console.log(`decoded ${decoder.name} with mode ${decoder.mode.toString(8)}`);
+/// ```
+#[wasm_bindgen(js_name = Decoder)]
+pub struct WasmDecoder {
+    decoder: Decoder,
+}
+
+impl Default for WasmDecoder {
+    fn default() -> Self {
+        WasmDecoder::new()
+    }
+}
+
+#[wasm_bindgen(js_class = Decoder)]
+impl WasmDecoder {
+    /// Creates a decoder with the default options.
+    #[wasm_bindgen(constructor)]
+    pub fn new() -> WasmDecoder {
+        WasmDecoder { decoder: Decoder::new(DecodeOptions::new()) }
+    }
+
+    /// Decodes the next piece of text, returning the bytes decoded from the lines it completes.
+    pub fn push(&mut self, data: &[u8]) -> Result<Vec<u8>, JsError> {
+        let mut decoded = Vec::new();
+        self.decoder.push(data, &mut decoded).map_err(js_error)?;
+        Ok(decoded)
+    }
+
+    /// Decodes any last line without a newline, returning its bytes, and checks that the file was complete.
+    pub fn finish(&mut self) -> Result<Vec<u8>, JsError> {
+        let mut decoded = Vec::new();
+        self.decoder.finish(&mut decoded).map_err(js_error)?;
+        Ok(decoded)
+    }
+
+    /// The file name from the `begin` line, or `undefined` if it has not been seen yet.
+    #[wasm_bindgen(getter)]
+    pub fn name(&self) -> Option<String> {
+        self.decoder.header().map(|(_, name)| name.to_string())
+    }
+
+    /// The permission bits from the `begin` line, or `undefined` if it has not been seen yet.
+    #[wasm_bindgen(getter)]
+    pub fn mode(&self) -> Option<u32> {
+        self.decoder.header().map(|(mode, _)| mode)
+    }
+
+    /// Whether the end of the file has been reached.
+    #[wasm_bindgen(getter, js_name = isDone)]
+    pub fn is_done(&self) -> bool {
+        self.decoder.is_done()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Tests the bindings as plain Rust functions; errors can only be built on a wasm target
+    #[test]
+    fn test_wasm_bindings() {
+        let source_data = std::fs::read("test_data/the_machine_stops.txt").expect("Can open test data");
+        let encoded = wasm_uuencode(&source_data).unwrap();
+        assert_eq!(wasm_uudecode(&encoded).unwrap(), uudecode(encoded.as_bytes()).unwrap(), "can encode and decode");
+
+        let encoded = wasm_uuencode_file(&source_data, "the_machine_stops.txt", 0o600).unwrap();
+        let file = wasm_uudecode_file(&encoded).unwrap();
+        assert_eq!((file.name().as_str(), file.mode(), file.data()), ("the_machine_stops.txt", 0o600, source_data.clone()), "can encode and decode a file");
+
+        let mut decoder = WasmDecoder::new();
+        let mut decoded = Vec::new();
+        for piece in encoded.as_bytes().chunks(100) {
+            decoded.extend(decoder.push(piece).unwrap());
+        }
+        decoded.extend(decoder.finish().unwrap());
+        assert_eq!((decoder.name().as_deref(), decoder.mode(), decoder.is_done()), (Some("the_machine_stops.txt"), Some(0o600), true), "reports the begin line");
+        assert_eq!(decoded, source_data, "can decode in pieces");
+    }
+}
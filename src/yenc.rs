@@ -0,0 +1,297 @@
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use crate::checksum::Crc32;
+use crate::file::numbered_lines;
+use crate::UUEncodeError;
+
+/// Options for yEnc encoding.
+/// Example:
+/// ```rust
+/// let options = uuencode_lite::YEncOptions::new("cat.txt").line_length(64);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct YEncOptions {
+    /// The file name written to the `=ybegin` line.
+    name: String,
+    /// The number of encoded characters per line, not counting a final escape.
+    line_length: usize,
+}
+
+impl YEncOptions {
+    /// Creates options for a file with the given name, and the customary line length of 128.
+    pub fn new(name: impl Into<String>) -> Self {
+        YEncOptions { name: name.into(), line_length: 128 }
+    }
+
+    /// Sets the number of encoded characters per line. Lines may be one character longer, to finish an escape.
+    pub fn line_length(mut self, line_length: usize) -> Self {
+        self.line_length = line_length.max(1);
+        self
+    }
+}
+
+/// A complete file decoded from yEnc.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct YEncFile {
+    /// The file name declared on the `=ybegin` line.
+    pub name: String,
+    /// The decoded contents of the file.
+    pub data: Vec<u8>,
+}
+
+/// One part of a multi-part yEnc post, decoded.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct YEncPart {
+    /// The file name declared on the `=ybegin` line.
+    pub name: String,
+    /// The part number, starting at 1.
+    pub part: u32,
+    /// The number of parts, if declared.
+    pub total: Option<u32>,
+    /// The size of the complete file.
+    pub file_size: usize,
+    /// The offset of this part's data within the complete file.
+    pub offset: usize,
+    /// The CRC-32 of the complete file, if declared on the `=yend` line.
+    pub file_crc32: Option<u32>,
+    /// The decoded contents of this part.
+    pub data: Vec<u8>,
+}
+
+/// Encodes one part's data as yEnc lines, appending to `encoded`.
+fn encode_lines(data: &[u8], line_length: usize, encoded: &mut Vec<u8>) {
+    let mut column = 0;
+    for (idx, &byte) in data.iter().enumerate() {
+        let ch = byte.wrapping_add(42);
+        let at_line_end = column + 1 >= line_length || idx + 1 == data.len();
+        let escape = match ch {
+            0x00 | 0x0A | 0x0D | b'=' => true,
+            b'\t' | b' ' => column == 0 || at_line_end,
+            b'.' => column == 0,
+            _ => false,
+        };
+        if escape {
+            encoded.push(b'=');
+            encoded.push(ch.wrapping_add(64));
+            column += 2;
+        } else {
+            encoded.push(ch);
+            column += 1;
+        }
+        if column >= line_length && idx + 1 < data.len() {
+            encoded.extend_from_slice(b"\r\n");
+            column = 0;
+        }
+    }
+    if column > 0 {
+        encoded.extend_from_slice(b"\r\n");
+    }
+}
+
+fn crc32(data: &[u8]) -> u32 {
+    let mut crc = Crc32::new();
+    crc.update(data);
+    crc.finish()
+}
+
+/// Encodes the input data as a single-part yEnc file, with `=ybegin` and `=yend` lines.
+/// As yEnc is an 8-bit encoding, the output is not necessarily valid UTF-8. Lines end with CRLF.
+/// Example:
+/// ```rust
+/// let encoded = uuencode_lite::yenc_encode(b"cat", &uuencode_lite::YEncOptions::new("cat.txt"));
+/// assert_eq!(encoded, b"=ybegin line=128 size=3 name=cat.txt\r\n\x8d\x8b\x9e\r\n=yend size=3 crc32=9e5e43a8\r\n");
+/// ```
+pub fn yenc_encode(data: &[u8], options: &YEncOptions) -> Vec<u8> {
+    let mut encoded = Vec::with_capacity(data.len() + data.len() / 32 + options.name.len() + 80);
+    encoded.extend_from_slice(format!("=ybegin line={} size={} name={}\r\n", options.line_length, data.len(), options.name).as_bytes());
+    encode_lines(data, options.line_length, &mut encoded);
+    encoded.extend_from_slice(format!("=yend size={} crc32={:08x}\r\n", data.len(), crc32(data)).as_bytes());
+    encoded
+}
+
+/// Encodes the input data as a multi-part yEnc file, with at most `part_size` bytes of data in each part.
+/// Each part has `=ybegin`, `=ypart` and `=yend` lines; the `=yend` line carries the part's CRC-32
+/// and the CRC-32 of the complete file.
+pub fn yenc_encode_parts(data: &[u8], options: &YEncOptions, part_size: usize) -> Vec<Vec<u8>> {
+    let part_size = part_size.max(1);
+    let total = data.len().div_ceil(part_size).max(1);
+    let file_crc = crc32(data);
+    (0..total)
+        .map(|idx| {
+            let begin = idx * part_size;
+            let part_data = &data[begin..(begin + part_size).min(data.len())];
+            let mut encoded = Vec::with_capacity(part_data.len() + part_data.len() / 32 + options.name.len() + 160);
+            encoded.extend_from_slice(format!("=ybegin part={} total={} line={} size={} name={}\r\n", idx + 1, total, options.line_length, data.len(), options.name).as_bytes());
+            encoded.extend_from_slice(format!("=ypart begin={} end={}\r\n", begin + 1, begin + part_data.len()).as_bytes());
+            encode_lines(part_data, options.line_length, &mut encoded);
+            encoded.extend_from_slice(format!("=yend size={} part={} pcrc32={:08x} crc32={:08x}\r\n", part_data.len(), idx + 1, crc32(part_data), file_crc).as_bytes());
+            encoded
+        })
+        .collect()
+}
+
+/// Looks up a `key=value` field in a yEnc header line. `name` always runs to the end of the line.
+fn header_field<'a>(line: &'a str, key: &str) -> Option<&'a str> {
+    if key == "name" {
+        return line.find(" name=").map(|idx| line[idx + 6..].trim_end());
+    }
+    let before_name = line.find(" name=").map_or(line, |idx| &line[..idx]);
+    before_name.split_whitespace().find_map(|field| field.strip_prefix(key)?.strip_prefix('='))
+}
+
+fn parse_field<T: core::str::FromStr>(line: &str, key: &str, cur_line: usize) -> Result<Option<T>, UUEncodeError> {
+    header_field(line, key).map(|value| value.parse().map_err(|_| UUEncodeError::new(cur_line, 0, format!("Invalid {} in yEnc header: {}", key, value)))).transpose()
+}
+
+fn parse_crc(line: &str, key: &str, cur_line: usize) -> Result<Option<u32>, UUEncodeError> {
+    header_field(line, key).map(|value| u32::from_str_radix(value, 16).map_err(|_| UUEncodeError::new(cur_line, 0, format!("Invalid {} in yEnc trailer: {}", key, value)))).transpose()
+}
+
+/// Decodes one yEnc part (or a complete single-part file) from the input, skipping any text before `=ybegin`.
+/// The decoded size and CRC-32 are verified against the `=yend` line.
+pub fn yenc_decode_part(data: &[u8]) -> Result<YEncPart, UUEncodeError> {
+    let mut lines = numbered_lines(data);
+    let Some((begin_line, begin)) = lines.find(|(_, line)| line.starts_with(b"=ybegin ")) else {
+        return Err(UUEncodeError::new(0, 0, "Missing =ybegin line"));
+    };
+    let begin = String::from_utf8_lossy(begin);
+    let name = header_field(&begin, "name").ok_or_else(|| UUEncodeError::new(begin_line, 0, "Missing name in =ybegin line"))?.to_string();
+    let file_size: usize = parse_field(&begin, "size", begin_line)?.ok_or_else(|| UUEncodeError::new(begin_line, 0, "Missing size in =ybegin line"))?;
+    let part: Option<u32> = parse_field(&begin, "part", begin_line)?;
+    let total: Option<u32> = parse_field(&begin, "total", begin_line)?;
+
+    let mut offset = 0;
+    let mut decoded = Vec::with_capacity(file_size.min(data.len()));
+    let mut escaped = false;
+    for (cur_line, line) in lines {
+        if part.is_some() && line.starts_with(b"=ypart ") {
+            let line = String::from_utf8_lossy(line);
+            let part_begin: usize = parse_field(&line, "begin", cur_line)?.ok_or_else(|| UUEncodeError::new(cur_line, 0, "Missing begin in =ypart line"))?;
+            offset = part_begin.saturating_sub(1);
+            continue;
+        }
+        if line.starts_with(b"=yend") {
+            let line = String::from_utf8_lossy(line);
+            let size: Option<usize> = parse_field(&line, "size", cur_line)?;
+            if size.is_some_and(|size| size != decoded.len()) {
+                return Err(UUEncodeError::new(cur_line, 0, format!("Size mismatch: =yend declares {} bytes, decoded {}", size.unwrap_or_default(), decoded.len())));
+            }
+            let (part_crc, file_crc) = match part {
+                Some(_) => (parse_crc(&line, "pcrc32", cur_line)?, parse_crc(&line, "crc32", cur_line)?),
+                None => (parse_crc(&line, "crc32", cur_line)?, None),
+            };
+            if part_crc.is_some_and(|crc| crc != crc32(&decoded)) {
+                return Err(UUEncodeError::new(cur_line, 0, "CRC-32 mismatch"));
+            }
+            if part.is_none() && decoded.len() != file_size {
+                return Err(UUEncodeError::new(cur_line, 0, format!("Size mismatch: =ybegin declares {} bytes, decoded {}", file_size, decoded.len())));
+            }
+            return Ok(YEncPart { name, part: part.unwrap_or(1), total, file_size, offset, file_crc32: file_crc, data: decoded });
+        }
+
+        // undo NNTP dot-stuffing
+        let line = if line.starts_with(b"..") { &line[1..] } else { line };
+        for &ch in line {
+            if escaped {
+                decoded.push(ch.wrapping_sub(64).wrapping_sub(42));
+                escaped = false;
+            } else if ch == b'=' {
+                escaped = true;
+            } else {
+                decoded.push(ch.wrapping_sub(42));
+            }
+        }
+        escaped = false;
+    }
+    Err(UUEncodeError::new(0, 0, "Missing =yend line"))
+}
+
+/// Decodes a complete single-part yEnc file, as produced by `yenc_encode`.
+/// Example:
+/// ```rust
+/// fn decode() -> Result<(), uuencode_lite::UUEncodeError> {
+///     let encoded = uuencode_lite::yenc_encode(b"cat", &uuencode_lite::YEncOptions::new("cat.txt"));
+///     let file = uuencode_lite::yenc_decode(&encoded)?;
+///     assert_eq!((file.name.as_str(), file.data.as_slice()), ("cat.txt", &b"cat"[..]));
+///     Ok(())
+/// }
+/// ```
+pub fn yenc_decode(data: &[u8]) -> Result<YEncFile, UUEncodeError> {
+    let part = yenc_decode_part(data)?;
+    if part.offset != 0 || part.data.len() != part.file_size {
+        return Err(UUEncodeError::new(0, 0, format!("Input is part {} of a multi-part file", part.part)));
+    }
+    Ok(YEncFile { name: part.name, data: part.data })
+}
+
+/// Decodes the parts of a multi-part yEnc file, given in any order, and joins them.
+/// Every byte of the file must be covered by a part, and the CRC-32 of the complete file is verified if declared.
+pub fn yenc_join(parts: &[&[u8]]) -> Result<YEncFile, UUEncodeError> {
+    let mut decoded: Vec<YEncPart> = parts.iter().map(|part| yenc_decode_part(part)).collect::<Result<_, _>>()?;
+    decoded.sort_by_key(|part| part.offset);
+    let Some(first) = decoded.first() else {
+        return Err(UUEncodeError::new(0, 0, "No parts to join"));
+    };
+    let (name, file_size, file_crc) = (first.name.clone(), first.file_size, decoded.iter().find_map(|part| part.file_crc32));
+
+    let mut data = Vec::with_capacity(file_size.min(parts.iter().map(|part| part.len()).sum()));
+    for part in &decoded {
+        if part.offset != data.len() {
+            return Err(UUEncodeError::new(0, 0, format!("Parts do not cover bytes {} to {}", data.len(), part.offset)));
+        }
+        data.extend_from_slice(&part.data);
+    }
+    if data.len() != file_size {
+        return Err(UUEncodeError::new(0, 0, format!("Parts cover {} of {} bytes", data.len(), file_size)));
+    }
+    if file_crc.is_some_and(|crc| crc != crc32(&data)) {
+        return Err(UUEncodeError::new(0, 0, "CRC-32 mismatch for the complete file"));
+    }
+    Ok(YEncFile { name, data })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Tests that every byte value round-trips, including the critical characters
+    #[test]
+    fn test_yenc_rt() {
+        let data: Vec<u8> = (0..=255u8).chain((0..=255u8).rev()).collect();
+        let encoded = yenc_encode(&data, &YEncOptions::new("all bytes.bin").line_length(16));
+        for line in encoded.split(|&ch| ch == b'\n') {
+            let line = line.strip_suffix(b"\r").unwrap_or(line);
+            if !line.starts_with(b"=y") {
+                assert!(line.len() <= 17, "limits line length");
+                assert!(!line.contains(&0) && !line.contains(&b'\r') && !line.starts_with(b"."), "escapes critical characters");
+            }
+        }
+        let file = yenc_decode(&encoded).unwrap();
+        assert_eq!(file, YEncFile { name: "all bytes.bin".to_string(), data }, "can round-trip every byte");
+    }
+
+    /// Tests encoding, shuffling and joining parts
+    #[test]
+    fn test_yenc_parts() {
+        let source_data = std::fs::read("test_data/random_data.bin").expect("Can open test data");
+        let parts = yenc_encode_parts(&source_data, &YEncOptions::new("random_data.bin"), 1000);
+        assert_eq!(parts.len(), source_data.len().div_ceil(1000));
+        let mut shuffled: Vec<&[u8]> = parts.iter().map(Vec::as_slice).collect();
+        shuffled.reverse();
+        assert_eq!(yenc_join(&shuffled).unwrap().data, source_data, "can join shuffled parts");
+        assert!(yenc_join(&shuffled[1..]).is_err(), "reports a missing part");
+        assert!(yenc_decode(&parts[0]).is_err(), "refuses a lone part as a complete file");
+    }
+
+    /// Tests that corruption is detected
+    #[test]
+    fn test_yenc_errors() {
+        let mut encoded = yenc_encode(b"cat", &YEncOptions::new("cat.txt"));
+        let body = encoded.iter().position(|&ch| ch == b'\n').unwrap() + 1;
+        encoded[body] += 1;
+        assert!(yenc_decode(&encoded).is_err(), "detects a CRC-32 mismatch");
+        assert!(yenc_decode(b"=ybegin line=128 size=3 name=cat.txt\r\n\x8d\x8b\x9e\r\n").is_err(), "requires an =yend line");
+        assert!(yenc_decode(b"=ybegin line=128 size=4 name=cat.txt\r\n\x8d\x8b\x9e\r\n=yend size=3\r\n").is_err(), "detects a size mismatch");
+    }
+}
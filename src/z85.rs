@@ -0,0 +1,102 @@
+use alloc::format;
+use alloc::string::String;
+use alloc::vec::Vec;
+use crate::{Message, UUEncodeError};
+
+/// The Z85 alphabet, from ZeroMQ RFC 32.
+const ALPHABET: &[u8; 85] = b"0123456789abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ.-:+=^!/*?&<>()[]{}@%$#";
+
+/// Marks a byte which is not part of the alphabet in `DECODE_TABLE`.
+const INVALID: u8 = 0xFF;
+
+const DECODE_TABLE: [u8; 256] = {
+    let mut table = [INVALID; 256];
+    let mut idx = 0;
+    while idx < 85 {
+        table[ALPHABET[idx] as usize] = idx as u8;
+        idx += 1;
+    }
+    table
+};
+
+/// Encodes the input data as Z85 (ZeroMQ Base85).
+/// Z85 has no padding, so the input length must be a multiple of 4.
+/// Example:
+/// ```rust
+/// fn encode() -> Result<(), uuencode_lite::UUEncodeError> {
+///     let encoded = uuencode_lite::z85_encode(&[0x86, 0x4F, 0xD2, 0x6F, 0xB5, 0x59, 0xF7, 0x5B])?;
+///     assert_eq!(encoded, "HelloWorld");
+///     Ok(())
+/// }
+/// ```
+pub fn z85_encode(data: &[u8]) -> Result<String, UUEncodeError> {
+    if !data.len().is_multiple_of(4) {
+        return Err(UUEncodeError::new(0, data.len(), format!("Z85 input length must be a multiple of 4, not {}", data.len())));
+    }
+    let mut encoded = String::with_capacity(data.len() / 4 * 5);
+    for chunk in data.chunks_exact(4) {
+        let mut value = u32::from_be_bytes([chunk[0], chunk[1], chunk[2], chunk[3]]);
+        let mut digits = [0u8; 5];
+        for digit in digits.iter_mut().rev() {
+            *digit = ALPHABET[(value % 85) as usize];
+            value /= 85;
+        }
+        encoded.extend(digits.iter().map(|&digit| digit as char));
+    }
+    Ok(encoded)
+}
+
+/// Decodes Z85 (ZeroMQ Base85) text. The input length must be a multiple of 5, and no whitespace is allowed.
+/// Example:
+/// ```rust
+/// fn decode() -> Result<(), uuencode_lite::UUEncodeError> {
+///     let decoded = uuencode_lite::z85_decode(b"HelloWorld")?;
+///     assert_eq!(decoded, [0x86, 0x4F, 0xD2, 0x6F, 0xB5, 0x59, 0xF7, 0x5B]);
+///     Ok(())
+/// }
+/// ```
+pub fn z85_decode(data: &[u8]) -> Result<Vec<u8>, UUEncodeError> {
+    if !data.len().is_multiple_of(5) {
+        return Err(UUEncodeError::new(0, data.len(), format!("Z85 input length must be a multiple of 5, not {}", data.len())));
+    }
+    let mut decoded = Vec::with_capacity(data.len() / 5 * 4);
+    for (group_idx, chunk) in data.chunks_exact(5).enumerate() {
+        let mut value = 0u32;
+        for (idx, &ch) in chunk.iter().enumerate() {
+            let cur_char = group_idx * 5 + idx;
+            let digit = match DECODE_TABLE[ch as usize] {
+                INVALID => return Err(UUEncodeError::new(0, cur_char, Message::InvalidCharacter(ch))),
+                digit => digit,
+            };
+            value = value.checked_mul(85).and_then(|value| value.checked_add(digit as u32)).ok_or_else(|| UUEncodeError::new(0, cur_char, "Z85 group out of range"))?;
+        }
+        decoded.extend_from_slice(&value.to_be_bytes());
+    }
+    Ok(decoded)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Tests round-tripping random data, such as a CurveZMQ key
+    #[test]
+    fn test_z85_rt() {
+        let source_data = std::fs::read("test_data/random_data.bin").expect("Can open test data");
+        let key = &source_data[..32];
+        let encoded = z85_encode(key).unwrap();
+        assert_eq!(encoded.len(), 40, "encodes a key as 40 characters");
+        assert_eq!(z85_decode(encoded.as_bytes()).unwrap(), key, "can round-trip a key");
+    }
+
+    /// Tests validation of lengths and characters
+    #[test]
+    fn test_z85_errors() {
+        assert!(z85_encode(b"cat").is_err(), "rejects an input length that is not a multiple of 4");
+        assert!(z85_decode(b"Hello").is_ok());
+        assert!(z85_decode(b"Hell").is_err(), "rejects an input length that is not a multiple of 5");
+        let err = z85_decode(b"Hello Worl").unwrap_err();
+        assert_eq!(err.character, 5, "reports the position of an invalid character");
+        assert!(z85_decode(b"%nSc1").is_err(), "rejects an out of range group");
+    }
+}
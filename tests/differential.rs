@@ -0,0 +1,143 @@
+//! Compares the library byte-for-byte with the system's `uuencode` and `uudecode`, from GNU sharutils and BusyBox,
+//! across a corpus of test data and awkward lengths. The tests are ignored by default, as they depend on what is
+//! installed; run them with `cargo test --test differential -- --ignored`. A tool which is not installed is skipped,
+//! with a note on standard error.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use uuencode_lite::{mode_from_permissions, uudecode_file, uuencode_file, FileOptions};
+
+/// An implementation of the command-line tools, as the command words which run each.
+struct Tool {
+    name: &'static str,
+    uuencode: &'static [&'static str],
+    uudecode: &'static [&'static str],
+}
+
+const TOOLS: &[Tool] = &[
+    Tool { name: "sharutils", uuencode: &["uuencode"], uudecode: &["uudecode"] },
+    Tool { name: "busybox", uuencode: &["busybox", "uuencode"], uudecode: &["busybox", "uudecode"] },
+];
+
+impl Tool {
+    fn command(words: &[&str]) -> Command {
+        let mut command = Command::new(words[0]);
+        command.args(&words[1..]).stdin(Stdio::null()).stderr(Stdio::null());
+        command
+    }
+
+    /// Whether the tool is installed, judged by encoding empty input with it.
+    fn is_installed(&self) -> bool {
+        let installed = Tool::command(self.uuencode).arg("probe").output().is_ok_and(|output| output.status.success());
+        if !installed {
+            eprintln!("{} uuencode is not installed; skipping it", self.name);
+        }
+        installed
+    }
+
+    /// Encodes the file at `path` under `name`, as Base64 with `base64`.
+    fn encode(&self, path: &Path, name: &str, base64: bool) -> Vec<u8> {
+        let mut command = Tool::command(self.uuencode);
+        if base64 {
+            command.arg("-m");
+        }
+        let output = command.arg(path).arg(name).output().expect("runs uuencode");
+        assert!(output.status.success(), "{} uuencode fails on {}", self.name, path.display());
+        output.stdout
+    }
+
+    /// Decodes the file at `path`, returning what was written to the file named on its `begin` line.
+    fn decode(&self, path: &Path, dir: &Path) -> Vec<u8> {
+        let decoded = dir.join("decoded");
+        let status = Tool::command(self.uudecode).arg("-o").arg(&decoded).arg(path).status().expect("runs uudecode");
+        assert!(status.success(), "{} uudecode fails on {}", self.name, path.display());
+        let data = fs::read(&decoded).expect("Can read decoded file");
+        fs::remove_file(&decoded).expect("Can remove decoded file");
+        data
+    }
+}
+
+/// The inputs compared: the test data, and lengths either side of each line and group boundary, with every byte
+/// value and with runs of zeros, which some encoders write as spaces rather than '`'.
+fn corpus() -> Vec<(String, Vec<u8>)> {
+    let mut corpus = Vec::new();
+    for name in ["random_data.bin", "the_machine_stops.txt", "the_machine_stops.txt.Z"] {
+        corpus.push((name.to_string(), fs::read(Path::new("test_data").join(name)).expect("Can open test data")));
+    }
+    let pattern: Vec<u8> = (0..=255u8).cycle().take(1000).collect();
+    for len in [0, 1, 2, 3, 4, 44, 45, 46, 89, 90, 91, 1000] {
+        corpus.push((format!("pattern_{}", len), pattern[..len].to_vec()));
+    }
+    corpus.push(("zeros".to_string(), vec![0; 100]));
+    corpus
+}
+
+/// A directory for a test's files, emptied first.
+fn scratch_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("uuencode_lite_differential_{}_{}", name, std::process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).expect("Can create scratch directory");
+    dir
+}
+
+/// Writes `data` to `dir` with mode 644, so the tools' `begin` lines don't depend on the umask.
+fn write_input(dir: &Path, name: &str, data: &[u8]) -> PathBuf {
+    let path = dir.join(name);
+    fs::write(&path, data).expect("Can write input");
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        fs::set_permissions(&path, fs::Permissions::from_mode(0o644)).expect("Can set permissions");
+    }
+    path
+}
+
+/// The first line on which two texts differ, so a mismatch is reported without printing the whole text.
+fn first_difference(left: &[u8], right: &[u8]) -> Option<usize> {
+    let lines = |text: &[u8]| text.split(|&ch| ch == b'\n').map(<[u8]>::to_vec).collect::<Vec<_>>();
+    let (left, right) = (lines(left), lines(right));
+    (0..left.len().max(right.len())).find(|&line| left.get(line) != right.get(line))
+}
+
+/// Tests that encoding matches the system tools byte-for-byte, as uuencode and as Base64
+#[test]
+#[ignore = "compares with the system's uuencode, if installed"]
+fn test_encode_matches() {
+    let dir = scratch_dir("encode");
+    for tool in TOOLS.iter().filter(|tool| tool.is_installed()) {
+        for (name, data) in corpus() {
+            let path = write_input(&dir, &name, &data);
+            let mode = mode_from_permissions(&fs::metadata(&path).expect("Can read metadata").permissions());
+            for base64 in [false, true] {
+                let expected = tool.encode(&path, &name, base64);
+                let encoded = uuencode_file(&data, &FileOptions::new(name.clone()).mode(mode).base64(base64)).unwrap();
+                let difference = first_difference(encoded.as_bytes(), &expected);
+                assert_eq!(difference, None, "encodes {} as {} does, with base64 {}", name, tool.name, base64);
+            }
+        }
+    }
+    fs::remove_dir_all(&dir).expect("Can remove scratch directory");
+}
+
+/// Tests that the system tools decode what is encoded here, and that what they encode decodes here
+#[test]
+#[ignore = "compares with the system's uudecode, if installed"]
+fn test_decode_matches() {
+    let dir = scratch_dir("decode");
+    for tool in TOOLS.iter().filter(|tool| tool.is_installed()) {
+        for (name, data) in corpus() {
+            for base64 in [false, true] {
+                let encoded = uuencode_file(&data, &FileOptions::new(name.clone()).base64(base64)).unwrap();
+                let path = write_input(&dir, "encoded.uu", encoded.as_bytes());
+                assert!(tool.decode(&path, &dir) == data, "{} decodes {} with base64 {}", tool.name, name, base64);
+
+                let input = write_input(&dir, &name, &data);
+                let file = uudecode_file(&tool.encode(&input, &name, base64)).unwrap();
+                assert_eq!(file.name, name, "decodes the name of {} from {}", name, tool.name);
+                assert!(file.data == data, "decodes {} from {} with base64 {}", name, tool.name, base64);
+            }
+        }
+    }
+    fs::remove_dir_all(&dir).expect("Can remove scratch directory");
+}